@@ -1,13 +1,208 @@
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Returns whether the target differs from the host.
+fn cross() -> bool {
+    env::var("HOST") != env::var("TARGET")
+}
+
+/// Reads the variable `name` for the target like cc reads `CXX`, i.e. `<name>_<target>`,
+/// `<name>_<target with underscores>`, `TARGET_<name>` when cross-compiling, and `<name>`, so
+/// that each target of a cross build can be configured separately.
+fn target_var(name: &str) -> Option<OsString> {
+    let target = env::var("TARGET").unwrap_or_default();
+    let mut vars = vec![
+        format!("{}_{}", name, target),
+        format!("{}_{}", name, target.replace('-', "_")),
+    ];
+    if cross() {
+        vars.push(format!("TARGET_{}", name));
+    }
+    vars.push(name.to_owned());
+    vars.into_iter().find_map(|var| {
+        println!("cargo:rerun-if-env-changed={}", var);
+        env::var_os(var)
+    })
+}
+
+/// An installed QuadProg++ to link instead of the vendored sources.
+struct System {
+    include_dirs: Vec<PathBuf>,
+    lib_dirs: Vec<PathBuf>,
+    libs: Vec<String>,
+}
+
+/// Returns the installed QuadProg++ if `QUADPROGPP_SYS_USE_SYSTEM=1`.
+///
+/// The headers are looked up in `QUADPROGPP_SYS_INCLUDE_DIR` and the library in
+/// `QUADPROGPP_SYS_LIB_DIR` if either is set, and with pkg-config otherwise. The library is
+/// `quadprog`, the name of upstream's CMake target, unless `QUADPROGPP_SYS_LIB_NAME` is set. All
+/// the variables can be set for a single target, see [`target_var`].
+///
+/// When cross-compiling, pkg-config is only used with `PKG_CONFIG_ALLOW_CROSS=1`, since it
+/// would find the library of the host otherwise, and `PKG_CONFIG` selects its binary.
+fn system() -> Option<System> {
+    if target_var("QUADPROGPP_SYS_USE_SYSTEM")? != "1" {
+        return None;
+    }
+    let name = target_var("QUADPROGPP_SYS_LIB_NAME")
+        .and_then(|name| name.into_string().ok())
+        .unwrap_or_else(|| "quadprog".to_owned());
+    let include_dir = target_var("QUADPROGPP_SYS_INCLUDE_DIR").map(PathBuf::from);
+    let lib_dir = target_var("QUADPROGPP_SYS_LIB_DIR").map(PathBuf::from);
+    if include_dir.is_some() || lib_dir.is_some() {
+        return Some(System {
+            include_dirs: include_dir.into_iter().collect(),
+            lib_dirs: lib_dir.into_iter().collect(),
+            libs: vec![name],
+        });
+    }
+    if cross() && target_var("PKG_CONFIG_ALLOW_CROSS").is_none_or(|allow| allow != "1") {
+        panic!(
+            "QUADPROGPP_SYS_USE_SYSTEM is set for a cross build; set QUADPROGPP_SYS_INCLUDE_DIR \
+             and QUADPROGPP_SYS_LIB_DIR, or PKG_CONFIG_ALLOW_CROSS=1 with a pkg-config set up \
+             for the target"
+        );
+    }
+    let pkg_config = target_var("PKG_CONFIG").unwrap_or_else(|| "pkg-config".into());
+    let output = Command::new(pkg_config)
+        .args(["--cflags-only-I", "--libs", &name])
+        .output()
+        .unwrap_or_else(|e| {
+            panic!(
+                "QUADPROGPP_SYS_USE_SYSTEM is set but pkg-config failed: {}",
+                e
+            )
+        });
+    if !output.status.success() {
+        panic!(
+            "QUADPROGPP_SYS_USE_SYSTEM is set but pkg-config can't find {}; set \
+             QUADPROGPP_SYS_INCLUDE_DIR and QUADPROGPP_SYS_LIB_DIR instead: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let mut system = System {
+        include_dirs: Vec::new(),
+        lib_dirs: Vec::new(),
+        libs: Vec::new(),
+    };
+    for arg in String::from_utf8_lossy(&output.stdout).split_whitespace() {
+        if let Some(dir) = arg.strip_prefix("-I") {
+            // upstream installs its headers in include/QuadProg++
+            system.include_dirs.push(Path::new(dir).join("QuadProg++"));
+            system.include_dirs.push(PathBuf::from(dir));
+        } else if let Some(dir) = arg.strip_prefix("-L") {
+            system.lib_dirs.push(PathBuf::from(dir));
+        } else if let Some(lib) = arg.strip_prefix("-l") {
+            system.libs.push(lib.to_owned());
+        }
+    }
+    Some(system)
+}
+
 fn main() {
+    let system = system();
     let mut build = cxx_build::bridge("src/lib.rs");
-    build
-        .file("upstream/src/Array.cc")
-        .file("upstream/src/QuadProg++.cc")
-        .include("upstream/src")
-        .flag("-std=c++14")
-        .flag("-Wno-extra");
-    if cfg!(features = "trace-solver") {
-        build.define("TRACE_SOLVER", "1");
-    }
-    build.compile("libquadprog.a");
+    build.file("src/solver.cc");
+    match &system {
+        Some(system) => {
+            build.includes(&system.include_dirs);
+        }
+        None => {
+            build
+                .file("upstream/src/Array.cc")
+                .file("upstream/src/QuadProg++.cc")
+                .include("upstream/src");
+        }
+    }
+    // MSVC takes its own flags, and only unwinds C++ exceptions, which the bridge turns into
+    // `Result`s, with /EHsc. Emscripten compiles them but aborts when one is thrown unless the
+    // objects and the final link both have -fexceptions. Cargo only passes link arguments to the
+    // targets of this package, so binaries depending on it need `-C link-arg=-fexceptions`.
+    let msvc = build.get_compiler().is_like_msvc();
+    let emscripten = env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("emscripten");
+    let mut flags = if msvc {
+        vec!["/std:c++14", "/EHsc"]
+    } else if emscripten {
+        println!("cargo:rustc-link-arg=-fexceptions");
+        vec!["-std=c++14", "-fexceptions"]
+    } else {
+        vec!["-std=c++14", "-Wno-extra"]
+    };
+    for flag in &flags {
+        build.flag(flag);
+    }
+    // Upstream's TRACE_SOLVER would dump to stdout, so only the extended solver is traced.
+    if env::var_os("CARGO_FEATURE_TRACE_SOLVER").is_some() {
+        build.define("QUADPROGPP_TRACE", "1");
+        flags.push(if msvc {
+            "/DQUADPROGPP_TRACE=1"
+        } else {
+            "-DQUADPROGPP_TRACE=1"
+        });
+    }
+    if env::var_os("CARGO_FEATURE_EXTENDED_PRECISION").is_some() {
+        build.define("QUADPROGPP_EXTENDED_PRECISION", "1");
+        flags.push(if msvc {
+            "/DQUADPROGPP_EXTENDED_PRECISION=1"
+        } else {
+            "-DQUADPROGPP_EXTENDED_PRECISION=1"
+        });
+    }
+    let mut flags: Vec<String> = flags.into_iter().map(String::from).collect();
+    // Overrides the optimization level of the Cargo profile, e.g. 3 for -O3 in debug builds.
+    println!("cargo:rerun-if-env-changed=QUADPROGPP_SYS_OPT_LEVEL");
+    if let Ok(level) = env::var("QUADPROGPP_SYS_OPT_LEVEL") {
+        build.opt_level_str(&level);
+        flags.push(format!("opt-level={}", level));
+    }
+    if env::var_os("CARGO_FEATURE_NATIVE_CPU").is_some() {
+        // The host CPU says nothing about the target when cross-compiling.
+        if msvc || cross() {
+            println!("cargo:warning=native-cpu is ignored for MSVC and cross-compilation");
+        } else {
+            build.flag("-march=native");
+            flags.push("-march=native".to_owned());
+        }
+    }
+    match &system {
+        Some(system) => {
+            // The bridge can't be named after the system library.
+            build.compile("libquadprogpp-bridge.a");
+            for dir in &system.lib_dirs {
+                println!("cargo:rustc-link-search=native={}", dir.display());
+            }
+            for lib in &system.libs {
+                println!("cargo:rustc-link-lib={}", lib);
+            }
+            println!("cargo:rustc-env=QUADPROGPP_SYSTEM_LIBRARY=1");
+        }
+        None => build.compile("libquadprog.a"),
+    }
+    // reported by quadprogpp_sys::COMPILE_FLAGS
+    println!(
+        "cargo:rustc-env=QUADPROGPP_COMPILE_FLAGS={}",
+        flags.join(" ")
+    );
+    // The packaged crate has no git metadata, and `git rev-parse` would find the repository of
+    // the bindings rather than the submodule without `upstream/.git`.
+    if system.is_none() && Path::new("upstream/.git").exists() {
+        let commit = Command::new("git")
+            .args(["-C", "upstream", "rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok());
+        if let Some(commit) = commit {
+            println!(
+                "cargo:rustc-env=QUADPROGPP_UPSTREAM_COMMIT={}",
+                commit.trim()
+            );
+        }
+    }
+    println!("cargo:rerun-if-changed=include");
+    println!("cargo:rerun-if-changed=src/solver.cc");
 }
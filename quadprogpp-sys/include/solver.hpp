@@ -0,0 +1,232 @@
+#pragma once
+
+#include <memory>
+#include "Array.hh"
+
+namespace quadprogpp {
+struct SolveParams;
+struct SolveInfo;
+struct Monitor;
+
+/// The scalar type of the arithmetic of the solver: `long double` with
+/// QUADPROGPP_EXTENDED_PRECISION and `double` otherwise. The problem and its
+/// solution are `double` either way.
+#ifdef QUADPROGPP_EXTENDED_PRECISION
+typedef long double Real;
+#else
+typedef double Real;
+#endif
+
+/// Buffers used by `solve_quadprog_ws`, which can be reused across solves of
+/// problems of the same size.
+struct Workspace
+{
+    Workspace(unsigned int n, unsigned int p, unsigned int m);
+
+    /// Resizes the buffers for a problem of the given size. This doesn't
+    /// allocate if the size is unchanged.
+    void resize(unsigned int n, unsigned int p, unsigned int m);
+
+    Matrix<Real> R, J;
+    Vector<Real> s, z, r, d, np, u, u_old;
+    Vector<double> x_old;
+    Vector<int> A, A_old, iai;
+    Vector<bool> iaexcl;
+
+    /// The Cholesky factor of `G`, the initial value of `J` and the estimate
+    /// of the condition number of `G` computed by the last factorization, kept
+    /// for `SolveParams::reuse_factorization`.
+    Matrix<Real> L, J0;
+    Real c1 = 0.0, c2 = 0.0;
+    /// The regularization added to the diagonal of `G` before the factorization
+    double regularization = 0.0;
+    /// Whether `L`, `J0`, `c1` and `c2` are valid
+    bool factorized = false;
+};
+
+std::unique_ptr<Workspace>
+new_workspace(unsigned int n, unsigned int p, unsigned int m);
+
+/// A read-only view of a row-major `n x m` matrix owned by the caller, which
+/// the solver reads the constraints from like a `Matrix<double>`.
+class MatrixView
+{
+  public:
+    MatrixView(const double* a, unsigned int n, unsigned int m)
+      : a(a)
+      , n(n)
+      , m(m)
+    {}
+    explicit MatrixView(const Matrix<double>& mat);
+
+    const double* operator[](unsigned int i) const { return a + i * m; }
+    unsigned int nrows() const { return n; }
+    unsigned int ncols() const { return m; }
+
+  private:
+    const double* a;
+    unsigned int n, m;
+};
+
+/// A read-only view of a vector of length `n` owned by the caller.
+class VectorView
+{
+  public:
+    VectorView(const double* a, unsigned int n)
+      : a(a)
+      , n(n)
+    {}
+    explicit VectorView(const Vector<double>& v);
+
+    double operator[](unsigned int i) const { return a[i]; }
+    unsigned int size() const { return n; }
+
+  private:
+    const double* a;
+    unsigned int n;
+};
+
+std::unique_ptr<MatrixView>
+new_matrix_view(const double* a, unsigned int n, unsigned int m);
+
+std::unique_ptr<VectorView>
+new_vector_view(const double* a, unsigned int n);
+
+/// Overwrites `G` with its Cholesky factor and caches the quantities derived
+/// from it in `ws`, so that subsequent calls to `solve_quadprog_ws` with
+/// `SolveParams::reuse_factorization` can skip the factorization. Returns the
+/// index of the nonpositive pivot if `G` isn't positive definite, and -1
+/// otherwise.
+int
+factorize_hessian(Workspace& ws, Matrix<double>& G);
+
+/// Overwrites the symmetric matrix `A` with the lower triangular `L` of its
+/// Cholesky factorization `A = L L^T`, zeroing the upper triangle. Returns the
+/// index of the nonpositive pivot if `A` isn't positive definite, in which
+/// case `A` is left partially factorized, and -1 otherwise.
+int
+cholesky_factorize(Matrix<double>& A);
+
+/// Solves `L y = b` for `y`, reading the lower triangle of `L`.
+void
+forward_substitute(const Matrix<double>& L,
+                   const Vector<double>& b,
+                   Vector<double>& y);
+
+/// Solves `U x = y` for `x`, reading the upper triangle of `U`.
+void
+backward_substitute(const Matrix<double>& U,
+                    const Vector<double>& y,
+                    Vector<double>& x);
+
+/// Solves `L L^T x = b` for `x`, reading the lower triangle of `L`.
+void
+cholesky_substitute(const Matrix<double>& L,
+                    const Vector<double>& b,
+                    Vector<double>& x);
+
+/// Same as `solve_quadprog_ext`, using the buffers in `ws`.
+double
+solve_quadprog_ws(Workspace& ws,
+                  Matrix<double>& G,
+                  Vector<double>& g0,
+                  const Matrix<double>& CE,
+                  const Vector<double>& ce0,
+                  const Matrix<double>& CI,
+                  const Vector<double>& ci0,
+                  Vector<double>& x,
+                  const SolveParams& params,
+                  SolveInfo& info);
+
+/// Same as `solve_quadprog_ws`, calling back `monitor` in each iteration of
+/// the active-set loop.
+double
+solve_quadprog_monitored(Workspace& ws,
+                         Matrix<double>& G,
+                         Vector<double>& g0,
+                         const Matrix<double>& CE,
+                         const Vector<double>& ce0,
+                         const Matrix<double>& CI,
+                         const Vector<double>& ci0,
+                         Vector<double>& x,
+                         const SolveParams& params,
+                         SolveInfo& info,
+                         Monitor& monitor);
+
+/// Solves the problem given by row-major arrays, writing the minimizer into
+/// `x`. All the C++ objects are created here, so
+/// this takes a single call from Rust.
+double
+solve_quadprog_raw(unsigned int n,
+                   unsigned int p,
+                   unsigned int m,
+                   const double* G,
+                   const double* g0,
+                   const double* CE,
+                   const double* ce0,
+                   const double* CI,
+                   const double* ci0,
+                   double* x,
+                   const SolveParams& params,
+                   SolveInfo& info);
+
+/// `solve_quadprog_ext` on `Matrix<float>` and `Vector<float>`. The solver
+/// computes in double precision, so the problem is widened on entry and the
+/// minimizer narrowed into `x` on return. `G` isn't modified.
+float
+solve_quadprog_f32(const Matrix<float>& G,
+                   const Vector<float>& g0,
+                   const Matrix<float>& CE,
+                   const Vector<float>& ce0,
+                   const Matrix<float>& CI,
+                   const Vector<float>& ci0,
+                   Vector<float>& x,
+                   const SolveParams& params,
+                   SolveInfo& info);
+
+/// Same as `solve_quadprog_ws`, reading the constraints through views instead
+/// of copies.
+double
+solve_quadprog_view(Workspace& ws,
+                    Matrix<double>& G,
+                    Vector<double>& g0,
+                    const MatrixView& CE,
+                    const VectorView& ce0,
+                    const MatrixView& CI,
+                    const VectorView& ci0,
+                    Vector<double>& x,
+                    const SolveParams& params,
+                    SolveInfo& info);
+
+/// Same as `solve_quadprog_monitored`, reading the constraints through views
+/// instead of copies.
+double
+solve_quadprog_monitored_view(Workspace& ws,
+                              Matrix<double>& G,
+                              Vector<double>& g0,
+                              const MatrixView& CE,
+                              const VectorView& ce0,
+                              const MatrixView& CI,
+                              const VectorView& ci0,
+                              Vector<double>& x,
+                              const SolveParams& params,
+                              SolveInfo& info,
+                              Monitor& monitor);
+
+/// The Goldfarb-Idnani solver of `QuadProg++.cc`, extended to report the
+/// state of the solver at termination.
+///
+/// The arguments are the same as those of `solve_quadprog` except `params`,
+/// which controls the termination of the solver, and `info`, which receives
+/// the solver state. See `SolveParams` and `SolveInfo` in `lib.rs`.
+double
+solve_quadprog_ext(Matrix<double>& G,
+                   Vector<double>& g0,
+                   const Matrix<double>& CE,
+                   const Vector<double>& ce0,
+                   const Matrix<double>& CI,
+                   const Vector<double>& ci0,
+                   Vector<double>& x,
+                   const SolveParams& params,
+                   SolveInfo& info);
+} // namespace quadprogpp
@@ -1,35 +1,204 @@
 #pragma once
 
+#include <algorithm>
+#include <cstddef>
 #include <memory>
-#include "../upstream/src/Array.hh"
-#include "../upstream/src/QuadProg++.hh"
+#include "rust/cxx.h"
+#include "Array.hh"
+#include "QuadProg++.hh"
 
 namespace quadprogpp {
 typedef quadprogpp::Vector<double> VectorF64;
 
-std::unique_ptr<VectorF64>
+inline std::unique_ptr<VectorF64>
 new_vector(const unsigned int n)
 {
-    return std::make_unique<VectorF64>(n);
+    return std::make_unique<VectorF64>(0.0, n);
 }
 
-std::unique_ptr<VectorF64>
+inline std::unique_ptr<VectorF64>
 new_vector_from_ptr(const double* a, const unsigned int n)
 {
     return std::make_unique<VectorF64>(a, n);
 }
 
-double
+inline std::unique_ptr<VectorF64>
+new_vector_from_f32_ptr(const float* a, const unsigned int n)
+{
+    auto v = std::make_unique<VectorF64>(n);
+    for (unsigned int i = 0; i < n; i++)
+        (*v)[i] = a[i];
+    return v;
+}
+
+inline void
+vector_set_from_ptr(VectorF64& v, const double* a, const unsigned int n)
+{
+    v.set(a, n);
+}
+
+inline unsigned int
+vector_len(const VectorF64& v)
+{
+    return v.size();
+}
+
+inline double
 vector_index(const VectorF64& v, const unsigned int i)
 {
     return v[i];
 }
 
+inline void
+vector_set(VectorF64& v, const unsigned int i, const double value)
+{
+    v[i] = value;
+}
+
+inline void
+vector_copy_to(const VectorF64& v, rust::Slice<double> out)
+{
+    const unsigned int n = std::min<size_t>(v.size(), out.size());
+    for (unsigned int i = 0; i < n; i++)
+        out[i] = v[i];
+}
+
 typedef quadprogpp::Matrix<double> MatrixF64;
 
-std::unique_ptr<MatrixF64>
+inline std::unique_ptr<MatrixF64>
+new_matrix(const unsigned int n, const unsigned int m)
+{
+    // The constructors of Matrix write the first row pointer even without rows.
+    if (n == 0)
+        return std::make_unique<MatrixF64>();
+    return std::make_unique<MatrixF64>(0.0, n, m);
+}
+
+inline unsigned int
+matrix_nrows(const MatrixF64& mat)
+{
+    return mat.nrows();
+}
+
+inline unsigned int
+matrix_ncols(const MatrixF64& mat)
+{
+    return mat.ncols();
+}
+
+inline double
+matrix_index(const MatrixF64& mat, const unsigned int i, const unsigned int j)
+{
+    return mat[i][j];
+}
+
+inline void
+matrix_set(MatrixF64& mat,
+           const unsigned int i,
+           const unsigned int j,
+           const double value)
+{
+    mat[i][j] = value;
+}
+
+inline void
+matrix_set_row(MatrixF64& mat, const unsigned int i, rust::Slice<const double> row)
+{
+    const unsigned int m = std::min<size_t>(mat.ncols(), row.size());
+    for (unsigned int j = 0; j < m; j++)
+        mat[i][j] = row[j];
+}
+
+inline void
+matrix_set_column(MatrixF64& mat, const unsigned int j, rust::Slice<const double> column)
+{
+    const unsigned int n = std::min<size_t>(mat.nrows(), column.size());
+    for (unsigned int i = 0; i < n; i++)
+        mat[i][j] = column[i];
+}
+
+inline std::unique_ptr<MatrixF64>
 new_matrix_from_ptr(const double* a, const unsigned int n, const unsigned int m)
 {
     return std::make_unique<MatrixF64>(a, n, m);
 }
+
+inline std::unique_ptr<MatrixF64>
+new_matrix_from_f32_ptr(const float* a, const unsigned int n, const unsigned int m)
+{
+    auto mat = std::make_unique<MatrixF64>(n, m);
+    for (unsigned int i = 0; i < n; i++)
+        for (unsigned int j = 0; j < m; j++)
+            (*mat)[i][j] = a[i * m + j];
+    return mat;
+}
+
+inline void
+matrix_set_from_ptr(MatrixF64& mat,
+                    const double* a,
+                    const unsigned int n,
+                    const unsigned int m)
+{
+    mat.set(a, n, m);
+}
+
+typedef quadprogpp::Vector<float> VectorF32;
+
+inline std::unique_ptr<VectorF32>
+new_vector_f32(const unsigned int n)
+{
+    return std::make_unique<VectorF32>(0.0f, n);
+}
+
+inline std::unique_ptr<VectorF32>
+new_vector_f32_from_ptr(const float* a, const unsigned int n)
+{
+    return std::make_unique<VectorF32>(a, n);
+}
+
+inline void
+vector_f32_copy_to(const VectorF32& v, rust::Slice<float> out)
+{
+    const unsigned int n = std::min<size_t>(v.size(), out.size());
+    for (unsigned int i = 0; i < n; i++)
+        out[i] = v[i];
+}
+
+typedef quadprogpp::Matrix<float> MatrixF32;
+
+inline std::unique_ptr<MatrixF32>
+new_matrix_f32_from_ptr(const float* a, const unsigned int n, const unsigned int m)
+{
+    // The constructors of Matrix write the first row pointer even without rows.
+    if (n == 0)
+        return std::make_unique<MatrixF32>();
+    return std::make_unique<MatrixF32>(a, n, m);
+}
+
+inline void
+matrix_set_from_ptr_strided(MatrixF64& mat,
+                            const double* a,
+                            const unsigned int n,
+                            const unsigned int m,
+                            const std::ptrdiff_t row_stride,
+                            const std::ptrdiff_t col_stride)
+{
+    mat.resize(n, m);
+    for (unsigned int i = 0; i < n; i++)
+        for (unsigned int j = 0; j < m; j++)
+            mat[i][j] = a[i * row_stride + j * col_stride];
+}
+
+inline std::unique_ptr<MatrixF64>
+new_matrix_from_ptr_strided(const double* a,
+                            const unsigned int n,
+                            const unsigned int m,
+                            const std::ptrdiff_t row_stride,
+                            const std::ptrdiff_t col_stride)
+{
+    auto mat = new_matrix(n, m);
+    if (n > 0)
+        matrix_set_from_ptr_strided(*mat, a, n, m, row_stride, col_stride);
+    return mat;
+}
 } // namespace quadprogpp
@@ -0,0 +1,375 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Deref;
+use std::pin::Pin;
+
+use cxx::UniquePtr;
+
+use crate::ffi::*;
+
+/// A safe wrapper of [`VectorF64`], whose accessors check the indices.
+///
+/// It dereferences to [`VectorF64`], so it can be passed to the solver functions as is, and
+/// [`pin_mut`](Self::pin_mut) gives the mutable reference they take for outputs.
+pub struct Vector(UniquePtr<VectorF64>);
+
+impl Vector {
+    /// Creates a zero-filled vector of length `n`.
+    pub fn zeros(n: usize) -> Self {
+        Self(new_vector(to_u32(n)))
+    }
+
+    /// Returns the length of the vector.
+    pub fn len(&self) -> usize {
+        vector_len(&self.0) as usize
+    }
+
+    /// Returns `true` if the vector has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element `i`, or `None` if it's out of range.
+    pub fn get(&self, i: usize) -> Option<f64> {
+        if i < self.len() {
+            Some(unsafe { vector_index(&self.0, i as u32) })
+        } else {
+            None
+        }
+    }
+
+    /// Sets the element `i` to `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of range.
+    pub fn set(&mut self, i: usize, value: f64) {
+        let len = self.len();
+        assert!(
+            i < len,
+            "index {} out of range for a vector of length {}",
+            i,
+            len
+        );
+        unsafe { vector_set(self.0.pin_mut(), i as u32, value) };
+    }
+
+    /// Copies the elements into a `Vec`.
+    pub fn to_vec(&self) -> Vec<f64> {
+        let mut v = vec![0.0; self.len()];
+        vector_copy_to(&self.0, &mut v);
+        v
+    }
+
+    /// Returns the mutable reference taken by the solver functions.
+    pub fn pin_mut(&mut self) -> Pin<&mut VectorF64> {
+        self.0.pin_mut()
+    }
+
+    /// Returns the wrapped vector.
+    pub fn into_inner(self) -> UniquePtr<VectorF64> {
+        self.0
+    }
+}
+
+impl From<&[f64]> for Vector {
+    fn from(a: &[f64]) -> Self {
+        Self(unsafe { new_vector_from_ptr(a.as_ptr(), to_u32(a.len())) })
+    }
+}
+
+impl Clone for Vector {
+    fn clone(&self) -> Self {
+        Self::from(&self.to_vec()[..])
+    }
+}
+
+impl Deref for Vector {
+    type Target = VectorF64;
+
+    fn deref(&self) -> &VectorF64 {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.to_vec()).finish()
+    }
+}
+
+/// A safe wrapper of [`MatrixF64`], whose accessors check the indices.
+///
+/// Like [`Vector`], it dereferences to [`MatrixF64`] and has [`pin_mut`](Self::pin_mut).
+pub struct Matrix(UniquePtr<MatrixF64>);
+
+impl Matrix {
+    /// Creates a zero-filled `n x m` matrix. QuadProg++ can't represent a matrix without rows but
+    /// with columns, so the matrix is `0 x 0` if `n` is zero.
+    pub fn zeros(n: usize, m: usize) -> Self {
+        Self(new_matrix(to_u32(n), to_u32(m)))
+    }
+
+    /// Creates an `n x m` matrix from a row-major slice. Like [`zeros`](Self::zeros), the matrix
+    /// is `0 x 0` if `n` is zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` isn't `n * m`.
+    pub fn from_row_major(a: &[f64], n: usize, m: usize) -> Self {
+        assert_eq!(a.len(), n * m, "the length of the matrix");
+        if n == 0 {
+            return Self::zeros(0, 0);
+        }
+        Self(unsafe { new_matrix_from_ptr(a.as_ptr(), to_u32(n), to_u32(m)) })
+    }
+
+    /// Returns the numbers of rows and columns.
+    pub fn dim(&self) -> (usize, usize) {
+        (
+            matrix_nrows(&self.0) as usize,
+            matrix_ncols(&self.0) as usize,
+        )
+    }
+
+    /// Returns the element `(i, j)`, or `None` if it's out of range.
+    pub fn get(&self, i: usize, j: usize) -> Option<f64> {
+        let (n, m) = self.dim();
+        if i < n && j < m {
+            Some(unsafe { matrix_index(&self.0, i as u32, j as u32) })
+        } else {
+            None
+        }
+    }
+
+    /// Sets the element `(i, j)` to `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `(i, j)` is out of range.
+    pub fn set(&mut self, i: usize, j: usize, value: f64) {
+        let (n, m) = self.dim();
+        assert!(
+            i < n && j < m,
+            "index ({}, {}) out of range for a {} x {} matrix",
+            i,
+            j,
+            n,
+            m
+        );
+        unsafe { matrix_set(self.0.pin_mut(), i as u32, j as u32, value) };
+    }
+
+    /// Overwrites the row `i` with `row`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of range or the length of `row` isn't the number of columns.
+    pub fn set_row(&mut self, i: usize, row: &[f64]) {
+        let (n, m) = self.dim();
+        assert!(i < n, "row {} out of range for a {} x {} matrix", i, n, m);
+        assert_eq!(row.len(), m, "the length of the row");
+        unsafe { matrix_set_row(self.0.pin_mut(), i as u32, row) };
+    }
+
+    /// Overwrites the column `j` with `column`, e.g. to replace a constraint of `CE` or `CI`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `j` is out of range or the length of `column` isn't the number of rows.
+    pub fn set_column(&mut self, j: usize, column: &[f64]) {
+        let (n, m) = self.dim();
+        assert!(
+            j < m,
+            "column {} out of range for a {} x {} matrix",
+            j,
+            n,
+            m
+        );
+        assert_eq!(column.len(), n, "the length of the column");
+        unsafe { matrix_set_column(self.0.pin_mut(), j as u32, column) };
+    }
+
+    /// Overwrites the matrix with the lower triangular `L` of its Cholesky factorization
+    /// `A = L L^T`, zeroing the upper triangle.
+    ///
+    /// # Errors
+    ///
+    /// Returns the index of the nonpositive pivot if the matrix isn't positive definite, in which
+    /// case it's left partially factorized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix isn't square.
+    pub fn cholesky(&mut self) -> Result<(), usize> {
+        let (n, m) = self.dim();
+        assert_eq!(n, m, "the matrix to factorize must be square");
+        match cholesky_factorize(self.0.pin_mut()).expect("the matrix is square") {
+            pivot if pivot >= 0 => Err(pivot as usize),
+            _ => Ok(()),
+        }
+    }
+
+    /// Solves `L y = b` for `y`, reading the lower triangle of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix isn't square or its size doesn't match `b`.
+    pub fn forward_substitute(&self, b: &Vector) -> Vector {
+        self.check_triangular(b);
+        let mut y = Vector::zeros(b.len());
+        forward_substitute(&self.0, b, y.pin_mut()).expect("the sizes have been checked");
+        y
+    }
+
+    /// Solves `U x = y` for `x`, reading the upper triangle of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix isn't square or its size doesn't match `y`.
+    pub fn backward_substitute(&self, y: &Vector) -> Vector {
+        self.check_triangular(y);
+        let mut x = Vector::zeros(y.len());
+        backward_substitute(&self.0, y, x.pin_mut()).expect("the sizes have been checked");
+        x
+    }
+
+    /// Solves `L L^T x = b` for `x`, where `L` is the lower triangle of the
+    /// matrix, e.g. as factorized by [`cholesky`](Self::cholesky).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix isn't square or its size doesn't match `b`.
+    pub fn cholesky_substitute(&self, b: &Vector) -> Vector {
+        self.check_triangular(b);
+        let mut x = Vector::zeros(b.len());
+        cholesky_substitute(&self.0, b, x.pin_mut()).expect("the sizes have been checked");
+        x
+    }
+
+    fn check_triangular(&self, b: &Vector) {
+        let (n, m) = self.dim();
+        assert_eq!(n, m, "the triangular matrix must be square");
+        assert_eq!(b.len(), n, "the length of the right-hand side");
+    }
+
+    /// Copies the elements into a row-major `Vec`.
+    pub fn to_vec(&self) -> Vec<f64> {
+        let (n, m) = self.dim();
+        let mut v = Vec::with_capacity(n * m);
+        for i in 0..n {
+            v.extend((0..m).map(|j| unsafe { matrix_index(&self.0, i as u32, j as u32) }));
+        }
+        v
+    }
+
+    /// Returns the mutable reference taken by the solver functions.
+    pub fn pin_mut(&mut self) -> Pin<&mut MatrixF64> {
+        self.0.pin_mut()
+    }
+
+    /// Returns the wrapped matrix.
+    pub fn into_inner(self) -> UniquePtr<MatrixF64> {
+        self.0
+    }
+}
+
+impl Clone for Matrix {
+    fn clone(&self) -> Self {
+        let (n, m) = self.dim();
+        Self::from_row_major(&self.to_vec(), n, m)
+    }
+}
+
+impl Deref for Matrix {
+    type Target = MatrixF64;
+
+    fn deref(&self) -> &MatrixF64 {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, m) = self.dim();
+        let v = self.to_vec();
+        f.debug_list().entries(v.chunks(m.max(1))).finish()
+    }
+}
+
+fn to_u32(n: usize) -> u32 {
+    u32::try_from(n).expect("QuadProg++ indexes arrays with u32")
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_ulps_eq;
+
+    use super::*;
+
+    #[test]
+    fn accessors() {
+        let mut v = Vector::from(&[1.0, 2.0][..]);
+        assert_eq!(v.len(), 2);
+        assert_eq!(v.get(1), Some(2.0));
+        assert_eq!(v.get(2), None);
+        v.set(0, 3.0);
+        assert_eq!(v.to_vec(), [3.0, 2.0]);
+        assert_eq!(format!("{:?}", v), "[3.0, 2.0]");
+        assert_eq!(Vector::zeros(3).to_vec(), [0.0; 3]);
+
+        let mut mat = Matrix::from_row_major(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3);
+        assert_eq!(mat.dim(), (2, 3));
+        assert_eq!(mat.get(1, 0), Some(4.0));
+        assert_eq!(mat.get(0, 3), None);
+        mat.set(1, 2, 7.0);
+        assert_eq!(mat.clone().to_vec(), [1.0, 2.0, 3.0, 4.0, 5.0, 7.0]);
+        assert_eq!(format!("{:?}", mat), "[[1.0, 2.0, 3.0], [4.0, 5.0, 7.0]]");
+        mat.set_row(0, &[0.0, -1.0, -2.0]);
+        mat.set_column(1, &[8.0, 9.0]);
+        assert_eq!(mat.to_vec(), [0.0, 8.0, -2.0, 4.0, 9.0, 7.0]);
+        assert_eq!(Matrix::zeros(0, 2).dim(), (0, 0));
+        assert_eq!(Matrix::zeros(2, 0).dim(), (2, 0));
+    }
+
+    #[test]
+    fn cholesky() {
+        let mut a = Matrix::from_row_major(&[4.0, 2.0, 2.0, 5.0], 2, 2);
+        a.cholesky().unwrap();
+        assert_ulps_eq!(&a.to_vec()[..], &[2.0, 0.0, 1.0, 2.0][..]);
+        let b = Vector::from(&[2.0, 5.0][..]);
+        assert_ulps_eq!(&a.forward_substitute(&b).to_vec()[..], &[1.0, 2.0][..]);
+        let u = Matrix::from_row_major(&[2.0, 1.0, 0.0, 2.0], 2, 2);
+        assert_ulps_eq!(&u.backward_substitute(&b).to_vec()[..], &[-0.25, 2.5][..]);
+        // [[4, 2], [2, 5]] [0, 1] = [2, 5]
+        assert_ulps_eq!(&a.cholesky_substitute(&b).to_vec()[..], &[0.0, 1.0][..]);
+
+        let mut indefinite = Matrix::from_row_major(&[1.0, 2.0, 2.0, 1.0], 2, 2);
+        assert_eq!(indefinite.cholesky(), Err(1));
+        let empty = Matrix::zeros(0, 0);
+        assert!(empty.cholesky_substitute(&Vector::zeros(0)).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "index (2, 0) out of range for a 2 x 3 matrix")]
+    fn set_out_of_range() {
+        Matrix::zeros(2, 3).set(2, 0, 1.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn solve() {
+        let mut G = Matrix::from_row_major(&[4.0, -2.0, -2.0, 4.0], 2, 2);
+        let mut g0 = Vector::from(&[6.0, 0.0][..]);
+        let CE = Matrix::from_row_major(&[1.0, 1.0], 2, 1);
+        let CI = Matrix::from_row_major(&[1.0, 0.0, 1.0, 0.0, 1.0, 1.0], 2, 3);
+        let (ce0, ci0) = (
+            Vector::from(&[-3.0][..]),
+            Vector::from(&[0.0, 0.0, -2.0][..]),
+        );
+        let mut x = Vector::zeros(2);
+        let r = solve_quadprog(G.pin_mut(), g0.pin_mut(), &CE, &ce0, &CI, &ci0, x.pin_mut());
+        assert_ulps_eq!(r.unwrap(), 12.0);
+        assert_ulps_eq!(&x.to_vec()[..], &[1.0, 2.0][..]);
+    }
+}
@@ -1,9 +1,294 @@
-pub use cxx::Exception;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::ops::Deref;
 
+pub use cxx::{Exception, UniquePtr};
+
+pub use array::{Matrix, Vector};
 pub use ffi::*;
+pub use pool::{ProblemBuffers, WorkspacePool};
+pub use trace::{set_trace_handler, TraceHandler};
+
+use trace::trace_message;
+
+mod array;
+mod pool;
+mod trace;
+
+/// The version of this crate.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Whether QuadProg++ is an installed library linked with `QUADPROGPP_SYS_USE_SYSTEM=1` rather
+/// than the vendored sources.
+pub const SYSTEM_LIBRARY: bool = option_env!("QUADPROGPP_SYSTEM_LIBRARY").is_some();
+
+/// The commit of the vendored QuadProg++ if it was built from a git checkout of the submodule.
+pub const UPSTREAM_COMMIT: Option<&str> = option_env!("QUADPROGPP_UPSTREAM_COMMIT");
+
+/// The flags the C++ sources were compiled with, besides those of the C++ compiler itself, and
+/// the optimization level if `QUADPROGPP_SYS_OPT_LEVEL` overrides that of the Cargo profile.
+pub const COMPILE_FLAGS: &str = env!("QUADPROGPP_COMPILE_FLAGS");
+
+/// Whether the solver computes in `long double`, i.e. was built with the `extended-precision`
+/// feature.
+pub const EXTENDED_PRECISION: bool = cfg!(feature = "extended-precision");
+
+/// Whether the solver was built with the `trace-solver` feature.
+pub const TRACE_SOLVER: bool = cfg!(feature = "trace-solver");
+
+// The C++ types own their buffers, and neither QuadProg++ nor the solver has global or static
+// state, so the types can be moved to other threads. The functions taking them by shared reference
+// only read them, so they can be shared between threads too.
+unsafe impl Send for VectorF64 {}
+unsafe impl Sync for VectorF64 {}
+unsafe impl Send for MatrixF64 {}
+unsafe impl Sync for MatrixF64 {}
+unsafe impl Send for VectorF32 {}
+unsafe impl Sync for VectorF32 {}
+unsafe impl Send for MatrixF32 {}
+unsafe impl Sync for MatrixF32 {}
+unsafe impl Send for Workspace {}
+unsafe impl Sync for Workspace {}
+// The views only read the borrowed data, like `&[f64]`.
+unsafe impl Send for MatrixView {}
+unsafe impl Sync for MatrixView {}
+unsafe impl Send for VectorView {}
+unsafe impl Sync for VectorView {}
+
+/// A [`MatrixView`] of a borrowed row-major slice, which can't outlive the slice.
+pub struct MatrixRef<'a> {
+    view: UniquePtr<MatrixView>,
+    data: PhantomData<&'a [f64]>,
+}
+
+impl<'a> MatrixRef<'a> {
+    /// Creates a view of `a` as an `n x m` matrix, without copying it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` isn't `n * m`.
+    pub fn new(a: &'a [f64], n: u32, m: u32) -> Self {
+        assert_eq!(a.len(), n as usize * m as usize, "the length of the matrix");
+        Self {
+            view: unsafe { new_matrix_view(a.as_ptr(), n, m) },
+            data: PhantomData,
+        }
+    }
+}
+
+impl Deref for MatrixRef<'_> {
+    type Target = MatrixView;
+
+    fn deref(&self) -> &MatrixView {
+        &self.view
+    }
+}
+
+/// A [`VectorView`] of a borrowed slice, which can't outlive the slice.
+pub struct VectorRef<'a> {
+    view: UniquePtr<VectorView>,
+    data: PhantomData<&'a [f64]>,
+}
+
+impl<'a> VectorRef<'a> {
+    /// Creates a view of `a`, without copying it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` doesn't fit in a `u32`.
+    pub fn new(a: &'a [f64]) -> Self {
+        let len = u32::try_from(a.len()).expect("the length of the vector fits in a u32");
+        Self {
+            view: unsafe { new_vector_view(a.as_ptr(), len) },
+            data: PhantomData,
+        }
+    }
+}
+
+impl Deref for VectorRef<'_> {
+    type Target = VectorView;
+
+    fn deref(&self) -> &VectorView {
+        &self.view
+    }
+}
+
+impl Default for SolveParams {
+    fn default() -> Self {
+        Self {
+            max_iterations: u32::MAX,
+            feasibility_tolerance: 0.0,
+            epsilon: f64::EPSILON,
+            active_set: Vec::new(),
+            initial_point: Vec::new(),
+            reuse_factorization: false,
+            lp_fallback: false,
+            regularization: 0.0,
+            refine_regularization: false,
+            max_degenerate_steps: u32::MAX,
+            anti_cycling: false,
+        }
+    }
+}
+
+/// Callbacks of [`solve_quadprog_monitored`].
+///
+/// Before each iteration of the active-set loop, `interrupted` is called with the number of
+/// iterations so far, and stops the solver with [`SolveStatus::Interrupted`] by returning `true`.
+/// Then `report` is called with the state of the solver once the iteration has computed the
+/// infeasibility of the current iterate.
+pub struct Monitor<'a> {
+    interrupted: &'a mut dyn FnMut(u32) -> bool,
+    report: &'a mut dyn FnMut(&Progress),
+}
+
+impl<'a> Monitor<'a> {
+    /// Creates a new [`Monitor`] calling `interrupted` and `report`.
+    pub fn new(
+        interrupted: &'a mut dyn FnMut(u32) -> bool,
+        report: &'a mut dyn FnMut(&Progress),
+    ) -> Self {
+        Self {
+            interrupted,
+            report,
+        }
+    }
+
+    fn interrupted(&mut self, iterations: u32) -> bool {
+        (self.interrupted)(iterations)
+    }
 
+    fn report(&mut self, progress: &Progress) {
+        (self.report)(progress)
+    }
+}
+
+impl Default for SolveStatus {
+    fn default() -> Self {
+        Self::Optimal
+    }
+}
+
+// cxx doesn't forward the `# Safety` sections below to the generated wrappers.
+#[allow(clippy::missing_safety_doc)]
 #[cxx::bridge(namespace = "quadprogpp")]
 mod ffi {
+    /// Parameters controlling the termination of [`solve_quadprog_ext`].
+    #[derive(Clone, Debug)]
+    struct SolveParams {
+        /// Maximum number of iterations of the active-set loop
+        max_iterations: u32,
+        /// Inequality constraints violated by no more than this are considered satisfied. Upstream
+        /// uses zero.
+        feasibility_tolerance: f64,
+        /// The relative tolerance of the termination test, and of the tests of linear dependence
+        /// and zero steps when adding a constraint. Upstream uses the machine epsilon.
+        epsilon: f64,
+        /// Inequality constraints to start the active-set loop with. They are added to the active
+        /// set before the first iteration, and discarded altogether unless they make a dual
+        /// feasible active set.
+        active_set: Vec<i32>,
+        /// An initial guess of the solution. If it isn't empty, the inequality constraints which
+        /// are tight or violated at this point are appended to `active_set`.
+        initial_point: Vec<f64>,
+        /// Whether `G` already holds the Cholesky factor computed by [`factorize_hessian`] or a
+        /// previous solve with the same [`Workspace`], in which case the factorization is skipped.
+        /// Only supported by [`solve_quadprog_ws`].
+        reuse_factorization: bool,
+        /// If `G` is zero, solve the problem as a linear program by the proximal point method
+        /// instead of failing to factorize `G`.
+        lp_fallback: bool,
+        /// Added to the diagonal of `G` before the factorization. The returned objective is the
+        /// one of the unregularized problem.
+        regularization: f64,
+        /// Whether to follow the regularized solve with a proximal point step which moves the
+        /// solution towards the one of the unregularized problem
+        refine_regularization: bool,
+        /// Maximum number of consecutive steps of zero length, which don't increase the
+        /// objective. The solver stops with [`SolveStatus::Degenerate`] when it's exceeded.
+        max_degenerate_steps: u32,
+        /// Whether to choose the constraints to add and drop by their smallest index, like
+        /// Bland's rule in the simplex method, after a step of zero length instead of the most
+        /// violated constraint and the first one in the active set
+        anti_cycling: bool,
+    }
+
+    /// How [`solve_quadprog_ext`] terminated.
+    #[derive(Debug)]
+    enum SolveStatus {
+        /// The solution is optimal.
+        Optimal,
+        /// The problem is infeasible.
+        Infeasible,
+        /// The iteration limit was reached before the solution became optimal.
+        MaxIterations,
+        /// `G` isn't positive definite.
+        NotPositiveDefinite,
+        /// The [`Monitor`] stopped the solver before the solution became optimal.
+        Interrupted,
+        /// More than [`SolveParams::max_degenerate_steps`] consecutive steps had zero length.
+        Degenerate,
+    }
+
+    /// The state of the solver in an iteration of the active-set loop, reported to [`Monitor`].
+    #[derive(Clone, Debug)]
+    struct Progress {
+        /// The iteration, counted from one
+        iteration: u32,
+        /// The objective function at the current iterate
+        objective: f64,
+        /// The sum of the violations of the inequality constraints at the current iterate,
+        /// beyond [`SolveParams::feasibility_tolerance`]
+        infeasibility: f64,
+        /// Number of inequality constraints in the active set
+        active_constraints: u32,
+    }
+
+    /// The state of the solver at termination, filled by [`solve_quadprog_ext`].
+    #[derive(Debug, Default)]
+    struct SolveInfo {
+        /// How the solver terminated
+        status: SolveStatus,
+        /// Whether the active set given in [`SolveParams`] was used
+        warm_started: bool,
+        /// Number of iterations of the active-set loop
+        iterations: u32,
+        /// Number of Cholesky factorizations of `G`
+        factorizations: u32,
+        /// Number of constraints added to the active set, including the equality constraints and
+        /// those of the warm start
+        additions: u32,
+        /// Number of inequality constraints dropped from the active set
+        deletions: u32,
+        /// Number of Givens rotations updating the factorization of the active constraints when
+        /// adding and dropping them
+        rotations: u32,
+        /// Constraints in the active set. Like the `A` array in upstream, equality constraint `i`
+        /// is encoded as `-i - 1`, and inequality constraint `i` as `i`.
+        active_set: Vec<i32>,
+        /// Lagrange multipliers of the equality constraints followed by those of the inequality
+        /// constraints. The multipliers of inactive constraints are zero.
+        multipliers: Vec<f64>,
+        /// Index of the inequality constraint which couldn't be satisfied if `status` is
+        /// `Infeasible`, and -1 otherwise
+        infeasible_constraint: i32,
+        /// Index of the nonpositive pivot of the Cholesky factorization of `G` if `status` is
+        /// `NotPositiveDefinite`, and -1 otherwise
+        singular_pivot: i32,
+    }
+
+    extern "Rust" {
+        type Monitor<'a>;
+
+        fn interrupted(self: &mut Monitor<'_>, iterations: u32) -> bool;
+
+        fn report(self: &mut Monitor<'_>, progress: &Progress);
+
+        /// Passes a line of the trace of the solver to the handler set by
+        /// [`set_trace_handler`](crate::set_trace_handler).
+        fn trace_message(message: &str);
+    }
+
     unsafe extern "C++" {
         include!("quadprogpp-sys/include/wrapper.hpp");
 
@@ -21,6 +306,17 @@ mod ffi {
         /// This is unsafe due to the use of a raw pointer.
         unsafe fn new_vector_from_ptr(a: *const f64, n: u32) -> UniquePtr<VectorF64>;
 
+        /// Creates a new [`VectorF64`] from a pointer to an array of f32 and its length, widening
+        /// the elements as it copies them.
+        ///
+        /// # Safety
+        ///
+        /// This is unsafe due to the use of a raw pointer.
+        unsafe fn new_vector_from_f32_ptr(a: *const f32, n: u32) -> UniquePtr<VectorF64>;
+
+        /// Returns the length of the vector.
+        fn vector_len(v: &VectorF64) -> u32;
+
         /// Performs indexing operation on the vector.
         ///
         /// # Safety
@@ -28,9 +324,61 @@ mod ffi {
         /// This is unsafe because the index range isn't checked.
         unsafe fn vector_index(v: &VectorF64, i: u32) -> f64;
 
+        /// Sets the element `i` of the vector to `value`.
+        ///
+        /// # Safety
+        ///
+        /// This is unsafe because the index range isn't checked.
+        unsafe fn vector_set(v: Pin<&mut VectorF64>, i: u32, value: f64);
+
+        /// Copies the elements of `v` into `out` in one call, or as many as fit if `out` is
+        /// shorter than `v`.
+        fn vector_copy_to(v: &VectorF64, out: &mut [f64]);
+
         /// A 2D matrix type whose element type is f64.
         type MatrixF64;
 
+        /// Creates a new zero-filled `n x m` [`MatrixF64`]. The matrix has no columns either if
+        /// `n` is zero.
+        fn new_matrix(n: u32, m: u32) -> UniquePtr<MatrixF64>;
+
+        /// Returns the number of rows of the matrix.
+        fn matrix_nrows(mat: &MatrixF64) -> u32;
+
+        /// Returns the number of columns of the matrix.
+        fn matrix_ncols(mat: &MatrixF64) -> u32;
+
+        /// Performs indexing operation on the matrix.
+        ///
+        /// # Safety
+        ///
+        /// This is unsafe because the index ranges aren't checked.
+        unsafe fn matrix_index(mat: &MatrixF64, i: u32, j: u32) -> f64;
+
+        /// Sets the element `(i, j)` of the matrix to `value`.
+        ///
+        /// # Safety
+        ///
+        /// This is unsafe because the index ranges aren't checked.
+        unsafe fn matrix_set(mat: Pin<&mut MatrixF64>, i: u32, j: u32, value: f64);
+
+        /// Overwrites the row `i` of the matrix with `row`, or its first `row.len()` elements if
+        /// `row` is shorter.
+        ///
+        /// # Safety
+        ///
+        /// This is unsafe because the index range isn't checked.
+        unsafe fn matrix_set_row(mat: Pin<&mut MatrixF64>, i: u32, row: &[f64]);
+
+        /// Overwrites the column `j` of the matrix with `column`, or its first `column.len()`
+        /// elements if `column` is shorter. The constraints are the columns of `CE` and `CI`, so
+        /// this replaces a constraint in place.
+        ///
+        /// # Safety
+        ///
+        /// This is unsafe because the index range isn't checked.
+        unsafe fn matrix_set_column(mat: Pin<&mut MatrixF64>, j: u32, column: &[f64]);
+
         /// Creates a new `n x m` [`MatrixF64`] from a pointer to a row-major array and its shape.
         /// Note that it copies the data.
         ///
@@ -38,10 +386,154 @@ mod ffi {
         ///
         /// This is unsafe due to the use of a raw pointer.
         unsafe fn new_matrix_from_ptr(a: *const f64, n: u32, m: u32) -> UniquePtr<MatrixF64>;
+
+        /// Creates a new `n x m` [`MatrixF64`] from a pointer to a row-major array of f32 and its
+        /// shape, widening the elements as it copies them.
+        ///
+        /// # Safety
+        ///
+        /// This is unsafe due to the use of a raw pointer.
+        unsafe fn new_matrix_from_f32_ptr(a: *const f32, n: u32, m: u32) -> UniquePtr<MatrixF64>;
+
+        /// Overwrites `v` with `n` elements copied from the array pointed to by `a`. `v` is
+        /// reallocated only if its length differs from `n`.
+        ///
+        /// # Safety
+        ///
+        /// This is unsafe due to the use of a raw pointer.
+        unsafe fn vector_set_from_ptr(v: Pin<&mut VectorF64>, a: *const f64, n: u32);
+
+        /// Overwrites `mat` with an `n x m` matrix copied from a pointer to a row-major array.
+        /// `mat` is reallocated only if its shape differs from `n x m`.
+        ///
+        /// # Safety
+        ///
+        /// This is unsafe due to the use of a raw pointer.
+        unsafe fn matrix_set_from_ptr(mat: Pin<&mut MatrixF64>, a: *const f64, n: u32, m: u32);
+
+        /// Creates a new `n x m` [`MatrixF64`] from a pointer to its first element and the
+        /// strides between its rows and columns, in elements like the strides of an
+        /// `ndarray::ArrayView2`, so that an array of any layout can be copied without making it
+        /// contiguous first. Strides may be negative.
+        ///
+        /// # Safety
+        ///
+        /// `a.offset(i * row_stride + j * col_stride)` must be readable for all `i < n` and
+        /// `j < m`.
+        unsafe fn new_matrix_from_ptr_strided(
+            a: *const f64,
+            n: u32,
+            m: u32,
+            row_stride: isize,
+            col_stride: isize,
+        ) -> UniquePtr<MatrixF64>;
+
+        /// Overwrites `mat` with an `n x m` matrix copied from a pointer to its first element and
+        /// the strides between its rows and columns, like [`new_matrix_from_ptr_strided`]. `mat`
+        /// is reallocated only if its shape differs from `n x m`.
+        ///
+        /// # Safety
+        ///
+        /// Same as [`new_matrix_from_ptr_strided`].
+        unsafe fn matrix_set_from_ptr_strided(
+            mat: Pin<&mut MatrixF64>,
+            a: *const f64,
+            n: u32,
+            m: u32,
+            row_stride: isize,
+            col_stride: isize,
+        );
+
+        /// A vector type whose element type is f32.
+        type VectorF32;
+
+        /// Creates a new zero-filled [`VectorF32`] of length `n`.
+        fn new_vector_f32(n: u32) -> UniquePtr<VectorF32>;
+
+        /// Creates a new [`VectorF32`] from a pointer to the array and its length. Note that it
+        /// copies the data.
+        ///
+        /// # Safety
+        ///
+        /// This is unsafe due to the use of a raw pointer.
+        unsafe fn new_vector_f32_from_ptr(a: *const f32, n: u32) -> UniquePtr<VectorF32>;
+
+        /// Copies the elements of `v` into `out`, or as many as fit if `out` is shorter than `v`.
+        fn vector_f32_copy_to(v: &VectorF32, out: &mut [f32]);
+
+        /// A 2D matrix type whose element type is f32.
+        type MatrixF32;
+
+        /// Creates a new `n x m` [`MatrixF32`] from a pointer to a row-major array and its shape.
+        /// Note that it copies the data. The matrix has no columns either if `n` is zero.
+        ///
+        /// # Safety
+        ///
+        /// This is unsafe due to the use of a raw pointer.
+        unsafe fn new_matrix_f32_from_ptr(a: *const f32, n: u32, m: u32) -> UniquePtr<MatrixF32>;
     }
 
     unsafe extern "C++" {
-        /// Sovles a quadratic programming problem.
+        include!("quadprogpp-sys/include/solver.hpp");
+
+        /// Buffers used by the solver, which can be reused across solves of problems of the same
+        /// size.
+        type Workspace;
+
+        /// Creates a new [`Workspace`] for a problem with `n` variables, `p` equality constraints
+        /// and `m` inequality constraints.
+        fn new_workspace(n: u32, p: u32, m: u32) -> UniquePtr<Workspace>;
+
+        /// A read-only view of a row-major matrix owned by the caller, which the solver reads
+        /// the constraints from without copying them. [`MatrixRef`](crate::MatrixRef) ties it to
+        /// the lifetime of the data.
+        type MatrixView;
+
+        /// Creates an `n x m` [`MatrixView`] of the row-major array pointed to by `a`.
+        ///
+        /// # Safety
+        ///
+        /// `a` must point to `n * m` elements, which must outlive the view.
+        unsafe fn new_matrix_view(a: *const f64, n: u32, m: u32) -> UniquePtr<MatrixView>;
+
+        /// A read-only view of a vector owned by the caller. [`VectorRef`](crate::VectorRef) ties
+        /// it to the lifetime of the data.
+        type VectorView;
+
+        /// Creates a [`VectorView`] of the `n` elements pointed to by `a`.
+        ///
+        /// # Safety
+        ///
+        /// `a` must point to `n` elements, which must outlive the view.
+        unsafe fn new_vector_view(a: *const f64, n: u32) -> UniquePtr<VectorView>;
+
+        /// Overwrites `G` with its Cholesky factor and caches the quantities derived from it in
+        /// `ws`, so that [`solve_quadprog_ws`] can skip the factorization when
+        /// [`SolveParams::reuse_factorization`] is set. Returns the index of the nonpositive pivot
+        /// if `G` isn't positive definite, and -1 otherwise.
+        fn factorize_hessian(ws: Pin<&mut Workspace>, G: Pin<&mut MatrixF64>) -> Result<i32>;
+
+        /// Overwrites the symmetric matrix `A` with the lower triangular `L` of its Cholesky
+        /// factorization `A = L L^T`, zeroing the upper triangle. Returns the index of the
+        /// nonpositive pivot if `A` isn't positive definite, in which case `A` is left partially
+        /// factorized, and -1 otherwise. Fails if `A` isn't square.
+        fn cholesky_factorize(A: Pin<&mut MatrixF64>) -> Result<i32>;
+
+        /// Solves `L y = b` for `y`, reading the lower triangle of `L`. `y` is resized to the
+        /// length of `b`. Fails if `L` isn't square or doesn't match `b`.
+        fn forward_substitute(L: &MatrixF64, b: &VectorF64, y: Pin<&mut VectorF64>) -> Result<()>;
+
+        /// Solves `U x = y` for `x`, reading the upper triangle of `U`. `x` is resized to the
+        /// length of `y`. Fails if `U` isn't square or doesn't match `y`.
+        fn backward_substitute(U: &MatrixF64, y: &VectorF64, x: Pin<&mut VectorF64>) -> Result<()>;
+
+        /// Solves `L L^T x = b` for `x` with the factor computed by [`cholesky_factorize`],
+        /// reading the lower triangle of `L`. `x` is resized to the length of `b`. Fails if `L`
+        /// isn't square or doesn't match `b`.
+        fn cholesky_substitute(L: &MatrixF64, b: &VectorF64, x: Pin<&mut VectorF64>) -> Result<()>;
+
+        /// Sovles a quadratic programming problem with the upstream solver, which isn't traced
+        /// with the `trace-solver` feature.
         fn solve_quadprog(
             G: Pin<&mut MatrixF64>,
             g0: Pin<&mut VectorF64>,
@@ -51,6 +543,128 @@ mod ffi {
             ci0: &VectorF64,
             x: Pin<&mut VectorF64>,
         ) -> Result<f64>;
+
+        /// Solves a quadratic programming problem like [`solve_quadprog`], additionally reporting
+        /// the state of the solver at termination in `info`.
+        #[allow(clippy::too_many_arguments)]
+        fn solve_quadprog_ext(
+            G: Pin<&mut MatrixF64>,
+            g0: Pin<&mut VectorF64>,
+            CE: &MatrixF64,
+            ce0: &VectorF64,
+            CI: &MatrixF64,
+            ci0: &VectorF64,
+            x: Pin<&mut VectorF64>,
+            params: &SolveParams,
+            info: &mut SolveInfo,
+        ) -> Result<f64>;
+
+        /// Same as [`solve_quadprog_ext`], but uses the buffers in `ws` instead of allocating
+        /// them. `ws` is resized if the problem size differs from the one it was created for.
+        #[allow(clippy::too_many_arguments)]
+        fn solve_quadprog_ws(
+            ws: Pin<&mut Workspace>,
+            G: Pin<&mut MatrixF64>,
+            g0: Pin<&mut VectorF64>,
+            CE: &MatrixF64,
+            ce0: &VectorF64,
+            CI: &MatrixF64,
+            ci0: &VectorF64,
+            x: Pin<&mut VectorF64>,
+            params: &SolveParams,
+            info: &mut SolveInfo,
+        ) -> Result<f64>;
+
+        /// Same as [`solve_quadprog_ws`], but calls back `monitor` in each iteration of the
+        /// active-set loop.
+        #[allow(clippy::too_many_arguments)]
+        fn solve_quadprog_monitored(
+            ws: Pin<&mut Workspace>,
+            G: Pin<&mut MatrixF64>,
+            g0: Pin<&mut VectorF64>,
+            CE: &MatrixF64,
+            ce0: &VectorF64,
+            CI: &MatrixF64,
+            ci0: &VectorF64,
+            x: Pin<&mut VectorF64>,
+            params: &SolveParams,
+            info: &mut SolveInfo,
+            monitor: &mut Monitor<'_>,
+        ) -> Result<f64>;
+
+        /// Solves the problem given by row-major arrays like [`solve_quadprog_ext`], and writes
+        /// the minimizer into the `n` elements pointed to by `x`. The C++ objects are all created
+        /// on the C++ side, so this takes one call instead of one for each of them.
+        ///
+        /// # Safety
+        ///
+        /// `G` must point to `n * n` elements, `g0` and `x` to `n`, `CE` to `n * p`, `ce0` to `p`,
+        /// `CI` to `n * m` and `ci0` to `m`.
+        #[allow(clippy::too_many_arguments)]
+        unsafe fn solve_quadprog_raw(
+            n: u32,
+            p: u32,
+            m: u32,
+            G: *const f64,
+            g0: *const f64,
+            CE: *const f64,
+            ce0: *const f64,
+            CI: *const f64,
+            ci0: *const f64,
+            x: *mut f64,
+            params: &SolveParams,
+            info: &mut SolveInfo,
+        ) -> Result<f64>;
+
+        /// Solves a quadratic programming problem in f32 like [`solve_quadprog_ext`]. The solver
+        /// computes in f64, so the problem is widened on the C++ side and the minimizer narrowed
+        /// into `x`. Unlike the f64 functions, `G` isn't overwritten.
+        #[allow(clippy::too_many_arguments)]
+        fn solve_quadprog_f32(
+            G: &MatrixF32,
+            g0: &VectorF32,
+            CE: &MatrixF32,
+            ce0: &VectorF32,
+            CI: &MatrixF32,
+            ci0: &VectorF32,
+            x: Pin<&mut VectorF32>,
+            params: &SolveParams,
+            info: &mut SolveInfo,
+        ) -> Result<f32>;
+
+        /// Same as [`solve_quadprog_ws`], but reads the constraints through views instead of
+        /// copies. `G` and `g0` are still copies, since the solver overwrites `G` with its
+        /// Cholesky factor.
+        #[allow(clippy::too_many_arguments)]
+        fn solve_quadprog_view(
+            ws: Pin<&mut Workspace>,
+            G: Pin<&mut MatrixF64>,
+            g0: Pin<&mut VectorF64>,
+            CE: &MatrixView,
+            ce0: &VectorView,
+            CI: &MatrixView,
+            ci0: &VectorView,
+            x: Pin<&mut VectorF64>,
+            params: &SolveParams,
+            info: &mut SolveInfo,
+        ) -> Result<f64>;
+
+        /// Same as [`solve_quadprog_monitored`], but reads the constraints through views like
+        /// [`solve_quadprog_view`].
+        #[allow(clippy::too_many_arguments)]
+        fn solve_quadprog_monitored_view(
+            ws: Pin<&mut Workspace>,
+            G: Pin<&mut MatrixF64>,
+            g0: Pin<&mut VectorF64>,
+            CE: &MatrixView,
+            ce0: &VectorView,
+            CI: &MatrixView,
+            ci0: &VectorView,
+            x: Pin<&mut VectorF64>,
+            params: &SolveParams,
+            info: &mut SolveInfo,
+            monitor: &mut Monitor<'_>,
+        ) -> Result<f64>;
     }
 }
 
@@ -66,15 +680,30 @@ mod tests {
         let n = 2;
         let m = 1;
         let p = 3;
-        let mut G =
-            unsafe { new_matrix_from_ptr([4.0, -2.0, -2.0, 4.0].as_ptr() as *const f64, n, n) };
-        let mut g0 = unsafe { new_vector_from_ptr([6.0, 0.0].as_ptr() as *const f64, n) };
-        let CE = unsafe { new_matrix_from_ptr([1.0, 1.0].as_ptr() as *const f64, n, m) };
-        let ce0 = unsafe { new_vector_from_ptr([-3.0].as_ptr() as *const f64, m) };
-        let CI = unsafe {
-            new_matrix_from_ptr([1.0, 0.0, 1.0, 0.0, 1.0, 1.0].as_ptr() as *const f64, n, p)
-        };
-        let ci0 = unsafe { new_vector_from_ptr([0.0, 0.0, -2.0].as_ptr() as *const f64, p) };
+        let mut G = unsafe { new_matrix_from_ptr([4.0, -2.0, -2.0, 4.0].as_ptr(), n, n) };
+        let mut g0 = unsafe { new_vector_from_ptr([6.0, 0.0].as_ptr(), n) };
+        let CE = unsafe { new_matrix_from_ptr([1.0, 1.0].as_ptr(), n, m) };
+        let ce0 = unsafe { new_vector_from_ptr([-3.0].as_ptr(), m) };
+        let CI = unsafe { new_matrix_from_ptr([1.0, 0.0, 1.0, 0.0, 1.0, 1.0].as_ptr(), n, p) };
+        let ci0 = unsafe { new_vector_from_ptr([0.0, 0.0, -2.0].as_ptr(), p) };
+        let mut x = new_vector(n);
+        let r =
+            solve_quadprog(G.pin_mut(), g0.pin_mut(), &CE, &ce0, &CI, &ci0, x.pin_mut()).unwrap();
+        assert_ulps_eq!(r, 12.0);
+        assert_ulps_eq!(unsafe { vector_index(&x, 0) }, 1.0);
+        assert_ulps_eq!(unsafe { vector_index(&x, 1) }, 2.0);
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names, non_snake_case)]
+    fn test_f32() {
+        let n = 2;
+        let mut G = unsafe { new_matrix_from_f32_ptr([4.0f32, -2.0, -2.0, 4.0].as_ptr(), n, n) };
+        let mut g0 = unsafe { new_vector_from_f32_ptr([6.0f32, 0.0].as_ptr(), n) };
+        let CE = unsafe { new_matrix_from_f32_ptr([1.0f32, 1.0].as_ptr(), n, 1) };
+        let ce0 = unsafe { new_vector_from_f32_ptr([-3.0f32].as_ptr(), 1) };
+        let CI = unsafe { new_matrix_from_f32_ptr(std::ptr::null(), n, 0) };
+        let ci0 = new_vector(0);
         let mut x = new_vector(n);
         let r =
             solve_quadprog(G.pin_mut(), g0.pin_mut(), &CE, &ce0, &CI, &ci0, x.pin_mut()).unwrap();
@@ -82,4 +711,249 @@ mod tests {
         assert_ulps_eq!(unsafe { vector_index(&x, 0) }, 1.0);
         assert_ulps_eq!(unsafe { vector_index(&x, 1) }, 2.0);
     }
+
+    #[test]
+    #[allow(clippy::many_single_char_names, non_snake_case)]
+    fn test_view() {
+        let n = 2;
+        let mut G = unsafe { new_matrix_from_ptr([4.0, -2.0, -2.0, 4.0].as_ptr(), n, n) };
+        let mut g0 = unsafe { new_vector_from_ptr([6.0, 0.0].as_ptr(), n) };
+        let (ce, ci) = ([1.0, 1.0], [1.0, 0.0, 1.0, 0.0, 1.0, 1.0]);
+        let (ce0, ci0) = ([-3.0], [0.0, 0.0, -2.0]);
+        let mut ws = new_workspace(n, 1, 3);
+        let mut x = new_vector(n);
+        let mut info = SolveInfo::default();
+        let r = solve_quadprog_view(
+            ws.pin_mut(),
+            G.pin_mut(),
+            g0.pin_mut(),
+            &MatrixRef::new(&ce, n, 1),
+            &VectorRef::new(&ce0),
+            &MatrixRef::new(&ci, n, 3),
+            &VectorRef::new(&ci0),
+            x.pin_mut(),
+            &SolveParams::default(),
+            &mut info,
+        )
+        .unwrap();
+        assert_ulps_eq!(r, 12.0);
+        let mut raw = [0.0; 2];
+        let r = unsafe {
+            solve_quadprog_raw(
+                n,
+                1,
+                3,
+                [4.0, -2.0, -2.0, 4.0].as_ptr(),
+                [6.0, 0.0].as_ptr(),
+                ce.as_ptr(),
+                ce0.as_ptr(),
+                ci.as_ptr(),
+                ci0.as_ptr(),
+                raw.as_mut_ptr(),
+                &SolveParams::default(),
+                &mut info,
+            )
+        }
+        .unwrap();
+        assert_ulps_eq!(r, 12.0);
+        let mut out = [0.0; 3];
+        vector_copy_to(&x, &mut out);
+        assert_ulps_eq!(&out[..], &[1.0, 2.0, 0.0][..]);
+        assert_ulps_eq!(&raw[..], &out[..2]);
+        assert_eq!(info.status, SolveStatus::Optimal);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_solve_f32() {
+        let f32_matrix = |a: &[f32], n, m| unsafe { new_matrix_f32_from_ptr(a.as_ptr(), n, m) };
+        let f32_vector = |a: &[f32]| unsafe { new_vector_f32_from_ptr(a.as_ptr(), a.len() as u32) };
+        let G = f32_matrix(&[4.0, -2.0, -2.0, 4.0], 2, 2);
+        let CE = f32_matrix(&[1.0, 1.0], 2, 1);
+        let CI = f32_matrix(&[1.0, 0.0, 1.0, 0.0, 1.0, 1.0], 2, 3);
+        let mut x = new_vector_f32(2);
+        let mut info = SolveInfo::default();
+        let r = solve_quadprog_f32(
+            &G,
+            &f32_vector(&[6.0, 0.0]),
+            &CE,
+            &f32_vector(&[-3.0]),
+            &CI,
+            &f32_vector(&[0.0, 0.0, -2.0]),
+            x.pin_mut(),
+            &SolveParams::default(),
+            &mut info,
+        )
+        .unwrap();
+        assert_ulps_eq!(r, 12.0);
+        let mut out = [0.0; 2];
+        vector_f32_copy_to(&x, &mut out);
+        assert_ulps_eq!(&out[..], &[1.0, 2.0][..]);
+        assert_eq!(info.status, SolveStatus::Optimal);
+    }
+
+    #[test]
+    fn test_strided() {
+        let elements = |mat: &MatrixF64| {
+            let (n, m) = (matrix_nrows(mat), matrix_ncols(mat));
+            let rows = (0..n).map(|i| (0..m).map(move |j| unsafe { matrix_index(mat, i, j) }));
+            rows.flatten().collect::<Vec<_>>()
+        };
+        // Column-major
+        let a = [1.0, 4.0, 2.0, 5.0, 3.0, 6.0];
+        let mut mat = unsafe { new_matrix_from_ptr_strided(a.as_ptr(), 2, 3, 1, 2) };
+        assert_eq!(elements(&mat), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        // Reversed rows of a row-major 3 x 2 matrix
+        unsafe { matrix_set_from_ptr_strided(mat.pin_mut(), a.as_ptr().add(4), 3, 2, -2, 1) };
+        assert_eq!(elements(&mat), [3.0, 6.0, 2.0, 5.0, 1.0, 4.0]);
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names, non_snake_case)]
+    fn test_ext() {
+        let n = 2;
+        let m = 1;
+        let p = 3;
+        let mut G = unsafe { new_matrix_from_ptr([4.0, -2.0, -2.0, 4.0].as_ptr(), n, n) };
+        let mut g0 = unsafe { new_vector_from_ptr([6.0, 0.0].as_ptr(), n) };
+        let CE = unsafe { new_matrix_from_ptr([1.0, 1.0].as_ptr(), n, m) };
+        let ce0 = unsafe { new_vector_from_ptr([-3.0].as_ptr(), m) };
+        let CI = unsafe { new_matrix_from_ptr([1.0, 0.0, 1.0, 0.0, 1.0, 1.0].as_ptr(), n, p) };
+        let ci0 = unsafe { new_vector_from_ptr([0.0, 0.0, -2.0].as_ptr(), p) };
+        let mut x = new_vector(n);
+        let mut info = SolveInfo::default();
+        let r = solve_quadprog_ext(
+            G.pin_mut(),
+            g0.pin_mut(),
+            &CE,
+            &ce0,
+            &CI,
+            &ci0,
+            x.pin_mut(),
+            &SolveParams::default(),
+            &mut info,
+        )
+        .unwrap();
+        assert_ulps_eq!(r, 12.0);
+        assert_ulps_eq!(unsafe { vector_index(&x, 0) }, 1.0);
+        assert_ulps_eq!(unsafe { vector_index(&x, 1) }, 2.0);
+        assert_eq!(info.status, SolveStatus::Optimal);
+        assert_eq!(info.factorizations, 1);
+        assert_eq!(info.active_set, [-1]);
+        assert_eq!(info.multipliers.len(), 4);
+        assert_ulps_eq!(info.multipliers[0], 6.0);
+        for &u in &info.multipliers[1..] {
+            assert_ulps_eq!(u, 0.0);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::many_single_char_names, non_snake_case)]
+    fn test_monitored() {
+        let n = 2;
+        let p = 3;
+        let G = [4.0, -2.0, -2.0, 4.0];
+        let mut g0 = unsafe { new_vector_from_ptr([6.0, 0.0].as_ptr(), n) };
+        let CE = unsafe { new_matrix_from_ptr(std::ptr::null(), n, 0) };
+        let ce0 = new_vector(0);
+        let CI = unsafe { new_matrix_from_ptr([1.0, 0.0, 1.0, 0.0, 1.0, 1.0].as_ptr(), n, p) };
+        let ci0 = unsafe { new_vector_from_ptr([0.0, 0.0, -2.0].as_ptr(), p) };
+        let mut ws = new_workspace(n, 0, p);
+        let mut x = new_vector(n);
+        for limit in 0..3 {
+            let mut calls = 0;
+            let mut interrupted = |iterations| {
+                assert_eq!(iterations, calls);
+                calls += 1;
+                iterations >= limit
+            };
+            let mut progress = Vec::new();
+            let mut report = |p: &Progress| progress.push(p.clone());
+            let mut info = SolveInfo::default();
+            let mut G = unsafe { new_matrix_from_ptr(G.as_ptr(), n, n) };
+            solve_quadprog_monitored(
+                ws.pin_mut(),
+                G.pin_mut(),
+                g0.pin_mut(),
+                &CE,
+                &ce0,
+                &CI,
+                &ci0,
+                x.pin_mut(),
+                &SolveParams::default(),
+                &mut info,
+                &mut Monitor::new(&mut interrupted, &mut report),
+            )
+            .unwrap();
+            assert_eq!(info.iterations, limit.min(2));
+            assert_eq!(progress.len() as u32, limit.min(2));
+            for (k, p) in progress.iter().enumerate() {
+                assert_eq!(p.iteration, k as u32 + 1);
+                assert_eq!(p.active_constraints, k as u32);
+            }
+            if let Some(p) = progress.first() {
+                // at the unconstrained minimizer (-2, -1)
+                assert_ulps_eq!(p.infeasibility, 8.0);
+            }
+            if let Some(p) = progress.get(1) {
+                assert_eq!(p.infeasibility, 0.0);
+                assert_ulps_eq!(p.objective, 6.5);
+            }
+            if limit < 2 {
+                assert_eq!(info.status, SolveStatus::Interrupted);
+            } else {
+                assert_eq!(info.status, SolveStatus::Optimal);
+                assert_ulps_eq!(unsafe { vector_index(&x, 0) }, 0.5);
+                assert_ulps_eq!(unsafe { vector_index(&x, 1) }, 1.5);
+            }
+        }
+    }
+
+    #[cfg(feature = "trace-solver")]
+    #[test]
+    fn test_trace() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let lines = Rc::new(RefCell::new(Vec::new()));
+        let sink = lines.clone();
+        let previous = set_trace_handler(Some(Box::new(move |line: &str| {
+            sink.borrow_mut().push(line.to_owned())
+        })));
+        let (g, g0) = ([4.0, -2.0, -2.0, 4.0], [6.0, 0.0]);
+        let (ce, ce0) = ([1.0, 1.0], [-3.0]);
+        let (ci, ci0) = ([1.0, 0.0, 1.0, 0.0, 1.0, 1.0], [0.0, 0.0, -2.0]);
+        let mut x = [0.0; 2];
+        let mut info = SolveInfo::default();
+        let r = unsafe {
+            solve_quadprog_raw(
+                2,
+                1,
+                3,
+                g.as_ptr(),
+                g0.as_ptr(),
+                ce.as_ptr(),
+                ce0.as_ptr(),
+                ci.as_ptr(),
+                ci0.as_ptr(),
+                x.as_mut_ptr(),
+                &SolveParams::default(),
+                &mut info,
+            )
+        }
+        .unwrap();
+        assert_ulps_eq!(r, 12.0);
+        assert!(set_trace_handler(previous).is_some());
+
+        let lines = lines.borrow();
+        assert!(lines[0].starts_with("Unconstrained solution: "));
+        assert!(lines
+            .iter()
+            .any(|line| line == "Added equality constraint 0: 12"));
+        assert!(lines.iter().any(|line| line == "Iteration 1"));
+        // The trace goes nowhere without a handler.
+        let len = lines.len();
+        trace_message("dropped");
+        assert_eq!(lines.len(), len);
+    }
 }
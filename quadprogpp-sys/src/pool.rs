@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use cxx::{Exception, UniquePtr};
+
+use crate::ffi::*;
+
+/// The C++ objects passed to [`solve_quadprog_ws`] for a problem of one size, which a
+/// [`WorkspacePool`] recycles.
+///
+/// The objects are created zero-filled with the shapes of the size, and
+/// [`refill_from_ptr`](Self::refill_from_ptr) overwrites them in place for each problem.
+pub struct ProblemBuffers {
+    dim: (u32, u32, u32),
+    pub workspace: UniquePtr<Workspace>,
+    pub g: UniquePtr<MatrixF64>,
+    pub g0: UniquePtr<VectorF64>,
+    pub ce: UniquePtr<MatrixF64>,
+    pub ce0: UniquePtr<VectorF64>,
+    pub ci: UniquePtr<MatrixF64>,
+    pub ci0: UniquePtr<VectorF64>,
+    pub x: UniquePtr<VectorF64>,
+}
+
+impl ProblemBuffers {
+    /// Creates the objects for problems with `n` variables, `p` equality constraints and `m`
+    /// inequality constraints.
+    pub fn new(n: u32, p: u32, m: u32) -> Self {
+        Self {
+            dim: (n, p, m),
+            workspace: new_workspace(n, p, m),
+            g: new_matrix(n, n),
+            g0: new_vector(n),
+            ce: new_matrix(n, p),
+            ce0: new_vector(p),
+            ci: new_matrix(n, m),
+            ci0: new_vector(m),
+            x: new_vector(n),
+        }
+    }
+
+    /// Returns the size of the problems the objects are for, i.e. the numbers of variables,
+    /// equality constraints and inequality constraints.
+    pub fn dim(&self) -> (u32, u32, u32) {
+        self.dim
+    }
+
+    /// Overwrites the problem with copies of the row-major arrays, without reallocating the
+    /// objects.
+    ///
+    /// # Safety
+    ///
+    /// With the size `(n, p, m)`, `g` must point to `n * n` elements, `g0` to `n`, `ce` to
+    /// `n * p`, `ce0` to `p`, `ci` to `n * m` and `ci0` to `m`.
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn refill_from_ptr(
+        &mut self,
+        g: *const f64,
+        g0: *const f64,
+        ce: *const f64,
+        ce0: *const f64,
+        ci: *const f64,
+        ci0: *const f64,
+    ) {
+        let (n, p, m) = self.dim;
+        matrix_set_from_ptr(self.g.pin_mut(), g, n, n);
+        vector_set_from_ptr(self.g0.pin_mut(), g0, n);
+        matrix_set_from_ptr(self.ce.pin_mut(), ce, n, p);
+        vector_set_from_ptr(self.ce0.pin_mut(), ce0, p);
+        matrix_set_from_ptr(self.ci.pin_mut(), ci, n, m);
+        vector_set_from_ptr(self.ci0.pin_mut(), ci0, m);
+    }
+
+    /// Solves the problem with [`solve_quadprog_ws`], leaving the minimizer in `x`. This
+    /// overwrites `g` with its Cholesky factor, so the problem has to be refilled before the
+    /// next solve.
+    pub fn solve(&mut self, params: &SolveParams, info: &mut SolveInfo) -> Result<f64, Exception> {
+        solve_quadprog_ws(
+            self.workspace.pin_mut(),
+            self.g.pin_mut(),
+            self.g0.pin_mut(),
+            &self.ce,
+            &self.ce0,
+            &self.ci,
+            &self.ci0,
+            self.x.pin_mut(),
+            params,
+            info,
+        )
+    }
+}
+
+/// A pool of [`ProblemBuffers`] keyed by the size of the problems, so that the C++ objects are
+/// allocated once for each size instead of once for each solve.
+///
+/// [`take`](Self::take) returns the objects of a size, reusing ones given back by
+/// [`put`](Self::put) if there are any.
+#[derive(Default)]
+pub struct WorkspacePool {
+    free: HashMap<(u32, u32, u32), Vec<ProblemBuffers>>,
+}
+
+impl WorkspacePool {
+    /// Creates an empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes the objects for problems with `n` variables, `p` equality constraints and `m`
+    /// inequality constraints out of the pool, or creates them if there are none.
+    pub fn take(&mut self, n: u32, p: u32, m: u32) -> ProblemBuffers {
+        self.free
+            .get_mut(&(n, p, m))
+            .and_then(Vec::pop)
+            .unwrap_or_else(|| ProblemBuffers::new(n, p, m))
+    }
+
+    /// Gives the objects back to the pool for later solves of the same size.
+    pub fn put(&mut self, buffers: ProblemBuffers) {
+        self.free.entry(buffers.dim).or_default().push(buffers);
+    }
+
+    /// Returns the number of objects in the pool.
+    pub fn len(&self) -> usize {
+        self.free.values().map(Vec::len).sum()
+    }
+
+    /// Returns `true` if the pool has no objects.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_ulps_eq;
+
+    use super::*;
+
+    #[test]
+    fn recycles_buffers() {
+        let mut pool = WorkspacePool::new();
+        let (g, g0) = ([4.0, -2.0, -2.0, 4.0], [6.0, 0.0]);
+        let (ce, ce0) = ([1.0, 1.0], [-3.0]);
+        let (ci, ci0) = ([1.0, 0.0, 1.0, 0.0, 1.0, 1.0], [0.0, 0.0, -2.0]);
+        for _ in 0..3 {
+            let mut buffers = pool.take(2, 1, 3);
+            assert_eq!(buffers.dim(), (2, 1, 3));
+            unsafe {
+                buffers.refill_from_ptr(
+                    g.as_ptr(),
+                    g0.as_ptr(),
+                    ce.as_ptr(),
+                    ce0.as_ptr(),
+                    ci.as_ptr(),
+                    ci0.as_ptr(),
+                );
+            }
+            let mut info = SolveInfo::default();
+            let r = buffers.solve(&SolveParams::default(), &mut info).unwrap();
+            assert_ulps_eq!(r, 12.0);
+            let mut x = [0.0; 2];
+            vector_copy_to(&buffers.x, &mut x);
+            assert_ulps_eq!(&x[..], &[1.0, 2.0][..]);
+            pool.put(buffers);
+            assert_eq!(pool.len(), 1);
+        }
+
+        // Patch the problem in place: x0 + x1 >= 4 instead of x0 + x1 >= 2
+        let mut buffers = pool.take(2, 1, 3);
+        unsafe {
+            buffers.refill_from_ptr(
+                g.as_ptr(),
+                g0.as_ptr(),
+                ce.as_ptr(),
+                ce0.as_ptr(),
+                ci.as_ptr(),
+                ci0.as_ptr(),
+            );
+            vector_set(buffers.ci0.pin_mut(), 2, -4.0);
+            // -x0 - x1 + 4 = 0 instead of x0 + x1 - 3 = 0
+            matrix_set_column(buffers.ce.pin_mut(), 0, &[-1.0, -1.0]);
+            vector_set(buffers.ce0.pin_mut(), 0, 4.0);
+        }
+        let mut info = SolveInfo::default();
+        buffers.solve(&SolveParams::default(), &mut info).unwrap();
+        let mut x = [0.0; 2];
+        vector_copy_to(&buffers.x, &mut x);
+        assert_ulps_eq!(x[0] + x[1], 4.0, max_ulps = 4);
+        pool.put(buffers);
+        let other = pool.take(1, 0, 0);
+        assert_eq!(other.dim(), (1, 0, 0));
+        assert_eq!(pool.len(), 1);
+    }
+}
@@ -0,0 +1,1264 @@
+// An extended version of the Goldfarb-Idnani solver found in the upstream
+// `QuadProg++.cc`. The algorithm is kept step-by-step identical to upstream
+// so that both produce the same iterates; only the bookkeeping needed to
+// report the solver state back to Rust is added.
+
+#include "quadprogpp-sys/include/solver.hpp"
+#include "quadprogpp-sys/src/lib.rs.h"
+
+#include <algorithm>
+#include <cmath>
+#include <limits>
+#include <sstream>
+#include <stdexcept>
+#include <vector>
+
+namespace quadprogpp {
+namespace {
+const double EPS = std::numeric_limits<double>::epsilon();
+
+// With QUADPROGPP_TRACE, the active-set loop reports its steps like upstream
+// does with TRACE_SOLVER, but through `trace_message` rather than on stdout.
+#ifdef QUADPROGPP_TRACE
+#define TRACE(message)                                                         \
+    do {                                                                       \
+        std::ostringstream trace_os;                                           \
+        trace_os << message;                                                   \
+        trace_message(trace_os.str());                                         \
+    } while (false)
+#define TRACE_VECTOR(name, v, n) trace_vector(name, v, n)
+
+// Traces the first `n` elements of `v` in the format of upstream's
+// `print_vector`.
+template<typename V>
+void
+trace_vector(const char* name, const V& v, unsigned int n)
+{
+    std::ostringstream os;
+    os << name << ": ";
+    for (unsigned int i = 0; i < n; i++)
+        os << v[i] << (i + 1 < n ? ", " : "");
+    trace_message(os.str());
+}
+#else
+#define TRACE(message)                                                         \
+    do {                                                                       \
+    } while (false)
+#define TRACE_VECTOR(name, v, n)                                               \
+    do {                                                                       \
+    } while (false)
+#endif
+
+template<typename T, typename U>
+Real
+scalar_product(const Vector<T>& x, const Vector<U>& y)
+{
+    const unsigned int n = x.size();
+    Real sum = 0.0;
+    for (unsigned int i = 0; i < n; i++)
+        sum += x[i] * y[i];
+    return sum;
+}
+
+Real
+distance(Real a, Real b)
+{
+    const Real a1 = std::fabs(a), b1 = std::fabs(b);
+    if (a1 > b1) {
+        const Real t = b1 / a1;
+        return a1 * std::sqrt(1.0 + t * t);
+    } else if (b1 > a1) {
+        const Real t = a1 / b1;
+        return b1 * std::sqrt(1.0 + t * t);
+    }
+    return a1 * std::sqrt(Real(2.0));
+}
+
+// Returns the index of the nonpositive pivot if A isn't positive definite,
+// and -1 otherwise.
+template<typename T>
+int
+cholesky_decomposition(Matrix<T>& A)
+{
+    const int n = A.nrows();
+    for (int i = 0; i < n; i++) {
+        for (int j = i; j < n; j++) {
+            T sum = A[i][j];
+            for (int k = i - 1; k >= 0; k--)
+                sum -= A[i][k] * A[j][k];
+            if (i == j) {
+                // NaN pivots fail too.
+                if (!(sum > 0.0))
+                    return i;
+                A[i][i] = std::sqrt(sum);
+            } else {
+                A[j][i] = sum / A[i][i];
+            }
+        }
+        for (int k = i + 1; k < n; k++)
+            A[i][k] = A[k][i];
+    }
+    return -1;
+}
+
+template<typename T, typename Y, typename B>
+void
+solve_lower(const Matrix<T>& L, Vector<Y>& y, const Vector<B>& b)
+{
+    const int n = L.nrows();
+    y[0] = b[0] / L[0][0];
+    for (int i = 1; i < n; i++) {
+        y[i] = b[i];
+        for (int j = 0; j < i; j++)
+            y[i] -= L[i][j] * y[j];
+        y[i] = y[i] / L[i][i];
+    }
+}
+
+template<typename T, typename X, typename Y>
+void
+solve_upper(const Matrix<T>& U, Vector<X>& x, const Vector<Y>& y)
+{
+    const int n = U.nrows();
+    x[n - 1] = y[n - 1] / U[n - 1][n - 1];
+    for (int i = n - 2; i >= 0; i--) {
+        x[i] = y[i];
+        for (int j = i + 1; j < n; j++)
+            x[i] -= U[i][j] * x[j];
+        x[i] = x[i] / U[i][i];
+    }
+}
+
+// Solves L L^T x = b, with the intermediate result in the precision of L.
+template<typename T>
+void
+solve_cholesky(const Matrix<T>& L, Vector<double>& x, const Vector<double>& b)
+{
+    Vector<T> y(L.nrows());
+    solve_lower(L, y, b);
+    solve_upper(L, x, y);
+}
+
+// d = J^T * np
+void
+compute_d(Vector<Real>& d, const Matrix<Real>& J, const Vector<Real>& np)
+{
+    const unsigned int n = d.size();
+    for (unsigned int i = 0; i < n; i++) {
+        Real sum = 0.0;
+        for (unsigned int j = 0; j < n; j++)
+            sum += J[j][i] * np[j];
+        d[i] = sum;
+    }
+}
+
+// z = J_2 * d_2
+void
+update_z(Vector<Real>& z,
+         const Matrix<Real>& J,
+         const Vector<Real>& d,
+         unsigned int iq)
+{
+    const unsigned int n = z.size();
+    for (unsigned int i = 0; i < n; i++) {
+        z[i] = 0.0;
+        for (unsigned int j = iq; j < n; j++)
+            z[i] += J[i][j] * d[j];
+    }
+}
+
+// r = R^-1 * d_1
+void
+update_r(const Matrix<Real>& R,
+         Vector<Real>& r,
+         const Vector<Real>& d,
+         unsigned int iq)
+{
+    for (int i = static_cast<int>(iq) - 1; i >= 0; i--) {
+        Real sum = 0.0;
+        for (unsigned int j = i + 1; j < iq; j++)
+            sum += R[i][j] * r[j];
+        r[i] = (d[i] - sum) / R[i][i];
+    }
+}
+
+bool
+add_constraint(Matrix<Real>& R,
+               Matrix<Real>& J,
+               Vector<Real>& d,
+               unsigned int& iq,
+               Real& R_norm,
+               double eps,
+               unsigned int& rotations)
+{
+    const unsigned int n = d.size();
+    // Reduce d[j] to zero with Givens rotations, applying them to J as well.
+    for (unsigned int j = n - 1; j >= iq + 1; j--) {
+        Real cc = d[j - 1];
+        Real ss = d[j];
+        const Real h = distance(cc, ss);
+        if (std::fabs(h) < EPS)
+            continue;
+        rotations++;
+        d[j] = 0.0;
+        ss = ss / h;
+        cc = cc / h;
+        if (cc < 0.0) {
+            cc = -cc;
+            ss = -ss;
+            d[j - 1] = -h;
+        } else {
+            d[j - 1] = h;
+        }
+        const Real xny = ss / (1.0 + cc);
+        for (unsigned int k = 0; k < n; k++) {
+            const Real t1 = J[k][j - 1];
+            const Real t2 = J[k][j];
+            J[k][j - 1] = t1 * cc + t2 * ss;
+            J[k][j] = xny * (t1 + J[k][j - 1]) - t2;
+        }
+    }
+    iq++;
+    for (unsigned int i = 0; i < iq; i++)
+        R[i][iq - 1] = d[i];
+    if (std::fabs(d[iq - 1]) <= eps * R_norm) {
+        // problem degenerate
+        return false;
+    }
+    R_norm = std::max<Real>(R_norm, std::fabs(d[iq - 1]));
+    return true;
+}
+
+void
+delete_constraint(Matrix<Real>& R,
+                  Matrix<Real>& J,
+                  Vector<int>& A,
+                  Vector<Real>& u,
+                  unsigned int n,
+                  unsigned int p,
+                  unsigned int& iq,
+                  int l,
+                  unsigned int& rotations)
+{
+    unsigned int qq = 0;
+    bool found = false;
+    for (unsigned int i = p; i < iq; i++) {
+        if (A[i] == l) {
+            qq = i;
+            found = true;
+            break;
+        }
+    }
+    if (!found) {
+        std::ostringstream os;
+        os << "Attempt to delete non existing constraint, constraint: " << l;
+        throw std::invalid_argument(os.str());
+    }
+    // Remove the constraint from the active set and the duals.
+    for (unsigned int i = qq; i < iq - 1; i++) {
+        A[i] = A[i + 1];
+        u[i] = u[i + 1];
+        for (unsigned int j = 0; j < n; j++)
+            R[j][i] = R[j][i + 1];
+    }
+    A[iq - 1] = A[iq];
+    u[iq - 1] = u[iq];
+    A[iq] = 0;
+    u[iq] = 0.0;
+    for (unsigned int j = 0; j < iq; j++)
+        R[j][iq - 1] = 0.0;
+    iq--;
+    if (iq == 0)
+        return;
+    // Restore the upper triangular form of R with Givens rotations.
+    for (unsigned int j = qq; j < iq; j++) {
+        Real cc = R[j][j];
+        Real ss = R[j + 1][j];
+        const Real h = distance(cc, ss);
+        if (std::fabs(h) < EPS)
+            continue;
+        rotations++;
+        cc = cc / h;
+        ss = ss / h;
+        R[j + 1][j] = 0.0;
+        if (cc < 0.0) {
+            R[j][j] = -h;
+            cc = -cc;
+            ss = -ss;
+        } else {
+            R[j][j] = h;
+        }
+        const Real xny = ss / (1.0 + cc);
+        for (unsigned int k = j + 1; k < iq; k++) {
+            const Real t1 = R[j][k];
+            const Real t2 = R[j + 1][k];
+            R[j][k] = t1 * cc + t2 * ss;
+            R[j + 1][k] = xny * (t1 + R[j][k]) - t2;
+        }
+        for (unsigned int k = 0; k < n; k++) {
+            const Real t1 = J[k][j];
+            const Real t2 = J[k][j + 1];
+            J[k][j] = t1 * cc + t2 * ss;
+            J[k][j + 1] = xny * (J[k][j] + t1) - t2;
+        }
+    }
+}
+
+void
+check_dimensions(const Matrix<double>& G,
+                 const Vector<double>& g0,
+                 const MatrixView& CE,
+                 const VectorView& ce0,
+                 const MatrixView& CI,
+                 const VectorView& ci0)
+{
+    std::ostringstream msg;
+    const unsigned int n = G.ncols(), p = CE.ncols(), m = CI.ncols();
+    if (G.nrows() != n) {
+        msg << "The matrix G is not a squared matrix (" << G.nrows() << " x "
+            << G.ncols() << ")";
+        throw std::logic_error(msg.str());
+    }
+    if (g0.size() != n) {
+        msg << "The vector g0 is incompatible (incorrect dimension "
+            << g0.size() << ", expecting " << n << ")";
+        throw std::logic_error(msg.str());
+    }
+    if (CE.nrows() != n) {
+        msg << "The matrix CE is incompatible (incorrect number of rows "
+            << CE.nrows() << " , expecting " << n << ")";
+        throw std::logic_error(msg.str());
+    }
+    if (ce0.size() != p) {
+        msg << "The vector ce0 is incompatible (incorrect dimension "
+            << ce0.size() << ", expecting " << p << ")";
+        throw std::logic_error(msg.str());
+    }
+    if (CI.nrows() != n) {
+        msg << "The matrix CI is incompatible (incorrect number of rows "
+            << CI.nrows() << " , expecting " << n << ")";
+        throw std::logic_error(msg.str());
+    }
+    if (ci0.size() != m) {
+        msg << "The vector ci0 is incompatible (incorrect dimension "
+            << ci0.size() << ", expecting " << m << ")";
+        throw std::logic_error(msg.str());
+    }
+    // Upstream writes past the end of R in this case.
+    if (p > n)
+        throw std::runtime_error("Constraints are linearly dependent");
+}
+
+// Adds `regularization` to the diagonal of G and factorizes it. Returns the
+// index of the nonpositive pivot if G isn't positive definite, and -1
+// otherwise.
+int
+factorize(Workspace& ws, Matrix<double>& G, double regularization)
+{
+    const unsigned int n = G.ncols();
+    if (G.nrows() != n) {
+        std::ostringstream msg;
+        msg << "The matrix G is not a squared matrix (" << G.nrows() << " x "
+            << G.ncols() << ")";
+        throw std::logic_error(msg.str());
+    }
+    ws.factorized = false;
+    for (unsigned int i = 0; i < n; i++)
+        G[i][i] += regularization;
+    // c1 * c2 is an estimate for cond(G)
+    Real c1 = 0.0;
+    for (unsigned int i = 0; i < n; i++)
+        c1 += G[i][i];
+    // The factor is computed in the precision of the solver, and G is
+    // overwritten with it rounded.
+    Matrix<Real>& L = ws.L;
+    L.resize(n, n);
+    for (unsigned int i = 0; i < n; i++)
+        for (unsigned int j = 0; j < n; j++)
+            L[i][j] = G[i][j];
+    const int pivot = cholesky_decomposition(L);
+    for (unsigned int i = 0; i < n; i++)
+        for (unsigned int j = 0; j < n; j++)
+            G[i][j] = static_cast<double>(L[i][j]);
+    if (pivot >= 0)
+        return pivot;
+    // J = L^-T, the initial value for H
+    Vector<Real> y(0.0, n), z(n);
+    ws.J0.resize(n, n);
+    Real c2 = 0.0;
+    for (unsigned int i = 0; i < n; i++) {
+        y[i] = 1.0;
+        solve_lower(L, z, y);
+        for (unsigned int j = 0; j < n; j++)
+            ws.J0[i][j] = z[j];
+        c2 += z[i];
+        y[i] = 0.0;
+    }
+    ws.c1 = c1;
+    ws.c2 = c2;
+    ws.regularization = regularization;
+    ws.factorized = true;
+    return -1;
+}
+
+bool
+is_zero(const Matrix<double>& G)
+{
+    for (unsigned int i = 0; i < G.nrows(); i++)
+        for (unsigned int j = 0; j < G.ncols(); j++)
+            if (G[i][j] != 0.0)
+                return false;
+    return true;
+}
+
+double
+solve_monitored(Workspace& ws,
+                Matrix<double>& G,
+                Vector<double>& g0,
+                const MatrixView& CE,
+                const VectorView& ce0,
+                const MatrixView& CI,
+                const VectorView& ci0,
+                Vector<double>& x,
+                const SolveParams& params,
+                SolveInfo& info,
+                Monitor* monitor);
+
+// Solves the linear program min g0^T x subject to the constraints by the
+// proximal point method, i.e. by solving a sequence of problems with G = rho I
+// and g0 - rho x_k until x_k stops moving. The method terminates in a finite
+// number of steps when the linear program has a solution.
+double
+solve_linear(Workspace& ws,
+             const Vector<double>& g0,
+             const MatrixView& CE,
+             const VectorView& ce0,
+             const MatrixView& CI,
+             const VectorView& ci0,
+             Vector<double>& x,
+             const SolveParams& params,
+             SolveInfo& info,
+             Monitor* monitor)
+{
+    const unsigned int n = g0.size();
+    const unsigned int max_steps = 100;
+    double g0_norm = 1.0;
+    for (unsigned int i = 0; i < n; i++)
+        g0_norm = std::max(g0_norm, std::fabs(g0[i]));
+    // A small rho takes long steps towards the solution, but loses accuracy
+    // as the unconstrained minimizers are far from it. Once the iterates
+    // converge, steps with a large rho polish the solution. The multipliers
+    // are off by rho (x - x_k), so the polishing continues until a step
+    // starting from a polished point.
+    double rho = std::sqrt(EPS) * g0_norm;
+    unsigned int polished = 0;
+    Matrix<double> P(0.0, n, n);
+    for (unsigned int i = 0; i < n; i++)
+        P[i][i] = rho;
+    Vector<double> q(n), x_k(0.0, n);
+    SolveParams inner = params;
+    inner.lp_fallback = false;
+    inner.reuse_factorization = false;
+    inner.regularization = 0.0;
+    unsigned int iterations = 0, factorizations = 0;
+    unsigned int additions = 0, deletions = 0, rotations = 0;
+    for (unsigned int step = 0; step < max_steps; step++) {
+        for (unsigned int i = 0; i < n; i++)
+            q[i] = g0[i] - rho * x_k[i];
+        if (iterations >= params.max_iterations) {
+            info.status = SolveStatus::MaxIterations;
+            break;
+        }
+        inner.max_iterations = params.max_iterations - iterations;
+        solve_monitored(ws, P, q, CE, ce0, CI, ci0, x, inner, info, monitor);
+        iterations += info.iterations;
+        factorizations += info.factorizations;
+        additions += info.additions;
+        deletions += info.deletions;
+        rotations += info.rotations;
+        info.iterations = iterations;
+        info.factorizations = factorizations;
+        info.additions = additions;
+        info.deletions = deletions;
+        info.rotations = rotations;
+        if (info.status != SolveStatus::Optimal)
+            break;
+        // P now holds its factor, which the next steps reuse.
+        inner.reuse_factorization = true;
+        inner.initial_point.clear();
+        // warm-start the next step with the current active set
+        inner.active_set.clear();
+        for (const int a : info.active_set)
+            if (a >= 0)
+                inner.active_set.push_back(a);
+        double dx = 0.0, x_norm = 1.0;
+        for (unsigned int i = 0; i < n; i++) {
+            dx = std::max(dx, std::fabs(x[i] - x_k[i]));
+            x_norm = std::max(x_norm, std::fabs(x[i]));
+            x_k[i] = x[i];
+        }
+        if (dx <= std::sqrt(EPS) * x_norm) {
+            if (polished >= 2) {
+                ws.factorized = false;
+                return scalar_product(g0, x);
+            }
+            if (polished++ == 0) {
+                rho = g0_norm;
+                P = 0.0;
+                for (unsigned int i = 0; i < n; i++)
+                    P[i][i] = rho;
+                inner.reuse_factorization = false;
+            }
+        }
+        if (step + 1 == max_steps)
+            info.status = SolveStatus::MaxIterations;
+    }
+    // The factorization cached in `ws` is that of P, not G.
+    ws.factorized = false;
+    return std::numeric_limits<double>::infinity();
+}
+} // namespace
+
+Workspace::Workspace(unsigned int n, unsigned int p, unsigned int m)
+  : R(n, n)
+  , J(n, n)
+  , s(m + p)
+  , z(n)
+  , r(m + p)
+  , d(n)
+  , np(n)
+  , u(m + p)
+  , u_old(m + p)
+  , x_old(n)
+  , A(m + p)
+  , A_old(m + p)
+  , iai(m + p)
+  , iaexcl(m + p)
+{}
+
+void
+Workspace::resize(unsigned int n, unsigned int p, unsigned int m)
+{
+    R.resize(n, n);
+    J.resize(n, n);
+    s.resize(m + p);
+    z.resize(n);
+    r.resize(m + p);
+    d.resize(n);
+    np.resize(n);
+    u.resize(m + p);
+    x_old.resize(n);
+    u_old.resize(m + p);
+    A.resize(m + p);
+    A_old.resize(m + p);
+    iai.resize(m + p);
+    iaexcl.resize(m + p);
+}
+
+std::unique_ptr<Workspace>
+new_workspace(unsigned int n, unsigned int p, unsigned int m)
+{
+    return std::unique_ptr<Workspace>(new Workspace(n, p, m));
+}
+
+int
+factorize_hessian(Workspace& ws, Matrix<double>& G)
+{
+    return factorize(ws, G, 0.0);
+}
+
+namespace {
+void
+check_triangular(const Matrix<double>& T, const Vector<double>& b)
+{
+    if (T.nrows() != T.ncols())
+        throw std::logic_error("The triangular matrix is not a squared matrix");
+    if (b.size() != T.nrows())
+        throw std::logic_error("The right-hand side is incompatible with the matrix");
+}
+} // namespace
+
+int
+cholesky_factorize(Matrix<double>& A)
+{
+    if (A.nrows() != A.ncols())
+        throw std::logic_error("The matrix to factorize is not a squared matrix");
+    const int pivot = cholesky_decomposition(A);
+    if (pivot < 0) {
+        // cholesky_decomposition leaves L^T in the upper triangle.
+        const unsigned int n = A.nrows();
+        for (unsigned int i = 0; i < n; i++)
+            for (unsigned int j = i + 1; j < n; j++)
+                A[i][j] = 0.0;
+    }
+    return pivot;
+}
+
+void
+forward_substitute(const Matrix<double>& L,
+                   const Vector<double>& b,
+                   Vector<double>& y)
+{
+    check_triangular(L, b);
+    y.resize(b.size());
+    if (b.size() > 0)
+        solve_lower(L, y, b);
+}
+
+void
+backward_substitute(const Matrix<double>& U,
+                    const Vector<double>& y,
+                    Vector<double>& x)
+{
+    check_triangular(U, y);
+    x.resize(y.size());
+    if (y.size() > 0)
+        solve_upper(U, x, y);
+}
+
+void
+cholesky_substitute(const Matrix<double>& L,
+                    const Vector<double>& b,
+                    Vector<double>& x)
+{
+    check_triangular(L, b);
+    const int n = b.size();
+    Vector<double> y(n);
+    x.resize(n);
+    if (n == 0)
+        return;
+    solve_lower(L, y, b);
+    // Backward elimination on L^T, read from the lower triangle
+    for (int i = n - 1; i >= 0; i--) {
+        x[i] = y[i];
+        for (int j = i + 1; j < n; j++)
+            x[i] -= L[j][i] * x[j];
+        x[i] = x[i] / L[i][i];
+    }
+}
+
+MatrixView::MatrixView(const Matrix<double>& mat)
+  : a(mat.nrows() > 0 ? mat[0] : nullptr)
+  , n(mat.nrows())
+  , m(mat.ncols())
+{}
+
+VectorView::VectorView(const Vector<double>& v)
+  : a(v.size() > 0 ? &v[0] : nullptr)
+  , n(v.size())
+{}
+
+std::unique_ptr<MatrixView>
+new_matrix_view(const double* a, unsigned int n, unsigned int m)
+{
+    return std::unique_ptr<MatrixView>(new MatrixView(a, n, m));
+}
+
+std::unique_ptr<VectorView>
+new_vector_view(const double* a, unsigned int n)
+{
+    return std::unique_ptr<VectorView>(new VectorView(a, n));
+}
+
+double
+solve_quadprog_ext(Matrix<double>& G,
+                   Vector<double>& g0,
+                   const Matrix<double>& CE,
+                   const Vector<double>& ce0,
+                   const Matrix<double>& CI,
+                   const Vector<double>& ci0,
+                   Vector<double>& x,
+                   const SolveParams& params,
+                   SolveInfo& info)
+{
+    Workspace ws(G.ncols(), CE.ncols(), CI.ncols());
+    return solve_quadprog_ws(ws, G, g0, CE, ce0, CI, ci0, x, params, info);
+}
+
+namespace {
+// The matrices are filled in place, since copying a Matrix without rows
+// writes out of bounds.
+void
+widen(const Matrix<float>& a, Matrix<double>& b)
+{
+    if (a.nrows() == 0)
+        return;
+    b.resize(a.nrows(), a.ncols());
+    for (unsigned int i = 0; i < a.nrows(); i++)
+        for (unsigned int j = 0; j < a.ncols(); j++)
+            b[i][j] = a[i][j];
+}
+
+void
+widen(const Vector<float>& a, Vector<double>& b)
+{
+    b.resize(a.size());
+    for (unsigned int i = 0; i < a.size(); i++)
+        b[i] = a[i];
+}
+} // namespace
+
+float
+solve_quadprog_f32(const Matrix<float>& G,
+                   const Vector<float>& g0,
+                   const Matrix<float>& CE,
+                   const Vector<float>& ce0,
+                   const Matrix<float>& CI,
+                   const Vector<float>& ci0,
+                   Vector<float>& x,
+                   const SolveParams& params,
+                   SolveInfo& info)
+{
+    Matrix<double> G64, CE64, CI64;
+    Vector<double> g064, ce064, ci064, x64(G.ncols());
+    widen(G, G64);
+    widen(g0, g064);
+    widen(CE, CE64);
+    widen(ce0, ce064);
+    widen(CI, CI64);
+    widen(ci0, ci064);
+    const double f = solve_quadprog_ext(
+      G64, g064, CE64, ce064, CI64, ci064, x64, params, info);
+    x.resize(x64.size());
+    for (unsigned int i = 0; i < x64.size(); i++)
+        x[i] = static_cast<float>(x64[i]);
+    return static_cast<float>(f);
+}
+
+namespace {
+// The active-set loop of `solve_quadprog_ws` on G + regularization I.
+double
+solve_active_set(Workspace& ws,
+                 Matrix<double>& G,
+                 const Vector<double>& g0,
+                 const MatrixView& CE,
+                 const VectorView& ce0,
+                 const MatrixView& CI,
+                 const VectorView& ci0,
+                 Vector<double>& x,
+                 const SolveParams& params,
+                 SolveInfo& info,
+                 Monitor* monitor)
+{
+    check_dimensions(G, g0, CE, ce0, CI, ci0);
+    const unsigned int n = G.ncols(), p = CE.ncols(), m = CI.ncols();
+    const double inf = std::numeric_limits<double>::infinity();
+    x.resize(n);
+    info.status = SolveStatus::Optimal;
+    info.warm_started = false;
+    info.iterations = 0;
+    info.factorizations = 0;
+    info.additions = 0;
+    info.deletions = 0;
+    info.rotations = 0;
+    info.active_set.clear();
+    info.multipliers.clear();
+    info.infeasible_constraint = -1;
+    info.singular_pivot = -1;
+
+    ws.resize(n, p, m);
+    Matrix<Real>&R = ws.R, &J = ws.J;
+    Vector<Real>&s = ws.s, &z = ws.z, &r = ws.r, &d = ws.d, &np = ws.np;
+    Vector<Real>&u = ws.u, &u_old = ws.u_old;
+    Vector<double>& x_old = ws.x_old;
+    Vector<int>&A = ws.A, &A_old = ws.A_old, &iai = ws.iai;
+    Vector<bool>& iaexcl = ws.iaexcl;
+    unsigned int iq = 0;
+
+    // Writes the active set and its multipliers into `info`.
+    const auto finish = [&](Real f_value) {
+        info.multipliers.reserve(p + m);
+        for (unsigned int i = 0; i < p + m; i++)
+            info.multipliers.push_back(0.0);
+        info.active_set.reserve(iq);
+        for (unsigned int i = 0; i < iq; i++) {
+            info.active_set.push_back(A[i]);
+            if (A[i] < 0)
+                info.multipliers[-A[i] - 1] = u[i];
+            else
+                info.multipliers[p + A[i]] = u[i];
+        }
+        return f_value;
+    };
+
+    if (params.reuse_factorization) {
+        if (!ws.factorized || ws.J0.nrows() != n)
+            throw std::logic_error("No factorization of G to reuse");
+    } else {
+        const int pivot = factorize(ws, G, params.regularization);
+        if (pivot >= 0) {
+            info.status = SolveStatus::NotPositiveDefinite;
+            info.singular_pivot = pivot;
+            return inf;
+        }
+        info.factorizations++;
+    }
+    // c1 * c2 is an estimate for cond(G)
+    const Real c1 = ws.c1, c2 = ws.c2;
+    for (unsigned int i = 0; i < n; i++) {
+        d[i] = 0.0;
+        for (unsigned int j = 0; j < n; j++) {
+            R[i][j] = 0.0;
+            J[i][j] = ws.J0[i][j];
+        }
+    }
+    Real R_norm = 1.0;
+
+    // The unconstrained minimizer x = -G^-1 g0 is feasible in the dual space.
+    solve_cholesky(ws.L, x, g0);
+    for (unsigned int i = 0; i < n; i++)
+        x[i] = -x[i];
+    Real f_value = 0.5 * scalar_product(g0, x);
+    TRACE("Unconstrained solution: " << f_value);
+    TRACE_VECTOR("x", x, n);
+
+    // Add the equality constraints to the working set A.
+    for (unsigned int i = 0; i < p; i++) {
+        for (unsigned int j = 0; j < n; j++)
+            np[j] = CE[j][i];
+        compute_d(d, J, np);
+        update_z(z, J, d, iq);
+        update_r(R, r, d, iq);
+        // full step length such that the constraint becomes feasible
+        Real t2 = 0.0;
+        if (std::fabs(scalar_product(z, z)) > params.epsilon)
+            t2 = (-scalar_product(np, x) - ce0[i]) / scalar_product(z, np);
+        for (unsigned int k = 0; k < n; k++)
+            x[k] += t2 * z[k];
+        u[iq] = t2;
+        for (unsigned int k = 0; k < iq; k++)
+            u[k] -= t2 * r[k];
+        f_value += 0.5 * (t2 * t2) * scalar_product(z, np);
+        A[i] = -static_cast<int>(i) - 1;
+        if (!add_constraint(
+              R, J, d, iq, R_norm, params.epsilon, info.rotations))
+            throw std::runtime_error("Constraints are linearly dependent");
+        info.additions++;
+        TRACE("Added equality constraint " << i << ": " << f_value);
+    }
+
+    for (unsigned int i = 0; i < m; i++)
+        iai[i] = i;
+
+    // Warm start: add the suggested inequality constraints in the same way as
+    // the equality constraints. The result is kept only if it is feasible in
+    // the dual space, i.e. all the inequality multipliers are nonnegative.
+    std::vector<int> active_set(params.active_set.begin(),
+                                params.active_set.end());
+    if (params.initial_point.size() == n) {
+        // guess the active set from the constraints tight at the point
+        for (unsigned int i = 0; i < m; i++) {
+            Real sum = ci0[i];
+            for (unsigned int j = 0; j < n; j++)
+                sum += CI[j][i] * params.initial_point[j];
+            const double tol = std::sqrt(EPS) * (1.0 + std::fabs(ci0[i]));
+            if (sum <= params.feasibility_tolerance + tol)
+                active_set.push_back(i);
+        }
+    }
+    if (!active_set.empty()) {
+        const Matrix<Real> R_cold = R, J_cold = J;
+        const Vector<double> x_cold = x;
+        const Vector<Real> u_cold = u;
+        const Vector<int> A_cold = A;
+        const unsigned int iq_cold = iq;
+        const Real f_cold = f_value, R_norm_cold = R_norm;
+        Vector<bool> added(false, m);
+        bool dual_feasible = true;
+        for (const int ip : active_set) {
+            if (iq >= n)
+                break;
+            if (ip < 0 || static_cast<unsigned int>(ip) >= m || added[ip])
+                continue;
+            for (unsigned int j = 0; j < n; j++)
+                np[j] = CI[j][ip];
+            compute_d(d, J, np);
+            update_z(z, J, d, iq);
+            // skip constraints linearly dependent on the active ones
+            if (std::fabs(scalar_product(z, z)) <= params.epsilon)
+                continue;
+            update_r(R, r, d, iq);
+            const Real t2 =
+              (-scalar_product(np, x) - ci0[ip]) / scalar_product(z, np);
+            for (unsigned int k = 0; k < n; k++)
+                x[k] += t2 * z[k];
+            u[iq] = t2;
+            for (unsigned int k = 0; k < iq; k++)
+                u[k] -= t2 * r[k];
+            f_value += 0.5 * (t2 * t2) * scalar_product(z, np);
+            A[iq] = ip;
+            if (!add_constraint(
+                  R, J, d, iq, R_norm, params.epsilon, info.rotations)) {
+                dual_feasible = false;
+                break;
+            }
+            info.additions++;
+            added[ip] = true;
+        }
+        for (unsigned int k = p; k < iq && dual_feasible; k++)
+            dual_feasible = u[k] >= 0.0;
+        if (!dual_feasible) {
+            R = R_cold;
+            J = J_cold;
+            x = x_cold;
+            u = u_cold;
+            A = A_cold;
+            iq = iq_cold;
+            f_value = f_cold;
+            R_norm = R_norm_cold;
+        }
+        info.warm_started = dual_feasible;
+        TRACE("Warm start " << (dual_feasible ? "accepted" : "rejected")
+                            << ": " << f_value);
+    }
+
+    // The number of consecutive steps of zero length, and whether they call
+    // for the anti-cycling rule
+    unsigned int degenerate_steps = 0;
+    bool bland = false;
+    for (;;) {
+        if (info.iterations >= params.max_iterations) {
+            info.status = SolveStatus::MaxIterations;
+            return finish(f_value);
+        }
+        if (monitor != nullptr && monitor->interrupted(info.iterations)) {
+            info.status = SolveStatus::Interrupted;
+            return finish(f_value);
+        }
+        info.iterations++;
+        TRACE("Iteration " << info.iterations);
+        TRACE_VECTOR("x", x, n);
+        // Step 1: choose a violated constraint
+        for (unsigned int i = p; i < iq; i++)
+            iai[A[i]] = -1;
+        Real psi = 0.0; // the sum of all infeasibilities
+        for (unsigned int i = 0; i < m; i++) {
+            iaexcl[i] = true;
+            Real sum = 0.0;
+            for (unsigned int j = 0; j < n; j++)
+                sum += CI[j][i] * x[j];
+            sum += ci0[i];
+            s[i] = sum;
+            psi += std::min<Real>(0.0, sum + params.feasibility_tolerance);
+        }
+        TRACE_VECTOR("s", s, m);
+        if (monitor != nullptr)
+            monitor->report(Progress{ info.iterations,
+                                      static_cast<double>(f_value),
+                                      static_cast<double>(-psi),
+                                      iq - p });
+        if (std::fabs(psi) <= m * params.epsilon * c1 * c2 * 100.0) {
+            // numerically there are not infeasibilities anymore
+            return finish(f_value);
+        }
+        for (unsigned int i = 0; i < iq; i++) {
+            u_old[i] = u[i];
+            A_old[i] = A[i];
+        }
+        for (unsigned int i = 0; i < n; i++)
+            x_old[i] = x[i];
+
+        Real ss = -params.feasibility_tolerance;
+        int ip = 0; // the index of the constraint to be added
+        bool full_step = false;
+        while (!full_step) {
+            // Step 2: check for feasibility and determine a new S-pair
+            for (unsigned int i = 0; i < m; i++) {
+                if (s[i] < ss && iai[i] != -1 && iaexcl[i]) {
+                    ss = s[i];
+                    ip = i;
+                    if (bland)
+                        break;
+                }
+            }
+            if (ss >= -params.feasibility_tolerance)
+                return finish(f_value);
+            for (unsigned int i = 0; i < n; i++)
+                np[i] = CI[i][ip];
+            u[iq] = 0.0;
+            A[iq] = ip;
+            TRACE("Trying with constraint " << ip);
+
+            for (;;) {
+                // Step 2a: determine step direction
+                compute_d(d, J, np);
+                update_z(z, J, d, iq);
+                update_r(R, r, d, iq);
+
+                // Step 2b: compute step length
+                int l = 0;
+                // partial step length: maximum step in dual space without
+                // violating dual feasibility
+                Real t1 = inf;
+                for (unsigned int k = p; k < iq; k++) {
+                    if (r[k] <= 0.0)
+                        continue;
+                    const Real ratio = u[k] / r[k];
+                    if (ratio < t1 || (bland && ratio == t1 && A[k] < l)) {
+                        t1 = ratio;
+                        l = A[k];
+                    }
+                }
+                // full step length: minimum step in primal space such that
+                // the constraint ip becomes feasible
+                Real t2 = inf;
+                if (std::fabs(scalar_product(z, z)) > params.epsilon) {
+                    t2 = -s[ip] / scalar_product(z, np);
+                    if (t2 < 0)
+                        t2 = inf;
+                }
+                const Real t = std::min(t1, t2);
+                TRACE("Step sizes: " << t << " (t1 = " << t1 << ", t2 = " << t2
+                                     << ")");
+                if (t > 0.0) {
+                    degenerate_steps = 0;
+                } else if (++degenerate_steps > params.max_degenerate_steps) {
+                    info.status = SolveStatus::Degenerate;
+                    return finish(f_value);
+                }
+                bland = params.anti_cycling && degenerate_steps > 0;
+
+                // Step 2c: determine new S-pair and take step
+                if (t >= inf) {
+                    // no step in primal or dual space: infeasible
+                    info.status = SolveStatus::Infeasible;
+                    info.infeasible_constraint = ip;
+                    return inf;
+                }
+                if (t2 >= inf) {
+                    // step in dual space
+                    for (unsigned int k = 0; k < iq; k++)
+                        u[k] -= t * r[k];
+                    u[iq] += t;
+                    iai[l] = l;
+                    delete_constraint(R, J, A, u, n, p, iq, l, info.rotations);
+                    info.deletions++;
+                    TRACE("Step in dual space, removed constraint " << l);
+                    continue;
+                }
+
+                // step in primal and dual space
+                for (unsigned int k = 0; k < n; k++)
+                    x[k] += t * z[k];
+                f_value += t * scalar_product(z, np) * (0.5 * t + u[iq]);
+                for (unsigned int k = 0; k < iq; k++)
+                    u[k] -= t * r[k];
+                u[iq] += t;
+                TRACE("Step in both spaces: " << f_value);
+
+                if (std::fabs(t - t2) < EPS) {
+                    // full step: add constraint ip to the active set
+                    if (!add_constraint(
+                          R, J, d, iq, R_norm, params.epsilon, info.rotations)) {
+                        iaexcl[ip] = false;
+                        delete_constraint(
+                          R, J, A, u, n, p, iq, ip, info.rotations);
+                        for (unsigned int i = 0; i < m; i++)
+                            iai[i] = i;
+                        for (unsigned int i = p; i < iq; i++) {
+                            A[i] = A_old[i];
+                            u[i] = u_old[i];
+                            iai[A[i]] = -1;
+                        }
+                        for (unsigned int i = 0; i < n; i++)
+                            x[i] = x_old[i];
+                        TRACE("Constraint " << ip << " is linearly dependent");
+                        break; // go to step 2
+                    }
+                    info.additions++;
+                    TRACE("Full step, added constraint " << ip);
+                    iai[ip] = -1;
+                    full_step = true;
+                    break; // go to step 1
+                }
+
+                // partial step: drop constraint l
+                iai[l] = l;
+                delete_constraint(R, J, A, u, n, p, iq, l, info.rotations);
+                info.deletions++;
+                TRACE("Partial step, removed constraint " << l);
+                Real sum = 0.0;
+                for (unsigned int k = 0; k < n; k++)
+                    sum += CI[k][ip] * x[k];
+                s[ip] = sum + ci0[ip];
+            }
+        }
+    }
+}
+
+// `solve_quadprog_ws`, calling back `monitor` in each iteration of the
+// active-set loop unless it is null.
+double
+solve_monitored(Workspace& ws,
+                Matrix<double>& G,
+                Vector<double>& g0,
+                const MatrixView& CE,
+                const VectorView& ce0,
+                const MatrixView& CI,
+                const VectorView& ci0,
+                Vector<double>& x,
+                const SolveParams& params,
+                SolveInfo& info,
+                Monitor* monitor)
+{
+    if (params.lp_fallback && !params.reuse_factorization && is_zero(G))
+        return solve_linear(ws, g0, CE, ce0, CI, ci0, x, params, info, monitor);
+    double f_value =
+      solve_active_set(ws, G, g0, CE, ce0, CI, ci0, x, params, info, monitor);
+    const double reg = ws.regularization;
+    if (info.status != SolveStatus::Optimal || reg == 0.0)
+        return f_value;
+    const unsigned int n = x.size();
+    if (params.refine_regularization) {
+        // One proximal point step: solve the regularized problem again with
+        // g0 - reg x_hat, which moves x towards the unregularized solution.
+        const Vector<double> x_hat = x;
+        Vector<double> q(n);
+        for (unsigned int i = 0; i < n; i++)
+            q[i] = g0[i] - reg * x_hat[i];
+        SolveParams inner = params;
+        inner.reuse_factorization = true;
+        inner.initial_point.clear();
+        inner.active_set.clear();
+        for (const int a : info.active_set)
+            if (a >= 0)
+                inner.active_set.push_back(a);
+        const unsigned int iterations = info.iterations;
+        const unsigned int factorizations = info.factorizations;
+        const unsigned int additions = info.additions;
+        const unsigned int deletions = info.deletions;
+        const unsigned int rotations = info.rotations;
+        const bool warm_started = info.warm_started;
+        f_value =
+          solve_active_set(ws, G, q, CE, ce0, CI, ci0, x, inner, info, monitor);
+        info.iterations += iterations;
+        info.factorizations += factorizations;
+        info.additions += additions;
+        info.deletions += deletions;
+        info.rotations += rotations;
+        info.warm_started = warm_started;
+        if (info.status != SolveStatus::Optimal)
+            return f_value;
+        // f(x) = f_q(x) - reg / 2 |x|^2 + reg x_hat^T x
+        f_value += reg * scalar_product(x_hat, x);
+    }
+    // f(x) = f_reg(x) - reg / 2 |x|^2
+    return f_value - 0.5 * reg * scalar_product(x, x);
+}
+} // namespace
+
+double
+solve_quadprog_ws(Workspace& ws,
+                  Matrix<double>& G,
+                  Vector<double>& g0,
+                  const Matrix<double>& CE,
+                  const Vector<double>& ce0,
+                  const Matrix<double>& CI,
+                  const Vector<double>& ci0,
+                  Vector<double>& x,
+                  const SolveParams& params,
+                  SolveInfo& info)
+{
+    return solve_monitored(ws,
+                           G,
+                           g0,
+                           MatrixView(CE),
+                           VectorView(ce0),
+                           MatrixView(CI),
+                           VectorView(ci0),
+                           x,
+                           params,
+                           info,
+                           nullptr);
+}
+
+double
+solve_quadprog_monitored(Workspace& ws,
+                         Matrix<double>& G,
+                         Vector<double>& g0,
+                         const Matrix<double>& CE,
+                         const Vector<double>& ce0,
+                         const Matrix<double>& CI,
+                         const Vector<double>& ci0,
+                         Vector<double>& x,
+                         const SolveParams& params,
+                         SolveInfo& info,
+                         Monitor& monitor)
+{
+    return solve_monitored(ws,
+                           G,
+                           g0,
+                           MatrixView(CE),
+                           VectorView(ce0),
+                           MatrixView(CI),
+                           VectorView(ci0),
+                           x,
+                           params,
+                           info,
+                           &monitor);
+}
+
+double
+solve_quadprog_raw(unsigned int n,
+                   unsigned int p,
+                   unsigned int m,
+                   const double* G,
+                   const double* g0,
+                   const double* CE,
+                   const double* ce0,
+                   const double* CI,
+                   const double* ci0,
+                   double* x,
+                   const SolveParams& params,
+                   SolveInfo& info)
+{
+    Workspace ws(n, p, m);
+    // G is overwritten with its Cholesky factor, so it's copied.
+    Matrix<double> G_copy(G, n, n);
+    Vector<double> g0_copy(g0, n), x_vec(n);
+    const double f = solve_quadprog_view(ws,
+                                         G_copy,
+                                         g0_copy,
+                                         MatrixView(CE, n, p),
+                                         VectorView(ce0, p),
+                                         MatrixView(CI, n, m),
+                                         VectorView(ci0, m),
+                                         x_vec,
+                                         params,
+                                         info);
+    for (unsigned int i = 0; i < n; i++)
+        x[i] = x_vec[i];
+    return f;
+}
+
+double
+solve_quadprog_view(Workspace& ws,
+                    Matrix<double>& G,
+                    Vector<double>& g0,
+                    const MatrixView& CE,
+                    const VectorView& ce0,
+                    const MatrixView& CI,
+                    const VectorView& ci0,
+                    Vector<double>& x,
+                    const SolveParams& params,
+                    SolveInfo& info)
+{
+    return solve_monitored(ws, G, g0, CE, ce0, CI, ci0, x, params, info, nullptr);
+}
+
+double
+solve_quadprog_monitored_view(Workspace& ws,
+                              Matrix<double>& G,
+                              Vector<double>& g0,
+                              const MatrixView& CE,
+                              const VectorView& ce0,
+                              const MatrixView& CI,
+                              const VectorView& ci0,
+                              Vector<double>& x,
+                              const SolveParams& params,
+                              SolveInfo& info,
+                              Monitor& monitor)
+{
+    return solve_monitored(ws, G, g0, CE, ce0, CI, ci0, x, params, info, &monitor);
+}
+} // namespace quadprogpp
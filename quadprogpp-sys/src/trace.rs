@@ -0,0 +1,47 @@
+use std::cell::RefCell;
+
+/// A handler of the trace of the solver, see [`set_trace_handler`].
+pub type TraceHandler = Box<dyn FnMut(&str)>;
+
+thread_local! {
+    static HANDLER: RefCell<Option<TraceHandler>> = RefCell::new(None);
+}
+
+/// Sets the handler receiving the trace of the solves on the current thread, one line per call,
+/// and returns the previous one. The trace is discarded without a handler.
+///
+/// The trace is only emitted with the `trace-solver` feature, by all the solvers but the upstream
+/// [`solve_quadprog`](crate::solve_quadprog). It lists the steps of the active-set loop, i.e. the
+/// constraints added to and removed from the active set, the step lengths and the iterates. Since the handler is
+/// thread-local, the trace of a solve can be captured and forwarded to a logger along with the
+/// context of the solve, even while other threads are solving.
+///
+/// # Examples
+///
+/// ```
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// # use quadprogpp_sys::set_trace_handler;
+/// let lines = Rc::new(RefCell::new(Vec::new()));
+/// let sink = lines.clone();
+/// let previous = set_trace_handler(Some(Box::new(move |line: &str| {
+///     sink.borrow_mut().push(line.to_owned())
+/// })));
+/// // ... solve ...
+/// set_trace_handler(previous);
+/// ```
+pub fn set_trace_handler(handler: Option<TraceHandler>) -> Option<TraceHandler> {
+    HANDLER.with(|cell| cell.replace(handler))
+}
+
+/// Passes a line of the trace to the handler of the current thread, called back by the solver.
+/// A handler which solves in turn doesn't receive the trace of its own solves.
+pub(crate) fn trace_message(message: &str) {
+    HANDLER.with(|cell| {
+        if let Ok(mut handler) = cell.try_borrow_mut() {
+            if let Some(handler) = handler.as_mut() {
+                handler(message);
+            }
+        }
+    });
+}
@@ -0,0 +1,53 @@
+//! Times [`solve_slices`] on random strictly convex problems with as many inequality constraints
+//! as variables, or as many as the `M` environment variable says. With the `pure-rust` feature,
+//! this measures the kernels of the pure-Rust port:
+//!
+//! ```text
+//! cargo bench --bench solve_slices --features pure-rust
+//! cargo bench --bench solve_slices --features pure-rust,simd
+//! ```
+
+use std::time::Instant;
+
+use quadprogpp::solve_slices;
+
+/// A linear congruential generator of numbers in \\([-0.5, 0.5)\\)
+struct Lcg(u64);
+
+impl Lcg {
+    fn sample(&mut self) -> f64 {
+        let state = &mut self.0;
+        *state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (*state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    }
+}
+
+fn main() {
+    for &n in &[50, 100, 200, 500] {
+        let m = std::env::var("M").map_or(n, |m| m.parse().expect("M is a number"));
+        let mut lcg = Lcg(12345);
+        // G = A^T A + I
+        let a: Vec<f64> = (0..n * n).map(|_| lcg.sample()).collect();
+        let mut g = vec![0.0; n * n];
+        for i in 0..n {
+            for j in 0..n {
+                let ata: f64 = (0..n).map(|k| a[k * n + i] * a[k * n + j]).sum();
+                g[i * n + j] = if i == j { ata + 1.0 } else { ata };
+            }
+        }
+        let g0: Vec<f64> = (0..n).map(|_| lcg.sample()).collect();
+        let ci: Vec<f64> = (0..n * m).map(|_| lcg.sample()).collect();
+        let ci0: Vec<f64> = (0..m).map(|_| lcg.sample() - 1.0).collect();
+        let mut x = vec![0.0; n];
+
+        let repetitions = (20_000 / n).max(3) as u32;
+        let start = Instant::now();
+        for _ in 0..repetitions {
+            solve_slices(n, &g, &g0, &[], &[], &ci, &ci0, &mut x).unwrap();
+        }
+        let elapsed = start.elapsed() / repetitions;
+        println!("n = {:3}, m = {:3}: {:?} per solve", n, m, elapsed);
+    }
+}
@@ -0,0 +1,42 @@
+#pragma once
+
+/* C interface of the quadprogpp crate, built with the `cdylib` feature. */
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum QppStatus
+{
+    QPP_OK = 0,
+    QPP_INFEASIBLE = 1,
+    QPP_SINGULAR_HESSIAN = 2,
+    QPP_LINEARLY_DEPENDENT = 3,
+    /* A pointer is null, or an element is infinite or NaN. */
+    QPP_INVALID_ARGUMENT = 4,
+    QPP_ERROR = 5,
+} QppStatus;
+
+/* Solves min 1/2 x^T G x + g0^T x s.t. CE^T x + ce0 = 0 and CI^T x + ci0 >= 0
+ * with n variables, p equality constraints and m inequality constraints. The
+ * matrices G (n x n), CE (n x p) and CI (n x m) are in row-major order.
+ * Pointers to empty arrays may be null. The minimizer is written to `x_out`
+ * and the objective to `obj_out` if the status is QPP_OK. */
+QppStatus
+qpp_solve(size_t n,
+          size_t p,
+          size_t m,
+          const double* g,
+          const double* g0,
+          const double* ce,
+          const double* ce0,
+          const double* ci,
+          const double* ci0,
+          double* x_out,
+          double* obj_out);
+
+#ifdef __cplusplus
+}
+#endif
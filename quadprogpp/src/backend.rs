@@ -0,0 +1,140 @@
+use core::fmt;
+
+#[cfg(feature = "cpp")]
+use quadprogpp_sys as sys;
+
+/// The [`BackendInfo::name`] of QuadProg++.
+#[cfg(feature = "cpp")]
+pub(crate) const CPP_NAME: &str = "QuadProg++";
+/// The [`BackendInfo::name`] of the pure-Rust port.
+#[cfg(any(not(feature = "cpp"), feature = "pure-rust"))]
+pub(crate) const PORT_NAME: &str = "goldfarb-idnani";
+/// The [`Solution::backend`](crate::Solution::backend) of the sparse solver of
+/// [`SparseHessian`](crate::SparseHessian).
+#[cfg(feature = "ndarray")]
+pub(crate) const SPARSE_NAME: &str = "sparse";
+/// The [`Solution::backend`](crate::Solution::backend) of the direct solver of problems without
+/// inequality constraints.
+#[cfg(feature = "ndarray")]
+pub(crate) const DIRECT_NAME: &str = "direct";
+
+/// The build of the solver behind this crate, returned by [`backend_info`].
+///
+/// Its [`Display`](fmt::Display) format is a single line for bug reports and logs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BackendInfo {
+    /// The solver: `"QuadProg++"`, or `"goldfarb-idnani"` for the pure-Rust port used without
+    /// the `cpp` feature
+    pub name: &'static str,
+    /// Version of the crate building the solver, i.e. `quadprogpp-sys` or this crate for the port
+    pub version: &'static str,
+    /// Commit of the vendored QuadProg++ if it was built from a git checkout
+    pub upstream_commit: Option<&'static str>,
+    /// Whether QuadProg++ is an installed library rather than the vendored sources, see the
+    /// `QUADPROGPP_SYS_USE_SYSTEM` variable of `quadprogpp-sys`
+    pub system_library: bool,
+    /// Flags the C++ sources were compiled with, empty for the port
+    pub compile_flags: &'static str,
+    /// Whether the solver emits the trace of the `trace-solver` feature
+    pub trace_solver: bool,
+    /// The scalar type of the arithmetic of the solver: `"f64"`, or `"long double"` with the
+    /// `extended-precision` feature. The problem and the solution are `f64` either way.
+    pub scalar: &'static str,
+}
+
+impl fmt::Display for BackendInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ({}", self.name, self.version, self.scalar)?;
+        if let Some(commit) = self.upstream_commit {
+            write!(f, ", upstream {}", commit)?;
+        }
+        if self.system_library {
+            f.write_str(", system library")?;
+        }
+        if !self.compile_flags.is_empty() {
+            write!(f, ", {}", self.compile_flags)?;
+        }
+        if self.trace_solver {
+            f.write_str(", traced")?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// Returns the build of the solver, to pin down what solved a problem in bug reports and logs.
+///
+/// # Examples
+///
+/// ```
+/// let info = quadprogpp::backend_info();
+/// assert!(!info.version.is_empty());
+/// println!("solved by {}", info);
+/// ```
+pub fn backend_info() -> BackendInfo {
+    #[cfg(feature = "cpp")]
+    let info = BackendInfo {
+        name: CPP_NAME,
+        version: sys::VERSION,
+        upstream_commit: sys::UPSTREAM_COMMIT,
+        system_library: sys::SYSTEM_LIBRARY,
+        compile_flags: sys::COMPILE_FLAGS,
+        trace_solver: sys::TRACE_SOLVER,
+        scalar: if sys::EXTENDED_PRECISION {
+            "long double"
+        } else {
+            "f64"
+        },
+    };
+    #[cfg(not(feature = "cpp"))]
+    let info = port_info();
+    info
+}
+
+/// Returns the build of the pure-Rust port of QuadProg++.
+#[cfg(any(not(feature = "cpp"), feature = "pure-rust"))]
+pub(crate) fn port_info() -> BackendInfo {
+    BackendInfo {
+        name: PORT_NAME,
+        version: env!("CARGO_PKG_VERSION"),
+        upstream_commit: None,
+        system_library: false,
+        compile_flags: "",
+        trace_solver: false,
+        scalar: "f64",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let info = BackendInfo {
+            name: "QuadProg++",
+            version: "0.1.1",
+            upstream_commit: Some("abc123"),
+            system_library: false,
+            compile_flags: "-std=c++14",
+            trace_solver: true,
+            scalar: "f64",
+        };
+        assert_eq!(
+            info.to_string(),
+            "QuadProg++ 0.1.1 (f64, upstream abc123, -std=c++14, traced)"
+        );
+        assert_eq!(
+            BackendInfo {
+                upstream_commit: None,
+                compile_flags: "",
+                trace_solver: false,
+                ..info
+            }
+            .to_string(),
+            "QuadProg++ 0.1.1 (f64)"
+        );
+        #[cfg(feature = "cpp")]
+        assert_eq!(backend_info().name, "QuadProg++");
+    }
+}
@@ -0,0 +1,196 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::{QpProblem, Result, Solution, SolveOptions, Solver};
+
+/// Solves a batch of problems like [`QpProblem::solve`], and returns the results in the order of
+/// `problems`.
+///
+/// One [`Solver`] is kept for each size of the problems, so the matrices passed to QuadProg++ and
+/// the buffers of the solver are allocated once for all the problems of the same size instead of
+/// once for each problem. The problems can be given by value or by reference.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// // min (x - c)^2 / 2 s.t. x >= 0, for several c
+/// let problems = [-1.0, 0.5, 2.0].iter().map(|&c| {
+///     QpProblem::new(1)
+///         .hessian(array![[1.0]])
+///         .linear(array![-c])
+///         .ineq(Constraints::new(array![[1.0]], array![0.0]))
+///         .build()
+/// });
+/// let problems = problems.collect::<Result<Vec<_>>>()?;
+/// let solutions = solve_batch(&problems);
+/// let x: Vec<f64> = solutions.into_iter().map(|s| s.map(|s| s.x[0])).collect::<Result<_>>()?;
+/// assert_eq!(x, [0.0, 0.5, 2.0]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn solve_batch<I>(problems: I) -> Vec<Result<Solution>>
+where
+    I: IntoIterator,
+    I::Item: Borrow<QpProblem>,
+{
+    solve_batch_with_options(problems, &SolveOptions::default())
+}
+
+/// Solves a batch of problems like [`QpProblem::solve_with_options`], and returns the results in
+/// the order of `problems`.
+///
+/// The solvers are reused like in [`solve_batch`], unless [`SolveOptions::presolve`] is enabled,
+/// which changes the size of each problem.
+pub fn solve_batch_with_options<I>(problems: I, options: &SolveOptions) -> Vec<Result<Solution>>
+where
+    I: IntoIterator,
+    I::Item: Borrow<QpProblem>,
+{
+    let mut solvers = Solvers::default();
+    problems
+        .into_iter()
+        .map(|problem| solvers.solve(problem.borrow(), options))
+        .collect()
+}
+
+/// Solves a batch of problems like [`solve_batch_with_options`] on all the available threads, and
+/// returns the results in the order of `problems`.
+///
+/// Each thread takes the next unsolved problem until none is left, and keeps its own solvers.
+/// QuadProg++ has no global state, so the threads don't share anything but the problems and the
+/// options. The callback of [`SolveOptions::on_iteration`] is called from all the threads.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let problems = (0..100)
+///     .map(|i| QpProblem::new(1).hessian(array![[1.0]]).linear(array![-(i as f64)]).build())
+///     .collect::<Result<Vec<_>>>()?;
+/// let solutions = par_solve_batch(&problems, &SolveOptions::default());
+/// assert_eq!(solutions[42].as_ref().unwrap().x, array![42.0]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn par_solve_batch<P>(problems: &[P], options: &SolveOptions) -> Vec<Result<Solution>>
+where
+    P: Borrow<QpProblem> + Sync,
+{
+    let threads = thread::available_parallelism()
+        .map_or(1, |threads| threads.get())
+        .min(problems.len());
+    if threads <= 1 {
+        return solve_batch_with_options(problems.iter().map(Borrow::borrow), options);
+    }
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, Result<Solution>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut solvers = Solvers::default();
+                    let mut results = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        match problems.get(i) {
+                            Some(problem) => {
+                                results.push((i, solvers.solve(problem.borrow(), options)))
+                            }
+                            None => return results,
+                        }
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    });
+    results.sort_unstable_by_key(|&(i, _)| i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// One [`Solver`] for each size of problems
+#[derive(Default)]
+pub(crate) struct Solvers(HashMap<(usize, usize, usize), Solver>);
+
+impl Solvers {
+    /// Creates the solvers of the given sizes ahead of the problems.
+    pub(crate) fn with_sizes(sizes: &[(usize, usize, usize)]) -> Self {
+        let solvers = sizes
+            .iter()
+            .map(|&(n, p, m)| ((n, p, m), Solver::new(n, p, m)));
+        Self(solvers.collect())
+    }
+
+    pub(crate) fn solve(
+        &mut self,
+        problem: &QpProblem,
+        options: &SolveOptions,
+    ) -> Result<Solution> {
+        // Presolving changes the size of the problem.
+        if options.presolve {
+            return problem.solve_with_options(options);
+        }
+        let (n, p, m) = problem.dim();
+        let solver = match self.0.entry((n, p, m)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Solver::try_new(n, p, m)?),
+        };
+        solver.solve_problem(problem, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+    use crate::{Bounds, Constraints, Error};
+
+    #[test]
+    fn matches_solve() -> Result<()> {
+        let problem = |c: f64, m: usize| {
+            let builder = QpProblem::new(2)
+                .hessian(array![[2.0, 1.0], [1.0, 2.0]])
+                .linear(array![-c, c])
+                .bounds(Bounds::lower(array![0.0, -1.0]));
+            match m {
+                0 => builder.build(),
+                _ => builder
+                    .ineq(Constraints::new(array![[-1.0], [-1.0]], array![c]))
+                    .build(),
+            }
+        };
+        let problems = vec![
+            problem(1.0, 1)?,
+            problem(2.0, 0)?,
+            problem(-3.0, 1)?,
+            problem(4.0, 1)?,
+            problem(-1.0, 0)?,
+        ];
+        let solutions = solve_batch(&problems);
+        assert_eq!(solutions.len(), problems.len());
+        for (problem, solution) in problems.iter().zip(solutions) {
+            match (solution, problem.solve()) {
+                (Ok(batch), Ok(single)) => assert_eq!(batch, single),
+                (Err(batch), Err(single)) => assert_eq!(batch.to_string(), single.to_string()),
+                (batch, single) => panic!("{:?} != {:?}", batch, single),
+            }
+        }
+        let parallel = par_solve_batch(&problems, &SolveOptions::default());
+        for (parallel, single) in parallel.iter().zip(problems.iter().map(QpProblem::solve)) {
+            assert_eq!(parallel.as_ref().ok(), single.as_ref().ok());
+        }
+        // -x0 - x1 - 3 >= 0 contradicts the bounds.
+        assert!(matches!(
+            solve_batch(vec![problem(-3.0, 1)?]).pop(),
+            Some(Err(Error::Infeasible { .. }))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,386 @@
+use ndarray::{s, Array1, Array2, ArrayBase, Axis, Ix1, Ix2, OwnedRepr, RawData};
+
+use crate::backend::CPP_NAME;
+use crate::{
+    solve_with_bounds, Bounds, Constraints, Error, IntoQpMatrix, Multipliers, Result, Solution,
+    SolveOptions, Status,
+};
+
+/// A block-diagonal matrix \\(G\\), whose diagonal blocks are square matrices.
+///
+/// [`solve`](crate::solve) splits a problem with a block-diagonal \\(G\\) into the subproblems
+/// which aren't coupled by any constraint, and solves them separately. Each constraint couples the
+/// blocks of the variables which it has nonzero coefficients for. If all the blocks are coupled,
+/// the problem is solved as a whole. The problem is also solved as a whole if the
+/// [`SolveOptions`] limit or observe the iterations or warm-start the solver, or if a subproblem
+/// fails, so that the errors refer to the original constraints and variables.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// // min (x0^2 + x1^2 + x2^2) / 2 - x2 s.t. x0 + x1 >= 1
+/// let g = BlockDiagHessian::new(vec![array![[1.0, 0.0], [0.0, 1.0]], array![[1.0]]]);
+/// let ci = Constraints::some(array![[1.0], [1.0], [0.0]], array![-1.0]);
+/// let solution = solve(g, array![0.0, 0.0, -1.0], Constraints::NONE, ci)?;
+/// assert_eq!(solution.x, array![0.5, 0.5, 1.0]);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub struct BlockDiagHessian {
+    blocks: Vec<Array2<f64>>,
+}
+
+impl BlockDiagHessian {
+    /// Creates a block-diagonal matrix from its diagonal blocks, in order.
+    pub fn new(blocks: Vec<Array2<f64>>) -> Self {
+        Self { blocks }
+    }
+
+    /// Returns the diagonal blocks.
+    pub fn blocks(&self) -> &[Array2<f64>] {
+        &self.blocks
+    }
+
+    /// Returns the number of rows and columns of the whole matrix.
+    pub fn dim(&self) -> usize {
+        self.blocks.iter().map(Array2::nrows).sum()
+    }
+}
+
+impl IntoQpMatrix for BlockDiagHessian {
+    type Data = OwnedRepr<f64>;
+
+    fn into_qp_matrix(self) -> Result<Array2<f64>> {
+        (&self).into_qp_matrix()
+    }
+
+    fn block_sizes(&self) -> Option<Vec<usize>> {
+        Some(self.blocks.iter().map(Array2::nrows).collect())
+    }
+}
+
+impl IntoQpMatrix for &BlockDiagHessian {
+    type Data = OwnedRepr<f64>;
+
+    /// Assembles the dense matrix.
+    ///
+    /// # Errors
+    ///
+    /// If a block isn't square, this method returns [`Error::SizeMismatch`].
+    fn into_qp_matrix(self) -> Result<Array2<f64>> {
+        let n = self.dim();
+        let mut g = Array2::zeros((n, n));
+        let mut offset = 0;
+        for block in &self.blocks {
+            let (rows, cols) = block.dim();
+            assert_size!(block, rows, cols);
+            g.slice_mut(s![offset..offset + rows, offset..offset + rows])
+                .assign(block);
+            offset += rows;
+        }
+        Ok(g)
+    }
+
+    fn block_sizes(&self) -> Option<Vec<usize>> {
+        (*self).block_sizes()
+    }
+}
+
+/// Solves the subproblems of a problem whose \\(G\\) has diagonal blocks of the given sizes, if
+/// the constraints leave more than one of them. Returns `None` if the problem has to be solved as
+/// a whole, including when the shapes are wrong or a subproblem fails.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn solve_blocks<G, G0, CE, CE0, CI, CI0>(
+    sizes: &[usize],
+    g: &ArrayBase<G, Ix2>,
+    g0: &ArrayBase<G0, Ix1>,
+    ce: &Option<Constraints<CE, CE0>>,
+    ci: &Option<Constraints<CI, CI0>>,
+    bounds: &Bounds,
+    options: &SolveOptions,
+) -> Option<Solution>
+where
+    G: RawData<Elem = f64>,
+    G0: RawData<Elem = f64>,
+    CE: RawData<Elem = f64>,
+    CE0: RawData<Elem = f64>,
+    CI: RawData<Elem = f64>,
+    CI0: RawData<Elem = f64>,
+{
+    let n = sizes.iter().sum();
+    if sizes.len() < 2 || g.dim() != (n, n) || g0.len() != n || !bounds.has_size(n) {
+        return None;
+    }
+    // Empty blocks are left out of the subproblems, which QuadProg++ needs variables for.
+    let nonempty = sizes.iter().position(|&size| size > 0)?;
+    let ce = ce.as_ref().map(owned).filter(|ce| ce.upper.is_none());
+    let ci = ci.as_ref().map(owned);
+    if ![&ce, &ci].iter().all(|c| c.iter().all(|c| c.has_size(n))) {
+        return None;
+    }
+    let g = unsafe { g.raw_view().deref_into_view() };
+    let g0 = unsafe { g0.raw_view().deref_into_view() };
+
+    let block_of: Vec<usize> = sizes
+        .iter()
+        .enumerate()
+        .flat_map(|(block, &size)| (0..size).map(move |_| block))
+        .collect();
+    // Union-find of the blocks coupled by constraints
+    let mut parent: Vec<usize> = (0..sizes.len()).collect();
+    fn find(parent: &mut [usize], mut block: usize) -> usize {
+        while parent[block] != block {
+            parent[block] = parent[parent[block]];
+            block = parent[block];
+        }
+        block
+    }
+    // The first block of each constraint, or the first nonempty block if it has no coefficients
+    let mut first_block = |c: &Option<Constraints<OwnedRepr<f64>, OwnedRepr<f64>>>| -> Vec<usize> {
+        let c = match c {
+            Some(c) => c,
+            None => return Vec::new(),
+        };
+        (c.coeffs.axis_iter(Axis(1)))
+            .map(|column| {
+                let mut blocks = (column.iter().enumerate())
+                    .filter(|&(_, &a)| a != 0.0)
+                    .map(|(i, _)| block_of[i]);
+                let first = blocks.next().unwrap_or(nonempty);
+                for block in blocks {
+                    let (root, other) = (find(&mut parent, first), find(&mut parent, block));
+                    parent[other] = root;
+                }
+                first
+            })
+            .collect()
+    };
+    let (eq_blocks, ineq_blocks) = (first_block(&ce), first_block(&ci));
+    let roots: Vec<usize> = (0..sizes.len()).map(|b| find(&mut parent, b)).collect();
+    let mut components: Vec<usize> = (0..sizes.len())
+        .filter(|&b| sizes[b] > 0)
+        .map(|b| roots[b])
+        .collect();
+    components.sort_unstable();
+    components.dedup();
+    if components.len() < 2 {
+        return None;
+    }
+
+    let (p, m) = (eq_blocks.len(), ineq_blocks.len());
+    let mut solution = Solution {
+        x: Array1::zeros(n),
+        status: Status::Optimal,
+        objective: 0.0,
+        multipliers: Multipliers {
+            eq: vec![0.0; p],
+            ineq: vec![0.0; m],
+            lower: vec![0.0; n],
+            upper: vec![0.0; n],
+        },
+        active_set: Vec::new(),
+        active_lower: Vec::new(),
+        active_upper: Vec::new(),
+        iterations: 0,
+        factorizations: 0,
+        additions: 0,
+        deletions: 0,
+        rotations: 0,
+        warm_started: false,
+        polished: false,
+        backend: CPP_NAME,
+    };
+    for root in components {
+        let vars: Vec<usize> = (0..n).filter(|&i| roots[block_of[i]] == root).collect();
+        let in_component = |blocks: &[usize]| -> Vec<usize> {
+            (0..blocks.len())
+                .filter(|&j| roots[blocks[j]] == root)
+                .collect()
+        };
+        let (eq, ineq) = (in_component(&eq_blocks), in_component(&ineq_blocks));
+        let restrict = |c: &Option<Constraints<OwnedRepr<f64>, OwnedRepr<f64>>>, cols: &[usize]| {
+            c.as_ref().filter(|_| !cols.is_empty()).map(|c| {
+                let mut c = c.select(cols);
+                c.coeffs = c.coeffs.select(Axis(0), &vars);
+                c
+            })
+        };
+        let part = solve_with_bounds(
+            g.select(Axis(0), &vars).select(Axis(1), &vars),
+            g0.select(Axis(0), &vars),
+            restrict(&ce, &eq),
+            restrict(&ci, &ineq),
+            &bounds.select(&vars),
+            options,
+        )
+        .ok()?;
+
+        for (k, &i) in vars.iter().enumerate() {
+            solution.x[i] = part.x[k];
+            solution.multipliers.lower[i] = part.multipliers.lower[k];
+            solution.multipliers.upper[i] = part.multipliers.upper[k];
+        }
+        for (k, &j) in eq.iter().enumerate() {
+            solution.multipliers.eq[j] = part.multipliers.eq[k];
+        }
+        for (k, &j) in ineq.iter().enumerate() {
+            solution.multipliers.ineq[j] = part.multipliers.ineq[k];
+        }
+        solution
+            .active_set
+            .extend(part.active_set.iter().map(|&k| ineq[k]));
+        solution
+            .active_lower
+            .extend(part.active_lower.iter().map(|&k| vars[k]));
+        solution
+            .active_upper
+            .extend(part.active_upper.iter().map(|&k| vars[k]));
+        solution.objective += part.objective;
+        solution.iterations += part.iterations;
+        solution.factorizations += part.factorizations;
+        solution.additions += part.additions;
+        solution.deletions += part.deletions;
+        solution.rotations += part.rotations;
+        if solution.status == Status::Optimal {
+            solution.status = part.status;
+        }
+    }
+    solution.active_set.sort_unstable();
+    solution.active_lower.sort_unstable();
+    solution.active_upper.sort_unstable();
+    Some(solution)
+}
+
+/// Copies the constraints into arrays in standard layout.
+pub(crate) fn owned<S, S0>(c: &Constraints<S, S0>) -> Constraints<OwnedRepr<f64>, OwnedRepr<f64>>
+where
+    S: RawData<Elem = f64>,
+    S0: RawData<Elem = f64>,
+{
+    let (coeffs, consts) = unsafe {
+        (
+            c.coeffs.raw_view().deref_into_view(),
+            c.consts.raw_view().deref_into_view(),
+        )
+    };
+    Constraints {
+        coeffs: coeffs.as_standard_layout().into_owned(),
+        consts: consts.to_owned(),
+        upper: c
+            .upper
+            .as_ref()
+            .map(|upper| unsafe { upper.raw_view().deref_into_view().to_owned() }),
+        names: c.names.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+    use crate::solve;
+
+    #[test]
+    fn matches_dense() -> Result<()> {
+        let g = BlockDiagHessian::new(vec![
+            array![[2.0, 1.0], [1.0, 2.0]],
+            array![[1.0]],
+            array![[3.0, 0.5], [0.5, 1.0]],
+        ]);
+        let g0 = array![-1.0, -2.0, 1.0, -1.0, 2.0];
+        // The first constraint couples the first and the last block, and the second and the third
+        // one are on the second block.
+        let ci = Constraints::new(
+            array![
+                [-1.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0],
+                [0.0, 1.0, -1.0],
+                [-1.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0],
+            ],
+            array![0.5, 1.0, 3.0],
+        );
+        let ce = Constraints::new(array![[0.0], [0.0], [1.0], [0.0], [0.0]], array![-0.25]);
+        let bounds = Bounds::lower(array![f64::NEG_INFINITY, 0.5, f64::NEG_INFINITY, -1.0, 0.0]);
+        let options = SolveOptions::default();
+        let sizes = g.block_sizes().unwrap();
+        let dense = g.into_qp_matrix()?;
+        let (ce, ci) = (Some(ce), Some(ci));
+        let split = solve_blocks(&sizes, &dense, &g0, &ce, &ci, &bounds, &options).unwrap();
+        let whole = solve_with_bounds(&dense, &g0, ce, ci, &bounds, &options)?;
+
+        assert_abs_diff_eq!(split.objective, whole.objective, epsilon = 1e-12);
+        let pairs = [
+            (split.x.to_vec(), whole.x.to_vec()),
+            (split.multipliers.eq, whole.multipliers.eq),
+            (split.multipliers.ineq, whole.multipliers.ineq),
+            (split.multipliers.lower, whole.multipliers.lower),
+        ];
+        for (split, whole) in &pairs {
+            assert_eq!(split.len(), whole.len());
+            for (&a, &b) in split.iter().zip(whole) {
+                assert_abs_diff_eq!(a, b, epsilon = 1e-12);
+            }
+        }
+        assert_eq!(split.active_set, whole.active_set);
+        assert_eq!(split.active_lower, whole.active_lower);
+        Ok(())
+    }
+
+    #[test]
+    fn falls_back() {
+        let g = BlockDiagHessian::new(vec![array![[1.0]], array![[1.0]]]);
+        let g0 = array![0.0, 0.0];
+        let dense = (&g).into_qp_matrix().unwrap();
+        let options = SolveOptions::default();
+        let bounds = Bounds::default();
+        // Coupled blocks
+        let ci = Some(Constraints::new(array![[1.0], [1.0]], array![-1.0]));
+        let none = Constraints::NONE;
+        assert!(solve_blocks(&[1, 1], &dense, &g0, &none, &ci, &bounds, &options).is_none());
+        // An infeasible subproblem is solved as a whole to report the original constraint.
+        let ci = Constraints::new(
+            array![[1.0, 0.0, 0.0], [0.0, 1.0, -1.0]],
+            array![1.0, 0.0, -1.0],
+        );
+        let err = solve(g, g0, Constraints::NONE, Some(ci)).unwrap_err();
+        assert!(
+            matches!(&err, Error::Infeasible { constraint } if constraint == "inequality constraint 1"),
+            "unexpected error: {:?}",
+            err
+        );
+
+        // An empty block, with a constraint without coefficients, 0 x + 1 >= 0
+        let g = BlockDiagHessian::new(vec![Array2::zeros((0, 0)), array![[1.0]], array![[1.0]]]);
+        let ci = Some(Constraints::new(array![[0.0], [0.0]], array![1.0]));
+        let g0 = array![-1.0, -2.0];
+        let dense = (&g).into_qp_matrix().unwrap();
+        let sizes = g.block_sizes().unwrap();
+        let split = solve_blocks(&sizes, &dense, &g0, &none, &ci, &bounds, &options).unwrap();
+        assert_eq!(split.x, array![1.0, 2.0]);
+        assert_eq!(split.multipliers.ineq, [0.0]);
+        let solution = solve(g, g0, Constraints::NONE, ci).unwrap();
+        assert_eq!(solution.x, array![1.0, 2.0]);
+        // Only empty blocks
+        let empty = Array2::zeros((0, 0));
+        let sizes = [0, 0];
+        let ci = Some(Constraints::new(Array2::zeros((0, 1)), array![1.0]));
+        let g0 = Array1::zeros(0);
+        assert!(solve_blocks(&sizes, &empty, &g0, &none, &ci, &bounds, &options).is_none());
+
+        let g = BlockDiagHessian::new(vec![array![[1.0, 0.0]]]);
+        assert!(matches!(
+            g.into_qp_matrix(),
+            Err(Error::SizeMismatch { term: "block", .. })
+        ));
+    }
+}
@@ -0,0 +1,152 @@
+use ndarray::{ArrayBase, Data, Ix1};
+
+/// Bounds on the variables, \\(lb \le x \le ub\\).
+///
+/// The bounds are equivalent to the inequality constraints \\(x_i - lb_i \ge 0\\) and
+/// \\(-x_i + ub_i \ge 0\\), but don't require building the \\(N \times 2N\\) coefficient matrix.
+/// Infinite bounds are ignored, so `f64::NEG_INFINITY` and `f64::INFINITY` can be used for the
+/// variables which are unbounded below or above.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let g = array![[1.0, 0.0], [0.0, 1.0]];
+/// let g0 = array![-2.0, 2.0];
+/// let bounds = Bounds::new(array![f64::NEG_INFINITY, -1.0], array![1.0, f64::INFINITY]);
+/// let solution = solve_with_bounds(
+///     g,
+///     g0,
+///     Constraints::NONE,
+///     Constraints::NONE,
+///     &bounds,
+///     &SolveOptions::default(),
+/// )?;
+/// assert_eq!(solution.x, array![1.0, -1.0]);
+/// assert_eq!(solution.multipliers.upper, [1.0, 0.0]);
+/// assert_eq!(solution.multipliers.lower, [0.0, 1.0]);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub struct Bounds {
+    lower: Vec<f64>,
+    upper: Vec<f64>,
+    names: Vec<String>,
+}
+
+impl Bounds {
+    /// Creates lower and upper bounds. Both vectors should have one element per variable.
+    pub fn new<L, U>(lower: ArrayBase<L, Ix1>, upper: ArrayBase<U, Ix1>) -> Self
+    where
+        L: Data<Elem = f64>,
+        U: Data<Elem = f64>,
+    {
+        Self {
+            lower: lower.to_vec(),
+            upper: upper.to_vec(),
+            names: Vec::new(),
+        }
+    }
+
+    /// Creates lower bounds only.
+    pub fn lower<L: Data<Elem = f64>>(lower: ArrayBase<L, Ix1>) -> Self {
+        Self {
+            lower: lower.to_vec(),
+            upper: Vec::new(),
+            names: Vec::new(),
+        }
+    }
+
+    /// Creates upper bounds only.
+    pub fn upper<U: Data<Elem = f64>>(upper: ArrayBase<U, Ix1>) -> Self {
+        Self {
+            lower: Vec::new(),
+            upper: upper.to_vec(),
+            names: Vec::new(),
+        }
+    }
+
+    /// Names the variables, one name per variable. The names are used in errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let bounds = Bounds::new(array![0.0, 1.0], array![1.0, 0.0]).with_names(["x", "y"]);
+    /// let err = solve_with_bounds(
+    ///     array![[1.0, 0.0], [0.0, 1.0]],
+    ///     array![0.0, 0.0],
+    ///     Constraints::NONE,
+    ///     Constraints::NONE,
+    ///     &bounds,
+    ///     &SolveOptions::default(),
+    /// )
+    /// .unwrap_err();
+    /// assert_eq!(err.to_string(), "no feasible solution (upper bound of 'y' can't be satisfied)");
+    /// ```
+    pub fn with_names<I>(mut self, names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the names of the variables, or an empty slice if they aren't named.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the lower bounds, or an empty slice if there are none.
+    pub fn lower_bounds(&self) -> &[f64] {
+        &self.lower
+    }
+
+    /// Returns the upper bounds, or an empty slice if there are none.
+    pub fn upper_bounds(&self) -> &[f64] {
+        &self.upper
+    }
+
+    /// Returns whether each of the bounds and names is either empty or has `n` elements.
+    pub(crate) fn has_size(&self, n: usize) -> bool {
+        [self.lower.len(), self.upper.len(), self.names.len()]
+            .iter()
+            .all(|&len| len == 0 || len == n)
+    }
+
+    /// Returns the bounds of the given variables, in the given order.
+    pub(crate) fn select(&self, indices: &[usize]) -> Self {
+        let select = |a: &[f64]| -> Vec<f64> {
+            if a.is_empty() {
+                Vec::new()
+            } else {
+                indices.iter().map(|&i| a[i]).collect()
+            }
+        };
+        Self {
+            lower: select(&self.lower),
+            upper: select(&self.upper),
+            names: if self.names.is_empty() {
+                Vec::new()
+            } else {
+                indices.iter().map(|&i| self.names[i].clone()).collect()
+            },
+        }
+    }
+
+    /// Returns the finite bounds as `(variable, is_upper, bound)` in the order they are appended
+    /// to the inequality constraints.
+    pub(crate) fn finite(&self) -> impl Iterator<Item = (usize, bool, f64)> + '_ {
+        let lower = self.lower.iter().enumerate().map(|(i, &b)| (i, false, b));
+        let upper = self.upper.iter().enumerate().map(|(i, &b)| (i, true, b));
+        lower.chain(upper).filter(|&(_, _, b)| b.is_finite())
+    }
+}
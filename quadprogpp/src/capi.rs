@@ -0,0 +1,187 @@
+//! C interface to [`solve_slices`], enabled by the `cdylib` feature.
+//!
+//! The declarations are in `include/quadprogpp.h`. A shared library can be built with
+//! `cargo rustc --release --features cdylib --crate-type cdylib`.
+
+use std::slice;
+
+use crate::{solve_slices, Error};
+
+/// The result of [`qpp_solve`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QppStatus {
+    /// The problem was solved.
+    Ok = 0,
+    /// The problem has no feasible solution.
+    Infeasible = 1,
+    /// \\(G\\) isn't positive definite.
+    SingularHessian = 2,
+    /// The constraints are linearly dependent.
+    LinearlyDependent = 3,
+    /// A pointer is null, or an element is infinite or NaN.
+    InvalidArgument = 4,
+    /// Any other error
+    Error = 5,
+}
+
+impl From<&Error> for QppStatus {
+    fn from(err: &Error) -> Self {
+        match err {
+            Error::Infeasible { .. } => Self::Infeasible,
+            Error::SingularHessian { .. } => Self::SingularHessian,
+            Error::LinearlyDependent => Self::LinearlyDependent,
+            Error::SizeMismatch { .. } | Error::NonFinite { .. } => Self::InvalidArgument,
+            _ => Self::Error,
+        }
+    }
+}
+
+/// Solves a quadratic programming problem like [`solve_slices`], with `n` variables, `p`
+/// equality constraints and `m` inequality constraints. The matrices are in row-major order.
+///
+/// The minimizer is written to `x_out` and the objective to `obj_out`, unless the status isn't
+/// [`QppStatus::Ok`].
+///
+/// # Safety
+///
+/// Each pointer must point to as many elements as its array has, i.e. `g` to `n * n`, `ce` to
+/// `n * p`, and so on. Pointers to empty arrays may be null.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn qpp_solve(
+    n: usize,
+    p: usize,
+    m: usize,
+    g: *const f64,
+    g0: *const f64,
+    ce: *const f64,
+    ce0: *const f64,
+    ci: *const f64,
+    ci0: *const f64,
+    x_out: *mut f64,
+    obj_out: *mut f64,
+) -> QppStatus {
+    let arrays = [
+        (g, n * n),
+        (g0, n),
+        (ce, n * p),
+        (ce0, p),
+        (ci, n * m),
+        (ci0, m),
+        (x_out, n),
+    ];
+    if obj_out.is_null() || arrays.iter().any(|&(a, len)| a.is_null() && len > 0) {
+        return QppStatus::InvalidArgument;
+    }
+    let [g, g0, ce, ce0, ci, ci0] = [
+        array(g, n * n),
+        array(g0, n),
+        array(ce, n * p),
+        array(ce0, p),
+        array(ci, n * m),
+        array(ci0, m),
+    ];
+    if ![g, g0, ce, ce0, ci, ci0]
+        .iter()
+        .all(|a| a.iter().all(|x| x.is_finite()))
+    {
+        return QppStatus::InvalidArgument;
+    }
+    let mut x = vec![0.0; n];
+    match solve_slices(n, g, g0, ce, ce0, ci, ci0, &mut x) {
+        Ok(objective) => {
+            if n > 0 {
+                slice::from_raw_parts_mut(x_out, n).copy_from_slice(&x);
+            }
+            *obj_out = objective;
+            QppStatus::Ok
+        }
+        Err(err) => QppStatus::from(&err),
+    }
+}
+
+/// Returns the array of `len` elements pointed to by `a`, which may be null if it's empty.
+unsafe fn array<'a>(a: *const f64, len: usize) -> &'a [f64] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(a, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    #[test]
+    fn qpp_solve_maps_errors() {
+        // The demo of QuadProg++
+        let g = [4.0, -2.0, -2.0, 4.0];
+        let ci = [1.0, 1.0, 0.0, 0.0, 1.0, 1.0];
+        let (mut x, mut objective) = ([0.0; 2], 0.0);
+        let status = unsafe {
+            qpp_solve(
+                2,
+                1,
+                3,
+                g.as_ptr(),
+                [6.0, 0.0].as_ptr(),
+                [1.0, 1.0].as_ptr(),
+                [-3.0].as_ptr(),
+                ci.as_ptr(),
+                [0.0, -2.0, 0.0].as_ptr(),
+                x.as_mut_ptr(),
+                &mut objective,
+            )
+        };
+        assert_eq!(status, QppStatus::Ok);
+        assert_abs_diff_eq!(objective, 12.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(x[0], 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(x[1], 2.0, epsilon = 1e-12);
+
+        let solve = |g: &[f64], g0: *const f64, x: *mut f64| unsafe {
+            let null = ptr::null();
+            qpp_solve(2, 0, 0, g.as_ptr(), g0, null, null, null, null, x, &mut 0.0)
+        };
+        let zero = [0.0; 2];
+        assert_eq!(
+            solve(&[1.0, 1.0, 1.0, 1.0], zero.as_ptr(), x.as_mut_ptr()),
+            QppStatus::SingularHessian
+        );
+        assert_eq!(
+            solve(&g, [f64::NAN, 0.0].as_ptr(), x.as_mut_ptr()),
+            QppStatus::InvalidArgument
+        );
+        assert_eq!(
+            solve(&g, zero.as_ptr(), ptr::null_mut()),
+            QppStatus::InvalidArgument
+        );
+
+        // No variables, with 0 x + ci0 >= 0
+        let solve = |ci0: f64| unsafe {
+            let (null, mut objective) = (ptr::null(), f64::NAN);
+            let ci0 = [ci0];
+            let status = qpp_solve(
+                0,
+                0,
+                1,
+                null,
+                null,
+                null,
+                null,
+                null,
+                ci0.as_ptr(),
+                ptr::null_mut(),
+                &mut objective,
+            );
+            (status, objective)
+        };
+        assert_eq!(solve(1.0), (QppStatus::Ok, 0.0));
+        assert_eq!(solve(-1.0).0, QppStatus::Infeasible);
+    }
+}
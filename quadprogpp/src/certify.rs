@@ -0,0 +1,601 @@
+//! Rigorous certification of computed solutions by interval arithmetic.
+//!
+//! A solution computed in floating point is only approximately feasible and optimal, and
+//! [`verify_kkt`](crate::verify_kkt) measures by how much with the same floating-point
+//! arithmetic. [`certify`] instead bounds every rounding error of evaluating the objective, the
+//! constraints and the Lagrangian at the solution, so that the optimum of the problem provably
+//! lies in [`Certificate::optimum`]:
+//!
+//! * The upper end (the lower end for [`Objective::Maximize`]) is the objective at the solution,
+//!   provided the solution satisfies every constraint and bound exactly.
+//! * The other end follows from weak duality. For multipliers \\(\lambda \ge 0\\) of the
+//!   inequality constraints and the bounds, the Lagrangian \\(L(x, \lambda)\\) is a lower bound
+//!   on the optimum after minimizing over \\(x\\), and if \\(G \succeq \sigma I\\) with
+//!   \\(\sigma > 0\\),
+//!   \\(\min_x L(x, \lambda) \ge L(\hat{x}, \lambda) - \lVert r \rVert^2 / (2 \sigma)\\) where
+//!   \\(r = \nabla_x L(\hat{x}, \lambda)\\) is the residual of stationarity at the solution
+//!   \\(\hat{x}\\). Neither the solution nor the multipliers have to be exact for this to hold.
+//!
+//! The operations on an [`Interval`] round to nearest and widen the result by one unit in the
+//! last place in each direction, which contains the exact result without changing the rounding
+//! mode of the processor.
+
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use ndarray::{ArrayView1, ArrayView2};
+
+use crate::linalg::{self, cholesky_solve};
+use crate::{Error, Objective, QpProblem, Result, Solution};
+
+/// A closed interval of real numbers \\([lo, hi]\\).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    /// The lower end
+    pub lo: f64,
+    /// The upper end
+    pub hi: f64,
+}
+
+impl Interval {
+    /// Creates the interval \\([lo, hi]\\).
+    pub const fn new(lo: f64, hi: f64) -> Self {
+        Interval { lo, hi }
+    }
+
+    /// Creates the interval containing only `x`.
+    pub const fn point(x: f64) -> Self {
+        Interval { lo: x, hi: x }
+    }
+
+    /// Returns whether `x` lies in the interval.
+    pub fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    /// Returns an upper bound on the width of the interval.
+    pub fn width(&self) -> f64 {
+        next_up(self.hi - self.lo)
+    }
+
+    /// Returns the largest magnitude of the elements of the interval.
+    pub fn mag(&self) -> f64 {
+        self.lo.abs().max(self.hi.abs())
+    }
+
+    /// Encloses the exact values of results rounded to nearest.
+    fn widen(lo: f64, hi: f64) -> Self {
+        Interval {
+            lo: next_down(lo),
+            hi: next_up(hi),
+        }
+    }
+
+    /// Encloses \\(\{x^2 : x \in [lo, hi]\}\\).
+    fn square(self) -> Self {
+        let lo = if self.contains(0.0) {
+            0.0
+        } else {
+            let min = self.lo.abs().min(self.hi.abs());
+            next_down(min * min).max(0.0)
+        };
+        let max = self.mag();
+        Interval {
+            lo,
+            hi: next_up(max * max),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+
+    fn neg(self) -> Interval {
+        Interval {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+
+    fn add(self, rhs: Interval) -> Interval {
+        Interval::widen(self.lo + rhs.lo, self.hi + rhs.hi)
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+
+    fn sub(self, rhs: Interval) -> Interval {
+        self + -rhs
+    }
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+
+    fn mul(self, rhs: Interval) -> Interval {
+        // Zero times an infinite end is zero, since the end isn't attained.
+        let product = |a: f64, b: f64| if a == 0.0 || b == 0.0 { 0.0 } else { a * b };
+        let products = [
+            product(self.lo, rhs.lo),
+            product(self.lo, rhs.hi),
+            product(self.hi, rhs.lo),
+            product(self.hi, rhs.hi),
+        ];
+        Interval::widen(
+            products.iter().copied().fold(f64::INFINITY, f64::min),
+            products.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+}
+
+impl Div for Interval {
+    type Output = Interval;
+
+    /// Divides by `rhs`, giving the whole real line if `rhs` contains zero.
+    fn div(self, rhs: Interval) -> Interval {
+        if rhs.contains(0.0) {
+            return Interval::new(f64::NEG_INFINITY, f64::INFINITY);
+        }
+        let quotients = [
+            self.lo / rhs.lo,
+            self.lo / rhs.hi,
+            self.hi / rhs.lo,
+            self.hi / rhs.hi,
+        ];
+        Interval::widen(
+            quotients.iter().copied().fold(f64::INFINITY, f64::min),
+            quotients.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+}
+
+/// Rigorous bounds on the optimum of a problem, returned by [`certify`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Certificate {
+    /// An enclosure of the objective at the solution
+    pub objective: Interval,
+    /// An upper bound on the largest violation of the constraints and the bounds at the
+    /// solution, which is zero if and only if the solution is certainly feasible
+    pub infeasibility: f64,
+    /// An enclosure of the optimum of the problem. Its end on the side of the objective is
+    /// infinite unless the solution is certainly feasible, and its other end is infinite unless
+    /// the Hessian is certainly positive definite.
+    pub optimum: Interval,
+}
+
+impl Certificate {
+    /// Returns whether the solution certainly satisfies every constraint and bound.
+    ///
+    /// Equality constraints can rarely be satisfied exactly in floating point, so this is
+    /// usually false for problems with them.
+    pub fn is_feasible(&self) -> bool {
+        self.infeasibility == 0.0
+    }
+
+    /// Returns an upper bound on the difference between the objective at the solution and the
+    /// optimum, which is infinite unless both ends of [`optimum`](Self::optimum) are finite.
+    pub fn gap(&self) -> f64 {
+        self.optimum.width()
+    }
+}
+
+/// Bounds the optimum of `problem` rigorously, given an approximate `solution`.
+///
+/// See the [module documentation](self) for the bounds.
+///
+/// # Errors
+///
+/// If the solution doesn't have one element per variable and one multiplier per constraint and
+/// bound, this function returns [`Error::SizeMismatch`], as [`verify_kkt`](crate::verify_kkt)
+/// does.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// # use quadprogpp::certify::certify;
+/// // min (x - 1)^2 + (y - 1)^2 s.t. x + y <= 1
+/// let problem = QpProblem::new(2)
+///     .hessian(array![[2.0, 0.0], [0.0, 2.0]])
+///     .linear(array![-2.0, -2.0])
+///     .ineq(Constraints::new(array![[-1.0], [-1.0]], array![1.0]))
+///     .build()?;
+/// let solution = problem.solve()?;
+/// let certificate = certify(&problem, &solution)?;
+/// // The optimum is -1.5, at (0.5, 0.5) which the solution misses by rounding errors.
+/// assert!(certificate.infeasibility < 1e-15);
+/// assert!(certificate.optimum.lo <= -1.5);
+/// assert!(certificate.optimum.lo > -1.5 - 1e-12);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn certify(problem: &QpProblem, solution: &Solution) -> Result<Certificate> {
+    let (n, p, m) = problem.dim();
+    let x = &solution.x;
+    let multipliers = &solution.multipliers;
+    assert_size!(solution.x, n, x.len());
+    assert_size!(solution.multipliers.eq, p, multipliers.eq.len());
+    assert_size!(solution.multipliers.ineq, m, multipliers.ineq.len());
+    assert_size!(solution.multipliers.lower, n, multipliers.lower.len());
+    assert_size!(solution.multipliers.upper, n, multipliers.upper.len());
+
+    let hessian = problem.hessian();
+    let point = |i: usize| Interval::point(x[i]);
+    let zero = Interval::point(0.0);
+    let mut gradient: Vec<Interval> = (0..n)
+        .map(|i| dot(hessian.row(i), x.view()) + Interval::point(problem.linear()[i]))
+        .collect();
+    let objective = (0..n).fold(zero, |sum, i| {
+        let half = Interval::point(0.5) * dot(hessian.row(i), x.view());
+        sum + point(i) * (half + Interval::point(problem.linear()[i]))
+    });
+
+    // The Lagrangian and its gradient with the multipliers of the inequality constraints and the
+    // bounds clipped to be dual feasible
+    let mut lagrangian = objective;
+    let mut infeasibility = 0.0_f64;
+    let subtract = |gradient: &mut [Interval], coeffs: ArrayView1<'_, f64>, u: f64| {
+        if u != 0.0 {
+            for (r, &a) in gradient.iter_mut().zip(coeffs) {
+                *r = *r - Interval::point(a) * Interval::point(u);
+            }
+        }
+    };
+    if let Some(ce) = problem.eq() {
+        for (j, &u) in multipliers.eq.iter().enumerate() {
+            let residual = dot(ce.coeffs.column(j), x.view()) + Interval::point(ce.consts[j]);
+            infeasibility = infeasibility.max(residual.mag());
+            lagrangian = lagrangian - Interval::point(u) * residual;
+            subtract(&mut gradient, ce.coeffs.column(j), u);
+        }
+    }
+    if let Some(ci) = problem.ineq() {
+        for (j, &u) in multipliers.ineq.iter().enumerate() {
+            let ax = dot(ci.coeffs.column(j), x.view());
+            let (lower, upper) = match &ci.upper {
+                Some(upper) => (ci.consts[j], upper[j]),
+                None => (-ci.consts[j], f64::INFINITY),
+            };
+            let lower_slack = ax - Interval::point(lower);
+            let upper_slack = Interval::point(upper) - ax;
+            infeasibility = infeasibility.max(-lower_slack.lo).max(-upper_slack.lo);
+            // A positive multiplier belongs to the lower limit and a negative one to the upper
+            // limit.
+            let u = if u > 0.0 && lower.is_finite() {
+                lagrangian = lagrangian - Interval::point(u) * lower_slack;
+                u
+            } else if u < 0.0 && upper.is_finite() {
+                lagrangian = lagrangian + Interval::point(u) * upper_slack;
+                u
+            } else {
+                0.0
+            };
+            subtract(&mut gradient, ci.coeffs.column(j), u);
+        }
+    }
+    let bounds = problem.bounds();
+    for (i, r) in gradient.iter_mut().enumerate() {
+        let lower = bounds
+            .lower_bounds()
+            .get(i)
+            .copied()
+            .unwrap_or(f64::NEG_INFINITY);
+        let upper = bounds
+            .upper_bounds()
+            .get(i)
+            .copied()
+            .unwrap_or(f64::INFINITY);
+        let lower_slack = point(i) - Interval::point(lower);
+        let upper_slack = Interval::point(upper) - point(i);
+        infeasibility = infeasibility.max(-lower_slack.lo).max(-upper_slack.lo);
+        let u = multipliers.lower[i];
+        if u > 0.0 && lower.is_finite() {
+            lagrangian = lagrangian - Interval::point(u) * lower_slack;
+            *r = *r - Interval::point(u);
+        }
+        let u = multipliers.upper[i];
+        if u > 0.0 && upper.is_finite() {
+            lagrangian = lagrangian - Interval::point(u) * upper_slack;
+            *r = *r + Interval::point(u);
+        }
+    }
+    let infeasibility = infeasibility.max(0.0);
+
+    let upper = if infeasibility == 0.0 {
+        objective.hi
+    } else {
+        f64::INFINITY
+    };
+    let lower = if n == 0 {
+        lagrangian.lo
+    } else {
+        match min_eigenvalue_bound(hessian) {
+            Some(sigma) => {
+                let norm = gradient.iter().fold(zero, |sum, r| sum + r.square());
+                (lagrangian - norm / (Interval::point(2.0) * Interval::point(sigma))).lo
+            }
+            None => f64::NEG_INFINITY,
+        }
+    };
+    let mut certificate = Certificate {
+        objective,
+        infeasibility,
+        optimum: Interval::new(lower, upper),
+    };
+    if problem.objective() == Objective::Maximize {
+        certificate.objective = -certificate.objective;
+        certificate.optimum = -certificate.optimum;
+    }
+    Ok(certificate)
+}
+
+/// Encloses \\(a^\intercal x\\).
+fn dot(a: ArrayView1<'_, f64>, x: ArrayView1<'_, f64>) -> Interval {
+    a.iter().zip(x).fold(Interval::point(0.0), |sum, (&a, &x)| {
+        sum + Interval::point(a) * Interval::point(x)
+    })
+}
+
+/// Returns a positive lower bound on the smallest eigenvalue of the symmetric matrix `g`, or
+/// `None` if none could be found.
+///
+/// If the Cholesky factorization \\(\tilde{L}\\) of \\(A = fl(G - s I)\\) succeeds in floating
+/// point, \\(\tilde{L} \tilde{L}^\intercal = A + \Delta A\\) with
+/// \\(|\Delta A| \le \gamma_{n+1} |\tilde{L}| |\tilde{L}^\intercal|\\) (Higham, Accuracy and
+/// Stability of Numerical Algorithms, Theorem 10.3), so
+/// \\(\lambda_{min}(G) \ge s - \delta - \gamma_{n+1} \lVert \tilde{L} \rVert_F^2\\) where
+/// \\(\delta\\) bounds the rounding of the diagonal of \\(A\\). The shift \\(s\\) is a fraction of
+/// an estimate of \\(\lambda_{min}(G)\\) by inverse iteration.
+///
+/// `g` only has to be symmetric up to rounding, like the Hessian of a [`QpProblem`], whereas the
+/// factorization only reads its upper triangle. The bound is on the eigenvalues of
+/// \\((G + G^\intercal) / 2\\), which differs from the symmetric matrix of the upper triangle by a
+/// matrix whose 2-norm is at most its largest absolute row sum \\(\eta\\), so \\(\eta\\) is
+/// subtracted from the bound as well (Weyl's inequality).
+fn min_eigenvalue_bound(g: ArrayView2<'_, f64>) -> Option<f64> {
+    let n = g.nrows();
+    let asymmetry = (0..n).fold(0.0_f64, |eta, i| {
+        let row = (0..n).fold(Interval::point(0.0), |sum, j| {
+            let difference = Interval::point(g[[i, j]]) - Interval::point(g[[j, i]]);
+            sum + Interval::point(difference.mag())
+        });
+        eta.max((Interval::point(0.5) * row).hi)
+    });
+    let factor = cholesky(g, 0.0)?;
+    let mut v: Vec<f64> = (0..n).map(|i| 1.0 + (i as f64).sqrt()).collect();
+    let mut estimate = 0.0;
+    for _ in 0..30 {
+        let norm = v.iter().map(|v| v * v).sum::<f64>().sqrt();
+        v.iter_mut().for_each(|v| *v /= norm);
+        let gv = g.dot(&ArrayView1::from(&v));
+        estimate = v.iter().zip(&gv).map(|(v, gv)| v * gv).sum();
+        v = cholesky_solve(&factor, &v);
+    }
+
+    let u = Interval::point(f64::EPSILON / 2.0);
+    let k = Interval::point((n + 1) as f64) * u;
+    let gamma = k / (Interval::point(1.0) - k);
+    for &fraction in &[0.9, 0.5, 0.1] {
+        let shift = fraction * estimate;
+        let factor = match cholesky(g, shift) {
+            Some(factor) => factor,
+            None => continue,
+        };
+        let delta = (0..n).fold(0.0_f64, |delta, i| {
+            delta.max((Interval::point(g[[i, i]]) - Interval::point(shift)).width())
+        });
+        let frobenius = (0..n)
+            .flat_map(|i| &factor[i * n..=i * n + i])
+            .map(|&l| Interval::point(l).square())
+            .fold(Interval::point(0.0), |sum, l| sum + l);
+        let bound = Interval::point(shift)
+            - Interval::point(delta)
+            - Interval::point(asymmetry)
+            - gamma * frobenius;
+        if bound.lo > 0.0 {
+            return Some(bound.lo);
+        }
+    }
+    None
+}
+
+/// Computes the Cholesky factorization of \\(G - s I\\) in floating point, stored like by
+/// [`linalg::cholesky`], or returns `None` if it breaks down.
+fn cholesky(g: ArrayView2<'_, f64>, shift: f64) -> Option<Vec<f64>> {
+    let n = g.nrows();
+    let mut factor: Vec<f64> = g.iter().copied().collect();
+    for i in 0..n {
+        factor[i * n + i] -= shift;
+    }
+    linalg::cholesky(&mut factor, n).ok()?;
+    if factor.iter().all(|l| l.is_finite()) {
+        Some(factor)
+    } else {
+        None
+    }
+}
+
+/// The least number greater than `x`, like `f64::next_up`, which requires Rust 1.86.
+fn next_up(x: f64) -> f64 {
+    if x.is_nan() || x == f64::INFINITY {
+        x
+    } else if x == 0.0 {
+        f64::from_bits(1)
+    } else if x > 0.0 {
+        f64::from_bits(x.to_bits() + 1)
+    } else {
+        f64::from_bits(x.to_bits() - 1)
+    }
+}
+
+/// The greatest number less than `x`, like `f64::next_down`.
+fn next_down(x: f64) -> f64 {
+    -next_up(-x)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array2};
+
+    use super::*;
+    use crate::{Bounds, Constraints};
+
+    #[test]
+    fn interval() {
+        assert_eq!(next_up(1.0), 1.0 + f64::EPSILON);
+        assert_eq!(next_down(1.0), 1.0 - f64::EPSILON / 2.0);
+        assert_eq!(next_up(-0.0), f64::from_bits(1));
+        assert_eq!(
+            next_down(f64::MIN_POSITIVE),
+            f64::MIN_POSITIVE - f64::from_bits(1)
+        );
+        assert_eq!(next_up(f64::NEG_INFINITY), f64::MIN);
+        assert_eq!(next_up(f64::MAX), f64::INFINITY);
+
+        let third = Interval::point(1.0) / Interval::point(3.0);
+        assert!((third * Interval::point(3.0)).contains(1.0));
+        assert!((Interval::point(0.1) + Interval::point(0.2)).width() < 1e-15);
+        assert_eq!(
+            Interval::point(0.0) * Interval::new(1.0, f64::INFINITY),
+            Interval::new(-f64::from_bits(1), f64::from_bits(1)),
+        );
+        assert_eq!(Interval::new(-2.0, 1.0).square().lo, 0.0);
+        assert!(Interval::new(-3.0, -2.0).square().contains(4.0));
+        let whole = Interval::point(1.0) / Interval::new(-1.0, 1.0);
+        assert_eq!(whole, Interval::new(f64::NEG_INFINITY, f64::INFINITY));
+        assert_eq!(Interval::new(1.0, 2.0).to_string(), "[1, 2]");
+    }
+
+    #[test]
+    fn ranged_and_bounds() -> Result<()> {
+        // max -x^2 + 6 x - y^2 - 2 y - z^2 s.t. 1 <= x + y <= 1.5, y >= 0, z >= 0.5, whose
+        // optimum is 6.5 at (1.5, 0, 0.5)
+        let problem = QpProblem::new(3)
+            .hessian(array![[-2.0, 0.0, 0.0], [0.0, -2.0, 0.0], [0.0, 0.0, -2.0]])
+            .linear(array![6.0, -2.0, 0.0])
+            .ineq(Constraints::ranged(
+                array![[1.0], [1.0], [0.0]],
+                array![1.0],
+                array![1.5],
+            ))
+            .bounds(Bounds::lower(array![f64::NEG_INFINITY, 0.0, 0.5]))
+            .objective(Objective::Maximize)
+            .build()?;
+        let solution = problem.solve()?;
+        let certificate = certify(&problem, &solution)?;
+        assert!(certificate.infeasibility < 1e-14, "{:?}", certificate);
+        assert!(certificate.objective.contains(solution.objective));
+        assert!(certificate.optimum.contains(6.5));
+        assert!(certificate.optimum.hi - 6.5 < 1e-12, "{:?}", certificate);
+
+        // An infeasible point still gives a lower bound on the optimum.
+        let mut wrong = solution.clone();
+        wrong.x[0] += 1e-3;
+        let certificate = certify(&problem, &wrong)?;
+        assert!(!certificate.is_feasible());
+        assert!((certificate.infeasibility - 1e-3).abs() < 1e-12);
+        assert_eq!(certificate.optimum.lo, f64::NEG_INFINITY);
+        assert!(certificate.optimum.contains(6.5));
+        assert!(certificate.optimum.hi - 6.5 < 1e-5);
+
+        // Wrong multipliers only weaken the bound.
+        let mut wrong = solution;
+        wrong.multipliers.ineq[0] = -wrong.multipliers.ineq[0];
+        wrong.multipliers.lower[2] = -1.0;
+        let certificate = certify(&problem, &wrong)?;
+        assert!(certificate.optimum.contains(6.5));
+        assert!(certificate.optimum.hi > 7.0);
+        Ok(())
+    }
+
+    #[test]
+    fn equality_and_semidefinite() -> Result<()> {
+        // min x^2 + y^2 s.t. x + y = 1 / 3, whose optimum is 1 / 18
+        let problem = QpProblem::new(2)
+            .hessian(array![[2.0, 0.0], [0.0, 2.0]])
+            .eq(Constraints::new(array![[1.0], [1.0]], array![-1.0 / 3.0]))
+            .build()?;
+        let solution = problem.solve()?;
+        let certificate = certify(&problem, &solution)?;
+        assert!(certificate.infeasibility < 1e-15);
+        assert!(certificate.optimum.lo <= 1.0 / 18.0);
+        assert!(certificate.optimum.lo > 1.0 / 18.0 - 1e-12);
+
+        // Without a positive definite Hessian, there is no lower bound.
+        let semidefinite = QpProblem::new(2)
+            .hessian(array![[2.0, 0.0], [0.0, 0.0]])
+            .eq(Constraints::new(array![[1.0], [1.0]], array![-1.0 / 3.0]))
+            .build()?;
+        let certificate = certify(&semidefinite, &solution)?;
+        assert_eq!(certificate.optimum.lo, f64::NEG_INFINITY);
+        Ok(())
+    }
+
+    #[test]
+    fn ill_conditioned() -> Result<()> {
+        // The Hilbert matrix of order 6 has a smallest eigenvalue of about 1.1e-7.
+        let n = 6;
+        let g = Array2::from_shape_fn((n, n), |(i, j)| 1.0 / (i + j + 1) as f64);
+        let sigma = min_eigenvalue_bound(g.view()).expect("positive definite");
+        assert!(sigma > 9e-8 && sigma < 1.09e-7, "{}", sigma);
+
+        let problem = QpProblem::new(n)
+            .hessian(g)
+            .linear(ndarray::Array1::from_elem(n, -1.0))
+            .bounds(Bounds::lower(ndarray::Array1::zeros(n)))
+            .build()?;
+        let solution = problem.solve()?;
+        let certificate = certify(&problem, &solution)?;
+        // The optimum is -5.5 at (0, 0, 0, 0, 0, 11).
+        assert!(certificate.optimum.lo <= -5.5);
+        assert!(certificate.optimum.lo > -5.5 - 1e-12, "{:?}", certificate);
+        Ok(())
+    }
+
+    #[test]
+    fn asymmetric() {
+        // The upper triangle is the identity, but (G + G^T) / 2 is singular.
+        let g = array![[1.0, 0.0], [2.0, 1.0]];
+        assert_eq!(min_eigenvalue_bound(g.view()), None);
+        // Within the tolerance of QpProblem, the asymmetry only loosens the bound.
+        let g = array![[2.0, 1.0], [1.0 + 4.0 * f64::EPSILON, 2.0]];
+        let sigma = min_eigenvalue_bound(g.view()).expect("positive definite");
+        let symmetric = min_eigenvalue_bound(array![[2.0, 1.0], [1.0, 2.0]].view()).unwrap();
+        assert!(sigma < symmetric && sigma > 0.8, "{} {}", sigma, symmetric);
+    }
+
+    #[test]
+    fn errors() -> Result<()> {
+        let problem = QpProblem::new(2)
+            .hessian(array![[1.0, 0.0], [0.0, 1.0]])
+            .build()?;
+        let mut solution = problem.solve()?;
+        solution.multipliers.lower.pop();
+        let err = certify(&problem, &solution).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "solution.multipliers.lower",
+                    expected: 2,
+                    actual: 1,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+//! Conversion from the problem data of [Clarabel](https://clarabel.org).
+//!
+//! Clarabel solves
+//!
+//! $$
+//! \min_{x} \quad \frac{1}{2} x^\intercal P x + q^\intercal x
+//! \quad \textrm{subject to} \quad A x + s = b, \quad s \in \mathcal{K}
+//! $$
+//!
+//! where \\(\mathcal{K}\\) is a product of cones. QuadProg++ only handles linear constraints, so
+//! the cones are restricted to the zero cone, \\(b_i - a_i^\intercal x = 0\\), and the
+//! nonnegative cone, \\(b_i - a_i^\intercal x \ge 0\\).
+
+use ndarray::{Array1, Axis};
+
+use crate::{utils, Constraints, Error, QpProblem, Result};
+
+/// A matrix in compressed sparse column format, with the same fields as
+/// `clarabel::algebra::CscMatrix`.
+///
+/// The row indices and the values of column `j` are `rowval[colptr[j]..colptr[j + 1]]` and the
+/// same range of `nzval`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CscMatrix<'a> {
+    /// Number of rows
+    pub m: usize,
+    /// Number of columns
+    pub n: usize,
+    /// Offsets of the columns in `rowval` and `nzval`, one more than the number of columns
+    pub colptr: &'a [usize],
+    /// Row indices of the nonzero elements
+    pub rowval: &'a [usize],
+    /// Values of the nonzero elements
+    pub nzval: &'a [f64],
+}
+
+/// A cone of the constraints, like `clarabel::solver::SupportedConeT`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cone {
+    /// The given number of equality constraints
+    Zero(usize),
+    /// The given number of inequality constraints
+    Nonnegative(usize),
+}
+
+/// Converts Clarabel's problem data to a [`QpProblem`].
+///
+/// Clarabel takes the upper triangle of \\(P\\), which is mirrored to the lower one. If elements
+/// below the diagonal are stored, \\(P\\) is taken as it is instead. The rows of \\(A\\) are
+/// divided among `cones` in order; each row of a zero cone becomes an equality constraint and
+/// each row of a nonnegative cone an inequality constraint, in the same order.
+///
+/// # Errors
+///
+/// * If the sizes of `cones` don't add up to the number of rows of \\(A\\), or the shapes of the
+///   matrices and vectors are inconsistent, this function returns [`Error::SizeMismatch`].
+/// * It returns the errors of [`QpProblemBuilder::build`](crate::QpProblemBuilder::build).
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// use quadprogpp::clarabel::{Cone, CscMatrix};
+///
+/// // min x^2 + y^2 s.t. x + y = 1 and x <= 0.25
+/// let p = CscMatrix { m: 2, n: 2, colptr: &[0, 1, 2], rowval: &[0, 1], nzval: &[2.0, 2.0] };
+/// let a = CscMatrix { m: 2, n: 2, colptr: &[0, 2, 3], rowval: &[0, 1, 0], nzval: &[1.0; 3] };
+/// let cones = [Cone::Zero(1), Cone::Nonnegative(1)];
+/// let problem = clarabel::to_problem(p, &[0.0, 0.0], a, &[1.0, 0.25], &cones)?;
+/// assert_eq!(problem.solve()?.x, array![0.25, 0.75]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn to_problem(
+    p: CscMatrix<'_>,
+    q: &[f64],
+    a: CscMatrix<'_>,
+    b: &[f64],
+    cones: &[Cone],
+) -> Result<QpProblem> {
+    let n = q.len();
+    assert_size!(p, n, p.m);
+    assert_size!(p, n, p.n);
+    assert_size!(a, n, a.n);
+    assert_size!(b, a.m, b.len());
+    let rows = cones
+        .iter()
+        .map(|&cone| match cone {
+            Cone::Zero(k) | Cone::Nonnegative(k) => k,
+        })
+        .sum();
+    assert_size!(cones, a.m, rows);
+
+    let mut g = utils::csc_to_dense((n, n), p.colptr, p.rowval, p.nzval)?;
+    let full = (0..n).any(|i| (0..i).any(|j| g[[i, j]] != 0.0));
+    if !full {
+        for i in 0..n {
+            for j in 0..i {
+                g[[i, j]] = g[[j, i]];
+            }
+        }
+    }
+    let a_dense = utils::csc_to_dense((a.m, n), a.colptr, a.rowval, a.nzval)?;
+
+    let mut eq = Vec::new();
+    let mut ineq = Vec::new();
+    let mut row = 0;
+    for &cone in cones {
+        let (rows, k) = match cone {
+            Cone::Zero(k) => (&mut eq, k),
+            Cone::Nonnegative(k) => (&mut ineq, k),
+        };
+        rows.extend(row..row + k);
+        row += k;
+    }
+    // b - a^T x is a constraint with the coefficients -a and the constant b.
+    let constraints = |rows: &[usize]| {
+        let coeffs = -a_dense.select(Axis(0), rows);
+        let consts: Array1<f64> = rows.iter().map(|&i| b[i]).collect();
+        Constraints::from_rows(coeffs, consts)
+    };
+    let mut builder = QpProblem::new(n)
+        .hessian(g)
+        .linear(Array1::from(q.to_vec()));
+    if !eq.is_empty() {
+        builder = builder.eq(constraints(&eq));
+    }
+    if !ineq.is_empty() {
+        builder = builder.ineq(constraints(&ineq));
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn to_problem_checks_cones() -> Result<()> {
+        // The full P, and cones interleaved
+        let p = CscMatrix {
+            m: 2,
+            n: 2,
+            colptr: &[0, 2, 4],
+            rowval: &[0, 1, 0, 1],
+            nzval: &[2.0, 1.0, 1.0, 2.0],
+        };
+        let a = CscMatrix {
+            m: 3,
+            n: 2,
+            colptr: &[0, 2, 4],
+            rowval: &[0, 2, 1, 2],
+            nzval: &[-1.0, 1.0, -1.0, 1.0],
+        };
+        let cones = [Cone::Nonnegative(2), Cone::Zero(1)];
+        let problem = to_problem(p, &[-3.0, 0.0], a, &[0.0, 0.0, 1.0], &cones)?;
+        assert_eq!(problem.hessian(), array![[2.0, 1.0], [1.0, 2.0]]);
+        assert_eq!(problem.dim(), (2, 1, 2));
+        // x + y = 1 with x, y >= 0
+        let x = problem.solve()?.x;
+        assert_abs_diff_eq!(x[0], 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(x[1], 0.0, epsilon = 1e-12);
+
+        let err = to_problem(p, &[-3.0, 0.0], a, &[0.0, 0.0, 1.0], &cones[..1]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "cones",
+                    expected: 3,
+                    actual: 2,
+                    shapes: None
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use ndarray::{
+    Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Data, Ix1, Ix2, OwnedRepr, RawData, ViewRepr,
+};
+
+use crate::{Error, Result};
+
+/// Conversion into the matrix \\(G\\) accepted by [`solve`](crate::solve).
+///
+/// This is implemented for ndarray arrays, which are passed through, references to them, which
+/// are viewed, as well as for `Vec<Vec<f64>>` and `&[[f64; N]]`, whose elements are the rows,
+/// and for a row-major slice with its shape, `((rows, cols), &[f64])`.
+///
+/// # Examples
+///
+/// ```
+/// # use quadprogpp::*;
+/// let g = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+/// let solution = solve(g, vec![-1.0, 1.0], Constraints::NONE, Constraints::NONE)?;
+/// assert_eq!(solution.x.to_vec(), [1.0, -1.0]);
+///
+/// let g = [[1.0, 0.0], [0.0, 1.0]];
+/// let solution = solve(&g[..], &[-1.0, 1.0][..], Constraints::NONE, Constraints::NONE)?;
+/// assert_eq!(solution.x.to_vec(), [1.0, -1.0]);
+/// # Ok::<(), Error>(())
+/// ```
+pub trait IntoQpMatrix {
+    /// The storage of the converted array
+    type Data: RawData<Elem = f64>;
+
+    /// Converts `self` into an array.
+    ///
+    /// # Errors
+    ///
+    /// If the rows have different lengths, or the length of a slice doesn't match its shape, this
+    /// method returns [`Error::SizeMismatch`].
+    fn into_qp_matrix(self) -> Result<ArrayBase<Self::Data, Ix2>>;
+
+    /// Returns the sizes of the diagonal blocks if the matrix is block diagonal, like
+    /// [`BlockDiagHessian`](crate::BlockDiagHessian), so that the solver can split the problem.
+    fn block_sizes(&self) -> Option<Vec<usize>> {
+        None
+    }
+
+    /// Returns the matrix if it's a [`SparseHessian`](crate::SparseHessian), so that the solver
+    /// can solve the problem without forming the dense matrix.
+    fn sparse_hessian(&self) -> Option<&crate::SparseHessian> {
+        None
+    }
+}
+
+/// Conversion into the vector \\(g_0\\) accepted by [`solve`](crate::solve).
+///
+/// This is implemented for ndarray arrays, which are passed through, references to them, which
+/// are viewed, as well as for `Vec<f64>` and `&[f64]`.
+pub trait IntoQpVector {
+    /// The storage of the converted array
+    type Data: RawData<Elem = f64>;
+
+    /// Converts `self` into an array.
+    fn into_qp_vector(self) -> ArrayBase<Self::Data, Ix1>;
+}
+
+impl<S: RawData<Elem = f64>> IntoQpMatrix for ArrayBase<S, Ix2> {
+    type Data = S;
+
+    fn into_qp_matrix(self) -> Result<ArrayBase<S, Ix2>> {
+        Ok(self)
+    }
+}
+
+impl<'a, S: Data<Elem = f64>> IntoQpMatrix for &'a ArrayBase<S, Ix2> {
+    type Data = ViewRepr<&'a f64>;
+
+    fn into_qp_matrix(self) -> Result<ArrayView2<'a, f64>> {
+        Ok(self.view())
+    }
+}
+
+impl IntoQpMatrix for Vec<Vec<f64>> {
+    type Data = OwnedRepr<f64>;
+
+    fn into_qp_matrix(self) -> Result<Array2<f64>> {
+        let shape = (self.len(), self.first().map_or(0, Vec::len));
+        for row in &self {
+            assert_size!(row, shape.1, row.len());
+        }
+        let elements = self.into_iter().flatten().collect();
+        Ok(Array2::from_shape_vec(shape, elements).unwrap())
+    }
+}
+
+impl<const N: usize> IntoQpMatrix for &[[f64; N]] {
+    type Data = OwnedRepr<f64>;
+
+    fn into_qp_matrix(self) -> Result<Array2<f64>> {
+        Ok(Array2::from_shape_fn((self.len(), N), |(i, j)| self[i][j]))
+    }
+}
+
+impl<'a> IntoQpMatrix for ((usize, usize), &'a [f64]) {
+    type Data = ViewRepr<&'a f64>;
+
+    fn into_qp_matrix(self) -> Result<ArrayView2<'a, f64>> {
+        let ((rows, cols), data) = self;
+        assert_size!(data, rows * cols, data.len());
+        Ok(ArrayView2::from_shape((rows, cols), data).unwrap())
+    }
+}
+
+impl<S: RawData<Elem = f64>> IntoQpVector for ArrayBase<S, Ix1> {
+    type Data = S;
+
+    fn into_qp_vector(self) -> ArrayBase<S, Ix1> {
+        self
+    }
+}
+
+impl<'a, S: Data<Elem = f64>> IntoQpVector for &'a ArrayBase<S, Ix1> {
+    type Data = ViewRepr<&'a f64>;
+
+    fn into_qp_vector(self) -> ArrayView1<'a, f64> {
+        self.view()
+    }
+}
+
+impl IntoQpVector for Vec<f64> {
+    type Data = OwnedRepr<f64>;
+
+    fn into_qp_vector(self) -> Array1<f64> {
+        Array1::from(self)
+    }
+}
+
+impl<'a> IntoQpVector for &'a [f64] {
+    type Data = ViewRepr<&'a f64>;
+
+    fn into_qp_vector(self) -> ArrayView1<'a, f64> {
+        ArrayView1::from(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn conversions() -> Result<()> {
+        let g = array![[1.0, 2.0], [3.0, 4.0]];
+        assert_eq!(vec![vec![1.0, 2.0], vec![3.0, 4.0]].into_qp_matrix()?, g);
+        assert_eq!((&[[1.0, 2.0], [3.0, 4.0]][..]).into_qp_matrix()?, g);
+        assert_eq!(((2, 2), &[1.0, 2.0, 3.0, 4.0][..]).into_qp_matrix()?, g);
+        assert_eq!(Vec::<Vec<f64>>::new().into_qp_matrix()?.dim(), (0, 0));
+        assert_eq!((&g).into_qp_matrix()?, g);
+        assert_eq!(vec![1.0, 2.0].into_qp_vector(), array![1.0, 2.0]);
+        assert_eq!((&array![1.0, 2.0]).into_qp_vector(), array![1.0, 2.0]);
+
+        let err = vec![vec![1.0, 2.0], vec![3.0]]
+            .into_qp_matrix()
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "row",
+                    expected: 2,
+                    actual: 1,
+                    shapes: None
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = ((2, 2), &[1.0, 2.0, 3.0][..]).into_qp_matrix().unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "data",
+                    expected: 4,
+                    actual: 3,
+                    shapes: None
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use ndarray::{Array1, ArrayBase, Ix1, Ix2, RawData};
+
+use crate::backend::DIRECT_NAME;
+use crate::linalg::{cholesky, cholesky_solve};
+use crate::{
+    utils, Constraints, Error, Multipliers, Result, Shapes, Solution, SolveOptions, Status,
+};
+
+/// Solves a problem without inequality constraints by solving its KKT system directly, without
+/// going through QuadProg++.
+///
+/// Returns `None` if the equality constraints are too ill-conditioned for the range-space method,
+/// so that the problem is solved by QuadProg++ instead.
+pub(crate) fn solve_direct<G, G0, CE, CE0>(
+    g: &ArrayBase<G, Ix2>,
+    g0: &ArrayBase<G0, Ix1>,
+    ce: &Option<Constraints<CE, CE0>>,
+    options: &SolveOptions,
+) -> Result<Option<Solution>>
+where
+    G: RawData<Elem = f64>,
+    G0: RawData<Elem = f64>,
+    CE: RawData<Elem = f64>,
+    CE0: RawData<Elem = f64>,
+{
+    let n = g.nrows();
+    let shapes = Shapes {
+        g: Some(g.dim()),
+        g0: g0.dim(),
+        ce: ce.as_ref().map(Constraints::shape),
+        ci: None,
+    };
+    let (g_n, g_m) = g.dim();
+    assert_size!(g, g_n, g_m, shapes);
+    let g0_n = g0.dim();
+    assert_size!(g0.dim(), n, g0_n, shapes);
+    if let Some(x0) = &options.initial_point {
+        assert_size!(initial_point, n, x0.len());
+    }
+    let (g, g0) = unsafe { (utils::row_major(g), utils::row_major(g0)) };
+    let g = options.prepare_hessian(&g, n)?;
+    assert_finite!(g);
+    assert_finite!(g0);
+    match ce {
+        Some(Constraints {
+            coeffs,
+            consts,
+            upper,
+            names,
+        }) => {
+            if upper.is_some() {
+                return Err(Error::RangedEquality);
+            }
+            let (ce_n, p) = coeffs.dim();
+            assert_size!(ce.dim(), n, ce_n, shapes);
+            let ce0_n = consts.dim();
+            assert_size!(ce0.dim(), ce0_n, p, shapes);
+            if !names.is_empty() {
+                assert_size!(ce.names(), p, names.len());
+            }
+            let (ce, ce0) = unsafe { (utils::row_major(coeffs), utils::row_major(consts)) };
+            assert_finite!(ce);
+            assert_finite!(ce0);
+            solve_equality(&g, &g0, &ce, &ce0)
+        }
+        None => solve_unconstrained(&g, &g0).map(Some),
+    }
+}
+
+/// Minimizes \\(\frac{1}{2} x^\intercal G x + g_0^\intercal x\\) without constraints by solving
+/// \\(G x = -g_0\\), where `g` is a row-major \\(N \times N\\) matrix.
+///
+/// The arithmetic is the same as that of QuadProg++ before it looks at the constraints, so the
+/// result is identical to the one of the FFI solver unless the `simd` feature is enabled. Only
+/// the upper triangle of `g` is read.
+fn solve_unconstrained(g: &[f64], g0: &[f64]) -> Result<Solution> {
+    let n = g0.len();
+    let mut l = g.to_vec();
+    cholesky(&mut l, n)?;
+    let mut x = Array1::from(cholesky_solve(&l, g0));
+    x.mapv_inplace(|x: f64| -x);
+    let mut objective = 0.0;
+    for (&g0, &x) in g0.iter().zip(&x) {
+        objective += g0 * x;
+    }
+    Ok(solution(x, 0.5 * objective, Vec::new()))
+}
+
+/// Solves the KKT system of a problem with equality constraints only,
+/// \\(G x + g_0 = CE \lambda\\) and \\(CE^\intercal x + ce_0 = 0\\), by the range-space method:
+/// \\(\lambda\\) is the solution of \\(CE^\intercal G^{-1} CE \lambda = CE^\intercal G^{-1} g_0 -
+/// ce_0\\) and \\(x = G^{-1} (CE \lambda - g_0)\\).
+///
+/// The method squares the condition number of the constraints, so it returns `None` if a pivot
+/// of the Cholesky factorization of \\(CE^\intercal G^{-1} CE\\) is small, i.e. if the constraints
+/// are ill-conditioned or linearly dependent, and QuadProg++ has to solve the problem.
+fn solve_equality(g: &[f64], g0: &[f64], ce: &[f64], ce0: &[f64]) -> Result<Option<Solution>> {
+    let n = g0.len();
+    let p = ce0.len();
+    let mut l = g.to_vec();
+    cholesky(&mut l, n)?;
+    // w = G^-1 g0, W = G^-1 CE
+    let w = cholesky_solve(&l, g0);
+    let mut column = vec![0.0; n];
+    let mut gce = Vec::with_capacity(p);
+    for j in 0..p {
+        for (i, c) in column.iter_mut().enumerate() {
+            *c = ce[i * p + j];
+        }
+        gce.push(cholesky_solve(&l, &column));
+    }
+    // S = CE^T W, r = CE^T w - ce0
+    let mut s = vec![0.0; p * p];
+    let mut r = vec![0.0; p];
+    for j in 0..p {
+        for k in 0..p {
+            s[j * p + k] = (0..n).map(|i| ce[i * p + j] * gce[k][i]).sum();
+        }
+        r[j] = (0..n).map(|i| ce[i * p + j] * w[i]).sum::<f64>() - ce0[j];
+    }
+    // The relative error of lambda is about cond(S) epsilon, where cond(S) is at least the ratio
+    // of the largest diagonal element of S to its smallest pivot.
+    let scale = (0..p).fold(0.0f64, |max, j| max.max(s[j * p + j]));
+    let tolerance = scale * f64::EPSILON.sqrt();
+    if cholesky(&mut s, p).is_err() || (0..p).any(|j| s[j * p + j].powi(2) <= tolerance) {
+        return Ok(None);
+    }
+    let lambda = cholesky_solve(&s, &r);
+    let mut x = Array1::zeros(n);
+    for (i, x) in x.iter_mut().enumerate() {
+        *x = (0..p).map(|j| gce[j][i] * lambda[j]).sum::<f64>() - w[i];
+    }
+    let mut objective = 0.0;
+    for i in 0..n {
+        let gx: f64 = (0..n).map(|j| g[i * n + j] * x[j]).sum();
+        objective += x[i] * (0.5 * gx + g0[i]);
+    }
+    Ok(Some(solution(x, objective, lambda)))
+}
+
+/// Returns a solution with the same statistics as the one of the FFI solver, which terminates in
+/// its first iteration when there are no inequality constraints, except that no Givens rotations
+/// are counted since the equality constraints aren't added one by one.
+fn solution(x: Array1<f64>, objective: f64, eq: Vec<f64>) -> Solution {
+    let n = x.len();
+    let additions = eq.len();
+    Solution {
+        x,
+        status: Status::Optimal,
+        objective,
+        multipliers: Multipliers {
+            eq,
+            ineq: Vec::new(),
+            lower: vec![0.0; n],
+            upper: vec![0.0; n],
+        },
+        active_set: Vec::new(),
+        active_lower: Vec::new(),
+        active_upper: Vec::new(),
+        iterations: 1,
+        factorizations: 1,
+        additions,
+        deletions: 0,
+        rotations: 0,
+        warm_started: false,
+        polished: false,
+        backend: DIRECT_NAME,
+    }
+}
@@ -0,0 +1,158 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::{QpProblem, Result, Solution, SolveOptions};
+
+/// Solves `problem` like [`QpProblem::solve_with_options`] on a new thread, and returns a future
+/// of the result, so that async code doesn't block its executor on a large problem.
+///
+/// The future works with any executor. The solver is stopped with [`Error::Interrupted`] when the
+/// future is [cancelled](SolveFuture::cancel) or dropped, for which the future replaces the
+/// [`cancellation`](SolveOptions::cancellation) flag of `options` with its own. The
+/// [`deadline`](SolveOptions::deadline) of `options` still applies.
+///
+/// [`Error::Interrupted`]: crate::Error::Interrupted
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// # fn block_on<F: std::future::Future>(future: F) -> F::Output {
+/// #     use std::task::{Context, Poll, Wake, Waker};
+/// #     struct Thread(std::thread::Thread);
+/// #     impl Wake for Thread {
+/// #         fn wake(self: std::sync::Arc<Self>) { self.0.unpark() }
+/// #     }
+/// #     let waker = Waker::from(std::sync::Arc::new(Thread(std::thread::current())));
+/// #     let mut future = Box::pin(future);
+/// #     loop {
+/// #         match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+/// #             Poll::Ready(output) => return output,
+/// #             Poll::Pending => std::thread::park(),
+/// #         }
+/// #     }
+/// # }
+/// let problem = QpProblem::new(1).hessian(array![[1.0]]).linear(array![-1.0]).build()?;
+/// // e.g. in an async request handler
+/// let solution = block_on(async { solve_async(problem, SolveOptions::default()).await })?;
+/// assert_eq!(solution.x, array![1.0]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn solve_async(problem: QpProblem, options: SolveOptions) -> SolveFuture {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let shared = Arc::new(Mutex::new(Shared::default()));
+    let options = options.cancellation(Arc::clone(&cancelled));
+    let thread_shared = Arc::clone(&shared);
+    thread::spawn(move || {
+        let result = problem.solve_with_options(&options);
+        let mut shared = thread_shared.lock().unwrap();
+        shared.result = Some(result);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    });
+    SolveFuture { shared, cancelled }
+}
+
+/// The future returned by [`solve_async`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled, and dropping this one stops the solver"]
+pub struct SolveFuture {
+    shared: Arc<Mutex<Shared>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// The state shared with the solving thread
+#[derive(Debug, Default)]
+struct Shared {
+    result: Option<Result<Solution>>,
+    waker: Option<Waker>,
+}
+
+impl SolveFuture {
+    /// Stops the solver at its next iteration, after which the future completes with
+    /// [`Error::Interrupted`](crate::Error::Interrupted), or with the result if the solver had
+    /// already finished.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Future for SolveFuture {
+    type Output = Result<Solution>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for SolveFuture {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Wake;
+    use std::time::Duration;
+
+    use ndarray::{Array1, Array2};
+
+    use super::*;
+    use crate::{Bounds, Error};
+
+    /// Polls `future` on the current thread until it completes.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct Unpark(thread::Thread);
+        impl Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark()
+            }
+        }
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut future = Box::pin(future);
+        loop {
+            match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn solve_async_cancels() -> Result<()> {
+        // min |x - (1, ..., 5)|^2 / 2 s.t. x <= 0, which takes an iteration per bound
+        let n = 5;
+        let problem = QpProblem::new(n)
+            .hessian(Array2::eye(n))
+            .linear(-Array1::range(1.0, 6.0, 1.0))
+            .bounds(Bounds::upper(Array1::zeros(n)))
+            .build()?;
+        let solution = block_on(solve_async(problem.clone(), SolveOptions::default()))?;
+        assert_eq!(solution, problem.solve()?);
+
+        let slow = SolveOptions::new().on_iteration(|_| thread::sleep(Duration::from_millis(50)));
+        let future = solve_async(problem, slow);
+        future.cancel();
+        let err = block_on(future).unwrap_err();
+        assert!(
+            matches!(err, Error::Interrupted { .. }),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+}
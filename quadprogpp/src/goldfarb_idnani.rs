@@ -0,0 +1,796 @@
+//! A pure-Rust port of the Goldfarb-Idnani solver of QuadProg++, used by
+//! [`solve_slices`](crate::solve_slices) when the `cpp` feature is disabled, e.g. on
+//! `wasm32-unknown-unknown`, or when the `pure-rust` feature is enabled.
+//!
+//! The port follows `solve_quadprog` of upstream, without the extensions of the C++ backend such
+//! as warm starts, termination parameters and the reuse of factorizations. It performs the same
+//! floating-point operations in the same order, so that it returns the same bits as the C++
+//! backend with the default options; the tests check that on a shared set of problems.
+//!
+//! The matrices are stored so that the inner loops run over contiguous rows: the Givens rotations
+//! are the kernels at the end of this module, and the dot products, the Cholesky factorization
+//! and the triangular solves those of `linalg`. With the `simd` feature, the dot products
+//! accumulate four partial sums, which the compiler keeps in vector registers. That reorders the
+//! additions, so the results then differ from the C++ backend in the last bits.
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec, vec::Vec};
+use core::ops::{Index, IndexMut};
+
+use crate::linalg::{self, dot, sqrt};
+use crate::{Error, Result};
+
+const EPS: f64 = f64::EPSILON;
+const INF: f64 = f64::INFINITY;
+
+/// A square matrix in row-major order
+struct Square<'a> {
+    n: usize,
+    data: &'a mut [f64],
+}
+
+impl Index<(usize, usize)> for Square<'_> {
+    type Output = f64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        &self.data[i * self.n + j]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Square<'_> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f64 {
+        &mut self.data[i * self.n + j]
+    }
+}
+
+impl Square<'_> {
+    fn row(&self, i: usize) -> &[f64] {
+        &self.data[i * self.n..(i + 1) * self.n]
+    }
+
+    /// Returns rows `i` and `i + 1`.
+    fn rows(&mut self, i: usize) -> (&mut [f64], &mut [f64]) {
+        let n = self.n;
+        self.data[i * n..(i + 2) * n].split_at_mut(n)
+    }
+}
+
+/// The storage of the solver, borrowed from a [`Storage`] or from the arrays of a
+/// [`StaticSolver`](crate::StaticSolver), so that the algorithm doesn't allocate.
+///
+/// The matrices \\(L\\), \\(R\\) and \\(J\\) have at least `n * n` elements, `iai`, `iaexcl` and
+/// `s` at least `m`, and the other vectors at least `n`, which bounds the number of active
+/// constraints.
+pub(crate) struct Workspace<'a> {
+    pub(crate) matrices: [&'a mut [f64]; 3],
+    /// `e`, `z`, `y`, `d`, `np`, `r`, `x_old`, `u` and `u_old`
+    pub(crate) vectors: [&'a mut [f64]; 9],
+    /// `a` and `a_old`
+    pub(crate) indices: [&'a mut [isize]; 2],
+    pub(crate) iai: &'a mut [isize],
+    pub(crate) iaexcl: &'a mut [bool],
+    pub(crate) s: &'a mut [f64],
+}
+
+impl Workspace<'_> {
+    /// Returns the active constraints at the end of [`solve_full`], where equality constraint `i`
+    /// is encoded as `-i - 1`, and their multipliers.
+    #[cfg_attr(not(all(feature = "ndarray", feature = "pure-rust")), allow(dead_code))]
+    pub(crate) fn active_set(&self, output: &Output) -> impl Iterator<Item = (isize, f64)> + '_ {
+        let (a, u) = (&self.indices[0], &self.vectors[7]);
+        a.iter().copied().zip(u.iter().copied()).take(output.active)
+    }
+}
+
+/// The storage of the solver on the heap
+pub(crate) struct Storage {
+    matrices: [Vec<f64>; 3],
+    vectors: [Vec<f64>; 9],
+    indices: [Vec<isize>; 2],
+    iai: Vec<isize>,
+    iaexcl: Vec<bool>,
+    s: Vec<f64>,
+}
+
+impl Storage {
+    /// Allocates the storage for `n` variables and `m` inequality constraints.
+    pub(crate) fn new(n: usize, m: usize) -> Self {
+        let mut storage = Self {
+            matrices: Default::default(),
+            vectors: Default::default(),
+            indices: Default::default(),
+            iai: vec![0; m],
+            iaexcl: vec![true; m],
+            s: vec![0.0; m],
+        };
+        for matrix in &mut storage.matrices {
+            matrix.resize(n * n, 0.0);
+        }
+        for vector in &mut storage.vectors {
+            vector.resize(n, 0.0);
+        }
+        for indices in &mut storage.indices {
+            indices.resize(n, 0);
+        }
+        storage
+    }
+
+    pub(crate) fn workspace(&mut self) -> Workspace<'_> {
+        let [l, r, j] = &mut self.matrices;
+        let [e, z, y, d, np, r_vector, x_old, u, u_old] = &mut self.vectors;
+        let [a, a_old] = &mut self.indices;
+        Workspace {
+            matrices: [l, r, j],
+            vectors: [e, z, y, d, np, r_vector, x_old, u, u_old],
+            indices: [a, a_old],
+            iai: &mut self.iai,
+            iaexcl: &mut self.iaexcl,
+            s: &mut self.s,
+        }
+    }
+}
+
+/// The active set and the factorizations which the dual method updates with it.
+struct ActiveSet<'a> {
+    /// The upper triangular matrix \\(R\\) of the QR factorization of \\(L^{-1} N\\), where
+    /// \\(N\\) holds the normals of the active constraints
+    r: Square<'a>,
+    /// \\(J^\intercal\\), where \\(J = L^{-\intercal} Q\\), so that the columns of \\(J\\) which
+    /// the Givens rotations combine are contiguous
+    j: Square<'a>,
+    /// The active constraints, where equality constraint `i` is encoded as `-i - 1`
+    a: &'a mut [isize],
+    /// The multipliers of the active constraints
+    u: &'a mut [f64],
+    /// The constraint being added, which QuadProg++ keeps after the active ones in `a`. Keeping
+    /// it apart bounds `a` and `u` by the number of variables rather than of constraints.
+    added: isize,
+    /// The multiplier of the constraint being added
+    u_added: f64,
+    /// Number of active constraints
+    iq: usize,
+    /// The largest magnitude of the diagonal elements of \\(R\\)
+    r_norm: f64,
+    /// Number of Givens rotations so far
+    rotations: usize,
+}
+
+impl<'a> ActiveSet<'a> {
+    fn new(r: Square<'a>, j: Square<'a>, a: &'a mut [isize], u: &'a mut [f64]) -> Self {
+        r.data.fill(0.0);
+        a.fill(0);
+        u.fill(0.0);
+        Self {
+            r,
+            j,
+            a,
+            u,
+            added: 0,
+            u_added: 0.0,
+            iq: 0,
+            r_norm: 1.0,
+            rotations: 0,
+        }
+    }
+
+    /// Computes \\(d = J^\intercal n_p\\).
+    fn compute_d(&self, np: &[f64], d: &mut [f64]) {
+        for (i, d) in d.iter_mut().enumerate() {
+            *d = dot(self.j.row(i), np);
+        }
+    }
+
+    /// Computes the step direction in the primal space, \\(z = J_2 d_2\\).
+    fn update_z(&self, d: &[f64], z: &mut [f64]) {
+        // The sums start from -0.0 like `Sum`, which adds the terms in the same order.
+        z.fill(-0.0);
+        for (j, &d) in d.iter().enumerate().skip(self.iq) {
+            axpy(d, self.j.row(j), z);
+        }
+    }
+
+    /// Computes the negative of the step direction in the dual space, \\(r = R^{-1} d_1\\).
+    fn update_r(&self, d: &[f64], r: &mut [f64]) {
+        for i in (0..self.iq).rev() {
+            let sum = dot(&self.r.row(i)[i + 1..self.iq], &r[i + 1..self.iq]);
+            r[i] = (d[i] - sum) / self.r[(i, i)];
+        }
+    }
+
+    /// Updates the factorizations for the constraint whose \\(d\\) is given, by Givens rotations
+    /// which reduce \\(d\\) to its first `iq + 1` elements, and makes the constraint being added
+    /// active. Returns `false` if the constraint is linearly dependent on the active ones, in which
+    /// case it's still counted in `iq`.
+    fn add_constraint(&mut self, d: &mut [f64]) -> bool {
+        let n = d.len();
+        for j in (self.iq + 1..n).rev() {
+            let (mut cc, mut ss) = (d[j - 1], d[j]);
+            let h = distance(cc, ss);
+            if h.abs() < EPS {
+                continue;
+            }
+            self.rotations += 1;
+            d[j] = 0.0;
+            ss /= h;
+            cc /= h;
+            if cc < 0.0 {
+                cc = -cc;
+                ss = -ss;
+                d[j - 1] = -h;
+            } else {
+                d[j - 1] = h;
+            }
+            let xny = ss / (1.0 + cc);
+            let (x, y) = self.j.rows(j - 1);
+            rotate(x, y, cc, ss, xny);
+        }
+        self.iq += 1;
+        let iq = self.iq;
+        self.a[iq - 1] = self.added;
+        self.u[iq - 1] = self.u_added;
+        for (i, &d) in d.iter().enumerate().take(iq) {
+            self.r[(i, iq - 1)] = d;
+        }
+        if d[iq - 1].abs() <= EPS * self.r_norm {
+            return false;
+        }
+        self.r_norm = self.r_norm.max(d[iq - 1].abs());
+        true
+    }
+
+    /// Removes the inequality constraint `l` from the active set, and restores the triangular
+    /// form of \\(R\\) by Givens rotations.
+    fn delete_constraint(&mut self, p: usize, l: isize) {
+        let n = self.r.n;
+        let qq = (p..self.iq)
+            .find(|&i| self.a[i] == l)
+            .expect("deleting a constraint which isn't active");
+        for i in qq..self.iq - 1 {
+            self.a[i] = self.a[i + 1];
+            self.u[i] = self.u[i + 1];
+            for j in 0..n {
+                self.r[(j, i)] = self.r[(j, i + 1)];
+            }
+        }
+        let iq = self.iq;
+        for j in 0..iq {
+            self.r[(j, iq - 1)] = 0.0;
+        }
+        self.iq -= 1;
+
+        for j in qq..self.iq {
+            let (mut cc, mut ss) = (self.r[(j, j)], self.r[(j + 1, j)]);
+            let h = distance(cc, ss);
+            if h.abs() < EPS {
+                continue;
+            }
+            self.rotations += 1;
+            cc /= h;
+            ss /= h;
+            self.r[(j + 1, j)] = 0.0;
+            if cc < 0.0 {
+                self.r[(j, j)] = -h;
+                cc = -cc;
+                ss = -ss;
+            } else {
+                self.r[(j, j)] = h;
+            }
+            let xny = ss / (1.0 + cc);
+            let (x, y) = self.r.rows(j);
+            rotate(&mut x[j + 1..self.iq], &mut y[j + 1..self.iq], cc, ss, xny);
+            let (x, y) = self.j.rows(j);
+            rotate(x, y, cc, ss, xny);
+        }
+    }
+}
+
+/// The statistics of a solve, returned by [`solve_full`].
+#[cfg_attr(not(all(feature = "ndarray", feature = "pure-rust")), allow(dead_code))]
+pub(crate) struct Output {
+    /// The value of the objective function
+    pub(crate) objective: f64,
+    /// Number of active constraints, given by [`Workspace::active_set`]
+    pub(crate) active: usize,
+    pub(crate) iterations: usize,
+    pub(crate) additions: usize,
+    pub(crate) deletions: usize,
+    pub(crate) rotations: usize,
+}
+
+/// Solves the problem of [`solve_slices`](crate::solve_slices), whose sizes have been checked.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn solve(
+    n: usize,
+    g: &[f64],
+    g0: &[f64],
+    ce: &[f64],
+    ce0: &[f64],
+    ci: &[f64],
+    ci0: &[f64],
+    x: &mut [f64],
+) -> Result<f64> {
+    let mut storage = Storage::new(n, ci0.len());
+    let mut workspace = storage.workspace();
+    let output = solve_full(n, g, g0, ce, ce0, ci, ci0, x, &mut workspace, inequality)?;
+    Ok(output.objective)
+}
+
+/// Describes inequality constraint `ip` in [`Error::Infeasible`].
+pub(crate) fn inequality(ip: usize) -> String {
+    format!("inequality constraint {}", ip)
+}
+
+/// Solves the problem like [`solve`] in `workspace`, describing the inequality constraint which
+/// can't be satisfied with `describe` if the problem is infeasible.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn solve_full(
+    n: usize,
+    g: &[f64],
+    g0: &[f64],
+    ce: &[f64],
+    ce0: &[f64],
+    ci: &[f64],
+    ci0: &[f64],
+    x: &mut [f64],
+    workspace: &mut Workspace<'_>,
+    describe: impl Fn(usize) -> String,
+) -> Result<Output> {
+    let (p, m) = (ce0.len(), ci0.len());
+    if p > n {
+        return Err(Error::LinearlyDependent);
+    }
+    let [l, r_matrix, j_matrix] = &mut workspace.matrices;
+    let [e, z, y, d, np, r, x_old, u, u_old] = &mut workspace.vectors;
+    let (e, z, y, d, np, r, x_old, u, u_old) = (
+        &mut e[..n],
+        &mut z[..n],
+        &mut y[..n],
+        &mut d[..n],
+        &mut np[..n],
+        &mut r[..n],
+        &mut x_old[..n],
+        &mut u[..n],
+        &mut u_old[..n],
+    );
+    let [a, a_old] = &mut workspace.indices;
+    let (iai, iaexcl, s) = (
+        &mut workspace.iai[..m],
+        &mut workspace.iaexcl[..m],
+        &mut workspace.s[..m],
+    );
+    let column = |a: &[f64], cols: usize, j: usize, np: &mut [f64]| {
+        for (i, np) in np.iter_mut().enumerate() {
+            *np = a[i * cols + j];
+        }
+    };
+    let slack = |j: usize, x: &[f64]| -> f64 {
+        x.iter()
+            .enumerate()
+            .map(|(i, x)| ci[i * m + j] * x)
+            .sum::<f64>()
+            + ci0[j]
+    };
+
+    // c1 * c2 is an estimate of the condition number of G.
+    let c1: f64 = (0..n).map(|i| g[i * n + i]).sum();
+    let l = &mut l[..n * n];
+    l.copy_from_slice(g);
+    linalg::cholesky(l, n)?;
+    let r_matrix = Square {
+        n,
+        data: &mut r_matrix[..n * n],
+    };
+    let j_matrix = Square {
+        n,
+        data: &mut j_matrix[..n * n],
+    };
+    let mut set = ActiveSet::new(r_matrix, j_matrix, &mut a[..n], u);
+    // The initial J is the inverse of L^T.
+    let mut c2 = 0.0;
+    e.fill(0.0);
+    for i in 0..n {
+        e[i] = 1.0;
+        linalg::forward_elimination(l, e, z);
+        for (j, &z) in z.iter().enumerate() {
+            set.j[(j, i)] = z;
+        }
+        c2 += z[i];
+        e[i] = 0.0;
+    }
+
+    // The unconstrained minimizer, x = -G^-1 g0
+    linalg::forward_elimination(l, g0, y);
+    linalg::backward_elimination(l, y, x);
+    x.iter_mut().for_each(|x| *x = -*x);
+    let mut f_value = 0.5 * dot(g0, x);
+
+    for (i, &ce0) in ce0.iter().enumerate() {
+        column(ce, p, i, np);
+        set.compute_d(np, d);
+        set.update_z(d, z);
+        set.update_r(d, r);
+        // The step which makes the constraint feasible
+        let mut t2 = 0.0;
+        if dot(z, z).abs() > EPS {
+            t2 = (-dot(np, x) - ce0) / dot(z, np);
+        }
+        x.iter_mut().zip(z.iter()).for_each(|(x, z)| *x += t2 * z);
+        let iq = set.iq;
+        set.u_added = t2;
+        set.u[..iq]
+            .iter_mut()
+            .zip(r.iter())
+            .for_each(|(u, r)| *u -= t2 * r);
+        f_value += 0.5 * t2 * t2 * dot(z, np);
+        set.added = -(i as isize) - 1;
+        if !set.add_constraint(d) {
+            return Err(Error::LinearlyDependent);
+        }
+    }
+    let (mut iterations, mut additions, mut deletions) = (0, p, 0);
+    let output = |set: &ActiveSet, objective, iterations, additions, deletions| Output {
+        objective,
+        active: set.iq,
+        iterations,
+        additions,
+        deletions,
+        rotations: set.rotations,
+    };
+
+    // iai holds the inactive inequality constraints, with -1 for the active ones.
+    for (i, iai) in iai.iter_mut().enumerate() {
+        *iai = i as isize;
+    }
+    loop {
+        iterations += 1;
+        // Step 1: choose a violated constraint
+        for i in p..set.iq {
+            iai[set.a[i] as usize] = -1;
+        }
+        let mut psi = 0.0; // the sum of all infeasibilities
+        s.fill(-0.0);
+        for (i, &x) in x.iter().enumerate() {
+            axpy(x, &ci[i * m..(i + 1) * m], s);
+        }
+        for (s, &ci0) in s.iter_mut().zip(ci0) {
+            *s += ci0;
+            psi += s.min(0.0);
+        }
+        iaexcl.iter_mut().for_each(|excl| *excl = true);
+        if psi.abs() <= m as f64 * EPS * c1 * c2 * 100.0 {
+            return Ok(output(&set, f_value, iterations, additions, deletions));
+        }
+        let iq = set.iq;
+        a_old[..iq].copy_from_slice(&set.a[..iq]);
+        u_old[..iq].copy_from_slice(&set.u[..iq]);
+        x_old.copy_from_slice(x);
+
+        let mut ss = 0.0;
+        let mut ip = 0; // the index of the constraint to be added
+        let mut full_step = false;
+        while !full_step {
+            // Step 2: check for feasibility and determine a new S-pair
+            for i in 0..m {
+                if s[i] < ss && iai[i] != -1 && iaexcl[i] {
+                    ss = s[i];
+                    ip = i;
+                }
+            }
+            if ss >= 0.0 {
+                return Ok(output(&set, f_value, iterations, additions, deletions));
+            }
+            column(ci, m, ip, np);
+            set.u_added = 0.0;
+            set.added = ip as isize;
+
+            loop {
+                // Step 2a: determine step direction
+                set.compute_d(np, d);
+                set.update_z(d, z);
+                set.update_r(d, r);
+
+                // Step 2b: compute step length
+                // partial step length: maximum step in dual space without violating dual
+                // feasibility
+                let mut l = 0;
+                let mut t1 = INF;
+                for (k, &r) in r.iter().enumerate().take(set.iq).skip(p) {
+                    if r > 0.0 && set.u[k] / r < t1 {
+                        t1 = set.u[k] / r;
+                        l = set.a[k];
+                    }
+                }
+                // full step length: minimum step in primal space such that the constraint ip
+                // becomes feasible
+                let mut t2 = INF;
+                if dot(z, z).abs() > EPS {
+                    t2 = -s[ip] / dot(z, np);
+                    if t2 < 0.0 {
+                        t2 = INF;
+                    }
+                }
+                let t = t1.min(t2);
+
+                // Step 2c: determine new S-pair and take step
+                if t >= INF {
+                    // no step in primal or dual space
+                    return Err(Error::Infeasible {
+                        constraint: describe(ip),
+                    });
+                }
+                let iq = set.iq;
+                if t2 >= INF {
+                    // step in dual space
+                    set.u[..iq]
+                        .iter_mut()
+                        .zip(r.iter())
+                        .for_each(|(u, r)| *u -= t * r);
+                    set.u_added += t;
+                    iai[l as usize] = l;
+                    set.delete_constraint(p, l);
+                    deletions += 1;
+                    continue;
+                }
+
+                // step in primal and dual space
+                x.iter_mut().zip(z.iter()).for_each(|(x, z)| *x += t * z);
+                f_value += t * dot(z, np) * (0.5 * t + set.u_added);
+                set.u[..iq]
+                    .iter_mut()
+                    .zip(r.iter())
+                    .for_each(|(u, r)| *u -= t * r);
+                set.u_added += t;
+
+                if (t - t2).abs() < EPS {
+                    // full step: add constraint ip to the active set
+                    if !set.add_constraint(d) {
+                        iaexcl[ip] = false;
+                        set.delete_constraint(p, ip as isize);
+                        for (i, iai) in iai.iter_mut().enumerate() {
+                            *iai = i as isize;
+                        }
+                        for i in p..set.iq {
+                            set.a[i] = a_old[i];
+                            set.u[i] = u_old[i];
+                            iai[set.a[i] as usize] = -1;
+                        }
+                        x.copy_from_slice(x_old);
+                        break; // go to step 2
+                    }
+                    additions += 1;
+                    iai[ip] = -1;
+                    full_step = true;
+                    break; // go to step 1
+                }
+
+                // partial step: drop constraint l
+                iai[l as usize] = l;
+                set.delete_constraint(p, l);
+                deletions += 1;
+                s[ip] = slack(ip, x);
+            }
+        }
+    }
+}
+
+/// Computes \\(\sqrt{a^2 + b^2}\\) like QuadProg++, which differs from [`f64::hypot`] in the
+/// last bits.
+fn distance(a: f64, b: f64) -> f64 {
+    let (a1, b1) = (a.abs(), b.abs());
+    if a1 > b1 {
+        let t = b1 / a1;
+        a1 * sqrt(1.0 + t * t)
+    } else if b1 > a1 {
+        let t = a1 / b1;
+        b1 * sqrt(1.0 + t * t)
+    } else {
+        a1 * sqrt(2.0)
+    }
+}
+
+/// Computes \\(y \leftarrow y + a x\\).
+fn axpy(a: f64, x: &[f64], y: &mut [f64]) {
+    for (y, x) in y.iter_mut().zip(x) {
+        *y += x * a;
+    }
+}
+
+/// Applies the Givens rotation given by \\(c\\), \\(s\\) and \\(s / (1 + c)\\) to the rows `x` and
+/// `y`, like QuadProg++.
+fn rotate(x: &mut [f64], y: &mut [f64], cc: f64, ss: f64, xny: f64) {
+    for (x, y) in x.iter_mut().zip(y) {
+        let (t1, t2) = (*x, *y);
+        *x = t1 * cc + t2 * ss;
+        *y = xny * (t1 + *x) - t2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    /// Solves the problem with this port, and with QuadProg++ if it's available, checking that
+    /// both agree to the last bit, or closely if QuadProg++ computes in `long double` or the port
+    /// sums dot products in lanes. The C++ backend stays the default until they do on every
+    /// problem here.
+    #[allow(clippy::too_many_arguments)]
+    fn check(
+        n: usize,
+        g: &[f64],
+        g0: &[f64],
+        ce: &[f64],
+        ce0: &[f64],
+        ci: &[f64],
+        ci0: &[f64],
+        expected: &[f64],
+    ) -> f64 {
+        let mut x = vec![0.0; n];
+        let objective = solve(n, g, g0, ce, ce0, ci, ci0, &mut x).unwrap();
+        for (&actual, &expected) in x.iter().zip(expected) {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-9);
+        }
+        #[cfg(feature = "cpp")]
+        {
+            let mut cpp = vec![0.0; n];
+            let cpp_objective =
+                crate::slices::solve_cpp(n, g, g0, ce, ce0, ci, ci0, &mut cpp).unwrap();
+            if quadprogpp_sys::EXTENDED_PRECISION || cfg!(feature = "simd") {
+                assert_abs_diff_eq!(objective, cpp_objective, epsilon = 1e-9);
+                for (&actual, &expected) in x.iter().zip(&cpp) {
+                    assert_abs_diff_eq!(actual, expected, epsilon = 1e-9);
+                }
+            } else {
+                assert_eq!((objective, &x), (cpp_objective, &cpp));
+            }
+        }
+        objective
+    }
+
+    #[test]
+    fn solves_problems() {
+        // The demo of QuadProg++
+        let g = [4.0, -2.0, -2.0, 4.0];
+        let ci = [1.0, 1.0, 0.0, 0.0, 1.0, 1.0];
+        let objective = check(
+            2,
+            &g,
+            &[6.0, 0.0],
+            &[1.0, 1.0],
+            &[-3.0],
+            &ci,
+            &[0.0, -2.0, 0.0],
+            &[1.0, 2.0],
+        );
+        assert_abs_diff_eq!(objective, 12.0, epsilon = 1e-12);
+
+        // min |x - (1, 2, 3)|^2 / 2 s.t. x0 + x1 + x2 <= 3, x0 >= 1.5, x2 - x1 <= 0.5
+        let g = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let ci = [-1.0, 1.0, 0.0, -1.0, 0.0, 1.0, -1.0, 0.0, -1.0];
+        check(
+            3,
+            &g,
+            &[-1.0, -2.0, -3.0],
+            &[],
+            &[],
+            &ci,
+            &[3.0, -1.5, 0.5],
+            &[1.5, 0.5, 1.0],
+        );
+
+        // Degenerate: the same constraint twice, and a redundant one
+        let ci = [1.0, 1.0, 2.0, 1.0, 1.0, 2.0];
+        check(
+            2,
+            &[2.0, 0.0, 0.0, 2.0],
+            &[0.0, 0.0],
+            &[],
+            &[],
+            &ci,
+            &[-1.0, -1.0, -1.0],
+            &[0.5, 0.5],
+        );
+
+        // min |x|^2 s.t. 2 x0 + 2 x1 >= 4, x0 >= 3: the first constraint is dropped by a
+        // partial step
+        check(
+            2,
+            &[2.0, 0.0, 0.0, 2.0],
+            &[0.0, 0.0],
+            &[],
+            &[],
+            &[2.0, 1.0, 2.0, 0.0],
+            &[-4.0, -3.0],
+            &[3.0, 0.0],
+        );
+
+        // A coupled Hessian with an equality constraint, x0 + x1 + x2 = 1, and x0 >= 0.5
+        let g = [4.0, 1.0, 0.0, 1.0, 3.0, 1.0, 0.0, 1.0, 2.0];
+        check(
+            3,
+            &g,
+            &[-1.0, -2.0, -3.0],
+            &[1.0, 1.0, 1.0],
+            &[-1.0],
+            &[1.0, 0.0, 0.0],
+            &[-0.5],
+            &[0.5, -1.0 / 3.0, 5.0 / 6.0],
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut x = [0.0; 2];
+        let identity = [1.0, 0.0, 0.0, 1.0];
+        // x0 <= 0 and x0 >= 1
+        let err = solve(
+            2,
+            &identity,
+            &[0.0; 2],
+            &[],
+            &[],
+            &[-1.0, 1.0, 0.0, 0.0],
+            &[0.0, -1.0],
+            &mut x,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, Error::Infeasible { constraint } if constraint == "inequality constraint 0"),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = solve(2, &[1.0; 4], &[0.0; 2], &[], &[], &[], &[], &mut x).unwrap_err();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+        // x0 + x1 = 1 twice
+        let err = solve(
+            2,
+            &identity,
+            &[0.0; 2],
+            &[1.0; 4],
+            &[-1.0; 2],
+            &[],
+            &[],
+            &mut x,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::LinearlyDependent),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn no_variables() {
+        // 0 x + 1 >= 0 and 0 x >= 0
+        let objective = check(0, &[], &[], &[], &[], &[], &[1.0, 0.0], &[]);
+        assert_eq!(objective.to_bits(), 0.0f64.to_bits());
+
+        // 0 x + 1 >= 0 and 0 x - 1 >= 0
+        let ci0 = [1.0, -1.0];
+        let err = solve(0, &[], &[], &[], &[], &[], &ci0, &mut []).unwrap_err();
+        assert!(
+            matches!(&err, Error::Infeasible { constraint } if constraint == "inequality constraint 1"),
+            "unexpected error: {:?}",
+            err
+        );
+        #[cfg(feature = "cpp")]
+        {
+            let cpp = crate::slices::solve_cpp(0, &[], &[], &[], &[], &[], &ci0, &mut []);
+            assert_eq!(cpp.unwrap_err().to_string(), err.to_string());
+        }
+    }
+
+    #[test]
+    fn rotate_rows() {
+        // A rotation by 90 degrees
+        let (mut x, mut y) = ([1.0, 2.0], [3.0, 4.0]);
+        rotate(&mut x, &mut y, 0.0, 1.0, 1.0);
+        assert_eq!((x, y), ([3.0, 4.0], [1.0, 2.0]));
+    }
+}
@@ -0,0 +1,190 @@
+use ndarray::{Array1, Array2, ArrayBase, Data, Ix1, Ix2, OwnedRepr};
+
+use crate::{solve_with_options, Constraints, Result, Solution, SolveOptions};
+
+/// A problem to which inequality constraints can be added after it's solved, as in cutting-plane
+/// methods.
+///
+/// Each [`add_constraints`](Self::add_constraints) appends the new constraints after the existing
+/// ones and re-solves the problem warm-started with the active set of the previous solution. The
+/// previous active set remains dual feasible, so the solver only has to deal with the new
+/// constraints which the previous solution violates.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// // min (x - 2)^2 + (y - 2)^2 subject to x^2 + y^2 <= 1, outer-approximated by tangent cuts
+/// let g = array![[2.0, 0.0], [0.0, 2.0]];
+/// let g0 = array![-4.0, -4.0];
+/// let mut problem = IncrementalSolver::new(g, g0, Constraints::NONE);
+/// let mut x = problem.solve()?.x.clone();
+/// for _ in 0..20 {
+///     let norm = x.dot(&x).sqrt();
+///     if norm <= 1.0 + 1e-6 {
+///         break;
+///     }
+///     // x^T x / |x| <= 1 at the current point
+///     let cut = Constraints::new((-&x / norm).insert_axis(ndarray::Axis(1)), array![1.0]);
+///     x = problem.add_constraints(cut)?.x.clone();
+/// }
+/// assert!((x[0] - 0.5f64.sqrt()).abs() < 1e-6);
+/// assert!((x[1] - 0.5f64.sqrt()).abs() < 1e-6);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct IncrementalSolver {
+    g: Array2<f64>,
+    g0: Array1<f64>,
+    ce: Option<Constraints<OwnedRepr<f64>, OwnedRepr<f64>>>,
+    ci: Constraints<OwnedRepr<f64>, OwnedRepr<f64>>,
+    options: SolveOptions,
+    solution: Option<Solution>,
+}
+
+impl IncrementalSolver {
+    /// Creates a problem without inequality constraints. The arguments are the same as those of
+    /// [`solve`](crate::solve).
+    pub fn new<G, G0, CE, CE0>(
+        g: ArrayBase<G, Ix2>,
+        g0: ArrayBase<G0, Ix1>,
+        ce: Option<Constraints<CE, CE0>>,
+    ) -> Self
+    where
+        G: Data<Elem = f64>,
+        G0: Data<Elem = f64>,
+        CE: Data<Elem = f64>,
+        CE0: Data<Elem = f64>,
+    {
+        let n = g.nrows();
+        Self {
+            g: g.as_standard_layout().into_owned(),
+            g0: g0.as_standard_layout().into_owned(),
+            ce: ce.map(Constraints::into_owned),
+            ci: Constraints::new(Array2::zeros((n, 0)), Array1::zeros(0)),
+            options: SolveOptions::default(),
+            solution: None,
+        }
+    }
+
+    /// Sets the options used by every solve. The warm-start active set is overridden by the one of
+    /// the previous solution after the first solve.
+    pub fn options(mut self, options: SolveOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns the inequality constraints added so far.
+    pub fn constraints(&self) -> &Constraints<OwnedRepr<f64>, OwnedRepr<f64>> {
+        &self.ci
+    }
+
+    /// Returns the last solution, or `None` if the problem hasn't been solved successfully yet.
+    pub fn solution(&self) -> Option<&Solution> {
+        self.solution.as_ref()
+    }
+
+    /// Solves the problem with the constraints added so far.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`solve_with_options`](crate::solve_with_options).
+    pub fn solve(&mut self) -> Result<&Solution> {
+        let options = match &self.solution {
+            Some(solution) => self.options.clone().warm_start(solution),
+            None => self.options.clone(),
+        };
+        self.solution = None;
+        let solution = solve_with_options(
+            self.g.view(),
+            self.g0.view(),
+            self.ce.as_ref().map(Constraints::view),
+            Some(self.ci.view()),
+            &options,
+        )?;
+        Ok(self.solution.insert(solution))
+    }
+
+    /// Appends inequality constraints and re-solves the problem.
+    ///
+    /// The constraints are appended even if the problem turns out to be infeasible. Indices in
+    /// [`Solution::active_set`] and [`Multipliers::ineq`](crate::Multipliers::ineq) refer to
+    /// [`constraints`](Self::constraints).
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`solve`](Self::solve), this method returns
+    /// [`Error::SizeMismatch`](crate::Error::SizeMismatch) if the constraints don't have one row per
+    /// variable, in which case they aren't appended.
+    pub fn add_constraints<S, S0>(&mut self, ci: Constraints<S, S0>) -> Result<&Solution>
+    where
+        S: Data<Elem = f64>,
+        S0: Data<Elem = f64>,
+    {
+        self.ci = self.ci.stack(&ci)?;
+        self.solve()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+    use crate::{solve, Error};
+
+    #[test]
+    fn matches_solve() -> Result<()> {
+        let g = array![[1.0, 0.0], [0.0, 1.0]];
+        let g0 = array![-2.0, -2.0];
+        let mut problem = IncrementalSolver::new(g.view(), g0.view(), Constraints::NONE);
+        let solution = problem.solve()?;
+        assert_eq!(solution.x, array![2.0, 2.0]);
+
+        // x <= 1, y <= 1, x + y <= 1.5
+        let cuts = [
+            (array![[-1.0], [0.0]], array![1.0]),
+            (array![[0.0], [-1.0]], array![1.0]),
+            (array![[-1.0], [-1.0]], array![1.5]),
+        ];
+        for (k, (coeffs, consts)) in cuts.iter().enumerate() {
+            let solution =
+                problem.add_constraints(Constraints::new(coeffs.view(), consts.view()))?;
+            assert_eq!(solution.warm_started, k > 0);
+        }
+        let solution = problem.solution().unwrap();
+        let expected = solve(
+            g.view(),
+            g0.view(),
+            Constraints::NONE,
+            Constraints::some(
+                array![[-1.0, 0.0, -1.0], [0.0, -1.0, -1.0]],
+                array![1.0, 1.0, 1.5],
+            ),
+        )?;
+        assert_abs_diff_eq!(solution.objective, expected.objective, epsilon = 1e-12);
+        assert_abs_diff_eq!(solution.x[0], expected.x[0], epsilon = 1e-12);
+        assert_abs_diff_eq!(solution.x[1], expected.x[1], epsilon = 1e-12);
+        assert_eq!(problem.constraints().consts().len(), 3);
+
+        let err = problem
+            .add_constraints(Constraints::new(array![[1.0]], array![0.0]))
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    expected: 2,
+                    actual: 1,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        assert_eq!(problem.constraints().consts().len(), 3);
+        Ok(())
+    }
+}
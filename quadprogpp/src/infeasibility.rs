@@ -0,0 +1,144 @@
+use ndarray::{Array1, Array2, Data};
+
+use crate::{solve, Constraints, Error, Result};
+
+/// A minimal set of conflicting constraints returned by [`diagnose_infeasibility`].
+///
+/// The constraints can't be satisfied together, but removing any one of them makes the rest
+/// satisfiable, i.e. they form an irreducible infeasible subset (IIS).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Conflict {
+    /// Indices of the conflicting equality constraints, i.e. columns of \\(CE\\)
+    pub eq: Vec<usize>,
+    /// Indices of the conflicting inequality constraints, i.e. columns of \\(CI\\)
+    pub ineq: Vec<usize>,
+}
+
+/// Identifies a minimal subset of the constraints which can't be satisfied together, to find out
+/// why [`solve`] failed with [`Error::Infeasible`]. Returns `None` if the constraints are
+/// feasible.
+///
+/// The constraints are the same as those of [`solve`]. Feasibility doesn't depend on the
+/// objective function, so this function doesn't take one. It runs the deletion filter: each
+/// constraint is dropped in turn, and stays dropped if the remaining ones are still infeasible.
+/// This takes one feasibility check, i.e. one solve, per constraint. A problem can have several
+/// conflicts, in which case one of them is returned.
+///
+/// Bounds on the variables aren't supported; pass them as constraints built by
+/// [`Constraints::from_bounds`] to include them in the diagnosis.
+///
+/// # Errors
+///
+/// This function returns the errors of [`solve`] other than [`Error::Infeasible`]. In particular,
+/// a conflict among the equality constraints alone makes them linearly dependent, so it's
+/// reported as [`Error::LinearlyDependent`].
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// // x + y = 1, x >= 0, y >= 0, x <= 2, x + y >= 2
+/// let ce = Constraints::some(array![[1.0], [1.0]], array![-1.0]);
+/// let ci = Constraints::some(
+///     array![[1.0, 0.0, -1.0, 1.0], [0.0, 1.0, 0.0, 1.0]],
+///     array![0.0, 0.0, 2.0, -2.0],
+/// );
+/// let g = array![[1.0, 0.0], [0.0, 1.0]];
+/// let g0 = array![0.0, 0.0];
+/// assert!(solve(g, g0, ce.clone(), ci.clone()).is_err());
+///
+/// let conflict = diagnose_infeasibility(ce, ci)?.unwrap();
+/// assert_eq!(conflict.eq, [0]);
+/// assert_eq!(conflict.ineq, [3]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn diagnose_infeasibility<CE, CE0, CI, CI0>(
+    ce: Option<Constraints<CE, CE0>>,
+    ci: Option<Constraints<CI, CI0>>,
+) -> Result<Option<Conflict>>
+where
+    CE: Data<Elem = f64>,
+    CE0: Data<Elem = f64>,
+    CI: Data<Elem = f64>,
+    CI0: Data<Elem = f64>,
+{
+    let (n, p) = ce.as_ref().map_or((0, 0), |ce| ce.coeffs.dim());
+    let (ci_n, m) = ci.as_ref().map_or((n, 0), |ci| ci.coeffs.dim());
+    let n = if ce.is_some() { n } else { ci_n };
+    assert_size!(ci.dim(), n, ci_n);
+    let infeasible = |eq: &[usize], ineq: &[usize]| {
+        let ce = ce
+            .as_ref()
+            .filter(|_| !eq.is_empty())
+            .map(|ce| ce.select(eq));
+        let ci = ci
+            .as_ref()
+            .filter(|_| !ineq.is_empty())
+            .map(|ci| ci.select(ineq));
+        match solve(Array2::eye(n), Array1::zeros(n), ce, ci) {
+            Ok(_) => Ok(false),
+            Err(Error::Infeasible { .. }) => Ok(true),
+            Err(err) => Err(err),
+        }
+    };
+    let mut conflict = Conflict {
+        eq: (0..p).collect(),
+        ineq: (0..m).collect(),
+    };
+    if !infeasible(&conflict.eq, &conflict.ineq)? {
+        return Ok(None);
+    }
+    for j in 0..m {
+        let ineq: Vec<_> = conflict.ineq.iter().copied().filter(|&k| k != j).collect();
+        if infeasible(&conflict.eq, &ineq)? {
+            conflict.ineq = ineq;
+        }
+    }
+    for j in 0..p {
+        let eq: Vec<_> = conflict.eq.iter().copied().filter(|&k| k != j).collect();
+        if infeasible(&eq, &conflict.ineq)? {
+            conflict.eq = eq;
+        }
+    }
+    Ok(Some(conflict))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+    use crate::solve;
+
+    #[test]
+    fn deletion_filter() -> Result<()> {
+        // x >= 1, y >= 1, x + y <= 3, x <= 0, -1 <= x - y <= 1 (ranged)
+        let ci = Constraints::ranged(
+            array![[1.0, 0.0, 1.0, 1.0, 1.0], [0.0, 1.0, 1.0, 0.0, -1.0]],
+            array![1.0, 1.0, f64::NEG_INFINITY, f64::NEG_INFINITY, -1.0],
+            array![f64::INFINITY, f64::INFINITY, 3.0, 0.0, 1.0],
+        )
+        .with_names(vec!["x", "y", "sum", "x_max", "spread"]);
+        let conflict = diagnose_infeasibility(Constraints::NONE, Some(ci.clone()))?.unwrap();
+        assert_eq!(conflict.eq, Vec::<usize>::new());
+        assert_eq!(conflict.ineq, [0, 3]);
+
+        // Without x <= 0, the constraints are feasible.
+        let feasible = ci.select(&[0, 1, 2, 4]);
+        assert_eq!(
+            diagnose_infeasibility(Constraints::NONE, Some(feasible.view()))?,
+            None
+        );
+        let g = array![[1.0, 0.0], [0.0, 1.0]];
+        let g0 = array![0.0, 0.0];
+        assert!(solve(g, g0, Constraints::NONE, Some(feasible)).is_ok());
+
+        // y = 2.5 conflicts with y >= 1, x + y <= 3 and x >= 1 only together.
+        let ce = Constraints::new(array![[0.0], [1.0]], array![-2.5]);
+        let conflict = diagnose_infeasibility(Some(ce), Some(ci.select(&[0, 1, 2])))?.unwrap();
+        assert_eq!(conflict.eq, [0]);
+        assert_eq!(conflict.ineq, [0, 2]);
+        Ok(())
+    }
+}
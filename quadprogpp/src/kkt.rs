@@ -0,0 +1,206 @@
+use ndarray::Array1;
+
+use crate::{Error, QpProblem, Result, Solution};
+
+/// The residuals of the Karush-Kuhn-Tucker conditions at a solution, returned by
+/// [`verify_kkt`].
+///
+/// Each field is the largest absolute violation of one condition, so all of them are zero at an
+/// exact solution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KktReport {
+    /// Stationarity,
+    /// \\(G x + g_0 = CE \, \lambda_{eq} + CI \, \lambda_{ineq} + \lambda_{lower} -
+    /// \lambda_{upper}\\)
+    pub stationarity: f64,
+    /// Primal feasibility, i.e. the violation of the constraints and the bounds
+    pub primal_feasibility: f64,
+    /// Dual feasibility, i.e. the negative part of the multipliers of the inequality constraints
+    /// and the bounds, or their magnitude if the corresponding limit is infinite
+    pub dual_feasibility: f64,
+    /// Complementary slackness, i.e. the products of the multipliers of the inequality
+    /// constraints and the bounds with their slacks
+    pub complementarity: f64,
+    /// The tolerance given to [`verify_kkt`]
+    pub tolerance: f64,
+}
+
+impl KktReport {
+    /// Returns whether all the conditions hold within the tolerance.
+    pub fn is_optimal(&self) -> bool {
+        self.stationarity <= self.tolerance
+            && self.primal_feasibility <= self.tolerance
+            && self.dual_feasibility <= self.tolerance
+            && self.complementarity <= self.tolerance
+    }
+
+    /// Updates the dual feasibility and the complementarity with the part of the multiplier `u`
+    /// which belongs to a limit with the given slack.
+    fn check_multiplier(&mut self, u: f64, slack: f64) {
+        if u <= 0.0 {
+            return;
+        }
+        if slack.is_infinite() {
+            self.dual_feasibility = self.dual_feasibility.max(u);
+        } else {
+            self.complementarity = self.complementarity.max((u * slack).abs());
+        }
+    }
+}
+
+/// Checks that `solution` satisfies the Karush-Kuhn-Tucker optimality conditions of `problem`.
+///
+/// The problem is convex, so the conditions are sufficient: a solution which satisfies them
+/// within `tolerance` is optimal up to that accuracy. This is useful in tests and for catching
+/// numerically bad answers on ill-conditioned problems.
+///
+/// # Errors
+///
+/// If the solution doesn't have one element per variable and one multiplier per constraint and
+/// bound, this function returns [`Error::SizeMismatch`]. This is the case for problems with
+/// [soft](crate::QpProblemBuilder::soft) constraints, since their slack variables are omitted
+/// from the solution.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let problem = QpProblem::new(2)
+///     .hessian(array![[4.0, -2.0], [-2.0, 4.0]])
+///     .linear(array![6.0, 0.0])
+///     .eq(Constraints::new(array![[1.0], [1.0]], array![-3.0]))
+///     .ineq(Constraints::new(array![[1.0, 1.0, 0.0], [0.0, 1.0, 1.0]], array![0.0, -2.0, 0.0]))
+///     .build()?;
+/// let mut solution = problem.solve()?;
+/// assert!(verify_kkt(&problem, &solution, 1e-12)?.is_optimal());
+///
+/// solution.x[0] += 1e-3;
+/// let report = verify_kkt(&problem, &solution, 1e-12)?;
+/// assert!(!report.is_optimal());
+/// assert!((report.primal_feasibility - 1e-3).abs() < 1e-12);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn verify_kkt(problem: &QpProblem, solution: &Solution, tolerance: f64) -> Result<KktReport> {
+    let (n, p, m) = problem.dim();
+    let x = &solution.x;
+    let multipliers = &solution.multipliers;
+    assert_size!(solution.x, n, x.len());
+    assert_size!(solution.multipliers.eq, p, multipliers.eq.len());
+    assert_size!(solution.multipliers.ineq, m, multipliers.ineq.len());
+    assert_size!(solution.multipliers.lower, n, multipliers.lower.len());
+    assert_size!(solution.multipliers.upper, n, multipliers.upper.len());
+
+    let mut report = KktReport {
+        stationarity: 0.0,
+        primal_feasibility: 0.0,
+        dual_feasibility: 0.0,
+        complementarity: 0.0,
+        tolerance,
+    };
+    let mut gradient = problem.hessian().dot(x) + problem.linear();
+    gradient -= &Array1::from(multipliers.lower.clone());
+    gradient += &Array1::from(multipliers.upper.clone());
+    if let Some(ce) = problem.eq() {
+        gradient -= &ce.coeffs.dot(&Array1::from(multipliers.eq.clone()));
+        report.primal_feasibility = ce.violations(x)?.max_residual();
+    }
+    if let Some(ci) = problem.ineq() {
+        gradient -= &ci.coeffs.dot(&Array1::from(multipliers.ineq.clone()));
+        let violations = ci.violations(x)?;
+        report.primal_feasibility = report.primal_feasibility.max(violations.max);
+        for (j, (&r, &u)) in violations
+            .residuals
+            .iter()
+            .zip(&multipliers.ineq)
+            .enumerate()
+        {
+            // A positive multiplier belongs to the lower limit and a negative one to the upper
+            // limit, which is infinite unless the constraints are ranged.
+            let (ax, lower, upper) = match &ci.upper {
+                Some(upper) => (r, ci.consts[j], upper[j]),
+                None => (r - ci.consts[j], -ci.consts[j], f64::INFINITY),
+            };
+            report.check_multiplier(u, ax - lower);
+            report.check_multiplier(-u, upper - ax);
+        }
+    }
+    let bounds = problem.bounds();
+    for i in 0..n {
+        let lower = bounds
+            .lower_bounds()
+            .get(i)
+            .copied()
+            .unwrap_or(f64::NEG_INFINITY);
+        let upper = bounds
+            .upper_bounds()
+            .get(i)
+            .copied()
+            .unwrap_or(f64::INFINITY);
+        report.primal_feasibility = report
+            .primal_feasibility
+            .max(lower - x[i])
+            .max(x[i] - upper);
+        for (u, slack) in [
+            (multipliers.lower[i], x[i] - lower),
+            (multipliers.upper[i], upper - x[i]),
+        ] {
+            report.dual_feasibility = report.dual_feasibility.max(-u);
+            report.check_multiplier(u, slack);
+        }
+    }
+    report.stationarity = gradient.iter().fold(0.0, |max, g| max.max(g.abs()));
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+    use crate::{Bounds, Constraints, Objective};
+
+    #[test]
+    fn ranged_and_bounds() -> Result<()> {
+        // max -(x - 3)^2 - (y + 1)^2 - z^2 s.t. 1 <= x + y <= 1.5, y >= 0, z >= 0.5
+        let problem = QpProblem::new(3)
+            .hessian(array![[-2.0, 0.0, 0.0], [0.0, -2.0, 0.0], [0.0, 0.0, -2.0]])
+            .linear(array![6.0, -2.0, 0.0])
+            .ineq(Constraints::ranged(
+                array![[1.0], [1.0], [0.0]],
+                array![1.0],
+                array![1.5],
+            ))
+            .bounds(Bounds::lower(array![f64::NEG_INFINITY, 0.0, 0.5]))
+            .objective(Objective::Maximize)
+            .build()?;
+        let solution = problem.solve()?;
+        assert!(solution.multipliers.ineq[0] < 0.0);
+        assert!(solution.multipliers.lower[1] > 0.0);
+        assert!(solution.multipliers.lower[2] > 0.0);
+        let report = verify_kkt(&problem, &solution, 1e-12)?;
+        assert!(report.is_optimal(), "{:?}", report);
+
+        // The multiplier of the binding upper limit at the lower limit
+        let mut wrong = solution.clone();
+        wrong.multipliers.ineq[0] = -wrong.multipliers.ineq[0];
+        let report = verify_kkt(&problem, &wrong, 1e-12)?;
+        assert!(report.stationarity > 1.0);
+        assert!(report.complementarity > 0.1);
+
+        // A negative multiplier of a bound
+        let mut wrong = solution.clone();
+        wrong.multipliers.lower[2] = -1.0;
+        let report = verify_kkt(&problem, &wrong, 1e-12)?;
+        assert_eq!(report.dual_feasibility, 1.0);
+
+        // A multiplier of an infinite bound
+        let mut wrong = solution;
+        wrong.multipliers.lower[0] = 0.5;
+        let report = verify_kkt(&problem, &wrong, 1e-12)?;
+        assert_eq!(report.dual_feasibility, 0.5);
+        assert_abs_diff_eq!(report.stationarity, 0.5, epsilon = 1e-12);
+        Ok(())
+    }
+}
@@ -1,39 +1,464 @@
 //! Rust bindings to [`QuadProg++`](https://github.com/liuq/QuadProgpp) which is a library for
 //! Quadratic Programming implementing the [Goldfarb-Idnani active-set dual method](https://citeseerx.ist.psu.edu/viewdoc/summary?doi=10.1.1.521.6352).
+//!
+//! Without the default `std` feature, the crate is `no_std` and requires `alloc`. It then only
+//! provides [`solve_slices`], [`solve_slices_f32`] and `StaticSolver`, by a pure-Rust port of
+//! QuadProg++.
 
-use std::ptr;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-use ndarray::{ArrayBase, Ix1, Ix2, OwnedRepr, RawData};
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(feature = "serde")]
+extern crate serde_crate as serde;
+
+#[cfg(all(feature = "ndarray", not(feature = "cpp")))]
+compile_error!("the ndarray feature requires the cpp feature");
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, format, string::String};
+use core::fmt;
+
+#[cfg(feature = "ndarray")]
+use ndarray::{
+    Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Axis, Data, Ix1, Ix2, OwnedRepr, RawData,
+    RawDataClone, ViewRepr,
+};
+#[cfg(feature = "cpp")]
 use quadprogpp_sys as sys;
-use thiserror::Error;
+
+macro_rules! assert_size {
+    ($term:expr, $expected:expr, $actual:expr) => {
+        if $expected != $actual {
+            return Err(Error::SizeMismatch {
+                term: stringify!($term),
+                expected: $expected,
+                actual: $actual,
+                shapes: None,
+            });
+        }
+    };
+    ($term:expr, $expected:expr, $actual:expr, $shapes:expr) => {
+        if $expected != $actual {
+            return Err(Error::SizeMismatch {
+                term: stringify!($term),
+                expected: $expected,
+                actual: $actual,
+                shapes: Some(Box::new($shapes)),
+            });
+        }
+    };
+}
+
+#[cfg(feature = "ndarray")]
+macro_rules! assert_finite {
+    ($term:expr) => {
+        assert_finite!($term, $term)
+    };
+    ($term:expr, $values:expr) => {
+        if !$values.iter().all(|x| x.is_finite()) {
+            return Err(Error::NonFinite {
+                term: stringify!($term),
+            });
+        }
+    };
+}
+
+#[cfg(feature = "ndarray")]
+macro_rules! assert_not_nan {
+    ($term:expr) => {
+        assert_not_nan!($term, $term)
+    };
+    ($term:expr, $values:expr) => {
+        if $values.iter().any(|x| x.is_nan()) {
+            return Err(Error::NonFinite {
+                term: stringify!($term),
+            });
+        }
+    };
+}
+
+#[cfg(feature = "ndarray")]
+mod batch;
+#[cfg(feature = "ndarray")]
+mod block;
+#[cfg(feature = "ndarray")]
+mod bounds;
+#[cfg(feature = "ndarray")]
+pub mod certify;
+#[cfg(feature = "ndarray")]
+pub mod clarabel;
+#[cfg(feature = "ndarray")]
+mod convert;
+#[cfg(feature = "ndarray")]
+mod direct;
+#[cfg(feature = "ndarray")]
+mod future;
+#[cfg(feature = "ndarray")]
+mod incremental;
+#[cfg(feature = "ndarray")]
+mod infeasibility;
+#[cfg(feature = "ndarray")]
+mod kkt;
+#[cfg(feature = "ndarray")]
+mod lsq;
+#[cfg(feature = "ndarray")]
+mod options;
+#[cfg(feature = "ndarray")]
+pub mod osqp;
+#[cfg(feature = "ndarray")]
+mod polish;
+#[cfg(feature = "ndarray")]
+mod presolve;
+#[cfg(feature = "ndarray")]
+mod problem;
+#[cfg(feature = "ndarray")]
+mod qp_backend;
+#[cfg(feature = "ndarray")]
+mod scaling;
+#[cfg(feature = "ndarray")]
+mod service;
+#[cfg(feature = "ndarray")]
+mod solution;
+#[cfg(feature = "ndarray")]
+mod solver;
+#[cfg(feature = "ndarray")]
+mod sparse;
+#[cfg(feature = "ndarray")]
+pub mod sqp;
+#[cfg(feature = "ndarray")]
+mod standard;
+#[cfg(feature = "ndarray")]
+pub mod utils;
+#[cfg(feature = "ndarray")]
+mod violations;
+
+#[cfg(feature = "ndarray")]
+pub use batch::{par_solve_batch, solve_batch, solve_batch_with_options};
+#[cfg(feature = "ndarray")]
+pub use block::BlockDiagHessian;
+#[cfg(feature = "ndarray")]
+pub use bounds::Bounds;
+#[cfg(feature = "ndarray")]
+pub use convert::{IntoQpMatrix, IntoQpVector};
+#[cfg(feature = "ndarray")]
+pub use future::{solve_async, SolveFuture};
+#[cfg(feature = "ndarray")]
+pub use incremental::IncrementalSolver;
+#[cfg(feature = "ndarray")]
+pub use infeasibility::{diagnose_infeasibility, Conflict};
+#[cfg(feature = "ndarray")]
+pub use kkt::{verify_kkt, KktReport};
+#[cfg(feature = "ndarray")]
+pub use lsq::{solve_lsq, solve_lsq_with_options, LsqMethod};
+#[cfg(feature = "ndarray")]
+pub use options::{Progress, SolveOptions};
+#[cfg(feature = "ndarray")]
+pub use problem::{Objective, Penalty, QpProblem, QpProblemBuilder};
+#[cfg(all(feature = "ndarray", feature = "pure-rust"))]
+pub use qp_backend::GoldfarbIdnani;
+#[cfg(feature = "ndarray")]
+pub use qp_backend::{BackendChain, QpBackend, QuadProgpp};
+#[cfg(feature = "ndarray")]
+pub use service::SolverService;
+#[cfg(feature = "ndarray")]
+pub use solution::{Multipliers, Solution, Status};
+#[cfg(feature = "ndarray")]
+pub use solver::Solver;
+#[cfg(feature = "ndarray")]
+pub use sparse::SparseHessian;
+#[cfg(feature = "ndarray")]
+pub use standard::solve_standard;
+#[cfg(feature = "ndarray")]
+pub use violations::Violations;
+
+mod backend;
+#[cfg(feature = "cdylib")]
+pub mod capi;
+#[cfg(any(test, not(feature = "cpp"), feature = "pure-rust"))]
+mod goldfarb_idnani;
+#[cfg(any(feature = "ndarray", test, not(feature = "cpp"), feature = "pure-rust"))]
+mod linalg;
+#[cfg(feature = "sensitivity")]
+mod sensitivity;
+mod slices;
+#[cfg(any(not(feature = "cpp"), feature = "pure-rust"))]
+mod static_solver;
+
+pub use backend::{backend_info, BackendInfo};
+#[cfg(feature = "sensitivity")]
+pub use sensitivity::{Gradients, Sensitivity};
+pub use slices::{solve_slices, solve_slices_f32};
+#[cfg(any(not(feature = "cpp"), feature = "pure-rust"))]
+pub use static_solver::StaticSolver;
+#[cfg(feature = "trace-solver")]
+pub use sys::{set_trace_handler, TraceHandler};
 
 /// The type returned by [`solve`].
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 /// Errors that can happen in [`solve`]
-#[derive(Debug, Error)]
+///
+/// With the `serde` feature, errors can be serialized, e.g. to report them from a remote solver.
+/// They can't be deserialized, since [`Error::SizeMismatch`] and [`Error::NonFinite`] refer to
+/// the arrays by static strings.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(crate = "serde"))]
 pub enum Error {
-    /// The problem has no feasible solution
-    #[error("no feasible solution")]
-    Infeasible,
-    /// The given metrices and vectors have inconsistent dimentionalities.
-    #[error("size mismatch on {term} (expected: {expected:?}, found: {actual:?})")]
+    /// The problem has no feasible solution. `constraint` describes the constraint or bound which
+    /// the solver failed to satisfy, by its name if it has one. The conflict usually involves
+    /// other constraints too, which [`diagnose_infeasibility`] identifies.
+    Infeasible { constraint: String },
+    /// The solver didn't converge within the iteration limit set by
+    /// [`SolveOptions::max_iterations`]. See [`SolveOptions::best_effort`] to get the last
+    /// iterate instead.
+    MaxIterations { iterations: usize },
+    /// The solver was stopped by [`SolveOptions::deadline`] or [`SolveOptions::cancellation`]
+    /// after `iterations` iterations.
+    Interrupted { iterations: usize },
+    /// The solver exceeded the limit of [`SolveOptions::max_degenerate_steps`] after
+    /// `iterations` iterations, i.e. it's likely cycling on a degenerate problem. See
+    /// [`SolveOptions::anti_cycling`].
+    Degenerate { iterations: usize },
+    /// The given metrices and vectors have inconsistent dimentionalities. `shapes` holds the
+    /// shapes of all the arrays of the problem if the error comes from a solver, in which case
+    /// the message also points out a constraint matrix which looks transposed.
     SizeMismatch {
         term: &'static str,
         expected: usize,
         actual: usize,
+        shapes: Option<Box<Shapes>>,
     },
-    #[error("non-standard layout matrix on {term}")]
+    /// Arrays which aren't in standard layout, e.g. transposed or sliced views, used to be
+    /// rejected with this error. They are copied now, so it's never returned.
+    #[deprecated(note = "arrays in any layout are accepted")]
     NonStandardLayout { term: &'static str },
-    /// FFI error
-    #[error("ffi error ({reason:?})")]
+    /// The Hessian isn't symmetric. `row` and `col` locate the element in the lower triangle
+    /// with the largest relative difference from its transpose.
+    NotSymmetric { row: usize, col: usize },
+    /// The Hessian isn't positive definite. `pivot` is the index of the nonpositive pivot found
+    /// by the Cholesky factorization, i.e. the leading `pivot + 1` rows and columns of \\(G\\)
+    /// are singular or indefinite. See [`SolveOptions::lp_fallback`] for problems with
+    /// \\(G = 0\\).
+    SingularHessian { pivot: usize },
+    /// A matrix or vector contains an infinite or NaN element.
+    NonFinite { term: &'static str },
+    /// A constraint index is out of range.
+    IndexOutOfRange { index: usize, len: usize },
+    /// The weight of a [`Penalty`] isn't positive and finite.
+    InvalidPenalty { weight: f64 },
+    /// The KKT system at the solution is singular, e.g. because the active constraints are
+    /// linearly dependent.
+    Singular,
+    /// Ranged constraints were given as equality constraints.
+    RangedEquality,
+    /// The equality constraints, together with the active inequality constraints, are linearly
+    /// dependent, or there are more equality constraints than variables.
+    LinearlyDependent,
+    /// QuadProg++ rejected the dimensions of the problem. The solvers check the dimensions
+    /// beforehand and return [`Error::SizeMismatch`], so this indicates a bug in this crate.
+    InvalidDimensions { reason: String },
+    /// An array of the problem has more rows, columns or elements than QuadProg++ can index,
+    /// i.e. more than `u32::MAX`. `len` is the number of elements, saturated at `usize::MAX`.
+    TooLarge { term: &'static str, len: usize },
+    /// [`Solver::solve_for`] was called before any Hessian was factorized.
+    NotFactorized,
+    /// A [`QpBackend`] doesn't implement an option which is set in [`SolveOptions`], e.g. the
+    /// pure-Rust backend only supports the options on the Hessian.
+    Unsupported {
+        backend: &'static str,
+        option: &'static str,
+    },
+    /// FFI error which doesn't fall into any of the other variants
     Ffi { reason: String },
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Infeasible { constraint } => write!(
+                f,
+                "no feasible solution ({} can't be satisfied)",
+                constraint
+            ),
+            Error::MaxIterations { iterations } => {
+                write!(f, "no solution within {} iterations", iterations)
+            }
+            Error::Interrupted { iterations } => {
+                write!(f, "interrupted after {} iterations", iterations)
+            }
+            Error::Degenerate { iterations } => write!(
+                f,
+                "no progress on a degenerate problem after {} iterations",
+                iterations
+            ),
+            Error::SizeMismatch {
+                term,
+                expected,
+                actual,
+                shapes,
+            } => write!(
+                f,
+                "size mismatch on {} (expected: {:?}, found: {:?}){}",
+                term,
+                expected,
+                actual,
+                Shapes::describe(shapes)
+            ),
+            #[allow(deprecated)]
+            Error::NonStandardLayout { term } => {
+                write!(f, "non-standard layout matrix on {}", term)
+            }
+            Error::NotSymmetric { row, col } => {
+                write!(f, "asymmetric hessian at ({}, {})", row, col)
+            }
+            Error::SingularHessian { pivot } => write!(
+                f,
+                "hessian isn't positive definite (nonpositive pivot at {})",
+                pivot
+            ),
+            Error::NonFinite { term } => write!(f, "non-finite element in {}", term),
+            Error::IndexOutOfRange { index, len } => write!(
+                f,
+                "constraint index {} out of range (number of constraints: {})",
+                index, len
+            ),
+            Error::InvalidPenalty { weight } => write!(f, "invalid penalty weight {}", weight),
+            Error::Singular => f.write_str("singular KKT system"),
+            Error::RangedEquality => {
+                f.write_str("ranged constraints can't be equality constraints")
+            }
+            Error::LinearlyDependent => f.write_str("constraints are linearly dependent"),
+            Error::InvalidDimensions { reason } => write!(f, "invalid dimensions ({})", reason),
+            Error::TooLarge { term, len } => {
+                write!(f, "{} is too large for QuadProg++ ({} elements)", term, len)
+            }
+            Error::NotFactorized => f.write_str("no factorization of the hessian to reuse"),
+            Error::Unsupported { backend, option } => {
+                write!(f, "{} isn't supported by the {} backend", option, backend)
+            }
+            Error::Ffi { reason } => write!(f, "ffi error ({:?})", reason),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// The shapes of the arrays passed to a solver, attached to [`Error::SizeMismatch`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub struct Shapes {
+    /// Shape of \\(G\\), or `None` if the factorization of a previous Hessian was reused
+    pub g: Option<(usize, usize)>,
+    /// Length of \\(g_0\\)
+    pub g0: usize,
+    /// Shapes of \\(CE\\) and \\(ce_0\\), if there are equality constraints
+    pub ce: Option<((usize, usize), usize)>,
+    /// Shapes of \\(CI\\) and \\(ci_0\\), if there are inequality constraints
+    pub ci: Option<((usize, usize), usize)>,
+}
+
+impl Shapes {
+    /// Returns `"ce"` or `"ci"` if the corresponding constraint matrix looks transposed, i.e. it's
+    /// \\(P \times N\\) or \\(M \times N\\) instead of having one row per variable.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "ndarray", doc = "```")]
+    #[cfg_attr(not(feature = "ndarray"), doc = "```ignore")]
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let g = array![[1.0, 0.0], [0.0, 1.0]];
+    /// let g0 = array![0.0, 0.0];
+    /// let ci = Constraints::some(array![[1.0, 1.0]], array![-1.0]);
+    /// match solve(g, g0, Constraints::NONE, ci).unwrap_err() {
+    ///     Error::SizeMismatch { shapes: Some(shapes), .. } => {
+    ///         assert_eq!(shapes.transposed(), Some("ci"));
+    ///     }
+    ///     err => panic!("unexpected error: {:?}", err),
+    /// }
+    /// ```
+    pub fn transposed(&self) -> Option<&'static str> {
+        let n = self.g.map_or(self.g0, |(n, _)| n);
+        let looks_transposed = |shape| match shape {
+            Some(((rows, cols), len)) => rows != n && cols == n && rows == len,
+            None => false,
+        };
+        if looks_transposed(self.ce) {
+            Some("ce")
+        } else if looks_transposed(self.ci) {
+            Some("ci")
+        } else {
+            None
+        }
+    }
+
+    /// Formats the shapes and the hint for the message of [`Error::SizeMismatch`].
+    fn describe(shapes: &Option<Box<Shapes>>) -> String {
+        let shapes = match shapes {
+            Some(shapes) => shapes,
+            None => return String::new(),
+        };
+        let mut description = format!(" with shapes {}", shapes);
+        if let Some(term) = shapes.transposed() {
+            description += &format!(
+                "; {} looks transposed, it should have one row per variable",
+                term
+            );
+        }
+        description
+    }
+}
+
+impl fmt::Display for Shapes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((rows, cols)) = self.g {
+            write!(f, "g: {}x{}, ", rows, cols)?;
+        }
+        write!(f, "g0: {}", self.g0)?;
+        for (name, shape) in [("ce", self.ce), ("ci", self.ci)] {
+            if let Some(((rows, cols), len)) = shape {
+                write!(f, ", {}: {}x{}, {}0: {}", name, rows, cols, name, len)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts the shape of an `n x m` array to the dimensions taken by QuadProg++, which indexes
+/// the elements of a matrix with `u32` too.
+#[cfg(feature = "cpp")]
+pub(crate) fn ffi_dim(term: &'static str, n: usize, m: usize) -> Result<(u32, u32)> {
+    use std::convert::TryFrom;
+
+    let len = n.saturating_mul(m);
+    match (u32::try_from(n), u32::try_from(m), u32::try_from(len)) {
+        (Ok(n), Ok(m), Ok(_)) => Ok((n, m)),
+        _ => Err(Error::TooLarge { term, len }),
+    }
+}
+
+#[cfg(feature = "cpp")]
 impl From<sys::Exception> for Error {
     fn from(exception: sys::Exception) -> Self {
-        Self::Ffi {
-            reason: format!("{}", exception),
+        let reason = exception.what();
+        if reason == "Constraints are linearly dependent" {
+            Self::LinearlyDependent
+        } else if reason == "No factorization of G to reuse" {
+            Self::NotFactorized
+        } else if reason.contains("is not a squared matrix") || reason.contains("is incompatible") {
+            Self::InvalidDimensions {
+                reason: reason.to_string(),
+            }
+        } else {
+            Self::Ffi {
+                reason: reason.to_string(),
+            }
         }
     }
 }
@@ -41,14 +466,68 @@ impl From<sys::Exception> for Error {
 /// Equality/inequality constraints.
 ///
 /// The coefficients matrix should be an NxP matric where N is the number of variables and P is
-/// the number of constraints. The constants should be a vector of length P.
+/// the number of constraints. The constants should be a vector of length P. A PxN matrix with a
+/// constraint in each row can be given to [`from_rows`](Self::from_rows).
+///
+/// Inequality constraints can also be ranged, i.e. \\(l \le A^\intercal x \le u\\). See
+/// [`ranged`](Self::ranged).
+#[cfg(feature = "ndarray")]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S: Data, S0: Data",
+        deserialize = "S: ndarray::DataOwned, S0: ndarray::DataOwned"
+    ))
+)]
 pub struct Constraints<S: RawData<Elem = f64>, S0: RawData<Elem = f64>> {
     /// Coefficient part of the constraints
     coeffs: ArrayBase<S, Ix2>,
-    /// Constant terms of the constraints
+    /// Constant terms of the constraints, or the lower limits of ranged constraints
     consts: ArrayBase<S0, Ix1>,
+    /// Upper limits of ranged constraints
+    upper: Option<ArrayBase<S0, Ix1>>,
+    /// Names of the constraints, used in errors
+    names: Vec<String>,
+}
+
+#[cfg(feature = "ndarray")]
+impl<S, S0> Clone for Constraints<S, S0>
+where
+    S: RawDataClone<Elem = f64>,
+    S0: RawDataClone<Elem = f64>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            coeffs: self.coeffs.clone(),
+            consts: self.consts.clone(),
+            upper: self.upper.clone(),
+            names: self.names.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl<S, S0> fmt::Debug for Constraints<S, S0>
+where
+    S: Data<Elem = f64>,
+    S0: Data<Elem = f64>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Constraints")
+            .field("coeffs", &self.coeffs)
+            .field("consts", &self.consts)
+            .field("upper", &self.upper)
+            .field("names", &self.names)
+            .finish()
+    }
 }
 
+#[cfg(feature = "ndarray")]
 impl Constraints<OwnedRepr<f64>, OwnedRepr<f64>> {
     /// Empty constraints
     ///
@@ -63,8 +542,125 @@ impl Constraints<OwnedRepr<f64>, OwnedRepr<f64>> {
     /// assert!(ce.is_none());
     /// ```
     pub const NONE: Option<Self> = None;
+
+    /// Creates inequality constraints equivalent to the bounds \\(lb \le x \le ub\\).
+    ///
+    /// There's a constraint \\(x_i - lb_i \ge 0\\) for each finite lower bound followed by a
+    /// constraint \\(-x_i + ub_i \ge 0\\) for each finite upper bound. Infinite bounds are
+    /// omitted. Unlike [`Bounds`], the constraints can be combined with other constraints.
+    ///
+    /// # Errors
+    ///
+    /// If `lb` and `ub` have different lengths, this function returns [`Error::SizeMismatch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let ci = Constraints::from_bounds(&array![0.0, f64::NEG_INFINITY], &array![1.0, 2.0])?;
+    /// assert_eq!(ci.coeffs(), array![[1.0, -1.0, 0.0], [0.0, 0.0, -1.0]]);
+    /// assert_eq!(ci.consts(), array![0.0, 1.0, 2.0]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn from_bounds<L, U>(lb: &ArrayBase<L, Ix1>, ub: &ArrayBase<U, Ix1>) -> Result<Self>
+    where
+        L: Data<Elem = f64>,
+        U: Data<Elem = f64>,
+    {
+        let n = lb.len();
+        assert_size!(ub, n, ub.len());
+        let lower = lb.iter().enumerate().filter(|(_, b)| b.is_finite());
+        let upper = ub.iter().enumerate().filter(|(_, b)| b.is_finite());
+        let m = lower.clone().count() + upper.clone().count();
+        let mut coeffs = Array2::zeros((n, m));
+        let mut consts = Array1::zeros(m);
+        let columns = lower
+            .map(|(i, &b)| (i, 1.0, -b))
+            .chain(upper.map(|(i, &b)| (i, -1.0, b)));
+        for (j, (i, sign, c)) in columns.enumerate() {
+            coeffs[[i, j]] = sign;
+            consts[j] = c;
+        }
+        Ok(Self::new(coeffs, consts))
+    }
+
+    /// Creates constraints whose coefficient matrix, with `n` rows, is given in compressed sparse
+    /// column (CSC) format. The row indices and the values of the coefficients of constraint `j`
+    /// are `row_indices[col_offsets[j]..col_offsets[j + 1]]` and the same range of `values`.
+    ///
+    /// This is the storage of `sprs::CsMat` in CSC order, i.e. its `indptr()`, `indices()` and
+    /// `data()`, and of `nalgebra_sparse::CscMatrix`. QuadProg++ is dense, so the matrix is
+    /// densified. See [`utils::hessian_from_csc`] for \\(G\\).
+    ///
+    /// # Errors
+    ///
+    /// If the length of `col_offsets` isn't the number of constraints plus one, or the offsets or
+    /// the row indices are out of range, this function returns [`Error::SizeMismatch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // x0 + x2 >= 1 and x1 >= 0
+    /// let ci = Constraints::from_csc(3, &[0, 2, 3], &[0, 2, 1], &[1.0, 1.0, 1.0], array![-1.0, 0.0])?;
+    /// assert_eq!(ci.coeffs(), array![[1.0, 0.0], [0.0, 1.0], [1.0, 0.0]]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn from_csc(
+        n: usize,
+        col_offsets: &[usize],
+        row_indices: &[usize],
+        values: &[f64],
+        consts: Array1<f64>,
+    ) -> Result<Self> {
+        let coeffs = utils::csc_to_dense((n, consts.len()), col_offsets, row_indices, values)?;
+        Ok(Self::new(coeffs, consts))
+    }
+
+    /// Creates `m` constraints on `n` variables from the nonzero coefficients given as
+    /// `(variable, constraint, value)` triplets, i.e. in coordinate (COO) format. Duplicate
+    /// triplets are summed.
+    ///
+    /// # Errors
+    ///
+    /// If the length of `consts` isn't `m`, or a triplet is out of range, this function returns
+    /// [`Error::SizeMismatch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // x0 + x2 >= 1 and x1 >= 0
+    /// let triplets = [(0, 0, 1.0), (2, 0, 1.0), (1, 1, 1.0)];
+    /// let ci = Constraints::from_triplets(3, 2, &triplets, array![-1.0, 0.0])?;
+    /// assert_eq!(ci.coeffs(), array![[1.0, 0.0], [0.0, 1.0], [1.0, 0.0]]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn from_triplets(
+        n: usize,
+        m: usize,
+        triplets: &[(usize, usize, f64)],
+        consts: Array1<f64>,
+    ) -> Result<Self> {
+        assert_size!(consts, m, consts.len());
+        let mut coeffs = Array2::zeros((n, m));
+        for &(i, j, value) in triplets {
+            if i >= n {
+                assert_size!(variable, n, i + 1);
+            }
+            if j >= m {
+                assert_size!(constraint, m, j + 1);
+            }
+            coeffs[[i, j]] += value;
+        }
+        Ok(Self::new(coeffs, consts))
+    }
 }
 
+#[cfg(feature = "ndarray")]
 impl<S, S0> Constraints<S, S0>
 where
     S: RawData<Elem = f64>,
@@ -72,7 +668,93 @@ where
 {
     /// Creates a new set of constraints
     pub fn new(coeffs: ArrayBase<S, Ix2>, consts: ArrayBase<S0, Ix1>) -> Self {
-        Self { coeffs, consts }
+        Self {
+            coeffs,
+            consts,
+            upper: None,
+            names: Vec::new(),
+        }
+    }
+
+    /// Creates a new set of constraints from a PxN coefficient matrix, where each row is one
+    /// constraint, i.e. the transpose of the coefficient matrix of [`new`](Self::new).
+    ///
+    /// The matrix is transposed without copying it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // x + y - 1 >= 0 and x - 2 y >= 0
+    /// let ci = Constraints::from_rows(array![[1.0, 1.0], [1.0, -2.0]], array![-1.0, 0.0]);
+    /// assert_eq!(ci.coeffs(), array![[1.0, 1.0], [1.0, -2.0]].t());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn from_rows(coeffs: ArrayBase<S, Ix2>, consts: ArrayBase<S0, Ix1>) -> Self {
+        Self::new(coeffs.reversed_axes(), consts)
+    }
+
+    /// Creates ranged inequality constraints \\(lower \le A^\intercal x \le upper\\), where
+    /// \\(A\\) is `coeffs`.
+    ///
+    /// Infinite limits are ignored, so one-sided constraints can be mixed with ranged ones. Each
+    /// constraint has a single multiplier in [`Multipliers::ineq`], which is positive if the lower
+    /// limit is active and negative if the upper limit is active. A ranged constraint given to
+    /// [`SolveOptions::active_set`] warm-starts the solver with its lower limit, unless it's
+    /// infinite.
+    ///
+    /// Ranged constraints can only be inequality constraints.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // 1 <= x + y <= 2
+    /// let ci = Constraints::ranged(array![[1.], [1.]], array![1.], array![2.]);
+    /// let solution = solve(array![[1., 0.], [0., 1.]], array![-2., -2.], Constraints::NONE, Some(ci))?;
+    /// assert_eq!(solution.x, array![1., 1.]);
+    /// assert_eq!(solution.multipliers.ineq, [-1.]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn ranged(
+        coeffs: ArrayBase<S, Ix2>,
+        lower: ArrayBase<S0, Ix1>,
+        upper: ArrayBase<S0, Ix1>,
+    ) -> Self {
+        Self {
+            coeffs,
+            consts: lower,
+            upper: Some(upper),
+            names: Vec::new(),
+        }
+    }
+
+    /// Names the constraints, one name per constraint. The names are used in errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // x <= 1 and x >= 2
+    /// let ci = Constraints::new(array![[-1., 1.]], array![1., -2.]).with_names(["cap", "floor"]);
+    /// let err = solve(array![[1.]], array![0.], Constraints::NONE, Some(ci)).unwrap_err();
+    /// assert_eq!(err.to_string(), "no feasible solution (constraint 'cap' can't be satisfied)");
+    /// ```
+    pub fn with_names<I>(mut self, names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the names of the constraints, or an empty slice if they aren't named.
+    pub fn names(&self) -> &[String] {
+        &self.names
     }
 
     /// Create a new set of constraints wrapped with Option.
@@ -88,28 +770,210 @@ where
     pub fn some(coeffs: ArrayBase<S, Ix2>, consts: ArrayBase<S0, Ix1>) -> Option<Self> {
         Some(Self::new(coeffs, consts))
     }
+
+    /// Returns the shapes of the coefficient matrix and the constants.
+    pub(crate) fn shape(&self) -> ((usize, usize), usize) {
+        (self.coeffs.dim(), self.consts.dim())
+    }
 }
 
-macro_rules! assert_size {
-    ($term:expr, $expected:expr, $actual:expr) => {
-        if $expected != $actual {
-            return Err(Error::SizeMismatch {
-                term: stringify!($term),
-                expected: $expected,
-                actual: $actual,
-            });
+#[cfg(feature = "ndarray")]
+impl<S, S0> Constraints<S, S0>
+where
+    S: Data<Elem = f64>,
+    S0: Data<Elem = f64>,
+{
+    /// Returns a view of the coefficient matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let ce = Constraints::new(array![[1.], [1.]], array![-3.]);
+    /// assert_eq!(ce.coeffs(), array![[1.], [1.]]);
+    /// ```
+    pub fn coeffs(&self) -> ArrayView2<'_, f64> {
+        self.coeffs.view()
+    }
+
+    /// Returns a view of the constant terms.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let ce = Constraints::new(array![[1.], [1.]], array![-3.]);
+    /// assert_eq!(ce.consts(), array![-3.]);
+    /// ```
+    pub fn consts(&self) -> ArrayView1<'_, f64> {
+        self.consts.view()
+    }
+
+    /// Returns a view of the upper limits if the constraints are [`ranged`](Self::ranged). The
+    /// lower limits are returned by [`consts`](Self::consts) in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let ci = Constraints::ranged(array![[1.], [1.]], array![1.], array![2.]);
+    /// assert_eq!(ci.consts(), array![1.]);
+    /// assert_eq!(ci.upper().unwrap(), array![2.]);
+    /// ```
+    pub fn upper(&self) -> Option<ArrayView1<'_, f64>> {
+        self.upper.as_ref().map(|upper| upper.view())
+    }
+
+    /// Stacks `other` after these constraints.
+    ///
+    /// If either set is [ranged](Self::ranged), so is the result: a constraint
+    /// \\(a^\intercal x + c \ge 0\\) becomes \\(-c \le a^\intercal x \le \infty\\). If either set
+    /// is named, the constraints of the other set get empty names, which errors treat as unnamed.
+    ///
+    /// # Errors
+    ///
+    /// If the sets have different numbers of variables, this method returns
+    /// [`Error::SizeMismatch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // x0 + x1 <= 1 and 0 <= x <= 1
+    /// let budget = Constraints::new(array![[-1.], [-1.]], array![1.]);
+    /// let bounds = Constraints::from_bounds(&array![0., 0.], &array![1., 1.])?;
+    /// let ci = budget.stack(&bounds)?;
+    /// assert_eq!(ci.coeffs().dim(), (2, 5));
+    /// assert_eq!(ci.consts(), array![1., 0., 0., 1., 1.]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn stack<T, T0>(
+        &self,
+        other: &Constraints<T, T0>,
+    ) -> Result<Constraints<OwnedRepr<f64>, OwnedRepr<f64>>>
+    where
+        T: Data<Elem = f64>,
+        T0: Data<Elem = f64>,
+    {
+        let n = self.coeffs.nrows();
+        assert_size!(other.coeffs(), n, other.coeffs.nrows());
+        let (m1, m2) = (self.coeffs.ncols(), other.coeffs.ncols());
+        let coeffs = ndarray::concatenate(Axis(1), &[self.coeffs.view(), other.coeffs.view()])
+            .expect("same number of rows")
+            .as_standard_layout()
+            .into_owned();
+        let (consts, upper) = if self.upper.is_none() && other.upper.is_none() {
+            let consts = ndarray::concatenate(Axis(0), &[self.consts.view(), other.consts.view()])
+                .expect("one-dimensional");
+            (consts, None)
+        } else {
+            let limits =
+                |consts: &ArrayView1<'_, f64>, upper: Option<ArrayView1<'_, f64>>| match upper {
+                    Some(upper) => (consts.to_owned(), upper.to_owned()),
+                    None => (-consts, Array1::from_elem(consts.len(), f64::INFINITY)),
+                };
+            let (l1, u1) = limits(&self.consts(), self.upper());
+            let (l2, u2) = limits(&other.consts(), other.upper());
+            let lower =
+                ndarray::concatenate(Axis(0), &[l1.view(), l2.view()]).expect("one-dimensional");
+            let upper =
+                ndarray::concatenate(Axis(0), &[u1.view(), u2.view()]).expect("one-dimensional");
+            (lower, Some(upper))
+        };
+        let names = if self.names.is_empty() && other.names.is_empty() {
+            Vec::new()
+        } else {
+            let names = |names: &[String], m: usize| {
+                if names.is_empty() {
+                    vec![String::new(); m]
+                } else {
+                    names.to_vec()
+                }
+            };
+            let mut stacked = names(&self.names, m1);
+            stacked.extend(names(&other.names, m2));
+            stacked
+        };
+        Ok(Constraints {
+            coeffs,
+            consts,
+            upper,
+            names,
+        })
+    }
+
+    /// Returns the constraints with views of the coefficients and constants, so that they can be
+    /// passed to [`solve`] repeatedly without cloning them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let g = array![[1.0, 0.0], [0.0, 1.0]];
+    /// let ci = Constraints::new(array![[1.0], [1.0]], array![-1.0]);
+    /// let cases = [(array![0.0, 0.0], array![0.5, 0.5]), (array![-1.0, -1.0], array![1.0, 1.0])];
+    /// for (g0, x) in cases {
+    ///     let solution = solve(&g, g0, Constraints::NONE, Some(ci.view()))?;
+    ///     assert_eq!(solution.x, x);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn view(&self) -> Constraints<ViewRepr<&f64>, ViewRepr<&f64>> {
+        Constraints {
+            coeffs: self.coeffs.view(),
+            consts: self.consts.view(),
+            upper: self.upper.as_ref().map(|upper| upper.view()),
+            names: self.names.clone(),
         }
-    };
-}
+    }
 
-macro_rules! assert_data_layout {
-    ($term:expr) => {
-        if !$term.is_standard_layout() {
-            return Err(Error::NonStandardLayout {
-                term: stringify!($term),
-            });
+    /// Returns whether the constraints are on `n` variables, and the constants, upper limits and
+    /// names have one element per constraint.
+    pub(crate) fn has_size(&self, n: usize) -> bool {
+        let m = self.coeffs.ncols();
+        self.coeffs.nrows() == n
+            && self.consts.len() == m
+            && self.upper.iter().all(|upper| upper.len() == m)
+            && (self.names.is_empty() || self.names.len() == m)
+    }
+
+    /// Returns the given constraints, in the given order.
+    pub(crate) fn select(&self, indices: &[usize]) -> Constraints<OwnedRepr<f64>, OwnedRepr<f64>> {
+        Constraints {
+            coeffs: self
+                .coeffs
+                .select(Axis(1), indices)
+                .as_standard_layout()
+                .into_owned(),
+            consts: self.consts.select(Axis(0), indices),
+            upper: self
+                .upper
+                .as_ref()
+                .map(|upper| upper.select(Axis(0), indices)),
+            names: if self.names.is_empty() {
+                Vec::new()
+            } else {
+                indices.iter().map(|&j| self.names[j].clone()).collect()
+            },
         }
-    };
+    }
+
+    /// Copies the coefficients and constants into standard layout arrays.
+    pub(crate) fn into_owned(self) -> Constraints<OwnedRepr<f64>, OwnedRepr<f64>> {
+        Constraints {
+            coeffs: self.coeffs.as_standard_layout().into_owned(),
+            consts: self.consts.as_standard_layout().into_owned(),
+            upper: self
+                .upper
+                .map(|upper| upper.as_standard_layout().into_owned()),
+            names: self.names,
+        }
+    }
 }
 
 /// Solves a quadratic programming problem using the Goldfarb-Idnani active-set dual method.
@@ -138,92 +1002,145 @@ macro_rules! assert_data_layout {
 /// * \\(ci_0\\) is an \\(M\\)-vector
 /// * \\(x\\) is an \\(N\\)-vector
 ///
-/// Each matrix/vector is generic over the array representations.
+/// Each matrix/vector is generic over the array representations, and may have any memory
+/// layout. Arrays which aren't in standard (row-major, contiguous) layout, e.g. transposed or
+/// sliced views, are copied. \\(G\\) and \\(g_0\\) can also be borrowed, or given as nested
+/// `Vec`s, slices and arrays; see [`IntoQpMatrix`] and [`IntoQpVector`]. The constraints can be
+/// borrowed with [`Constraints::view`].
+///
+/// If there are no inequality constraints, the KKT system is solved directly without going
+/// through QuadProg++, unless the equality constraints are ill-conditioned. If \\(G\\) is a [`SparseHessian`], the problem is solved by a sparse
+/// solver without forming the dense matrix.
 ///
 /// # Errors
 ///
 /// * If the problem doesn't have a feasible solution, this function returns [`Error::Infeasible`].
-/// * If the shapes of input matrices/vectors are wrong, it returns [`Error::SizeMismatch`].
+/// * If the shapes of input matrices/vectors are wrong, or the rows of \\(G\\) have different
+///   lengths, it returns [`Error::SizeMismatch`].
+/// * If [ranged](Constraints::ranged) constraints are given as `ce`, it returns
+///   [`Error::RangedEquality`].
+/// * If an element of the input is infinite or NaN, it returns [`Error::NonFinite`]. Only the
+///   limits of ranged constraints may be infinite.
+/// * If \\(G\\) isn't positive definite, it returns [`Error::SingularHessian`].
+/// * If the equality constraints are linearly dependent, it returns [`Error::LinearlyDependent`].
 /// * If there's an error propagated from the underlying QuadProgpp library, it returns [`Error::Ffi`].
+#[cfg(feature = "ndarray")]
 pub fn solve<G, G0, CE, CE0, CI, CI0>(
-    g: ArrayBase<G, Ix2>,
-    g0: ArrayBase<G0, Ix1>,
+    g: G,
+    g0: G0,
     ce: Option<Constraints<CE, CE0>>,
     ci: Option<Constraints<CI, CI0>>,
-) -> Result<(Vec<f64>, f64)>
+) -> Result<Solution>
 where
-    G: RawData<Elem = f64>,
-    G0: RawData<Elem = f64>,
+    G: IntoQpMatrix,
+    G0: IntoQpVector,
     CE: RawData<Elem = f64>,
     CE0: RawData<Elem = f64>,
     CI: RawData<Elem = f64>,
     CI0: RawData<Elem = f64>,
 {
-    let (g_n, g_m) = g.dim();
-    assert_size!(g, g_n, g_m);
-    let mut g = unsafe { sys::new_matrix_from_ptr(g.as_ptr(), g_n as u32, g_m as u32) };
-    let g0_n = g0.dim();
-    assert_size!(g0.dim(), g_n, g0_n);
-    let mut g0 = unsafe { sys::new_vector_from_ptr(g0.as_ptr(), g0_n as u32) };
-    let (ce, ce0) = match ce {
-        Some(Constraints { coeffs, consts }) => {
-            assert_data_layout!(coeffs);
-            assert_data_layout!(consts);
-            let (ce_n, ce_m) = coeffs.dim();
-            assert_size!(ce.dim(), g_n, ce_n);
-            let ce = unsafe { sys::new_matrix_from_ptr(coeffs.as_ptr(), ce_n as u32, ce_m as u32) };
-            let ce0_n = consts.dim();
-            assert_size!(ce0.dim(), ce0_n, ce_m);
-            let ce0 = unsafe { sys::new_vector_from_ptr(consts.as_ptr(), ce0_n as u32) };
-            (ce, ce0)
-        }
-        None => {
-            let ce = unsafe { sys::new_matrix_from_ptr(ptr::null(), g_n as u32, 0) };
-            let ce0 = unsafe { sys::new_vector_from_ptr(ptr::null(), 0) };
-            (ce, ce0)
-        }
-    };
-    let (ci, ci0) = match ci {
-        Some(Constraints { coeffs, consts }) => {
-            assert_data_layout!(coeffs);
-            assert_data_layout!(consts);
-            let (ci_n, ci_m) = coeffs.dim();
-            assert_size!(ci.dim(), g_n, ci_n);
-            let ci = unsafe { sys::new_matrix_from_ptr(coeffs.as_ptr(), ci_n as u32, ci_m as u32) };
-            let ci0_n = consts.dim();
-            assert_size!(ci0.dim(), ci0_n, ci_m);
-            let ci0 = unsafe { sys::new_vector_from_ptr(consts.as_ptr(), ci0_n as u32) };
-            (ci, ci0)
-        }
-        None => {
-            let ci = unsafe { sys::new_matrix_from_ptr(ptr::null(), g_n as u32, 0) };
-            let ci0 = unsafe { sys::new_vector_from_ptr(ptr::null(), 0) };
-            (ci, ci0)
-        }
-    };
-    let mut x = sys::new_vector(g_n as u32);
-    let best = sys::solve_quadprog(g.pin_mut(), g0.pin_mut(), &ce, &ce0, &ci, &ci0, x.pin_mut())?;
-    if best.is_infinite() {
-        return Err(Error::Infeasible);
-    }
-    let mut v = Vec::with_capacity(g_n);
-    for i in 0..g_n {
-        v.push(unsafe { sys::vector_index(&x, i as u32) });
-    }
-    assert_size!(v.len(), g_n, v.len());
-    Ok((v, best))
+    solve_with_options(g, g0, ce, ci, &SolveOptions::default())
 }
 
-#[cfg(test)]
-mod tests {
-    use approx::{assert_abs_diff_eq, assert_ulps_eq};
-    use ndarray::{array, Array, Array2};
-
-    use super::*;
+/// Solves a quadratic programming problem like [`solve`] with the given [`SolveOptions`].
+///
+/// # Errors
+///
+/// In addition to the errors of [`solve`], this function returns [`Error::MaxIterations`] if the
+/// iteration limit is reached, [`Error::Interrupted`] if the deadline passes or the solver is
+/// cancelled, [`Error::Degenerate`] if the limit of degenerate steps is exceeded,
+/// [`Error::IndexOutOfRange`] if the warm-start active set refers to a nonexistent inequality
+/// constraint, and [`Error::NotSymmetric`] if [`SolveOptions::symmetry_tolerance`] is set and
+/// \\(G\\) isn't symmetric.
+#[cfg(feature = "ndarray")]
+pub fn solve_with_options<G, G0, CE, CE0, CI, CI0>(
+    g: G,
+    g0: G0,
+    ce: Option<Constraints<CE, CE0>>,
+    ci: Option<Constraints<CI, CI0>>,
+    options: &SolveOptions,
+) -> Result<Solution>
+where
+    G: IntoQpMatrix,
+    G0: IntoQpVector,
+    CE: RawData<Elem = f64>,
+    CE0: RawData<Elem = f64>,
+    CI: RawData<Elem = f64>,
+    CI0: RawData<Elem = f64>,
+{
+    solve_with_bounds(g, g0, ce, ci, &Bounds::default(), options)
+}
 
-    #[test]
-    fn quadprogpp_demo() -> Result<()> {
-        #[rustfmt::skip]
+/// Solves a quadratic programming problem like [`solve_with_options`] with additional [`Bounds`]
+/// on the variables.
+///
+/// The multipliers of the bounds are returned in [`Multipliers::lower`] and
+/// [`Multipliers::upper`]. The bounds never appear in [`Solution::active_set`]; the active ones are
+/// listed in [`Solution::active_lower`] and [`Solution::active_upper`] instead.
+///
+/// # Errors
+///
+/// In addition to the errors of [`solve_with_options`], this function returns
+/// [`Error::SizeMismatch`] if the bounds don't have one element per variable.
+#[cfg(feature = "ndarray")]
+pub fn solve_with_bounds<G, G0, CE, CE0, CI, CI0>(
+    g: G,
+    g0: G0,
+    ce: Option<Constraints<CE, CE0>>,
+    ci: Option<Constraints<CI, CI0>>,
+    bounds: &Bounds,
+    options: &SolveOptions,
+) -> Result<Solution>
+where
+    G: IntoQpMatrix,
+    G0: IntoQpVector,
+    CE: RawData<Elem = f64>,
+    CE0: RawData<Elem = f64>,
+    CI: RawData<Elem = f64>,
+    CI0: RawData<Elem = f64>,
+{
+    let sizes = g.block_sizes().filter(|_| options.is_separable());
+    let g0 = g0.into_qp_vector();
+    if let Some(hessian) = g.sparse_hessian() {
+        if let Some(result) = sparse::solve_sparse(hessian, &g0, &ce, &ci, bounds, options) {
+            return result;
+        }
+    }
+    let g = g.into_qp_matrix()?;
+    if let Some(sizes) = sizes {
+        if let Some(solution) = block::solve_blocks(&sizes, &g, &g0, &ce, &ci, bounds, options) {
+            return Ok(solution);
+        }
+    }
+    let n = g.nrows();
+    // The direct solver computes in f64, which would undo the `extended-precision` feature.
+    if ci.is_none()
+        && *bounds == Bounds::default()
+        && options.is_plain()
+        && !sys::EXTENDED_PRECISION
+    {
+        if let Some(solution) = direct::solve_direct(&g, &g0, &ce, options)? {
+            return Ok(solution);
+        }
+    }
+    let p = ce.as_ref().map_or(0, |ce| ce.coeffs.ncols());
+    let m = ci.as_ref().map_or(0, |ci| ci.coeffs.ncols());
+    Solver::try_new(n, p, m)?.solve_with_bounds(g, g0, ce, ci, bounds, options)
+}
+
+#[cfg(all(test, feature = "ndarray"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use approx::{assert_abs_diff_eq, assert_ulps_eq};
+    use ndarray::{array, s, Array, Array2, ShapeBuilder};
+
+    use super::*;
+
+    #[test]
+    fn quadprogpp_demo() -> Result<()> {
+        #[rustfmt::skip]
         let g = array![
             [4.0, -2.0],
             [-2.0, 4.0],
@@ -241,18 +1158,75 @@ mod tests {
             [0.0, 1.0, 1.0],
         ];
         let ci0 = array![0.0, -2.0, 0.0];
-        let (x, best) = solve(
+        let Solution { x, objective, .. } = solve(
             g,
             g0,
             Constraints::some(ce, ce0),
             Constraints::some(ci, ci0),
         )?;
-        assert_ulps_eq!(best, 12.0);
+        assert_ulps_eq!(objective, 12.0);
         assert_ulps_eq!(x[0], 1.0);
         assert_ulps_eq!(x[1], 2.0);
         Ok(())
     }
 
+    #[test]
+    fn non_standard_layout() -> Result<()> {
+        // The QuadProg++ demo with a sliced G, a reversed g0, a transposed CE, and CI and ci0
+        // sliced with a step
+        let g = array![[4.0, -2.0, 9.0], [-2.0, 4.0, 9.0], [9.0, 9.0, 9.0]];
+        let g = g.slice(s![..2, ..2]);
+        let g0 = array![0.0, 6.0];
+        let g0 = g0.slice(s![..;-1]);
+        let ce = array![[1.0, 1.0]];
+        let ce0 = array![-3.0];
+        let ci = array![[1.0, 9.0, 1.0, 9.0, 0.0], [0.0, 9.0, 1.0, 9.0, 1.0]];
+        let ci = ci.slice(s![.., ..;2]);
+        let ci0 = array![0.0, 9.0, -2.0, 9.0, 0.0];
+        let ci0 = ci0.slice(s![..;2]);
+        assert!(!g.is_standard_layout() && !ci.is_standard_layout());
+        let solution = solve(
+            g,
+            g0,
+            Constraints::some(ce.t(), ce0.view()),
+            Constraints::some(ci, ci0),
+        )?;
+        let expected = solve(
+            g.to_owned(),
+            g0.to_owned(),
+            Constraints::some(ce.t().to_owned(), ce0.clone()),
+            Constraints::some(ci.to_owned(), ci0.to_owned()),
+        )?;
+        assert_eq!(solution, expected);
+        assert_ulps_eq!(solution.objective, 12.0);
+
+        // Without inequality constraints, the KKT system is solved directly.
+        let solution = solve(
+            g,
+            g0,
+            Constraints::some(ce.t(), ce0.view()),
+            Constraints::NONE,
+        )?;
+        let expected = solve(
+            g.to_owned(),
+            g0.to_owned(),
+            Constraints::some(ce.t().to_owned(), ce0),
+            Constraints::NONE,
+        )?;
+        assert_eq!(solution, expected);
+
+        // A column-major Hessian with the ranged constraints 0 <= x0 - x1 <= 1 and
+        // -5 <= x1 <= 5, whose limits are the columns of a matrix
+        let g = Array2::from_shape_vec((2, 2).f(), vec![4.0, -1.0, -1.0, 2.0]).unwrap();
+        let limits = array![[0.0, 1.0], [-5.0, 5.0]];
+        let ci = array![[1.0, 0.0], [-1.0, 1.0]];
+        let ci = Constraints::ranged(ci.view(), limits.column(0), limits.column(1));
+        let solution = solve(g.view(), array![-8.0, 0.0], Constraints::NONE, Some(ci))?;
+        assert_abs_diff_eq!(solution.x[0] - solution.x[1], 1.0, epsilon = 1e-12);
+        assert_eq!(solution.active_set, [0]);
+        Ok(())
+    }
+
     #[test]
     fn eiquadprog_demo() {
         #[rustfmt::skip]
@@ -271,14 +1245,14 @@ mod tests {
             [0.0, 0.0, 1.0,  0.0]
         ];
         let ci0 = array![0.0, 0.0, 0.0, 10.0];
-        let (x, best) = solve(
+        let Solution { x, objective, .. } = solve(
             g,
             g0,
             Constraints::some(ce, ce0),
             Constraints::some(ci, ci0),
         )
         .unwrap();
-        assert_ulps_eq!(best, 6.4);
+        assert_ulps_eq!(objective, 6.4);
         assert_ulps_eq!(x[0], 0.0);
         assert_ulps_eq!(x[1], 2.0);
         assert_ulps_eq!(x[2], 0.0);
@@ -299,7 +1273,8 @@ mod tests {
             [0.0, 1.0, -2.0],
         ];
         let ci0 = array![0.0, 0.0, 2.0];
-        let (answer, _) = solve(g, g0, Constraints::NONE, Constraints::some(ci, ci0))?;
+        let Solution { x: answer, .. } =
+            solve(g, g0, Constraints::NONE, Constraints::some(ci, ci0))?;
         assert_abs_diff_eq!(answer[0], 2.0 / 9.0, epsilon = 1e-12);
         assert_abs_diff_eq!(answer[1], 8.0 / 9.0, epsilon = 1e-12);
         Ok(())
@@ -325,7 +1300,7 @@ mod tests {
             [0.0, -1.0 / 3.0,  1.0 / 3.0]
         ];
         let ci0 = array![0.0, 0.0, 2.0];
-        let (answer, _) = solve(
+        let Solution { x: answer, .. } = solve(
             g,
             g0,
             Constraints::some(ce, ce0),
@@ -336,4 +1311,1183 @@ mod tests {
         assert_abs_diff_eq!(answer[2], -14.0 / 9.0, epsilon = 1e-5);
         Ok(())
     }
+
+    #[test]
+    fn polish() -> Result<()> {
+        // Problem 1 from hmatrix-quadprogpp, which the active-set loop may solve inaccurately
+        let offset: Array2<f64> = Array::eye(3) * 1e-12;
+        #[rustfmt::skip]
+        let g = array![
+            [      1.0, 2.0 / 3.0, 1.0 / 3.0],
+            [2.0 / 3.0, 2.0 / 3.0,       0.0],
+            [1.0 / 3.0,       0.0, 1.0 / 3.0],
+        ] + offset;
+        let g0 = array![-2.0, -4.0, 2.0];
+        let ce = array![[-3.0], [2.0], [1.0]];
+        let ce0 = array![0.0];
+        #[rustfmt::skip]
+        let ci = array![
+            [1.0,        0.0,        0.0],
+            [0.0,  1.0 / 3.0, -4.0 / 3.0],
+            [0.0, -1.0 / 3.0,  1.0 / 3.0]
+        ];
+        let ci0 = array![0.0, 0.0, 2.0];
+        let expected = [2.0 / 9.0, 10.0 / 9.0, -14.0 / 9.0];
+        let solve = |options: &SolveOptions| {
+            solve_with_options(
+                g.view(),
+                g0.view(),
+                Constraints::some(ce.view(), ce0.view()),
+                Constraints::some(ci.view(), ci0.view()),
+                options,
+            )
+        };
+        let solution = solve(&SolveOptions::new())?;
+        assert!(!solution.polished);
+        let error = |x: &Array1<f64>| {
+            x.iter()
+                .zip(&expected)
+                .fold(0.0, |max: f64, (x, e)| max.max((x - e).abs()))
+        };
+
+        // The accuracy of the active-set loop depends on the build, e.g. on the
+        // `extended-precision` feature, but the polished solution is accurate either way.
+        let polished = solve(&SolveOptions::new().polish(true))?;
+        assert!(polished.polished);
+        // The exact solution differs from the expected one by the effect of the offset
+        assert!(error(&polished.x) < 1e-11, "{}", polished.x);
+        assert_eq!(polished.active_set, solution.active_set);
+        assert_abs_diff_eq!(polished.objective, solution.objective, epsilon = 1e-8);
+        for (&actual, &expected) in polished
+            .multipliers
+            .ineq
+            .iter()
+            .zip(&solution.multipliers.ineq)
+        {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-4);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn equilibrate() -> Result<()> {
+        // min 1e8 x^2 + (y - 1)^2 s.t. 1e4 x + y >= 2, y <= 1.2
+        let g = array![[2e8, 0.0], [0.0, 2.0]];
+        let g0 = array![0.0, -2.0];
+        let ci = array![[1e4], [1.0]];
+        let ci0 = array![-2.0];
+        let bounds = Bounds::upper(array![f64::INFINITY, 1.2]);
+        let options = SolveOptions::new().equilibrate(true);
+        for options in [SolveOptions::new(), options.clone()] {
+            let solution = solve_with_bounds(
+                g.view(),
+                g0.view(),
+                Constraints::NONE,
+                Constraints::some(ci.view(), ci0.view()),
+                &bounds,
+                &options,
+            )?;
+            assert_abs_diff_eq!(solution.x[0], 0.8e-4, epsilon = 1e-15);
+            assert_abs_diff_eq!(solution.x[1], 1.2, epsilon = 1e-11);
+            assert_abs_diff_eq!(solution.objective, 0.64 + 0.04 - 1.0, epsilon = 1e-11);
+            assert_abs_diff_eq!(solution.multipliers.ineq[0], 1.6, epsilon = 1e-10);
+            assert_abs_diff_eq!(solution.multipliers.upper[1], 1.2, epsilon = 1e-10);
+            assert_eq!(solution.active_upper, [1]);
+        }
+
+        // The following solves reuse the scaling of the Hessian.
+        let mut solver = Solver::new(2, 0, 1);
+        solver.solve_with_options(
+            g.view(),
+            g0.view(),
+            Constraints::NONE,
+            Constraints::some(ci.view(), ci0.view()),
+            &options,
+        )?;
+        let ci = Constraints::some(ci.view(), ci0.view());
+        let solution =
+            solver.solve_for_with_options(array![0.0, 0.0], Constraints::NONE, ci, &options)?;
+        assert_eq!(solution.factorizations, 0);
+        assert_abs_diff_eq!(solution.x[0], 1e-4, epsilon = 1e-15);
+        assert_abs_diff_eq!(solution.x[1], 1.0, epsilon = 1e-11);
+        assert_abs_diff_eq!(solution.objective, 2.0, epsilon = 1e-11);
+        assert_abs_diff_eq!(solution.multipliers.ineq[0], 2.0, epsilon = 1e-10);
+        Ok(())
+    }
+
+    #[test]
+    fn regularization() -> Result<()> {
+        // Problem 1 from hmatrix-quadprogpp, whose Hessian is positive semidefinite
+        #[rustfmt::skip]
+        let g = array![
+            [      1.0, 2.0 / 3.0, 1.0 / 3.0],
+            [2.0 / 3.0, 2.0 / 3.0,       0.0],
+            [1.0 / 3.0,       0.0, 1.0 / 3.0],
+        ];
+        let g0 = array![-2.0, -4.0, 2.0];
+        let ce = array![[-3.0], [2.0], [1.0]];
+        let ce0 = array![0.0];
+        #[rustfmt::skip]
+        let ci = array![
+            [1.0,        0.0,        0.0],
+            [0.0,  1.0 / 3.0, -4.0 / 3.0],
+            [0.0, -1.0 / 3.0,  1.0 / 3.0]
+        ];
+        let ci0 = array![0.0, 0.0, 2.0];
+        let expected = [2.0 / 9.0, 10.0 / 9.0, -14.0 / 9.0];
+        // In extended precision, the rounding of the factorization leaves a positive pivot.
+        if !sys::EXTENDED_PRECISION {
+            let err = solve(
+                g.view(),
+                g0.view(),
+                Constraints::some(ce.view(), ce0.view()),
+                Constraints::some(ci.view(), ci0.view()),
+            )
+            .unwrap_err();
+            assert!(
+                matches!(err, Error::SingularHessian { .. }),
+                "unexpected error: {:?}",
+                err
+            );
+        }
+
+        let objective = |x: &Array1<f64>| 0.5 * x.dot(&g.dot(x)) + g0.dot(x);
+        for &(epsilon, refine, tolerance) in &[(1e-6, false, 1e-4), (1e-6, true, 1e-8)] {
+            let options = SolveOptions::new()
+                .regularization(epsilon)
+                .refine_regularization(refine);
+            let solution = solve_with_options(
+                g.view(),
+                g0.view(),
+                Constraints::some(ce.view(), ce0.view()),
+                Constraints::some(ci.view(), ci0.view()),
+                &options,
+            )?;
+            for i in 0..3 {
+                assert_abs_diff_eq!(solution.x[i], expected[i], epsilon = tolerance);
+            }
+            assert_abs_diff_eq!(solution.objective, objective(&solution.x), epsilon = 1e-10);
+            assert_eq!(solution.factorizations, 1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn indefinite_hessian() {
+        #[rustfmt::skip]
+        let g = array![
+            [1.0,  0.0],
+            [0.0, -1.0],
+        ];
+        let g0 = array![0.0, 0.0];
+        let err = solve(g, g0, Constraints::NONE, Constraints::NONE).unwrap_err();
+        match err {
+            Error::SingularHessian { pivot } => assert_eq!(pivot, 1),
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn non_finite() {
+        let g = array![[1.0, 0.0], [0.0, 1.0]];
+        let g0 = array![0.0, 0.0];
+        let nan_g = array![[1.0, 0.0], [0.0, f64::NAN]];
+        let nan_g0 = array![f64::NAN, 0.0];
+        // x >= 0
+        let ci = || Constraints::some(Array2::eye(2), array![0.0, 0.0]);
+        let nan_ce = Constraints::some(array![[1.0], [f64::NAN]], array![0.0]);
+        let nan_ci = Constraints::some(array![[f64::NAN, 0.0], [0.0, 1.0]], array![1.0, 0.0]);
+        let infinite_ci0 = Constraints::some(Array2::eye(2), array![0.0, f64::NEG_INFINITY]);
+        let nan_upper =
+            Constraints::ranged(Array2::eye(2), array![0.0, 0.0], array![1.0, f64::NAN]);
+        let none = Constraints::NONE;
+        let cases = [
+            (
+                solve(nan_g.view(), g0.view(), none.clone(), none.clone()),
+                "g",
+            ),
+            (solve(nan_g.view(), g0.view(), none.clone(), ci()), "g"),
+            (
+                solve(g.view(), nan_g0.view(), none.clone(), none.clone()),
+                "g0",
+            ),
+            (solve(g.view(), nan_g0.view(), none.clone(), ci()), "g0"),
+            (
+                solve(g.view(), g0.view(), nan_ce.clone(), none.clone()),
+                "ce",
+            ),
+            (solve(g.view(), g0.view(), nan_ce, ci()), "ce"),
+            (solve(g.view(), g0.view(), none.clone(), nan_ci), "ci"),
+            (
+                solve(g.view(), g0.view(), none.clone(), infinite_ci0),
+                "ci0",
+            ),
+            (
+                solve(g.view(), g0.view(), none.clone(), Some(nan_upper)),
+                "upper",
+            ),
+            (
+                Solver::new(2, 0, 2).solve(g.view(), nan_g0.view(), none.clone(), ci()),
+                "g0",
+            ),
+        ];
+        for (result, term) in cases {
+            match result {
+                Err(Error::NonFinite { term: actual }) => assert_eq!(actual, term),
+                _ => panic!("unexpected result for {}: {:?}", term, result),
+            }
+        }
+        // The limits of ranged constraints may be infinite.
+        let ranged = Constraints::ranged(
+            Array2::eye(2),
+            array![0.0, f64::NEG_INFINITY],
+            array![1.0, 1.0],
+        );
+        assert!(solve(g.view(), g0.view(), none.clone(), Some(ranged)).is_ok());
+
+        // The factorizations fail at a NaN pivot.
+        let err = Solver::new(2, 0, 0)
+            .with_hessian(nan_g.view())
+            .err()
+            .unwrap();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = utils::cholesky(&nan_g).unwrap_err();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn symmetry() -> Result<()> {
+        #[rustfmt::skip]
+        let g = array![
+            [4.0, -2.0, 1.0],
+            [-2.0 + 1e-15, 4.0, 0.5],
+            [1.0, 0.7, 3.0],
+        ];
+        let g0 = array![6.0, 0.0, -1.0];
+        let ci = Constraints::some(array![[1.0], [1.0], [1.0]], array![0.0]);
+        let options = SolveOptions::new().symmetry_tolerance(1e-12);
+        for ci in [None, ci.clone()] {
+            let err = solve_with_options(g.view(), g0.view(), Constraints::NONE, ci, &options)
+                .unwrap_err();
+            assert!(
+                matches!(err, Error::NotSymmetric { row: 2, col: 1 }),
+                "unexpected error: {:?}",
+                err
+            );
+        }
+
+        let options = options.symmetry_tolerance(0.5).symmetrize(true);
+        let solution =
+            solve_with_options(g.view(), g0.view(), Constraints::NONE, ci.clone(), &options)?;
+        let mut symmetric = g.clone();
+        symmetric[[1, 0]] = -2.0;
+        symmetric[[1, 2]] = 0.6;
+        symmetric[[2, 1]] = 0.6;
+        let expected = solve(symmetric.view(), g0.view(), Constraints::NONE, ci.clone())?;
+        assert_abs_diff_eq!(solution.objective, expected.objective, epsilon = 1e-12);
+        for (&actual, &expected) in solution.x.iter().zip(&expected.x) {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+        }
+
+        // Only the upper triangle is read, regardless of the other options
+        let mut upper = symmetric.clone();
+        upper[[1, 0]] = f64::NAN;
+        upper[[2, 0]] = 0.0;
+        upper[[2, 1]] = 100.0;
+        let options = options.upper_triangle(true);
+        for ci in [None, ci] {
+            let solution = solve_with_options(
+                upper.view(),
+                g0.view(),
+                Constraints::NONE,
+                ci.clone(),
+                &options,
+            )?;
+            let expected = solve(symmetric.view(), g0.view(), Constraints::NONE, ci)?;
+            assert_eq!(solution.x, expected.x);
+            assert_eq!(solution.objective, expected.objective);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn size_mismatch_shapes() {
+        let g = array![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let g0 = array![0.0, 0.0, 0.0];
+        let ce = Constraints::some(array![[1.0, 1.0, 1.0]], array![-1.0]);
+        let err = solve(g.view(), g0.view(), ce.clone(), Constraints::NONE).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "size mismatch on ce.dim() (expected: 3, found: 1) with shapes g: 3x3, g0: 3, \
+             ce: 1x3, ce0: 1; ce looks transposed, it should have one row per variable"
+        );
+
+        let ci = Constraints::some(array![[1.0, 0.0], [0.0, 1.0], [0.0, 0.0]], array![0.0]);
+        let err = solve(g.view(), g0.view(), Constraints::NONE, ci).unwrap_err();
+        match err {
+            Error::SizeMismatch {
+                shapes: Some(shapes),
+                ..
+            } => {
+                assert_eq!(
+                    *shapes,
+                    Shapes {
+                        g: Some((3, 3)),
+                        g0: 3,
+                        ce: None,
+                        ci: Some(((3, 2), 1)),
+                    }
+                );
+                assert_eq!(shapes.transposed(), None);
+            }
+            _ => panic!("unexpected error: {:?}", err),
+        }
+
+        let ci = Constraints::some(array![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], array![0.0, 0.0]);
+        let err = Solver::new(3, 1, 2)
+            .solve(g.view(), g0.view(), ce, ci)
+            .unwrap_err();
+        match err {
+            Error::SizeMismatch {
+                shapes: Some(shapes),
+                ..
+            } => assert_eq!(shapes.transposed(), Some("ce")),
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn unconstrained() -> Result<()> {
+        #[rustfmt::skip]
+        let g = array![
+            [4.0, -2.0, 1.0],
+            [-2.0, 4.0, 0.5],
+            [1.0, 0.5, 3.0],
+        ];
+        let g0 = array![6.0, 0.0, -1.0];
+        let solution = solve(g.view(), g0.view(), Constraints::NONE, Constraints::NONE)?;
+        let expected = Solver::new(3, 0, 0).solve(
+            g.view(),
+            g0.view(),
+            Constraints::some(Array2::zeros((3, 0)), Array1::zeros(0)),
+            Constraints::NONE,
+        )?;
+        let solution = Solution {
+            backend: expected.backend,
+            ..solution
+        };
+        assert_eq!(solution, expected);
+
+        let err = solve(g.view(), array![1.0], Constraints::NONE, Constraints::NONE).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    expected: 3,
+                    actual: 1,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn equality_only() -> Result<()> {
+        #[rustfmt::skip]
+        let g = array![
+            [4.0, -2.0, 1.0],
+            [-2.0, 4.0, 0.5],
+            [1.0, 0.5, 3.0],
+        ];
+        let g0 = array![6.0, 0.0, -1.0];
+        let ce = array![[1.0, 0.0], [1.0, 1.0], [0.0, -1.0]];
+        let ce0 = array![-3.0, 1.0];
+        let solution = solve(
+            g.view(),
+            g0.view(),
+            Constraints::some(ce.view(), ce0.view()),
+            Constraints::NONE,
+        )?;
+        let expected = Solver::new(3, 2, 0).solve(
+            g.view(),
+            g0.view(),
+            Constraints::some(ce.view(), ce0.view()),
+            Constraints::NONE,
+        )?;
+        assert_abs_diff_eq!(solution.objective, expected.objective, epsilon = 1e-12);
+        for i in 0..3 {
+            assert_abs_diff_eq!(solution.x[i], expected.x[i], epsilon = 1e-12);
+        }
+        for j in 0..2 {
+            assert_abs_diff_eq!(
+                solution.multipliers.eq[j],
+                expected.multipliers.eq[j],
+                epsilon = 1e-12
+            );
+        }
+        assert_eq!(solution.iterations, expected.iterations);
+        if !sys::EXTENDED_PRECISION {
+            assert_eq!(solution.backend, "direct");
+        }
+        assert_eq!(expected.backend, "QuadProg++");
+        // Options which the direct solver would ignore go through QuadProg++.
+        let options = SolveOptions::new().equilibrate(true);
+        let equilibrated = solve_with_options(
+            g.view(),
+            g0.view(),
+            Constraints::some(ce.view(), ce0.view()),
+            Constraints::NONE,
+            &options,
+        )?;
+        assert_eq!(equilibrated.backend, "QuadProg++");
+
+        // Nearly parallel constraints are solved by QuadProg++, since the range-space method
+        // squares their condition number.
+        let nearly_parallel = array![[1.0, 1.0], [1.0, 1.0 + 1e-6], [0.0, 0.0]];
+        let solution = solve(
+            g.view(),
+            g0.view(),
+            Constraints::some(nearly_parallel.view(), ce0.view()),
+            Constraints::NONE,
+        )?;
+        let expected = Solver::new(3, 2, 0).solve(
+            g.view(),
+            g0.view(),
+            Constraints::some(nearly_parallel.view(), ce0.view()),
+            Constraints::NONE,
+        )?;
+        assert_eq!(solution, expected);
+
+        // the second constraint is twice the first one
+        let ce = array![[1.0, 2.0], [1.0, 2.0], [0.0, 0.0]];
+        let err = solve(
+            g.view(),
+            g0.view(),
+            Constraints::some(ce.view(), ce0.view()),
+            Constraints::NONE,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::LinearlyDependent),
+            "unexpected error: {:?}",
+            err
+        );
+        // the same through QuadProg++
+        let err = solve(
+            g.view(),
+            g0.view(),
+            Constraints::some(ce, ce0),
+            Constraints::some(array![[1.0], [0.0], [0.0]], array![10.0]),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::LinearlyDependent),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lp_fallback() -> Result<()> {
+        let g = Array2::zeros((2, 2));
+        // min x - y s.t. x + y = 1, x >= 0, y >= 0
+        let g0 = array![1.0, -1.0];
+        let ce = array![[1.0], [1.0]];
+        let ce0 = array![-1.0];
+        let ci: Array2<f64> = Array::eye(2);
+        let ci0 = array![0.0, 0.0];
+        let err = solve(
+            g.view(),
+            g0.view(),
+            Constraints::some(ce.view(), ce0.view()),
+            Constraints::some(ci.view(), ci0.view()),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 0 }),
+            "unexpected error: {:?}",
+            err
+        );
+
+        let iterations = Arc::new(Mutex::new(Vec::new()));
+        let sink = iterations.clone();
+        let options = SolveOptions::new()
+            .lp_fallback(true)
+            .on_iteration(move |progress| sink.lock().unwrap().push(progress.iteration));
+        let solution = solve_with_options(
+            g.view(),
+            g0.view(),
+            Constraints::some(ce.view(), ce0.view()),
+            Constraints::some(ci.view(), ci0.view()),
+            &options,
+        )?;
+        // numbered across the subproblems
+        assert_eq!(
+            *iterations.lock().unwrap(),
+            (1..=solution.iterations).collect::<Vec<_>>()
+        );
+        assert_abs_diff_eq!(solution.x[0], 0.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(solution.x[1], 1.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(solution.objective, -1.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(solution.multipliers.eq[0], -1.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(solution.multipliers.ineq[0], 2.0, epsilon = 1e-9);
+        assert_eq!(solution.active_set, [0]);
+
+        // unbounded without x >= 0
+        let err = solve_with_options(
+            g.view(),
+            g0.view(),
+            Constraints::some(ce.view(), ce0.view()),
+            Constraints::some(array![[0.0], [1.0]], array![0.0]),
+            &options,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::MaxIterations { .. }),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn infeasible() {
+        let g = array![[1.0]];
+        let g0 = array![0.0];
+        // x >= 1 and x <= 0
+        let ci = array![[1.0, -1.0]];
+        let ci0 = array![-1.0, 0.0];
+        let err = solve(g, g0, Constraints::NONE, Constraints::some(ci, ci0)).unwrap_err();
+        assert!(
+            matches!(err, Error::Infeasible { ref constraint } if constraint == "inequality constraint 1"),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn multipliers_and_active_set() -> Result<()> {
+        #[rustfmt::skip]
+        let g = array![
+            [2.1, 0.0, 1.0],
+            [1.5, 2.2, 0.0],
+            [1.2, 1.3, 3.1],
+        ];
+        let g0 = array![6.0, 1.0, 1.0];
+        let ce = array![[1.0], [2.0], [-1.0]];
+        let ce0 = array![-4.0];
+        #[rustfmt::skip]
+        let ci = array![
+            [1.0, 0.0, 0.0, -1.0],
+            [0.0, 1.0, 0.0, -1.0],
+            [0.0, 0.0, 1.0,  0.0]
+        ];
+        let ci0 = array![0.0, 0.0, 0.0, 10.0];
+        let solution = solve(
+            g.clone(),
+            g0.clone(),
+            Constraints::some(ce.clone(), ce0),
+            Constraints::some(ci.clone(), ci0),
+        )?;
+        let u = solution.multipliers;
+        assert_eq!(u.eq.len(), 1);
+        assert_eq!(u.ineq.len(), 4);
+        assert!(u.ineq.iter().all(|&u| u >= 0.0));
+        assert_eq!(solution.active_set, [0, 2]);
+        assert_eq!(solution.factorizations, 1);
+        assert!(solution.iterations > 0);
+        // x[1] >= 0 and x[0] + x[1] <= 10 aren't active.
+        assert_ulps_eq!(u.ineq[1], 0.0);
+        assert_ulps_eq!(u.ineq[3], 0.0);
+        // The Cholesky factorization in QuadProg++ only looks at the upper triangle of G.
+        let g = Array2::from_shape_fn((3, 3), |(i, j)| g[[i.min(j), i.max(j)]]);
+        let residual =
+            g.dot(&solution.x) + g0 - ce.dot(&Array::from(u.eq)) - ci.dot(&Array::from(u.ineq));
+        for r in residual.iter() {
+            assert_abs_diff_eq!(*r, 0.0, epsilon = 1e-12);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn max_iterations() {
+        #[rustfmt::skip]
+        let g = array![
+            [4.0, 0.0],
+            [0.0, 2.0],
+        ];
+        let g0 = array![-4.0, -8.0];
+        #[rustfmt::skip]
+        let ci = array![
+            [1.0, 0.0, -1.0],
+            [0.0, 1.0, -2.0],
+        ];
+        let ci0 = array![0.0, 0.0, 2.0];
+        let solution = solve(
+            g.clone(),
+            g0.clone(),
+            Constraints::NONE,
+            Constraints::some(ci.clone(), ci0.clone()),
+        )
+        .unwrap();
+        assert!(solution.iterations > 1);
+        assert_eq!(solution.status, Status::Optimal);
+        let options = SolveOptions::new().max_iterations(1);
+        let err = solve_with_options(
+            g.view(),
+            g0.view(),
+            Constraints::NONE,
+            Constraints::some(ci.view(), ci0.view()),
+            &options,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::MaxIterations { iterations: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+
+        let options = options.best_effort(true);
+        let best = solve_with_options(
+            g.view(),
+            g0.view(),
+            Constraints::NONE,
+            Constraints::some(ci.view(), ci0.view()),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(best.status, Status::MaxIterations);
+        assert_eq!(best.iterations, 1);
+        // the minimizer subject to x + 2y <= 2 alone
+        assert_eq!(best.active_set, [2]);
+        assert_abs_diff_eq!(best.x[0], 2.0 / 9.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(best.x[1], 8.0 / 9.0, epsilon = 1e-12);
+        assert!(best.objective <= solution.objective);
+    }
+
+    #[test]
+    fn degenerate_steps() -> Result<()> {
+        // min x^2 + y^2 s.t. x >= 0, x + y >= 1
+        let g = array![[2.0, 0.0], [0.0, 2.0]];
+        let g0 = array![0.0, 0.0];
+        let ci = array![[1.0, 1.0], [0.0, 1.0]];
+        let ci0 = array![0.0, -1.0];
+        let solve = |options: &SolveOptions| {
+            solve_with_options(
+                g.view(),
+                g0.view(),
+                Constraints::NONE,
+                Constraints::some(ci.view(), ci0.view()),
+                options,
+            )
+        };
+        // x >= 0 is weakly active at the unconstrained minimizer, so adding x + y >= 1 drops it
+        // with a step of zero length.
+        let options = SolveOptions::new().active_set(vec![0]);
+        let solution = solve(&options)?;
+        assert!(solution.warm_started);
+        assert_eq!(solution.active_set, [1]);
+        assert_ulps_eq!(solution.x[0], 0.5);
+        assert_ulps_eq!(solution.x[1], 0.5);
+        let anti_cycling = solve(&options.clone().anti_cycling(true))?;
+        assert_eq!(anti_cycling.x, solution.x);
+
+        let options = options.max_degenerate_steps(0);
+        let err = solve(&options).unwrap_err();
+        assert!(
+            matches!(err, Error::Degenerate { iterations: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+        let best = solve(&options.best_effort(true))?;
+        assert_eq!(best.status, Status::Degenerate);
+        assert_eq!(best.x, array![0.0, 0.0]);
+        Ok(())
+    }
+
+    #[test]
+    fn deadline() -> Result<()> {
+        let g = array![[4.0, 0.0], [0.0, 2.0]];
+        let g0 = array![-4.0, -8.0];
+        let ci = array![[1.0, 0.0, -1.0], [0.0, 1.0, -2.0]];
+        let ci0 = array![0.0, 0.0, 2.0];
+        let solve = |options: &SolveOptions| {
+            solve_with_options(
+                g.view(),
+                g0.view(),
+                Constraints::NONE,
+                Constraints::some(ci.view(), ci0.view()),
+                options,
+            )
+        };
+        let options = SolveOptions::new().deadline(Instant::now() + Duration::from_secs(3600));
+        assert_eq!(solve(&options)?.status, Status::Optimal);
+
+        let options = SolveOptions::new().deadline(Instant::now());
+        let err = solve(&options).unwrap_err();
+        assert!(
+            matches!(err, Error::Interrupted { iterations: 0 }),
+            "unexpected error: {:?}",
+            err
+        );
+        let best = solve(&options.best_effort(true))?;
+        assert_eq!(best.status, Status::Interrupted);
+        // the unconstrained minimizer
+        assert_ulps_eq!(best.x[0], 1.0);
+        assert_ulps_eq!(best.x[1], 4.0);
+        assert_ulps_eq!(best.objective, -18.0);
+
+        // Without inequality constraints too
+        let options = SolveOptions::new().deadline(Instant::now());
+        let ce = Constraints::some(array![[1.0], [1.0]], array![-3.0]);
+        let err = solve_with_options(g.view(), g0.view(), ce.clone(), Constraints::NONE, &options)
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::Interrupted { .. }),
+            "unexpected error: {:?}",
+            err
+        );
+        let iterations = Arc::new(Mutex::new(0));
+        let sink = iterations.clone();
+        let options = SolveOptions::new().on_iteration(move |_| *sink.lock().unwrap() += 1);
+        let solution = solve_with_options(g.view(), g0.view(), ce, Constraints::NONE, &options)?;
+        assert_eq!(*iterations.lock().unwrap(), solution.iterations);
+        assert!(solution.iterations > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn statistics() -> Result<()> {
+        // min x0^2 + x1^2 s.t. 2 x0 + 2 x1 >= 4, x0 >= 3: the first constraint is added at the
+        // unconstrained minimizer and dropped by the second one.
+        let g = Array2::eye(2) * 2.0;
+        let g0 = array![0.0, 0.0];
+        let ci = Constraints::some(array![[2.0, 1.0], [2.0, 0.0]], array![-4.0, -3.0]);
+        let solution = solve(g, g0, Constraints::NONE, ci)?;
+        assert_abs_diff_eq!(solution.x[0], 3.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(solution.x[1], 0.0, epsilon = 1e-12);
+        assert_eq!(solution.active_set, [1]);
+        assert_eq!(solution.iterations, 3);
+        assert_eq!(solution.additions, 2);
+        assert_eq!(solution.deletions, 1);
+        assert!(solution.rotations > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn epsilon() -> Result<()> {
+        let g = Array2::eye(2);
+        let g0 = array![0.0, 0.0];
+        // x0 + x1 = 1 and x0 + (1 + 1e-6) x1 = 1, which are nearly dependent
+        let ce = Constraints::some(array![[1.0, 1.0], [1.0, 1.0 + 1e-6]], array![-1.0, -1.0]);
+        let solution = solve(g.view(), g0.view(), ce.clone(), Constraints::NONE)?;
+        assert_abs_diff_eq!(solution.x[0] + solution.x[1], 1.0, epsilon = 1e-9);
+        let options = SolveOptions::new().epsilon(1e-3);
+        let err = solve_with_options(g, g0, ce, Constraints::NONE, &options).unwrap_err();
+        assert!(
+            matches!(err, Error::LinearlyDependent),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn feasibility_tolerance() -> Result<()> {
+        let g = array![[1.0]];
+        let g0 = array![0.0];
+        // x >= 1e-6 is considered satisfied by the unconstrained minimizer
+        let ci = array![[1.0]];
+        let ci0 = array![-1e-6];
+        let options = SolveOptions::new().feasibility_tolerance(1e-3);
+        let solution = solve_with_options(
+            g.clone(),
+            g0.clone(),
+            Constraints::NONE,
+            Constraints::some(ci.clone(), ci0.clone()),
+            &options,
+        )?;
+        assert_eq!(solution.x, array![0.0]);
+        assert!(solution.active_set.is_empty());
+        let solution = solve(g, g0, Constraints::NONE, Constraints::some(ci, ci0))?;
+        assert_ulps_eq!(solution.x[0], 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn warm_start() -> Result<()> {
+        #[rustfmt::skip]
+        let g = array![
+            [4.0, 0.0],
+            [0.0, 2.0],
+        ];
+        let g0 = array![-4.0, -8.0];
+        #[rustfmt::skip]
+        let ci = array![
+            [1.0, 0.0, -1.0],
+            [0.0, 1.0, -2.0],
+        ];
+        let ci0 = array![0.0, 0.0, 2.0];
+        let cold = solve(
+            g.clone(),
+            g0.clone(),
+            Constraints::NONE,
+            Constraints::some(ci.clone(), ci0.clone()),
+        )?;
+        assert_eq!(cold.active_set, [2]);
+
+        let options = SolveOptions::new().warm_start(&cold);
+        let warm = solve_with_options(
+            g.clone(),
+            g0.clone(),
+            Constraints::NONE,
+            Constraints::some(ci.clone(), ci0.clone()),
+            &options,
+        )?;
+        assert!(warm.warm_started);
+        assert_eq!(warm.iterations, 1);
+        assert_eq!(warm.active_set, cold.active_set);
+        assert_abs_diff_eq!(warm.x[0], cold.x[0], epsilon = 1e-12);
+        assert_abs_diff_eq!(warm.x[1], cold.x[1], epsilon = 1e-12);
+
+        let options = SolveOptions::new().initial_point(&cold.x);
+        let guessed = solve_with_options(
+            g.clone(),
+            g0.clone(),
+            Constraints::NONE,
+            Constraints::some(ci.clone(), ci0.clone()),
+            &options,
+        )?;
+        assert_eq!(guessed.iterations, 1);
+        assert_eq!(guessed.active_set, cold.active_set);
+
+        // A wrong guess is discarded.
+        let options = SolveOptions::new().active_set(vec![0, 1]);
+        let wrong = solve_with_options(
+            g.clone(),
+            g0.clone(),
+            Constraints::NONE,
+            Constraints::some(ci.clone(), ci0.clone()),
+            &options,
+        )?;
+        assert!(!wrong.warm_started);
+        assert_eq!(wrong.active_set, cold.active_set);
+        assert_abs_diff_eq!(wrong.x[0], cold.x[0], epsilon = 1e-12);
+        assert_abs_diff_eq!(wrong.x[1], cold.x[1], epsilon = 1e-12);
+
+        let options = SolveOptions::new().active_set(vec![3]);
+        let err = solve_with_options(
+            g,
+            g0,
+            Constraints::NONE,
+            Constraints::some(ci, ci0),
+            &options,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::IndexOutOfRange { index: 3, len: 3 }),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bounds() -> Result<()> {
+        let g: Array2<f64> = Array::eye(3);
+        let g0 = array![-2.0, -2.0, 3.0];
+        // x0 + x1 <= 2
+        let ci = array![[-1.0], [-1.0], [0.0]];
+        let ci0 = array![2.0];
+        let bounds = Bounds::new(
+            array![0.0, f64::NEG_INFINITY, -1.0],
+            array![0.5, f64::INFINITY, f64::INFINITY],
+        );
+        let bounded = solve_with_bounds(
+            g.view(),
+            g0.view(),
+            Constraints::NONE,
+            Constraints::some(ci.view(), ci0.view()),
+            &bounds,
+            &SolveOptions::default(),
+        )?;
+
+        #[rustfmt::skip]
+        let explicit_ci = array![
+            [-1.0, 1.0, 0.0, -1.0],
+            [-1.0, 0.0, 0.0,  0.0],
+            [ 0.0, 0.0, 1.0,  0.0],
+        ];
+        let explicit_ci0 = array![2.0, 0.0, 1.0, 0.5];
+        let explicit = solve(
+            g,
+            g0,
+            Constraints::NONE,
+            Constraints::some(explicit_ci, explicit_ci0),
+        )?;
+        assert_ulps_eq!(bounded.objective, explicit.objective);
+        for i in 0..3 {
+            assert_ulps_eq!(bounded.x[i], explicit.x[i]);
+        }
+        assert_ulps_eq!(bounded.x[0], 0.5);
+        assert_ulps_eq!(bounded.x[1], 1.5);
+        assert_ulps_eq!(bounded.x[2], -1.0);
+        let multipliers = &explicit.multipliers.ineq;
+        assert_eq!(bounded.multipliers.ineq, &multipliers[..1]);
+        assert_eq!(
+            bounded.multipliers.lower,
+            [multipliers[1], 0.0, multipliers[2]]
+        );
+        assert_eq!(bounded.multipliers.upper, [multipliers[3], 0.0, 0.0]);
+        assert_eq!(bounded.active_set, [0]);
+        assert_eq!(bounded.active_lower, [2]);
+        assert_eq!(bounded.active_upper, [0]);
+
+        let err = solve_with_bounds(
+            array![[1.0]],
+            array![0.0],
+            Constraints::NONE,
+            Constraints::NONE,
+            &Bounds::lower(array![0.0, 0.0]),
+            &SolveOptions::default(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    expected: 1,
+                    actual: 2,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ranged_constraints() -> Result<()> {
+        let g: Array2<f64> = Array::eye(2);
+        let g0 = array![-4.0, 1.0];
+        // 1 <= x0 + x1 <= 2, x0 - x1 >= -10
+        let ci = array![[1.0, 1.0], [1.0, -1.0]];
+        let lower = array![1.0, -10.0];
+        let upper = array![2.0, f64::INFINITY];
+        let ranged = solve(
+            g.view(),
+            g0.view(),
+            Constraints::NONE,
+            Some(Constraints::ranged(ci.view(), lower.view(), upper.view())),
+        )?;
+
+        #[rustfmt::skip]
+        let explicit_ci = array![
+            [1.0, -1.0,  1.0],
+            [1.0, -1.0, -1.0],
+        ];
+        let explicit_ci0 = array![-1.0, 2.0, 10.0];
+        let explicit = solve(
+            g.view(),
+            g0.view(),
+            Constraints::NONE,
+            Constraints::some(explicit_ci, explicit_ci0),
+        )?;
+        assert_ulps_eq!(ranged.objective, explicit.objective);
+        assert_ulps_eq!(ranged.x[0], explicit.x[0]);
+        assert_ulps_eq!(ranged.x[1], explicit.x[1]);
+        let multipliers = &explicit.multipliers.ineq;
+        assert_eq!(
+            ranged.multipliers.ineq,
+            [multipliers[0] - multipliers[1], multipliers[2]]
+        );
+        assert!(ranged.multipliers.ineq[0] < 0.0);
+        assert_eq!(ranged.active_set, [0]);
+
+        let options = SolveOptions::new().warm_start(&ranged);
+        let warm = solve_with_options(
+            g.view(),
+            g0.view(),
+            Constraints::NONE,
+            Some(Constraints::ranged(ci.view(), lower.view(), upper.view())),
+            &options,
+        )?;
+        assert_eq!(warm.active_set, [0]);
+        assert_ulps_eq!(warm.x[0], ranged.x[0]);
+        assert_ulps_eq!(warm.x[1], ranged.x[1]);
+
+        let err = solve(
+            g,
+            g0,
+            Some(Constraints::ranged(ci, lower, upper)),
+            Constraints::NONE,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::RangedEquality),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn constraints_from_bounds() -> Result<()> {
+        let g: Array2<f64> = Array::eye(3);
+        let g0 = array![-2.0, 2.0, 0.0];
+        let lb = array![f64::NEG_INFINITY, -1.0, 0.5];
+        let ub = array![1.0, f64::INFINITY, 0.5];
+        let ci = Constraints::from_bounds(&lb, &ub)?;
+        assert_eq!(ci.consts().len(), 4);
+        let constrained = solve(g.view(), g0.view(), Constraints::NONE, Some(ci))?;
+        let bounded = solve_with_bounds(
+            g,
+            g0,
+            Constraints::NONE,
+            Constraints::NONE,
+            &Bounds::new(lb.view(), ub.view()),
+            &SolveOptions::default(),
+        )?;
+        assert_eq!(constrained.x, bounded.x);
+        assert_eq!(constrained.x, array![1.0, -1.0, 0.5]);
+
+        let err = Constraints::from_bounds(&lb, &array![1.0]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    expected: 3,
+                    actual: 1,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        fn assert_serde<T: serde::Serialize + serde::de::DeserializeOwned>() {}
+        assert_serde::<QpProblemBuilder>();
+        assert_serde::<Constraints<OwnedRepr<f64>, OwnedRepr<f64>>>();
+        assert_serde::<SolveOptions>();
+        assert_serde::<Solution>();
+        fn assert_serialize<T: serde::Serialize>() {}
+        assert_serialize::<Constraints<ViewRepr<&f64>, ViewRepr<&f64>>>();
+        assert_serialize::<Error>();
+    }
+
+    #[test]
+    fn constraints_from_rows() -> Result<()> {
+        let g: Array2<f64> = Array::eye(3);
+        let g0 = array![-1.0, -2.0, -3.0];
+        // x0 + x1 + x2 = 1 and x2 - x0 <= 0.5
+        let ce = array![[1.0, 1.0, 1.0]];
+        let ci = array![[1.0, 0.0, -1.0]];
+        let rows = solve(
+            &g,
+            &g0,
+            Some(Constraints::from_rows(ce.view(), array![-1.0])),
+            Some(Constraints::from_rows(ci.view(), array![0.5])),
+        )?;
+        let columns = solve(
+            &g,
+            &g0,
+            Constraints::some(ce.t(), array![-1.0]),
+            Constraints::some(ci.t(), array![0.5]),
+        )?;
+        assert_eq!(rows.x, columns.x);
+        assert_abs_diff_eq!(rows.x[2] - rows.x[0], 0.5, epsilon = 1e-12);
+        assert_abs_diff_eq!(rows.x.sum(), 1.0, epsilon = 1e-12);
+        Ok(())
+    }
+
+    #[test]
+    fn constraints_from_triplets() -> Result<()> {
+        // x0 + x1 = 1 and x1 - x0 >= 0.5, with a duplicate triplet
+        let ce = Constraints::from_triplets(2, 1, &[(0, 0, 1.0), (1, 0, 1.0)], array![-1.0])?;
+        let triplets = [(0, 0, -0.5), (1, 0, 1.0), (0, 0, -0.5)];
+        let ci = Constraints::from_triplets(2, 1, &triplets, array![-0.5])?;
+        assert_eq!(ci.coeffs(), array![[-1.0], [1.0]]);
+        let solution = solve(Array::eye(2), array![0.0, 0.0], Some(ce), Some(ci))?;
+        assert_abs_diff_eq!(solution.x[0], 0.25, epsilon = 1e-12);
+        assert_abs_diff_eq!(solution.x[1], 0.75, epsilon = 1e-12);
+
+        let err = Constraints::from_triplets(2, 1, &[(0, 1, 1.0)], array![0.0]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "constraint",
+                    expected: 1,
+                    actual: 2,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stack() -> Result<()> {
+        let g: Array2<f64> = Array::eye(2);
+        let g0 = array![-2.0, -2.0];
+        // x0 + x1 <= 1, x0 - x1 in [0.5, 2]
+        let budget = Constraints::new(array![[-1.0], [-1.0]], array![1.0]).with_names(["budget"]);
+        let spread = Constraints::ranged(array![[1.0], [-1.0]], array![0.5], array![2.0]);
+        let bounds = Constraints::from_bounds(&array![0.0, 0.0], &array![1.0, 1.0])?;
+        let ci = budget.stack(&spread)?.stack(&bounds)?;
+        assert_eq!(ci.coeffs().dim(), (2, 6));
+        assert_eq!(ci.consts(), array![-1.0, 0.5, 0.0, 0.0, -1.0, -1.0]);
+        assert_eq!(
+            ci.upper().unwrap(),
+            array![
+                f64::INFINITY,
+                2.0,
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::INFINITY
+            ]
+        );
+        assert_eq!(ci.names(), ["budget", "", "", "", "", ""]);
+        let solution = solve(g.view(), g0.view(), Constraints::NONE, Some(ci))?;
+        assert_abs_diff_eq!(solution.x[0], 0.75, epsilon = 1e-12);
+        assert_abs_diff_eq!(solution.x[1], 0.25, epsilon = 1e-12);
+
+        let err = budget
+            .stack(&Constraints::new(array![[1.0]], array![0.0]))
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    expected: 2,
+                    actual: 1,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
 }
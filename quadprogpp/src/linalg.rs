@@ -0,0 +1,333 @@
+//! Dense linear algebra shared by the solvers.
+//!
+//! The kernels on row-major slices at the end of this module are those of QuadProg++, shared by
+//! the pure-Rust port and the direct solver of problems without inequality constraints. They
+//! perform the same floating-point operations in the same order as QuadProg++, unless the `simd`
+//! feature is enabled: the dot products then accumulate four partial sums, which the compiler
+//! keeps in vector registers.
+
+#[cfg(feature = "ndarray")]
+use ndarray::{Array1, Array2, ArrayView1};
+
+use crate::{Error, Result};
+
+/// An LU factorization with partial pivoting of a square matrix.
+#[cfg(feature = "ndarray")]
+#[derive(Clone, Debug)]
+pub(crate) struct Lu {
+    lu: Array2<f64>,
+    pivots: Vec<usize>,
+}
+
+#[cfg(feature = "ndarray")]
+impl Lu {
+    /// Factorizes `a`, or returns `None` if it's singular.
+    pub(crate) fn new(mut a: Array2<f64>) -> Option<Self> {
+        let n = a.nrows();
+        let scale = a.iter().fold(0.0f64, |max, x| max.max(x.abs()));
+        let tolerance = scale * n as f64 * f64::EPSILON;
+        let mut pivots = Vec::with_capacity(n);
+        for k in 0..n {
+            let (pivot, max) = (k..n)
+                .map(|i| (i, a[[i, k]].abs()))
+                .fold((k, -1.0), |best, c| if c.1 > best.1 { c } else { best });
+            if max <= tolerance {
+                return None;
+            }
+            pivots.push(pivot);
+            if pivot != k {
+                for j in 0..n {
+                    a.swap([k, j], [pivot, j]);
+                }
+            }
+            for i in k + 1..n {
+                let factor = a[[i, k]] / a[[k, k]];
+                a[[i, k]] = factor;
+                for j in k + 1..n {
+                    a[[i, j]] -= factor * a[[k, j]];
+                }
+            }
+        }
+        Some(Self { lu: a, pivots })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.pivots.len()
+    }
+
+    /// Solves \\(A x = b\\).
+    pub(crate) fn solve(&self, b: ArrayView1<'_, f64>) -> Array1<f64> {
+        let n = self.len();
+        let mut x = b.to_owned();
+        for (k, &pivot) in self.pivots.iter().enumerate() {
+            x.swap(k, pivot);
+        }
+        for i in 0..n {
+            for j in 0..i {
+                x[i] -= self.lu[[i, j]] * x[j];
+            }
+        }
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                x[i] -= self.lu[[i, j]] * x[j];
+            }
+            x[i] /= self.lu[[i, i]];
+        }
+        x
+    }
+}
+
+/// Computes \\(\sum_i a_i b_i\\) as accurately as if it were computed in twice the working
+/// precision, by the compensated algorithm `Dot2` of Ogita, Rump and Oishi (2005).
+#[cfg(feature = "ndarray")]
+pub(crate) fn dot2<I: IntoIterator<Item = (f64, f64)>>(terms: I) -> f64 {
+    let mut sum = 0.0;
+    let mut error = 0.0;
+    for (a, b) in terms {
+        let product = a * b;
+        let product_error = a.mul_add(b, -product);
+        let total = sum + product;
+        let z = total - sum;
+        error += (sum - (total - z)) + (product - z) + product_error;
+        sum = total;
+    }
+    sum + error
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn sqrt(x: f64) -> f64 {
+    x.sqrt()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) use soft_sqrt as sqrt;
+
+/// The correctly rounded square root, like [`f64::sqrt`], which `core` lacks.
+#[cfg(any(not(feature = "std"), test))]
+pub(crate) fn soft_sqrt(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+    if x == 0.0 || x == f64::INFINITY {
+        return x;
+    }
+    // x = m * 2^e with the leading bit of m at 52, and e even
+    let bits = x.to_bits();
+    let (mut m, mut e) = match (bits >> 52) as i32 {
+        0 => (bits, -1074),
+        exponent => (bits & ((1 << 52) - 1) | 1 << 52, exponent - 1075),
+    };
+    let shift = m.leading_zeros() as i32 - 11;
+    m <<= shift;
+    e -= shift;
+    if e % 2 != 0 {
+        m <<= 1;
+        e -= 1;
+    }
+    // sqrt(m * 2^64) has at least 58 bits, the last one of which is set if it isn't exact, so
+    // that the conversion rounds it like the exact root.
+    let scaled = u128::from(m) << 64;
+    let root = isqrt(scaled);
+    let sticky = u128::from(root * root != scaled);
+    let exponent = (e - 64) / 2;
+    (root | sticky) as f64 * f64::from_bits(((exponent + 1023) as u64) << 52)
+}
+
+/// The integer square root of a positive `x`, like `u128::isqrt`, which requires Rust 1.84.
+#[cfg(any(not(feature = "std"), test))]
+fn isqrt(x: u128) -> u128 {
+    // Newton's iteration decreases monotonically to the root from any start above it.
+    let mut root = 1 << ((129 - x.leading_zeros()) / 2);
+    loop {
+        let next = (root + x / root) / 2;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+/// The number of partial sums of [`dot`] with the `simd` feature, i.e. four lanes of 256-bit
+/// vectors
+#[cfg(feature = "simd")]
+const LANES: usize = 4;
+
+/// Computes \\(x^\intercal y\\) from \\(+0\\) like QuadProg++, whereas `sum` starts from
+/// \\(-0\\), e.g. for empty vectors.
+#[cfg(not(feature = "simd"))]
+pub(crate) fn dot(x: &[f64], y: &[f64]) -> f64 {
+    x.iter().zip(y).fold(0.0, |sum, (x, y)| sum + x * y)
+}
+
+#[cfg(feature = "simd")]
+pub(crate) fn dot(x: &[f64], y: &[f64]) -> f64 {
+    let len = x.len().min(y.len());
+    let (x, y) = (&x[..len], &y[..len]);
+    let mut sums = [0.0; LANES];
+    let (x_chunks, y_chunks) = (x.chunks_exact(LANES), y.chunks_exact(LANES));
+    let tail = dot_tail(x_chunks.remainder(), y_chunks.remainder());
+    for (x, y) in x_chunks.zip(y_chunks) {
+        for (sum, (x, y)) in sums.iter_mut().zip(x.iter().zip(y)) {
+            *sum += x * y;
+        }
+    }
+    (sums[0] + sums[2]) + (sums[1] + sums[3]) + tail
+}
+
+#[cfg(feature = "simd")]
+fn dot_tail(x: &[f64], y: &[f64]) -> f64 {
+    x.iter().zip(y).map(|(x, y)| x * y).sum()
+}
+
+/// Computes \\(s - x^\intercal y\\), subtracting the products one by one from the last like
+/// QuadProg++ unless the `simd` feature is enabled.
+fn sub_dot_rev(s: f64, x: &[f64], y: &[f64]) -> f64 {
+    if cfg!(feature = "simd") {
+        s - dot(x, y)
+    } else {
+        x.iter().zip(y).rev().fold(s, |s, (x, y)| s - x * y)
+    }
+}
+
+/// Computes \\(s - x^\intercal y\\), subtracting the products one by one like QuadProg++ unless
+/// the `simd` feature is enabled.
+fn sub_dot(s: f64, x: &[f64], y: &[f64]) -> f64 {
+    if cfg!(feature = "simd") {
+        s - dot(x, y)
+    } else {
+        x.iter().zip(y).fold(s, |s, (x, y)| s - x * y)
+    }
+}
+
+/// Overwrites the row-major \\(N \times N\\) matrix `a` with its Cholesky factor \\(L\\) in the
+/// lower triangle and \\(L^\intercal\\) in the upper triangle, like `cholesky_decomposition` in
+/// QuadProg++. Fails with [`Error::SingularHessian`] if `a` isn't positive definite.
+// `!(sum > 0.0)` also rejects NaN pivots.
+#[allow(clippy::neg_cmp_op_on_partial_ord)]
+pub(crate) fn cholesky(a: &mut [f64], n: usize) -> Result<()> {
+    for i in 0..n {
+        for j in i..n {
+            let sum = sub_dot_rev(a[i * n + j], &a[i * n..][..i], &a[j * n..][..i]);
+            if i == j {
+                if !(sum > 0.0) {
+                    return Err(Error::SingularHessian { pivot: i });
+                }
+                a[i * n + i] = sqrt(sum);
+            } else {
+                a[j * n + i] = sum / a[i * n + i];
+            }
+        }
+        for k in i + 1..n {
+            a[i * n + k] = a[k * n + i];
+        }
+    }
+    Ok(())
+}
+
+/// Solves \\(L y = b\\) given the factor computed by [`cholesky`].
+pub(crate) fn forward_elimination(l: &[f64], b: &[f64], y: &mut [f64]) {
+    let n = b.len();
+    // The elements of y before the first nonzero of b are +0.0, whose products don't change the
+    // sums, which matters for the columns of the identity which give the initial J of the
+    // pure-Rust port.
+    let start = b.iter().position(|b| b.to_bits() != 0).unwrap_or(n);
+    y[..start].fill(0.0);
+    for i in start..n {
+        y[i] = sub_dot(b[i], &l[i * n + start..i * n + i], &y[start..i]) / l[i * n + i];
+    }
+}
+
+/// Solves \\(L^\intercal x = y\\) by the upper triangle of the factor computed by [`cholesky`].
+pub(crate) fn backward_elimination(l: &[f64], y: &[f64], x: &mut [f64]) {
+    let n = y.len();
+    for i in (0..n).rev() {
+        x[i] = sub_dot(y[i], &l[i * n + i + 1..(i + 1) * n], &x[i + 1..n]) / l[i * n + i];
+    }
+}
+
+/// Solves \\(L L^\intercal x = b\\) given the factor computed by [`cholesky`], like
+/// `cholesky_solve` in QuadProg++.
+#[cfg(feature = "ndarray")]
+pub(crate) fn cholesky_solve(l: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut y = vec![0.0; b.len()];
+    let mut x = vec![0.0; b.len()];
+    forward_elimination(l, b, &mut y);
+    backward_elimination(l, &y, &mut x);
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kernels() {
+        // Sums of small integers are exact in any order.
+        let x: Vec<f64> = (1..=7).map(f64::from).collect();
+        let y: Vec<f64> = (1..=7).rev().map(f64::from).collect();
+        assert_eq!(dot(&x, &y), 84.0);
+        assert_eq!(dot(&x[..6], &y), 77.0);
+        assert_eq!(sub_dot(100.0, &x[..3], &y[..3]), 66.0);
+        assert_eq!(sub_dot_rev(100.0, &x[..3], &y[..3]), 66.0);
+
+        // [[16, 8], [8, 13]] = L L^T with L = [[4, 0], [2, 3]]
+        let mut l = [16.0, 8.0, 8.0, 13.0];
+        cholesky(&mut l, 2).unwrap();
+        assert_eq!(l, [4.0, 2.0, 2.0, 3.0]);
+        let mut y = [0.0; 2];
+        forward_elimination(&l, &[0.0, 3.0], &mut y);
+        assert_eq!(y, [0.0, 1.0]);
+        forward_elimination(&l, &[8.0, 7.0], &mut y);
+        assert_eq!(y, [2.0, 1.0]);
+        let mut x = [0.0; 2];
+        backward_elimination(&l, &[8.0, 3.0], &mut x);
+        assert_eq!(x, [1.5, 1.0]);
+
+        let mut singular = [1.0, 1.0, 1.0, 1.0];
+        let err = cholesky(&mut singular, 2).unwrap_err();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+        let mut nan = [1.0, 0.0, 0.0, f64::NAN];
+        let err = cholesky(&mut nan, 2).unwrap_err();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn soft_sqrt_is_correctly_rounded() {
+        let special = [
+            0.0,
+            -0.0,
+            1.0,
+            2.0,
+            0.25,
+            f64::MIN_POSITIVE,
+            f64::MAX,
+            f64::INFINITY,
+        ];
+        let subnormal = [f64::from_bits(1), f64::from_bits(0x000f_ffff_ffff_ffff)];
+        // A xorshift sequence of positive finite numbers
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let random = (0..100_000).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            f64::from_bits(state % 0x7ff0_0000_0000_0000)
+        });
+        for x in special.iter().chain(&subnormal).copied().chain(random) {
+            assert_eq!(soft_sqrt(x).to_bits(), x.sqrt().to_bits(), "sqrt({:e})", x);
+        }
+        assert_eq!(isqrt(1), 1);
+        assert_eq!(isqrt(8), 2);
+        assert_eq!(isqrt(9), 3);
+        assert_eq!(isqrt(u128::MAX), u128::from(u64::MAX));
+        assert!(soft_sqrt(-1.0).is_nan());
+        assert!(soft_sqrt(f64::NAN).is_nan());
+    }
+}
@@ -0,0 +1,425 @@
+use ndarray::{s, Array1, Array2, ArrayBase, ArrayViewMut1, Axis, Data, Ix1, Ix2, RawData};
+
+use crate::problem::OwnedConstraints;
+use crate::{
+    block, solve_with_options, Constraints, Error, Result, Solution, SolveOptions, Status,
+};
+
+/// How [`solve_lsq_with_options`] reduces a least-squares problem to a quadratic program.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub enum LsqMethod {
+    /// Forms the normal equations, \\(G = A^\intercal A\\) and \\(g_0 = -A^\intercal b\\). This is
+    /// the cheapest method, but the condition number of \\(G\\) is the square of that of
+    /// \\(A\\).
+    #[default]
+    NormalEquations,
+    /// Factorizes \\(A = Q R\\) by Householder reflections, and solves for \\(y = R x\\), whose
+    /// Hessian is the identity. The constraints are transformed by \\(R^{-\intercal}\\), and the
+    /// multipliers are those of the original problem. This keeps the accuracy which the normal
+    /// equations lose when \\(A\\) is ill-conditioned, at the cost of the factorization.
+    Qr,
+}
+
+/// Solves a constrained linear least-squares problem:
+///
+/// $$
+/// \begin{align*}
+/// &\min_{x} \quad \frac{1}{2} \lVert A x - b \rVert^2 \\\\
+/// &\textrm{subject to} \\\\
+/// &\qquad\qquad
+/// \begin{aligned}
+/// CE^\intercal x + ce_0 &= 0 \\\\
+/// CI^\intercal x + ci_0 &\ge 0
+/// \end{aligned}
+/// \end{align*}
+/// $$
+///
+/// where \\(A\\) is an \\(M \times N\\) matrix with one row per observation, \\(b\\) is an
+/// \\(M\\)-vector, and the constraints are those of [`solve`](crate::solve). This is the problem
+/// of [`solve`](crate::solve) with \\(G = A^\intercal A\\) and \\(g_0 = -A^\intercal b\\), so
+/// \\(A\\) must have full column rank. [`Solution::objective`] is
+/// \\(\frac{1}{2} \lVert A x - b \rVert^2\\).
+///
+/// # Errors
+///
+/// If \\(b\\) doesn't have one element per row of \\(A\\), this function returns
+/// [`Error::SizeMismatch`]. Otherwise, it returns the errors of [`solve`](crate::solve), e.g.
+/// [`Error::SingularHessian`] if \\(A\\) is rank deficient.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// // Fits y = c0 + c1 t to (0, 1), (1, 2), (2, 4) with the intercept c0 <= 0.5
+/// let a = array![[1.0, 0.0], [1.0, 1.0], [1.0, 2.0]];
+/// let b = array![1.0, 2.0, 4.0];
+/// let ci = Constraints::some(array![[-1.0], [0.0]], array![0.5]);
+/// let solution = solve_lsq(&a, &b, Constraints::NONE, ci)?;
+/// assert!((solution.x[0] - 0.5).abs() < 1e-12);
+/// assert!((solution.x[1] - 1.7).abs() < 1e-12);
+/// assert!((solution.objective - 0.15).abs() < 1e-12);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn solve_lsq<A, B, CE, CE0, CI, CI0>(
+    a: &ArrayBase<A, Ix2>,
+    b: &ArrayBase<B, Ix1>,
+    ce: Option<Constraints<CE, CE0>>,
+    ci: Option<Constraints<CI, CI0>>,
+) -> Result<Solution>
+where
+    A: Data<Elem = f64>,
+    B: Data<Elem = f64>,
+    CE: RawData<Elem = f64>,
+    CE0: RawData<Elem = f64>,
+    CI: RawData<Elem = f64>,
+    CI0: RawData<Elem = f64>,
+{
+    solve_lsq_with_options(a, b, ce, ci, LsqMethod::default(), &SolveOptions::default())
+}
+
+/// Solves a constrained linear least-squares problem like [`solve_lsq`] by the given method and
+/// with options.
+///
+/// [`SolveOptions::regularization`] adds \\(\frac{\epsilon}{2} \lVert x \rVert^2\\) to the
+/// objective, i.e. turns the problem into ridge regression, which is also how the
+/// [`Qr`](LsqMethod::Qr) method applies it. [`SolveOptions::refine_regularization`] takes the same
+/// step with either method. [`Solution::objective`] remains
+/// \\(\frac{1}{2} \lVert A x - b \rVert^2\\).
+///
+/// # Errors
+///
+/// Same as [`solve_lsq`]. With the [`Qr`](LsqMethod::Qr) method, a rank-deficient \\(A\\) gives
+/// [`Error::SingularHessian`] at the first column of \\(A\\) which depends on the previous ones.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// // The condition number of A is about 1e7, so that of A^T A is about 1e14.
+/// let a = array![[1.0, 1.0], [1.0, 1.0 + 1e-7], [1.0, 1.0 - 1e-7]];
+/// let b = array![2.0, 2.0 + 1e-7, 2.0 - 1e-7];
+/// let options = SolveOptions::default();
+/// let qr = solve_lsq_with_options(&a, &b, Constraints::NONE, Constraints::NONE, LsqMethod::Qr, &options)?;
+/// assert!((qr.x[0] - 1.0).abs() < 1e-8);
+/// assert!((qr.x[1] - 1.0).abs() < 1e-8);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn solve_lsq_with_options<A, B, CE, CE0, CI, CI0>(
+    a: &ArrayBase<A, Ix2>,
+    b: &ArrayBase<B, Ix1>,
+    ce: Option<Constraints<CE, CE0>>,
+    ci: Option<Constraints<CI, CI0>>,
+    method: LsqMethod,
+    options: &SolveOptions,
+) -> Result<Solution>
+where
+    A: Data<Elem = f64>,
+    B: Data<Elem = f64>,
+    CE: RawData<Elem = f64>,
+    CE0: RawData<Elem = f64>,
+    CI: RawData<Elem = f64>,
+    CI0: RawData<Elem = f64>,
+{
+    assert_size!(b, a.nrows(), b.len());
+    let mut solution = match method {
+        LsqMethod::NormalEquations => {
+            solve_with_options(a.t().dot(a), -a.t().dot(b), ce, ci, options)?
+        }
+        LsqMethod::Qr => solve_qr(a, b, ce, ci, options)?,
+    };
+    let residual = a.dot(&solution.x) - b;
+    solution.objective = 0.5 * residual.dot(&residual);
+    Ok(solution)
+}
+
+/// Solves for \\(y = R x\\), where \\(Q R\\) factorizes \\(A\\) with rows of
+/// \\(\sqrt{\epsilon} I\\) appended for the regularization.
+///
+/// The refinement of the regularization solves again with \\(g_0 - \epsilon \hat{x}\\) like
+/// QuadProg++ does for the normal equations, i.e. with \\(-\epsilon R^{-\intercal} \hat{x}\\)
+/// added to the linear term of \\(y\\).
+fn solve_qr<A, B, CE, CE0, CI, CI0>(
+    a: &ArrayBase<A, Ix2>,
+    b: &ArrayBase<B, Ix1>,
+    ce: Option<Constraints<CE, CE0>>,
+    ci: Option<Constraints<CI, CI0>>,
+    options: &SolveOptions,
+) -> Result<Solution>
+where
+    A: Data<Elem = f64>,
+    B: Data<Elem = f64>,
+    CE: RawData<Elem = f64>,
+    CE0: RawData<Elem = f64>,
+    CI: RawData<Elem = f64>,
+    CI0: RawData<Elem = f64>,
+{
+    let (m, n) = a.dim();
+    let epsilon = options.regularization;
+    let rows = if epsilon > 0.0 { m + n } else { m };
+    let mut qr = Array2::zeros((rows, n + 1));
+    qr.slice_mut(s![..m, ..n]).assign(a);
+    qr.slice_mut(s![..m, n]).assign(b);
+    if epsilon > 0.0 {
+        qr.slice_mut(s![m.., ..n]).diag_mut().fill(epsilon.sqrt());
+    }
+    householder(&mut qr);
+    let r = qr.slice(s![..n.min(rows), ..n]);
+    // The usual tolerance of the numerical rank
+    let norm = r.iter().fold(0.0, |max: f64, r| max.max(r.abs()));
+    let tolerance = rows.max(n) as f64 * f64::EPSILON * norm;
+    for pivot in 0..n {
+        if pivot >= rows || r[[pivot, pivot]].abs() <= tolerance {
+            return Err(Error::SingularHessian { pivot });
+        }
+    }
+    let r = r.to_owned();
+
+    let ce = ce.map(|ce| transform(&ce, &r)).transpose()?;
+    let ci = ci.map(|ci| transform(&ci, &r)).transpose()?;
+
+    let refine = options.refine_regularization && epsilon > 0.0;
+    let mut options = options
+        .clone()
+        .regularization(0.0)
+        .refine_regularization(false);
+    if let Some(x) = options.initial_point.as_mut() {
+        if x.len() == n {
+            *x = r.dot(&Array1::from(x.clone())).to_vec();
+        }
+    }
+    let g0 = -&qr.slice(s![..n, n]);
+    let mut solution = solve_with_options(
+        Array2::eye(n),
+        g0.view(),
+        ce.as_ref().map(Constraints::view),
+        ci.as_ref().map(Constraints::view),
+        &options,
+    )?;
+    back_substitute(&r, solution.x.view_mut());
+    if !refine || solution.status != Status::Optimal {
+        return Ok(solution);
+    }
+
+    // -epsilon x_hat^T x = -epsilon (R^-T x_hat)^T y
+    let mut shift = solution.x.mapv(|x| epsilon * x);
+    forward_substitute(&r, shift.view_mut());
+    options.initial_point = None;
+    options.active_set = solution.active_set.clone();
+    let mut refined = solve_with_options(Array2::eye(n), g0 - shift, ce, ci, &options)?;
+    back_substitute(&r, refined.x.view_mut());
+    refined.iterations += solution.iterations;
+    refined.factorizations += solution.factorizations;
+    refined.additions += solution.additions;
+    refined.deletions += solution.deletions;
+    refined.rotations += solution.rotations;
+    refined.warm_started = solution.warm_started;
+    Ok(refined)
+}
+
+/// Solves \\(R x = y\\) in place.
+fn back_substitute(r: &Array2<f64>, mut y: ArrayViewMut1<f64>) {
+    for i in (0..r.nrows()).rev() {
+        let sum = (i + 1..r.nrows()).fold(y[i], |sum, k| sum - r[[i, k]] * y[k]);
+        y[i] = sum / r[[i, i]];
+    }
+}
+
+/// Solves \\(R^\intercal y = c\\) in place.
+fn forward_substitute(r: &Array2<f64>, mut c: ArrayViewMut1<f64>) {
+    for i in 0..r.nrows() {
+        let sum = (0..i).fold(c[i], |sum, k| sum - r[[k, i]] * c[k]);
+        c[i] = sum / r[[i, i]];
+    }
+}
+
+/// Returns the constraints on \\(y = R x\\), whose coefficients are \\(R^{-\intercal} C\\).
+fn transform<S, S0>(c: &Constraints<S, S0>, r: &Array2<f64>) -> Result<OwnedConstraints>
+where
+    S: RawData<Elem = f64>,
+    S0: RawData<Elem = f64>,
+{
+    let n = r.nrows();
+    let mut c = block::owned(c);
+    assert_size!(c.coeffs, n, c.coeffs.nrows());
+    for column in c.coeffs.axis_iter_mut(Axis(1)) {
+        forward_substitute(r, column);
+    }
+    Ok(c)
+}
+
+/// Reduces `a` to upper triangular form in place by Householder reflections on its rows, which
+/// also apply \\(Q^\intercal\\) to its columns after the first `ncols - 1`.
+fn householder(a: &mut Array2<f64>) {
+    let (m, cols) = a.dim();
+    for j in 0..(cols - 1).min(m) {
+        let norm = a.slice(s![j.., j]).dot(&a.slice(s![j.., j])).sqrt();
+        if norm == 0.0 {
+            continue;
+        }
+        let alpha = if a[[j, j]] > 0.0 { -norm } else { norm };
+        let mut v: Array1<f64> = a.slice(s![j.., j]).to_owned();
+        v[0] -= alpha;
+        let vv = v.dot(&v);
+        for k in j..cols {
+            let mut column = a.slice_mut(s![j.., k]);
+            let factor = 2.0 * v.dot(&column) / vv;
+            column.scaled_add(-factor, &v);
+        }
+        a[[j, j]] = alpha;
+        a.slice_mut(s![j + 1.., j]).fill(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+    use crate::solve;
+
+    #[test]
+    fn matches_solve() -> Result<()> {
+        // Four observations of three variables, with x0 + x1 + x2 = 1 and x >= 0
+        #[rustfmt::skip]
+        let a = array![
+            [1.0, 2.0, 0.0],
+            [0.0, 1.0, 1.0],
+            [2.0, 0.0, 1.0],
+            [1.0, 1.0, 1.0],
+        ];
+        let b = array![1.0, -1.0, 3.0, 0.5];
+        let ce = || Constraints::some(array![[1.0], [1.0], [1.0]], array![-1.0]);
+        let ci = || Constraints::some(Array2::eye(3), Array1::zeros(3));
+        let expected = solve(a.t().dot(&a), -a.t().dot(&b), ce(), ci())?;
+        let options = SolveOptions::default();
+        for method in [LsqMethod::NormalEquations, LsqMethod::Qr] {
+            let solution = solve_lsq_with_options(&a, &b, ce(), ci(), method, &options)?;
+            for (&actual, &expected) in solution.x.iter().zip(&expected.x) {
+                assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+            }
+            let residual = a.dot(&solution.x) - &b;
+            assert_abs_diff_eq!(
+                solution.objective,
+                0.5 * residual.dot(&residual),
+                epsilon = 1e-12
+            );
+            assert_abs_diff_eq!(
+                solution.multipliers.eq[0],
+                expected.multipliers.eq[0],
+                epsilon = 1e-12
+            );
+            for (&actual, &expected) in solution
+                .multipliers
+                .ineq
+                .iter()
+                .zip(&expected.multipliers.ineq)
+            {
+                assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+            }
+            assert_eq!(solution.active_set, expected.active_set);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ill_conditioned() -> Result<()> {
+        let delta = 1e-7;
+        let a = array![[1.0, 1.0], [1.0, 1.0 + delta], [1.0, 1.0 - delta]];
+        // b = A (1, 1), with x1 <= 0.5 active
+        let b = array![2.0, 2.0 + delta, 2.0 - delta];
+        let ci = || Constraints::some(array![[0.0], [-1.0]], array![0.5]);
+        let options = SolveOptions::default();
+        let qr = solve_lsq_with_options(&a, &b, Constraints::NONE, ci(), LsqMethod::Qr, &options)?;
+        // The minimizer on x1 = 0.5 is x0 = 1.5.
+        assert_abs_diff_eq!(qr.x[0], 1.5, epsilon = 1e-8);
+        assert_abs_diff_eq!(qr.x[1], 0.5, epsilon = 1e-8);
+        // The gradient of the objective is A^T (A x - b) = (0, -delta^2) at the minimizer.
+        assert_abs_diff_eq!(qr.multipliers.ineq[0], delta * delta, epsilon = 1e-18);
+        Ok(())
+    }
+
+    #[test]
+    fn regularization() -> Result<()> {
+        // min (x - 1)^2 / 2 + x^2 / 2 at x = 0.5, whose residual is 0.5
+        let a = array![[1.0]];
+        let b = array![1.0];
+        let options = SolveOptions::new().regularization(1.0);
+        for method in [LsqMethod::NormalEquations, LsqMethod::Qr] {
+            let solution = solve_lsq_with_options(
+                &a,
+                &b,
+                Constraints::NONE,
+                Constraints::NONE,
+                method,
+                &options,
+            )?;
+            assert_abs_diff_eq!(solution.x[0], 0.5, epsilon = 1e-12);
+            assert_abs_diff_eq!(solution.objective, 0.125, epsilon = 1e-12);
+        }
+
+        // min |x - (1, 2)|^2 / 2 s.t. x0 + x1 <= 1.5 at x1 - x0 = 1. The regularization gives
+        // x1 - x0 = 1 / (1 + epsilon), and the refinement
+        // (1 + epsilon / (1 + epsilon)) / (1 + epsilon).
+        let a = array![[1.0, 0.0], [0.0, 1.0]];
+        let b = array![1.0, 2.0];
+        let ci = || Constraints::some(array![[-1.0], [-1.0]], array![1.5]);
+        let epsilon = 1e-3;
+        let options = SolveOptions::new()
+            .regularization(epsilon)
+            .refine_regularization(true);
+        let difference = (1.0 + epsilon / (1.0 + epsilon)) / (1.0 + epsilon);
+        for method in [LsqMethod::NormalEquations, LsqMethod::Qr] {
+            let solution =
+                solve_lsq_with_options(&a, &b, Constraints::NONE, ci(), method, &options)?;
+            assert_abs_diff_eq!(solution.x[0], (1.5 - difference) / 2.0, epsilon = 1e-12);
+            assert_abs_diff_eq!(solution.x[1], (1.5 + difference) / 2.0, epsilon = 1e-12);
+            assert_eq!(solution.active_set, [0]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let a = array![[1.0, 2.0], [2.0, 4.0], [3.0, 6.0]];
+        let err =
+            solve_lsq(&a, &array![1.0, 2.0], Constraints::NONE, Constraints::NONE).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "b",
+                    expected: 3,
+                    actual: 2,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+
+        // The second column is twice the first.
+        let b = array![1.0, 2.0, 3.0];
+        let err = solve_lsq_with_options(
+            &a,
+            &b,
+            Constraints::NONE,
+            Constraints::NONE,
+            LsqMethod::Qr,
+            &SolveOptions::default(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
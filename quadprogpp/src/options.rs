@@ -0,0 +1,716 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use ndarray::{ArrayBase, Data, Ix1};
+use quadprogpp_sys as sys;
+
+use crate::{utils, Error, Result, Solution};
+
+/// Options controlling the termination of the solver, passed to
+/// [`solve_with_options`](crate::solve_with_options).
+///
+/// The defaults reproduce the behaviour of the upstream QuadProg++ solver.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let options = SolveOptions::new()
+///     .max_iterations(100)
+///     .feasibility_tolerance(1e-9);
+/// let solution = solve_with_options(
+///     array![[1.0]],
+///     array![0.0],
+///     Constraints::NONE,
+///     Constraints::some(array![[1.0]], array![-1.0]),
+///     &options,
+/// )?;
+/// assert_eq!(solution.x, array![1.0]);
+/// # Ok::<(), Error>(())
+/// ```
+///
+/// With the `serde` feature, the options can be serialized except for the
+/// [`deadline`](Self::deadline), the [`cancellation`](Self::cancellation) flag and the
+/// [`on_iteration`](Self::on_iteration) callback, which are left unset when deserialized.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SolveOptions {
+    max_iterations: Option<u32>,
+    pub(crate) best_effort: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    deadline: Option<Instant>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cancellation: Option<Cancellation>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    on_iteration: Option<Callback>,
+    pub(crate) feasibility_tolerance: f64,
+    epsilon: f64,
+    symmetry_tolerance: Option<f64>,
+    symmetrize: bool,
+    upper_triangle: bool,
+    pub(crate) polish: bool,
+    lp_fallback: bool,
+    pub(crate) regularization: f64,
+    pub(crate) refine_regularization: bool,
+    max_degenerate_steps: Option<u32>,
+    anti_cycling: bool,
+    pub(crate) equilibrate: bool,
+    pub(crate) presolve: bool,
+    pub(crate) active_set: Vec<usize>,
+    pub(crate) initial_point: Option<Vec<f64>>,
+}
+
+/// The flag of [`SolveOptions::cancellation`], compared by identity.
+#[derive(Clone, Debug)]
+struct Cancellation(Arc<AtomicBool>);
+
+impl PartialEq for Cancellation {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// The callback of [`SolveOptions::on_iteration`], compared by identity.
+#[derive(Clone)]
+struct Callback(Arc<dyn Fn(&Progress) + Send + Sync>);
+
+impl PartialEq for Callback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for Callback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Callback")
+    }
+}
+
+/// The state of the solver in an iteration of the active-set loop, passed to
+/// [`SolveOptions::on_iteration`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+#[non_exhaustive]
+pub struct Progress {
+    /// The iteration, counted from one
+    pub iteration: usize,
+    /// The objective function of the minimization at the current iterate
+    pub objective: f64,
+    /// The sum of the violations of the inequality constraints and bounds at the current
+    /// iterate, beyond [`SolveOptions::feasibility_tolerance`]
+    pub infeasibility: f64,
+    /// Number of inequality constraints and bounds in the active set, counting both limits of
+    /// ranged constraints
+    pub active_set_size: usize,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        Self {
+            max_iterations: None,
+            best_effort: false,
+            deadline: None,
+            cancellation: None,
+            on_iteration: None,
+            feasibility_tolerance: 0.0,
+            epsilon: f64::EPSILON,
+            symmetry_tolerance: None,
+            symmetrize: false,
+            upper_triangle: false,
+            polish: false,
+            lp_fallback: false,
+            regularization: 0.0,
+            refine_regularization: false,
+            max_degenerate_steps: None,
+            anti_cycling: false,
+            equilibrate: false,
+            presolve: false,
+            active_set: Vec::new(),
+            initial_point: None,
+        }
+    }
+}
+
+impl SolveOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the number of iterations of the active-set loop. There's no limit by default.
+    ///
+    /// The solver fails with [`Error::MaxIterations`](crate::Error::MaxIterations) when the limit
+    /// is reached, unless [`best_effort`](Self::best_effort) is enabled.
+    pub fn max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    /// Returns the last iterate with [`Status::MaxIterations`](crate::Status::MaxIterations)
+    /// instead of failing with [`Error::MaxIterations`](crate::Error::MaxIterations) when the
+    /// iteration limit is reached. Disabled by default.
+    ///
+    /// Likewise, the solver returns [`Status::Interrupted`](crate::Status::Interrupted) at the
+    /// [`deadline`](Self::deadline) and [`Status::Degenerate`](crate::Status::Degenerate) beyond
+    /// [`max_degenerate_steps`](Self::max_degenerate_steps) instead of failing.
+    ///
+    /// This guarantees a result within a bounded time, but not a feasible one. The dual method of
+    /// Goldfarb and Idnani only adds violated constraints to the active set one at a time, so each
+    /// iterate satisfies the equality constraints and the active inequality constraints, and may
+    /// violate the others: the first iterate which satisfies all of them is the optimum. Hence
+    /// there's no feasible iterate to fall back to, and the last one is returned instead. Its
+    /// objective increases with every iteration and is a lower bound of the optimal one. Check
+    /// [`Solution::status`] before relying on its feasibility.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // min x^2 + y^2 s.t. x >= 1, y >= 1
+    /// let g = array![[2.0, 0.0], [0.0, 2.0]];
+    /// let g0 = array![0.0, 0.0];
+    /// let ci = Constraints::some(array![[1.0, 0.0], [0.0, 1.0]], array![-1.0, -1.0]);
+    /// let options = SolveOptions::new().max_iterations(1).best_effort(true);
+    /// let solution = solve_with_options(g, g0, Constraints::NONE, ci, &options)?;
+    /// assert_eq!(solution.status, Status::MaxIterations);
+    /// assert_eq!(solution.active_set.len(), 1);
+    /// // The other constraint is still violated, and the objective is below the optimal 2.
+    /// assert!(solution.x.iter().any(|&x| x < 1.0));
+    /// assert!(solution.objective < 2.0);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn best_effort(mut self, enabled: bool) -> Self {
+        self.best_effort = enabled;
+        self
+    }
+
+    /// Stops the solver with [`Error::Interrupted`](crate::Error::Interrupted) if it's still
+    /// running at `deadline`. There's no deadline by default.
+    ///
+    /// The deadline is checked before each iteration of the active-set loop, so the solver may
+    /// overrun it by the time of one iteration. With [`best_effort`](Self::best_effort), the last
+    /// iterate is returned with [`Status::Interrupted`](crate::Status::Interrupted) instead.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stops the solver with [`Error::Interrupted`](crate::Error::Interrupted) once `flag` is set,
+    /// e.g. by another thread.
+    ///
+    /// Like [`deadline`](Self::deadline), the flag is checked before each iteration of the
+    /// active-set loop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::sync::atomic::AtomicBool;
+    /// # use std::sync::Arc;
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let g = array![[1.0, 0.0], [0.0, 1.0]];
+    /// let g0 = array![0.0, 0.0];
+    /// let ci = Constraints::some(array![[1.0, 0.0], [0.0, 1.0]], array![-1.0, -1.0]);
+    /// let options = SolveOptions::new().cancellation(Arc::new(AtomicBool::new(true)));
+    /// let err = solve_with_options(g, g0, Constraints::NONE, ci, &options).unwrap_err();
+    /// assert!(matches!(err, Error::Interrupted { iterations: 0 }));
+    /// ```
+    pub fn cancellation(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancellation = Some(Cancellation(flag));
+        self
+    }
+
+    /// Calls `callback` in each iteration of the active-set loop, once the infeasibility of the
+    /// current iterate is known, e.g. to log the progress of the solver.
+    ///
+    /// The dual method starts from the unconstrained minimizer and adds a violated constraint to
+    /// the active set in each iteration, so the objective increases and the infeasibility
+    /// typically decreases until the last iteration, where it's zero. With
+    /// [`lp_fallback`](Self::lp_fallback) and
+    /// [`refine_regularization`](Self::refine_regularization), the objective is the one of the
+    /// current subproblem.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::sync::{Arc, Mutex};
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let g = array![[1.0, 0.0], [0.0, 1.0]];
+    /// let g0 = array![0.0, 0.0];
+    /// let ci = Constraints::some(array![[1.0, 0.0], [0.0, 1.0]], array![-1.0, -1.0]);
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = log.clone();
+    /// let options = SolveOptions::new()
+    ///     .on_iteration(move |progress| sink.lock().unwrap().push(progress.infeasibility));
+    /// solve_with_options(g, g0, Constraints::NONE, ci, &options)?;
+    /// assert_eq!(*log.lock().unwrap(), [2.0, 1.0, 0.0]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn on_iteration<F: Fn(&Progress) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_iteration = Some(Callback(Arc::new(callback)));
+        self
+    }
+
+    /// Sets the amount by which an inequality constraint may be violated and still be considered
+    /// satisfied. Defaults to zero.
+    pub fn feasibility_tolerance(mut self, tolerance: f64) -> Self {
+        self.feasibility_tolerance = tolerance;
+        self
+    }
+
+    /// Sets the relative tolerance of the optimality test, scaled by the number of inequality
+    /// constraints and an estimate of the condition number of \\(G\\). Defaults to
+    /// [`f64::EPSILON`].
+    ///
+    /// The same tolerance decides when a constraint being added to the active set is linearly
+    /// dependent on the active ones, and when a step in the primal space is zero. A looser
+    /// epsilon rejects nearly dependent constraints of ill-conditioned problems with
+    /// [`Error::LinearlyDependent`](crate::Error::LinearlyDependent) instead of taking huge
+    /// steps along them, and a tighter one accepts them.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Checks that \\(G\\) is symmetric before solving. Disabled by default, in which case an
+    /// asymmetric \\(G\\) silently yields a wrong solution.
+    ///
+    /// The solver fails with [`Error::NotSymmetric`](crate::Error::NotSymmetric) if
+    /// \\(|G_{ij} - G_{ji}| > \tau \max(|G_{ij}|, |G_{ji}|)\\) for some \\(i, j\\), where
+    /// \\(\tau\\) is `tolerance`. The error locates the element with the largest relative
+    /// difference.
+    pub fn symmetry_tolerance(mut self, tolerance: f64) -> Self {
+        self.symmetry_tolerance = Some(tolerance);
+        self
+    }
+
+    /// Replaces \\(G\\) with \\((G + G^\intercal) / 2\\) before solving. Disabled by default.
+    ///
+    /// If [`symmetry_tolerance`](Self::symmetry_tolerance) is also set, \\(G\\) is checked
+    /// first, so that only rounding errors are symmetrized away.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let g = array![[2.0, 1.0], [0.0, 2.0]];
+    /// let g0 = array![-3.0, -3.0];
+    /// let options = SolveOptions::new().symmetry_tolerance(1e-12);
+    /// let err = solve_with_options(g.view(), g0.view(), Constraints::NONE, Constraints::NONE, &options)
+    ///     .unwrap_err();
+    /// assert!(matches!(err, Error::NotSymmetric { row: 1, col: 0 }));
+    ///
+    /// let options = SolveOptions::new().symmetrize(true);
+    /// let solution = solve_with_options(g, g0, Constraints::NONE, Constraints::NONE, &options)?;
+    /// assert!((solution.x[0] - 1.2).abs() < 1e-12);
+    /// assert!((solution.x[1] - 1.2).abs() < 1e-12);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn symmetrize(mut self, enabled: bool) -> Self {
+        self.symmetrize = enabled;
+        self
+    }
+
+    /// Reads only the upper triangle of \\(G\\), including the diagonal, and mirrors it into
+    /// the lower triangle before solving. Disabled by default.
+    ///
+    /// The lower triangle is ignored, so it may hold anything, e.g. zeros when \\(G\\) comes
+    /// from a pipeline which only computes one triangle of a covariance matrix, or NaN. This
+    /// option takes precedence over [`symmetry_tolerance`](Self::symmetry_tolerance) and
+    /// [`symmetrize`](Self::symmetrize). See [`utils::unpack_upper`](crate::utils::unpack_upper)
+    /// for packed upper triangles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let g = array![[2.0, 1.0], [f64::NAN, 2.0]];
+    /// let g0 = array![-3.0, -3.0];
+    /// let options = SolveOptions::new().upper_triangle(true);
+    /// let solution = solve_with_options(g, g0, Constraints::NONE, Constraints::NONE, &options)?;
+    /// assert!((solution.x[0] - 1.0).abs() < 1e-12);
+    /// assert!((solution.x[1] - 1.0).abs() < 1e-12);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn upper_triangle(mut self, enabled: bool) -> Self {
+        self.upper_triangle = enabled;
+        self
+    }
+
+    /// Adds \\(\epsilon I\\) to \\(G\\) before solving, which makes positive semidefinite
+    /// Hessians positive definite. Defaults to zero.
+    ///
+    /// The regularization biases the solution towards zero by
+    /// \\(O(\epsilon)\\). [`refine_regularization`](Self::refine_regularization) reduces the bias.
+    /// [`Solution::objective`] is the value of the objective function of the unregularized problem.
+    ///
+    /// [`Solver::solve_for`](crate::Solver::solve_for) reuses the factorization of the Hessian as
+    /// it was regularized when it was factorized, and ignores this option.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // min x^2 / 2 s.t. x + y = 2
+    /// let g = array![[1.0, 0.0], [0.0, 0.0]];
+    /// let g0 = array![0.0, 0.0];
+    /// let ce = Constraints::some(array![[1.0], [1.0]], array![-2.0]);
+    /// let err = solve(g.view(), g0.view(), ce.clone(), Constraints::NONE).unwrap_err();
+    /// assert!(matches!(err, Error::SingularHessian { pivot: 1 }));
+    ///
+    /// let options = SolveOptions::new().regularization(1e-9);
+    /// let solution = solve_with_options(g, g0, ce, Constraints::NONE, &options)?;
+    /// assert!(solution.x[0].abs() < 1e-6);
+    /// assert!((solution.x[1] - 2.0).abs() < 1e-6);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn regularization(mut self, epsilon: f64) -> Self {
+        self.regularization = epsilon;
+        self
+    }
+
+    /// Follows the [regularized](Self::regularization) solve with one step of iterative
+    /// refinement against the unregularized problem. Disabled by default.
+    ///
+    /// The step solves the regularized problem again with \\(g_0 - \epsilon \hat{x}\\),
+    /// where \\(\hat{x}\\) is the regularized solution, which reduces the bias from
+    /// \\(O(\epsilon)\\) to \\(O(\epsilon^2)\\) for well-conditioned problems. The
+    /// factorization is reused, so the step is cheap.
+    pub fn refine_regularization(mut self, enabled: bool) -> Self {
+        self.refine_regularization = enabled;
+        self
+    }
+
+    /// Limits the number of consecutive steps of zero length. There's no limit by default.
+    ///
+    /// On degenerate problems, e.g. with more constraints active at the solution than variables,
+    /// the active set can change without any progress in the objective, and in rare cases the
+    /// solver cycles through the same active sets forever. Steps which drop constraints from the
+    /// active set aren't counted as iterations, so [`max_iterations`](Self::max_iterations)
+    /// doesn't stop such cycles. The solver fails with
+    /// [`Error::Degenerate`](crate::Error::Degenerate) when the limit is exceeded, unless
+    /// [`best_effort`](Self::best_effort) is enabled.
+    pub fn max_degenerate_steps(mut self, max_degenerate_steps: u32) -> Self {
+        self.max_degenerate_steps = Some(max_degenerate_steps);
+        self
+    }
+
+    /// Prevents cycling on degenerate problems. Disabled by default.
+    ///
+    /// After a step of zero length, the solver adds the violated constraint with the smallest
+    /// index instead of the most violated one, and drops the blocking constraint with the smallest
+    /// index, like Bland's rule in the simplex method. This may take more iterations than the
+    /// default rule, so it's usually combined with
+    /// [`max_degenerate_steps`](Self::max_degenerate_steps).
+    pub fn anti_cycling(mut self, enabled: bool) -> Self {
+        self.anti_cycling = enabled;
+        self
+    }
+
+    /// Scales the problem by Ruiz equilibration before solving it. Disabled by default.
+    ///
+    /// The variables and the constraints are scaled so that the rows and columns of the KKT
+    /// matrix \\(\begin{bmatrix} G & C \\\\ C^\intercal & 0 \end{bmatrix}\\) have similar norms,
+    /// which improves the accuracy on badly scaled problems, e.g. with variables in basis points
+    /// and notionals. The solution and the multipliers are mapped back to the original problem.
+    ///
+    /// The tolerances, [`feasibility_tolerance`](Self::feasibility_tolerance) and
+    /// [`regularization`](Self::regularization) apply to the scaled problem.
+    /// [`Solver::solve_for`](crate::Solver::solve_for) keeps the scaling of the variables of the
+    /// factorized Hessian, and only rescales the constraints.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // min 1e8 x^2 + y^2 - 2y s.t. 1e4 x + y >= 2
+    /// let g = array![[2e8, 0.0], [0.0, 2.0]];
+    /// let g0 = array![0.0, -2.0];
+    /// let ci = Constraints::some(array![[1e4], [1.0]], array![-2.0]);
+    /// let options = SolveOptions::new().equilibrate(true);
+    /// let solution = solve_with_options(g, g0, Constraints::NONE, ci, &options)?;
+    /// assert!((solution.x[0] - 0.5e-4).abs() < 1e-15);
+    /// assert!((solution.x[1] - 1.5).abs() < 1e-12);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn equilibrate(mut self, enabled: bool) -> Self {
+        self.equilibrate = enabled;
+        self
+    }
+
+    /// Simplifies a [`QpProblem`](crate::QpProblem) before solving it. Disabled by default.
+    ///
+    /// The presolve substitutes the variables fixed by equal lower and upper bounds, drops the
+    /// constraints whose coefficients are all zero, keeps one of the constraints with identical
+    /// coefficients (intersecting the limits of duplicate inequality constraints), and fails
+    /// with [`Error::Infeasible`](crate::Error::Infeasible) if any of these are contradictory or
+    /// a lower bound exceeds its upper bound. Up to
+    /// [`feasibility_tolerance`](Self::feasibility_tolerance), the check is exact.
+    ///
+    /// The solution and the multipliers are mapped back to the original problem: the multiplier
+    /// of a fixed variable is that of its lower or upper bound depending on its sign, and the
+    /// multiplier of duplicate constraints goes to the one with the binding limit.
+    ///
+    /// Presolving changes the size of the problem, so it's only done by
+    /// [`QpProblem::solve_with_options`](crate::QpProblem::solve_with_options). The other
+    /// solvers ignore this option.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // min x^2 + y^2 + z^2 s.t. x + y + z >= 3, x + y + z >= 1, z = 2
+    /// let problem = QpProblem::new(3)
+    ///     .hessian(array![[2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.0]])
+    ///     .ineq(Constraints::new(array![[1.0, 1.0], [1.0, 1.0], [1.0, 1.0]], array![-3.0, -1.0]))
+    ///     .bounds(Bounds::new(array![-9.0, -9.0, 2.0], array![9.0, 9.0, 2.0]))
+    ///     .build()?;
+    /// let solution = problem.solve_with_options(&SolveOptions::new().presolve(true))?;
+    /// assert_eq!(solution.x, array![0.5, 0.5, 2.0]);
+    /// assert!((solution.multipliers.ineq[0] - 1.0).abs() < 1e-12);
+    /// assert_eq!(solution.multipliers.ineq[1], 0.0);
+    /// assert!((solution.multipliers.lower[2] - 3.0).abs() < 1e-12);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn presolve(mut self, enabled: bool) -> Self {
+        self.presolve = enabled;
+        self
+    }
+
+    /// Polishes the solution by iterative refinement of the KKT system of the final active set.
+    /// Disabled by default.
+    ///
+    /// The residuals of the KKT system are computed in twice the working precision, so the
+    /// polished solution is accurate to the working precision unless the system is nearly
+    /// singular, whereas the accuracy of the active-set loop degrades with the condition number
+    /// of \\(G\\). The polished solution is discarded if it's less feasible than the original one
+    /// or has negative multipliers. [`Solution::polished`] tells whether it was kept.
+    ///
+    /// The polishing is relative to \\(G\\) without [`regularization`](Self::regularization),
+    /// so it also removes the bias of the regularization when the KKT system is nonsingular.
+    pub fn polish(mut self, enabled: bool) -> Self {
+        self.polish = enabled;
+        self
+    }
+
+    /// Solves problems whose Hessian \\(G\\) is exactly zero as linear programs instead of
+    /// failing with [`Error::SingularHessian`](crate::Error::SingularHessian). Disabled by
+    /// default.
+    ///
+    /// The linear program is solved by the proximal point method, i.e. as a sequence of
+    /// quadratic programs with \\(G = \rho I\\) for a small \\(\rho\\), each warm-started
+    /// with the active set of the previous one. The solver fails with
+    /// [`Error::MaxIterations`](crate::Error::MaxIterations) if the sequence doesn't converge,
+    /// which is the case when the linear program is unbounded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // min -x - y s.t. x + 2y <= 4, 3x + y <= 6, x, y >= 0
+    /// let ci = array![[-1.0, -3.0, 1.0, 0.0], [-2.0, -1.0, 0.0, 1.0]];
+    /// let ci0 = array![4.0, 6.0, 0.0, 0.0];
+    /// let solution = solve_with_options(
+    ///     array![[0.0, 0.0], [0.0, 0.0]],
+    ///     array![-1.0, -1.0],
+    ///     Constraints::NONE,
+    ///     Constraints::some(ci, ci0),
+    ///     &SolveOptions::new().lp_fallback(true),
+    /// )?;
+    /// assert!((solution.x[0] - 1.6).abs() < 1e-9);
+    /// assert!((solution.x[1] - 1.2).abs() < 1e-9);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn lp_fallback(mut self, enabled: bool) -> Self {
+        self.lp_fallback = enabled;
+        self
+    }
+
+    /// Warm-starts the solver with the given inequality constraints as the initial active set.
+    ///
+    /// The constraints are made active before the first iteration, which saves an iteration per
+    /// constraint when they are active at the solution. If they don't form a valid starting point
+    /// for the dual method (i.e. some of their Lagrange multipliers would be negative), they are
+    /// ignored and the solver starts from scratch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let g = array![[1.0, 0.0], [0.0, 1.0]];
+    /// let g0 = array![0.0, 0.0];
+    /// let ci = array![[1.0, 0.0], [0.0, 1.0]];
+    /// let ci0 = array![-1.0, -1.0];
+    /// let options = SolveOptions::new().active_set(vec![0, 1]);
+    /// let solution = solve_with_options(g, g0, Constraints::NONE, Constraints::some(ci, ci0), &options)?;
+    /// assert_eq!(solution.x, array![1.0, 1.0]);
+    /// assert_eq!(solution.iterations, 1);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn active_set<I: IntoIterator<Item = usize>>(mut self, active_set: I) -> Self {
+        self.active_set = active_set.into_iter().collect();
+        self
+    }
+
+    /// Warm-starts the solver with the active set of a previous solution of a similar problem.
+    ///
+    /// See [`active_set`](Self::active_set).
+    pub fn warm_start(self, solution: &Solution) -> Self {
+        self.active_set(solution.active_set.iter().copied())
+    }
+
+    /// Warm-starts the solver from an initial guess of the solution.
+    ///
+    /// The dual method always starts from the unconstrained minimizer, so the point is only used
+    /// to guess the initial active set: the inequality constraints which are tight (up to a
+    /// relative tolerance of \\(\sqrt{\epsilon}\\)) or violated at `x0` are added to the
+    /// [`active_set`](Self::active_set).
+    pub fn initial_point<S: Data<Elem = f64>>(mut self, x0: &ArrayBase<S, Ix1>) -> Self {
+        self.initial_point = Some(x0.to_vec());
+        self
+    }
+
+    /// Returns whether a [`deadline`](Self::deadline), a [`cancellation`](Self::cancellation)
+    /// flag or an [`on_iteration`](Self::on_iteration) callback is set.
+    pub(crate) fn is_monitored(&self) -> bool {
+        self.deadline.is_some() || self.cancellation.is_some() || self.on_iteration.is_some()
+    }
+
+    /// Calls the [`on_iteration`](Self::on_iteration) callback, if any.
+    pub(crate) fn report(&self, progress: &Progress) {
+        if let Some(Callback(callback)) = &self.on_iteration {
+            callback(progress);
+        }
+    }
+
+    /// Returns whether the solver has to be stopped by the [`deadline`](Self::deadline) or the
+    /// [`cancellation`](Self::cancellation) flag.
+    pub(crate) fn interrupted(&self) -> bool {
+        matches!(&self.cancellation, Some(Cancellation(flag)) if flag.load(Ordering::Relaxed))
+            || matches!(self.deadline, Some(deadline) if Instant::now() >= deadline)
+    }
+
+    /// Returns whether the options have no effect on a problem without inequality constraints,
+    /// i.e. they neither warm-start the solver, stop or observe its iterations, scale the problem
+    /// nor change the test of the linear dependence of the equality constraints.
+    pub(crate) fn is_plain(&self) -> bool {
+        self.active_set.is_empty()
+            && !self.is_monitored()
+            && self.epsilon == f64::EPSILON
+            && self.max_iterations != Some(0)
+            && !self.lp_fallback
+            && self.regularization == 0.0
+            && !self.polish
+            && !self.equilibrate
+    }
+
+    /// Returns the first option which is set but not implemented by the pure-Rust port of
+    /// QuadProg++, which only supports the options on the Hessian.
+    #[cfg(feature = "pure-rust")]
+    pub(crate) fn unsupported(&self) -> Option<&'static str> {
+        let set = [
+            ("max_iterations", self.max_iterations.is_some()),
+            ("deadline", self.deadline.is_some()),
+            ("cancellation", self.cancellation.is_some()),
+            ("on_iteration", self.on_iteration.is_some()),
+            ("feasibility_tolerance", self.feasibility_tolerance != 0.0),
+            ("epsilon", self.epsilon != f64::EPSILON),
+            ("polish", self.polish),
+            ("lp_fallback", self.lp_fallback),
+            ("regularization", self.regularization != 0.0),
+            ("max_degenerate_steps", self.max_degenerate_steps.is_some()),
+            ("anti_cycling", self.anti_cycling),
+            ("equilibrate", self.equilibrate),
+            ("presolve", self.presolve),
+            ("active_set", !self.active_set.is_empty()),
+            ("initial_point", self.initial_point.is_some()),
+        ];
+        set.iter().find(|(_, set)| *set).map(|&(option, _)| option)
+    }
+
+    /// Returns whether the options apply to the independent parts of a problem like to the whole,
+    /// i.e. they neither limit nor observe the iterations, nor warm-start the solver.
+    pub(crate) fn is_separable(&self) -> bool {
+        self.is_plain()
+            && self.max_iterations.is_none()
+            && self.max_degenerate_steps.is_none()
+            && self.initial_point.is_none()
+    }
+
+    /// Mirrors, checks and symmetrizes the row-major \\(N \times N\\) matrix `g` as requested
+    /// by [`upper_triangle`](Self::upper_triangle),
+    /// [`symmetry_tolerance`](Self::symmetry_tolerance) and [`symmetrize`](Self::symmetrize).
+    pub(crate) fn prepare_hessian<'a>(&self, g: &'a [f64], n: usize) -> Result<Cow<'a, [f64]>> {
+        if self.upper_triangle {
+            let mut g = g.to_vec();
+            for i in 0..n {
+                for j in 0..i {
+                    g[i * n + j] = g[j * n + i];
+                }
+            }
+            return Ok(Cow::Owned(g));
+        }
+        if let Some(tolerance) = self.symmetry_tolerance {
+            if let Some((row, col)) = utils::asymmetry(g, n, tolerance) {
+                return Err(Error::NotSymmetric { row, col });
+            }
+        }
+        if !self.symmetrize {
+            return Ok(Cow::Borrowed(g));
+        }
+        let mut g = g.to_vec();
+        for i in 0..n {
+            for j in 0..i {
+                let mean = 0.5 * (g[i * n + j] + g[j * n + i]);
+                g[i * n + j] = mean;
+                g[j * n + i] = mean;
+            }
+        }
+        Ok(Cow::Owned(g))
+    }
+
+    /// Writes the options into `params`, reusing its buffers.
+    pub(crate) fn fill_params(&self, params: &mut sys::SolveParams) {
+        params.max_iterations = self.max_iterations.unwrap_or(u32::MAX);
+        params.feasibility_tolerance = self.feasibility_tolerance;
+        params.epsilon = self.epsilon;
+        params.lp_fallback = self.lp_fallback;
+        params.regularization = self.regularization;
+        params.refine_regularization = self.refine_regularization;
+        params.max_degenerate_steps = self.max_degenerate_steps.unwrap_or(u32::MAX);
+        params.anti_cycling = self.anti_cycling;
+        params.active_set.clear();
+        params.active_set.extend(
+            self.active_set
+                .iter()
+                .map(|&i| i32::try_from(i).unwrap_or(i32::MAX)),
+        );
+        params.initial_point.clear();
+        if let Some(x0) = &self.initial_point {
+            params.initial_point.extend_from_slice(x0);
+        }
+    }
+}
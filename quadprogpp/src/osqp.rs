@@ -0,0 +1,272 @@
+//! Conversion to and from the problem representation of [OSQP](https://osqp.org).
+//!
+//! OSQP solves
+//!
+//! $$
+//! \min_{x} \quad \frac{1}{2} x^\intercal P x + q^\intercal x
+//! \quad \textrm{subject to} \quad l \le A x \le u
+//! $$
+//!
+//! where each row of \\(A\\) is a constraint, equality constraints have \\(l_i = u_i\\), and
+//! one-sided constraints have an infinite limit. Its dual variables \\(y\\) satisfy
+//! \\(P x + q + A^\intercal y = 0\\), so they're the negated multipliers of this crate, i.e.
+//! negative when a lower limit is active.
+//!
+//! The `osqp` crate takes \\(P\\) and \\(A\\) in compressed sparse column format, which
+//! [`OsqpProblem::p_csc`] and [`OsqpProblem::a_csc`] return.
+
+use ndarray::{s, Array1, Array2, ArrayView2, Axis};
+
+use crate::{Bounds, Constraints, QpProblem, Result, Solution};
+
+/// A problem in the form of OSQP.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OsqpProblem {
+    /// The \\(N \times N\\) Hessian \\(P\\)
+    pub p: Array2<f64>,
+    /// The \\(N\\)-vector \\(q\\)
+    pub q: Array1<f64>,
+    /// The \\(M \times N\\) constraint matrix \\(A\\), one row per constraint
+    pub a: Array2<f64>,
+    /// The lower limits of the constraints
+    pub l: Array1<f64>,
+    /// The upper limits of the constraints
+    pub u: Array1<f64>,
+}
+
+/// A matrix in compressed sparse column format, with the same fields as `osqp::CscMatrix`.
+///
+/// The row indices and the values of column `j` are `indices[indptr[j]..indptr[j + 1]]` and the
+/// same range of `data`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CscMatrix {
+    /// Number of rows
+    pub nrows: usize,
+    /// Number of columns
+    pub ncols: usize,
+    /// Offsets of the columns in `indices` and `data`, one more than the number of columns
+    pub indptr: Vec<usize>,
+    /// Row indices of the nonzero elements
+    pub indices: Vec<usize>,
+    /// Values of the nonzero elements
+    pub data: Vec<f64>,
+}
+
+impl CscMatrix {
+    /// Compresses the nonzero elements of `a`, or only those of its upper triangle if `upper` is
+    /// `true`.
+    fn new(a: ArrayView2<'_, f64>, upper: bool) -> Self {
+        let (nrows, ncols) = a.dim();
+        let mut csc = Self {
+            nrows,
+            ncols,
+            indptr: vec![0],
+            indices: Vec::new(),
+            data: Vec::new(),
+        };
+        for (j, column) in a.axis_iter(Axis(1)).enumerate() {
+            let rows = if upper { (j + 1).min(nrows) } else { nrows };
+            for (i, &value) in column.slice(s![..rows]).iter().enumerate() {
+                if value != 0.0 {
+                    csc.indices.push(i);
+                    csc.data.push(value);
+                }
+            }
+            csc.indptr.push(csc.indices.len());
+        }
+        csc
+    }
+}
+
+impl OsqpProblem {
+    /// Converts `problem` to the form of OSQP.
+    ///
+    /// The rows of \\(A\\) are the equality constraints, the inequality constraints, and a row
+    /// of the identity for each variable with a finite bound, in this order. The objective is
+    /// the minimized one, i.e. negated if `problem` is a maximization, and the variables include
+    /// the slack variables of [soft](crate::QpProblemBuilder::soft) constraints.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // min x^2 / 2 - x s.t. x + 1 >= 0 and x <= 0.5
+    /// let problem = QpProblem::new(1)
+    ///     .hessian(array![[1.0]])
+    ///     .linear(array![-1.0])
+    ///     .ineq(Constraints::new(array![[1.0]], array![1.0]))
+    ///     .bounds(Bounds::upper(array![0.5]))
+    ///     .build()?;
+    /// let data = osqp::OsqpProblem::from_problem(&problem);
+    /// assert_eq!(data.a, array![[1.0], [1.0]]);
+    /// assert_eq!(data.l, array![-1.0, f64::NEG_INFINITY]);
+    /// assert_eq!(data.u, array![f64::INFINITY, 0.5]);
+    ///
+    /// let solution = problem.solve()?;
+    /// assert_eq!(osqp::dual(&problem, &solution), array![0.0, 0.5]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn from_problem(problem: &QpProblem) -> Self {
+        let (n, _, _) = problem.dim();
+        let mut rows = Vec::new();
+        let mut l = Vec::new();
+        let mut u = Vec::new();
+        if let Some(ce) = problem.eq() {
+            for (column, &c) in ce.coeffs().axis_iter(Axis(1)).zip(ce.consts()) {
+                rows.push(column.to_owned());
+                l.push(-c);
+                u.push(-c);
+            }
+        }
+        if let Some(ci) = problem.ineq() {
+            for (j, column) in ci.coeffs().axis_iter(Axis(1)).enumerate() {
+                rows.push(column.to_owned());
+                match ci.upper() {
+                    Some(upper) => {
+                        l.push(ci.consts()[j]);
+                        u.push(upper[j]);
+                    }
+                    None => {
+                        l.push(-ci.consts()[j]);
+                        u.push(f64::INFINITY);
+                    }
+                }
+            }
+        }
+        for (i, lower, upper) in bounded(problem.bounds(), n) {
+            let mut row = Array1::zeros(n);
+            row[i] = 1.0;
+            rows.push(row);
+            l.push(lower);
+            u.push(upper);
+        }
+        let mut a = Array2::zeros((rows.len(), n));
+        for (mut a, row) in a.outer_iter_mut().zip(&rows) {
+            a.assign(row);
+        }
+        Self {
+            p: problem.hessian().to_owned(),
+            q: problem.linear().to_owned(),
+            a,
+            l: Array1::from(l),
+            u: Array1::from(u),
+        }
+    }
+
+    /// Converts the problem to a [`QpProblem`].
+    ///
+    /// The constraints with \\(l_i = u_i\\) become equality constraints, and the others become
+    /// inequality constraints, which are [ranged](Constraints::ranged) unless all of them have
+    /// an infinite upper limit. Rows of the identity aren't recognized as bounds.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the errors of [`QpProblemBuilder::build`](crate::QpProblemBuilder::build).
+    pub fn into_problem(self) -> Result<QpProblem> {
+        let Self { p, q, a, l, u } = self;
+        let (eq, ineq): (Vec<usize>, Vec<usize>) = (0..l.len()).partition(|&i| l[i] == u[i]);
+        let mut builder = QpProblem::new(q.len()).hessian(p).linear(q);
+        if !eq.is_empty() {
+            let coeffs = a.select(Axis(0), &eq);
+            let consts: Array1<f64> = eq.iter().map(|&i| -l[i]).collect();
+            builder = builder.eq(Constraints::from_rows(coeffs, consts));
+        }
+        if !ineq.is_empty() {
+            let coeffs = a.select(Axis(0), &ineq);
+            let lower: Array1<f64> = ineq.iter().map(|&i| l[i]).collect();
+            let upper: Array1<f64> = ineq.iter().map(|&i| u[i]).collect();
+            let ci = if upper.iter().all(|&u| u == f64::INFINITY) {
+                Constraints::from_rows(coeffs, -lower)
+            } else {
+                Constraints::ranged(coeffs.reversed_axes(), lower, upper)
+            };
+            builder = builder.ineq(ci);
+        }
+        builder.build()
+    }
+
+    /// Returns the upper triangle of \\(P\\), which is what OSQP expects.
+    pub fn p_csc(&self) -> CscMatrix {
+        CscMatrix::new(self.p.view(), true)
+    }
+
+    /// Returns \\(A\\).
+    pub fn a_csc(&self) -> CscMatrix {
+        CscMatrix::new(self.a.view(), false)
+    }
+}
+
+/// Returns the dual variables \\(y\\) of OSQP corresponding to `solution`, in the order of the
+/// rows of [`OsqpProblem::from_problem`].
+pub fn dual(problem: &QpProblem, solution: &Solution) -> Array1<f64> {
+    let (n, _, _) = problem.dim();
+    let multipliers = &solution.multipliers;
+    let bound = |multipliers: &[f64], i: usize| multipliers.get(i).copied().unwrap_or(0.0);
+    let bounds = bounded(problem.bounds(), n)
+        .map(|(i, _, _)| bound(&multipliers.lower, i) - bound(&multipliers.upper, i));
+    (multipliers.eq.iter().chain(&multipliers.ineq).copied())
+        .chain(bounds)
+        .map(|u| -u)
+        .collect()
+}
+
+/// Returns the variables with a finite bound, with their lower and upper bounds.
+fn bounded(bounds: &Bounds, n: usize) -> impl Iterator<Item = (usize, f64, f64)> + '_ {
+    let limit = |limits: &[f64], i: usize, default: f64| limits.get(i).copied().unwrap_or(default);
+    (0..n)
+        .map(move |i| {
+            let lower = limit(bounds.lower_bounds(), i, f64::NEG_INFINITY);
+            let upper = limit(bounds.upper_bounds(), i, f64::INFINITY);
+            (i, lower, upper)
+        })
+        .filter(|&(_, lower, upper)| lower.is_finite() || upper.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let problem = QpProblem::new(3)
+            .hessian(array![[2.0, 1.0, 0.0], [1.0, 2.0, 0.0], [0.0, 0.0, 1.0]])
+            .linear(array![-1.0, -4.0, 2.0])
+            .eq(Constraints::new(array![[1.0], [1.0], [1.0]], array![-1.0]))
+            .ineq(Constraints::ranged(
+                array![[1.0], [-1.0], [0.0]],
+                array![-0.5],
+                array![0.5],
+            ))
+            .bounds(Bounds::lower(array![0.0, f64::NEG_INFINITY, -1.0]))
+            .build()?;
+        let solution = problem.solve()?;
+
+        let data = OsqpProblem::from_problem(&problem);
+        assert_eq!(data.l, array![1.0, -0.5, 0.0, -1.0]);
+        assert_eq!(data.u, array![1.0, 0.5, f64::INFINITY, f64::INFINITY]);
+        // Stationarity in the convention of OSQP
+        let y = dual(&problem, &solution);
+        let residual = data.p.dot(&solution.x) + &data.q + data.a.t().dot(&y);
+        for &r in &residual {
+            assert_abs_diff_eq!(r, 0.0, epsilon = 1e-12);
+        }
+
+        let p = data.p_csc();
+        assert_eq!((p.indptr, p.indices), (vec![0, 1, 3, 4], vec![0, 0, 1, 2]));
+        let a = data.a_csc();
+        assert_eq!(a.indptr, [0, 3, 5, 7]);
+
+        // The bounds come back as constraints.
+        let converted = data.into_problem()?;
+        assert_eq!(converted.dim(), (3, 1, 3));
+        let x = converted.solve()?.x;
+        for (&actual, &expected) in x.iter().zip(&solution.x) {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use ndarray::{Array1, Array2};
+
+use crate::linalg::{dot2, Lu};
+
+/// Maximum number of steps of iterative refinement. Each step gains roughly as many digits as
+/// the KKT system loses to its condition number, so a few are enough unless the system is too
+/// ill-conditioned to refine at all.
+const MAX_STEPS: usize = 5;
+
+/// A problem in the form passed to QuadProg++, with row-major matrices.
+pub(crate) struct Problem<'a> {
+    pub(crate) g: &'a [f64],
+    pub(crate) g0: &'a [f64],
+    pub(crate) ce: &'a [f64],
+    pub(crate) ce0: &'a [f64],
+    pub(crate) ci: &'a [f64],
+    pub(crate) ci0: &'a [f64],
+}
+
+impl Problem<'_> {
+    /// Returns the value of the objective function at `x`, in twice the working precision.
+    pub(crate) fn objective(&self, x: &[f64]) -> f64 {
+        let n = x.len();
+        let gx = (0..n).map(|i| dot2((0..n).map(|j| (self.g[i * n + j], x[j]))));
+        let quadratic = x.iter().zip(gx).map(|(&x, gx)| (x, 0.5 * gx));
+        dot2(quadratic.chain(self.g0.iter().copied().zip(x.iter().copied())))
+    }
+
+    /// Returns the largest violation of the constraints at `x`.
+    fn violation(&self, x: &[f64]) -> f64 {
+        let (p, m) = (self.ce0.len(), self.ci0.len());
+        let eq = (0..p).map(|j| residual(self.ce, self.ce0, j, x).abs());
+        let ineq = (0..m).map(|j| -residual(self.ci, self.ci0, j, x));
+        eq.chain(ineq).fold(0.0, f64::max)
+    }
+}
+
+/// Returns \\(a_j^\intercal x + c_j\\) for the `j`-th column of the row-major matrix `a`.
+fn residual(a: &[f64], c: &[f64], j: usize, x: &[f64]) -> f64 {
+    let m = c.len();
+    dot2(
+        (0..x.len())
+            .map(|i| (a[i * m + j], x[i]))
+            .chain([(c[j], 1.0)]),
+    )
+}
+
+/// Polishes a solution by iterative refinement of the KKT system of its active set,
+/// \\(G x + g_0 = C y\\) and \\(C^\intercal x + c = 0\\), where \\(C\\) holds the equality
+/// constraints and the active inequality constraints.
+///
+/// `active` holds the indices of the active inequality constraints, and `multipliers` those of
+/// the equality constraints followed by those of all the inequality constraints. The residuals are
+/// computed in twice the working precision, so the refined solution is accurate to the working
+/// precision unless the KKT system is nearly singular.
+///
+/// The refined solution is rejected if it isn't at least as feasible as the original one, or
+/// the multipliers of the active inequality constraints become negative, i.e. the active set
+/// isn't the optimal one after all. Returns whether it was accepted.
+pub(crate) fn polish(
+    problem: &Problem<'_>,
+    active: &[usize],
+    x: &mut [f64],
+    multipliers: &mut [f64],
+) -> bool {
+    let (n, p, m) = (x.len(), problem.ce0.len(), problem.ci0.len());
+    let k = p + active.len();
+    let column = |l: usize, i: usize| {
+        if l < p {
+            problem.ce[i * p + l]
+        } else {
+            problem.ci[i * m + active[l - p]]
+        }
+    };
+    let constant = |l: usize| {
+        if l < p {
+            problem.ce0[l]
+        } else {
+            problem.ci0[active[l - p]]
+        }
+    };
+
+    // K = [[G, -C], [-C^T, 0]]
+    let mut kkt = Array2::zeros((n + k, n + k));
+    for i in 0..n {
+        for j in 0..n {
+            kkt[[i, j]] = problem.g[i * n + j];
+        }
+        for l in 0..k {
+            kkt[[i, n + l]] = -column(l, i);
+            kkt[[n + l, i]] = -column(l, i);
+        }
+    }
+    let lu = match Lu::new(kkt) {
+        Some(lu) => lu,
+        None => return false,
+    };
+
+    // The residual of K [x; y] = [-g0; c]
+    let residual = |z: &[f64]| {
+        let (x, y) = z.split_at(n);
+        let stationarity = (0..n).map(|i| {
+            let g = (0..n).map(|j| (problem.g[i * n + j], x[j]));
+            let c = (0..k).map(|l| (-column(l, i), y[l]));
+            -dot2(g.chain(c).chain([(problem.g0[i], 1.0)]))
+        });
+        let feasibility = (0..k).map(|l| {
+            let c = (0..n).map(|i| (column(l, i), x[i]));
+            dot2(c.chain([(constant(l), 1.0)]))
+        });
+        stationarity.chain(feasibility).collect::<Array1<f64>>()
+    };
+    let norm = |r: &Array1<f64>| r.iter().fold(0.0, |max: f64, r| max.max(r.abs()));
+
+    let mut z = x.to_vec();
+    z.extend_from_slice(&multipliers[..p]);
+    z.extend(active.iter().map(|&j| multipliers[p + j]));
+    let mut r = residual(&z);
+    let mut best = norm(&r);
+    for _ in 0..MAX_STEPS {
+        if best == 0.0 {
+            break;
+        }
+        let dz = lu.solve(r.view());
+        let candidate: Vec<f64> = z.iter().zip(&dz).map(|(z, dz)| z + dz).collect();
+        let candidate_r = residual(&candidate);
+        let candidate_norm = norm(&candidate_r);
+        if candidate_norm >= best {
+            break;
+        }
+        z = candidate;
+        r = candidate_r;
+        best = candidate_norm;
+    }
+
+    let (refined_x, y) = z.split_at(n);
+    let scale = y.iter().fold(1.0, |max: f64, y| max.max(y.abs()));
+    if y[p..].iter().any(|&y| y < -f64::EPSILON * scale) {
+        return false;
+    }
+    let tolerance = f64::EPSILON * refined_x.iter().fold(1.0, |max: f64, x| max.max(x.abs()));
+    if problem.violation(refined_x) > problem.violation(x).max(tolerance) {
+        return false;
+    }
+    x.copy_from_slice(refined_x);
+    multipliers[..p].copy_from_slice(&y[..p]);
+    for (&j, &y) in active.iter().zip(&y[p..]) {
+        multipliers[p + j] = y.max(0.0);
+    }
+    true
+}
@@ -0,0 +1,491 @@
+use ndarray::{s, Array1, Array2, ArrayView1, Axis};
+
+use crate::backend::CPP_NAME;
+use crate::problem::OwnedConstraints;
+use crate::solver::{describe_constraint, describe_variable};
+use crate::{
+    Bounds, Constraints, Error, Multipliers, Objective, QpProblem, Result, Solution, SolveOptions,
+    Solver, Status,
+};
+
+/// A [`QpProblem`] simplified by [`SolveOptions::presolve`], with the map of its solution back
+/// to the original problem.
+pub(crate) struct Presolved<'a> {
+    original: &'a QpProblem,
+    /// The reduced problem
+    problem: QpProblem,
+    /// The values of the fixed variables of the original problem, or `None` for the kept ones
+    fixed: Vec<Option<f64>>,
+    /// The kept variables, i.e. the original index of each variable of the reduced problem
+    kept: Vec<usize>,
+    /// The original index of each equality constraint of the reduced problem
+    eq: Vec<usize>,
+    /// The original indices of the constraints which set the lower and upper limits of each
+    /// inequality constraint of the reduced problem
+    ineq: Vec<(usize, usize)>,
+    /// The part of the objective function of the minimization which depends on the fixed
+    /// variables only
+    constant: f64,
+}
+
+impl<'a> Presolved<'a> {
+    /// Simplifies `problem`.
+    ///
+    /// # Errors
+    ///
+    /// If the bounds or the constraints are trivially contradictory, this function returns
+    /// [`Error::Infeasible`]. If the initial point of `options` doesn't have one element per
+    /// variable, it returns [`Error::SizeMismatch`].
+    pub(crate) fn new(problem: &'a QpProblem, options: &SolveOptions) -> Result<Self> {
+        let (n, _, _) = problem.dim();
+        let tolerance = options.feasibility_tolerance;
+        if let Some(x0) = &options.initial_point {
+            assert_size!(initial_point, n, x0.len());
+        }
+        let bounds = &problem.bounds;
+        let lower = |i| bounds.lower_bounds().get(i).copied();
+        let upper = |i| bounds.upper_bounds().get(i).copied();
+        let mut fixed = Vec::with_capacity(n);
+        for i in 0..n {
+            let l = lower(i).unwrap_or(f64::NEG_INFINITY);
+            let u = upper(i).unwrap_or(f64::INFINITY);
+            if l > u + tolerance {
+                let variable = describe_variable(bounds.names(), i);
+                return Err(Error::Infeasible {
+                    constraint: format!("upper bound of {}", variable),
+                });
+            }
+            fixed.push(if l == u && l.is_finite() {
+                Some(l)
+            } else {
+                None
+            });
+        }
+        let kept: Vec<usize> = (0..n).filter(|&i| fixed[i].is_none()).collect();
+        let values = Array1::from_iter(fixed.iter().map(|x| x.unwrap_or(0.0)));
+
+        // Substitute the fixed variables into the objective function and the constraints.
+        let g = &problem.hessian;
+        let gx = g.dot(&values);
+        let constant = values.dot(&(&gx * 0.5 + &problem.linear));
+        let hessian = g.select(Axis(0), &kept).select(Axis(1), &kept);
+        let linear = (&problem.linear + &gx).select(Axis(0), &kept);
+
+        let mut eq = Vec::new();
+        let ce = match &problem.eq {
+            Some(ce) => {
+                let coeffs = ce.coeffs.select(Axis(0), &kept);
+                let consts = &ce.consts + &ce.coeffs.t().dot(&values);
+                for j in 0..consts.len() {
+                    let a = coeffs.column(j);
+                    let duplicate = eq.iter().find(|&&k| coeffs.column(k) == a);
+                    let conflict = match duplicate {
+                        Some(&k) => (consts[j] - consts[k]).abs() > tolerance,
+                        None if is_zero(a) => consts[j].abs() > tolerance,
+                        None => {
+                            eq.push(j);
+                            false
+                        }
+                    };
+                    if conflict {
+                        return Err(Error::Infeasible {
+                            constraint: describe_constraint("equality", &ce.names, j),
+                        });
+                    }
+                }
+                let consts = consts.select(Axis(0), &eq);
+                Some(reduce(ce, coeffs, consts, None, &eq))
+            }
+            None => None,
+        };
+
+        let mut ineq: Vec<(usize, usize)> = Vec::new();
+        let ci = match &problem.ineq {
+            Some(ci) => {
+                let coeffs = ci.coeffs.select(Axis(0), &kept);
+                let shift = ci.coeffs.t().dot(&values);
+                // A constraint a^T x + c >= 0 has the limits -c <= a^T x <= inf.
+                let (lower, upper) = match &ci.upper {
+                    Some(upper) => (&ci.consts - &shift, upper - &shift),
+                    None => (
+                        -(&ci.consts + &shift),
+                        Array1::from_elem(shift.len(), f64::INFINITY),
+                    ),
+                };
+                for j in 0..lower.len() {
+                    let a = coeffs.column(j);
+                    let duplicate = ineq.iter().position(|&(k, _)| coeffs.column(k) == a);
+                    let conflict = match duplicate {
+                        Some(r) => {
+                            let (k, l) = ineq[r];
+                            if lower[j] > lower[k] {
+                                ineq[r].0 = j;
+                            }
+                            if upper[j] < upper[l] {
+                                ineq[r].1 = j;
+                            }
+                            let (k, l) = ineq[r];
+                            lower[k] > upper[l] + tolerance
+                        }
+                        None if is_zero(a) => lower[j] > tolerance || upper[j] < -tolerance,
+                        None => {
+                            ineq.push((j, j));
+                            false
+                        }
+                    };
+                    if conflict {
+                        return Err(Error::Infeasible {
+                            constraint: describe_constraint("inequality", &ci.names, j),
+                        });
+                    }
+                }
+                let rows: Vec<usize> = ineq.iter().map(|&(k, _)| k).collect();
+                let lower = lower.select(Axis(0), &rows);
+                let upper = Array1::from_iter(ineq.iter().map(|&(_, l)| upper[l]));
+                Some(match ci.upper {
+                    Some(_) => reduce(ci, coeffs, lower, Some(upper), &rows),
+                    None => reduce(ci, coeffs, -lower, None, &rows),
+                })
+            }
+            None => None,
+        };
+
+        let select = |limits: &[f64]| -> Array1<f64> {
+            if limits.is_empty() {
+                Array1::zeros(0)
+            } else {
+                kept.iter().map(|&i| limits[i]).collect()
+            }
+        };
+        let names = bounds.names();
+        let reduced = Bounds::new(select(bounds.lower_bounds()), select(bounds.upper_bounds()))
+            .with_names(
+                names
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| fixed[i].is_none())
+                    .map(|(_, name)| name.clone()),
+            );
+        let removed = fixed[..problem.n].iter().filter(|x| x.is_some()).count();
+        Ok(Self {
+            original: problem,
+            problem: QpProblem {
+                hessian,
+                linear,
+                eq: ce,
+                ineq: ci,
+                bounds: reduced,
+                objective: problem.objective,
+                n: problem.n - removed,
+            },
+            fixed,
+            kept,
+            eq,
+            ineq,
+            constant,
+        })
+    }
+
+    /// Solves the reduced problem and maps its solution back to the original problem.
+    pub(crate) fn solve(&self, options: &SolveOptions) -> Result<Solution> {
+        let (n, p, m) = self.problem.dim();
+        let (_, _, original_m) = self.original.dim();
+        let mut options = options.clone();
+        let mut active_set = Vec::with_capacity(options.active_set.len());
+        for &index in &options.active_set {
+            if index >= original_m {
+                return Err(Error::IndexOutOfRange {
+                    index,
+                    len: original_m,
+                });
+            }
+            let k = self
+                .ineq
+                .iter()
+                .position(|&(k, l)| k == index || l == index);
+            active_set.extend(k);
+        }
+        options.active_set = active_set;
+        if let Some(x0) = &mut options.initial_point {
+            *x0 = self.kept.iter().map(|&i| x0[i]).collect();
+        }
+        let solution = if n == 0 {
+            // All the variables are fixed, so all the constraints have been dropped.
+            Solution {
+                x: Array1::zeros(0),
+                status: Status::Optimal,
+                objective: 0.0,
+                multipliers: Multipliers {
+                    eq: Vec::new(),
+                    ineq: Vec::new(),
+                    lower: Vec::new(),
+                    upper: Vec::new(),
+                },
+                active_set: Vec::new(),
+                active_lower: Vec::new(),
+                active_upper: Vec::new(),
+                iterations: 0,
+                factorizations: 0,
+                additions: 0,
+                deletions: 0,
+                rotations: 0,
+                warm_started: false,
+                polished: false,
+                backend: CPP_NAME,
+            }
+        } else {
+            Solver::try_new(n, p, m)?.solve_problem(&self.problem, &options)?
+        };
+        Ok(self.postsolve(solution))
+    }
+
+    /// Maps a solution of the reduced problem back to the original problem.
+    fn postsolve(&self, mut solution: Solution) -> Solution {
+        let original = self.original;
+        let (_, p, m) = original.dim();
+        let n = original.n;
+        let mut x = Array1::from_iter(self.fixed[..n].iter().map(|x| x.unwrap_or(0.0)));
+        for (&i, &xi) in self.kept.iter().zip(&solution.x) {
+            x[i] = xi;
+        }
+
+        let reduced = &solution.multipliers;
+        let mut eq = vec![0.0; p];
+        for (&j, &u) in self.eq.iter().zip(&reduced.eq) {
+            eq[j] = u;
+        }
+        let mut ineq = vec![0.0; m];
+        for (&(k, l), &u) in self.ineq.iter().zip(&reduced.ineq) {
+            if u > 0.0 {
+                ineq[k] = u;
+            } else if u < 0.0 {
+                ineq[l] = u;
+            }
+        }
+        let active_set = solution.active_set.iter().map(|&r| {
+            let (k, l) = self.ineq[r];
+            let lower_is_finite = match &self.problem.ineq {
+                Some(Constraints {
+                    consts,
+                    upper: Some(_),
+                    ..
+                }) => consts[r].is_finite(),
+                _ => true,
+            };
+            if reduced.ineq[r] < 0.0 || !lower_is_finite {
+                l
+            } else {
+                k
+            }
+        });
+        let active_set = active_set.collect();
+
+        // The multiplier of a fixed variable balances its row of the stationarity condition.
+        let mut lower = vec![0.0; n];
+        let mut upper = vec![0.0; n];
+        for (&i, (&l, &u)) in self
+            .kept
+            .iter()
+            .zip(reduced.lower.iter().zip(&reduced.upper))
+        {
+            lower[i] = l;
+            upper[i] = u;
+        }
+        let mut active_lower: Vec<usize> = solution
+            .active_lower
+            .iter()
+            .map(|&k| self.kept[k])
+            .collect();
+        let mut active_upper: Vec<usize> = solution
+            .active_upper
+            .iter()
+            .map(|&k| self.kept[k])
+            .collect();
+        let g = original.hessian.slice(s![..n, ..n]);
+        for i in (0..n).filter(|&i| self.fixed[i].is_some()) {
+            let mut residual = g.row(i).dot(&x) + original.linear[i];
+            if let Some(ce) = &original.eq {
+                residual -= ce.coeffs.row(i).dot(&ArrayView1::from(&eq));
+            }
+            if let Some(ci) = &original.ineq {
+                residual -= ci.coeffs.row(i).dot(&ArrayView1::from(&ineq));
+            }
+            if residual >= 0.0 {
+                lower[i] = residual;
+                active_lower.push(i);
+            } else {
+                upper[i] = -residual;
+                active_upper.push(i);
+            }
+        }
+
+        let constant = match original.objective {
+            Objective::Minimize => self.constant,
+            Objective::Maximize => -self.constant,
+        };
+        solution.x = x;
+        solution.objective += constant;
+        solution.multipliers = Multipliers {
+            eq,
+            ineq,
+            lower,
+            upper,
+        };
+        solution.active_set = active_set;
+        solution.active_lower = active_lower;
+        solution.active_upper = active_upper;
+        solution
+    }
+}
+
+/// Returns whether the coefficients of a constraint are all zero.
+fn is_zero(a: ArrayView1<'_, f64>) -> bool {
+    a.iter().all(|&a| a == 0.0)
+}
+
+/// Builds the constraints of the reduced problem from the given columns of `coeffs` and their
+/// limits, keeping the names of the original constraints `c`.
+fn reduce(
+    c: &OwnedConstraints,
+    coeffs: Array2<f64>,
+    consts: Array1<f64>,
+    upper: Option<Array1<f64>>,
+    columns: &[usize],
+) -> OwnedConstraints {
+    let coeffs = coeffs.select(Axis(1), columns);
+    let names = if c.names.is_empty() {
+        Vec::new()
+    } else {
+        columns.iter().map(|&j| c.names[j].clone()).collect()
+    };
+    Constraints {
+        coeffs,
+        consts,
+        upper,
+        names,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+    use crate::verify_kkt;
+
+    #[test]
+    fn redundant_problem() -> Result<()> {
+        // x2 is fixed to 1, the equality constraints are x0 - x1 + x2 = 1 twice and 0 = 0, and
+        // the inequality constraints are -1 <= x0 + x1 <= 2, 0 <= x0 + x1 <= 5 and
+        // 0 <= x2 <= 2, which doesn't depend on the other variables.
+        let problem = QpProblem::new(3)
+            .hessian(array![[2.0, 0.5, 0.0], [0.5, 1.0, 0.0], [0.0, 0.0, 1.0]])
+            .linear(array![1.0, 1.0, 0.5])
+            .eq(Constraints::new(
+                array![[1.0, 1.0, 0.0], [-1.0, -1.0, 0.0], [1.0, 1.0, 0.0]],
+                array![-1.0, -1.0, 0.0],
+            ))
+            .ineq(Constraints::ranged(
+                array![[1.0, 1.0, 0.0], [1.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+                array![-1.0, 0.0, 0.0],
+                array![2.0, 5.0, 2.0],
+            ))
+            .bounds(Bounds::new(
+                array![f64::NEG_INFINITY, f64::NEG_INFINITY, 1.0],
+                array![f64::INFINITY, 0.5, 1.0],
+            ))
+            .build()?;
+        let err = problem.solve().unwrap_err();
+        assert!(
+            matches!(err, Error::LinearlyDependent),
+            "unexpected error: {:?}",
+            err
+        );
+
+        let options = SolveOptions::new().presolve(true);
+        let presolved = Presolved::new(&problem, &options)?;
+        assert_eq!(presolved.problem.dim(), (2, 1, 1));
+        assert_eq!(presolved.ineq, [(1, 0)]);
+        let solution = problem.solve_with_options(&options)?;
+        assert_abs_diff_eq!(solution.x[0], 0.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(solution.x[1], 0.0, epsilon = 1e-12);
+        assert_eq!(solution.x[2], 1.0);
+        assert_abs_diff_eq!(solution.objective, 1.0, epsilon = 1e-12);
+        assert_eq!(solution.active_set, [1]);
+        assert_eq!(solution.active_lower, [2]);
+        assert_abs_diff_eq!(solution.multipliers.ineq[1], 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(solution.multipliers.lower[2], 1.5, epsilon = 1e-12);
+        let report = verify_kkt(&problem, &solution, 1e-12)?;
+        assert!(report.is_optimal(), "{:?}", report);
+
+        // Warm-starting with the duplicate constraint
+        let warm = problem.solve_with_options(&options.active_set([0]))?;
+        assert!(warm.warm_started);
+        assert_eq!(warm.x, solution.x);
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_variables() -> Result<()> {
+        // max -x^2 - y^2 + 2x + 4y s.t. x + y >= 3 with x = 2 and y = 1
+        let problem = QpProblem::new(2)
+            .hessian(array![[-2.0, 0.0], [0.0, -2.0]])
+            .linear(array![2.0, 4.0])
+            .ineq(Constraints::new(array![[1.0], [1.0]], array![-3.0]))
+            .bounds(Bounds::new(array![2.0, 1.0], array![2.0, 1.0]))
+            .objective(Objective::Maximize)
+            .build()?;
+        let solution = problem.solve_with_options(&SolveOptions::new().presolve(true))?;
+        assert_eq!(solution.x, array![2.0, 1.0]);
+        assert_eq!(solution.objective, 3.0);
+        assert_eq!(solution.iterations, 0);
+        assert_eq!(solution.multipliers.lower, [2.0, 0.0]);
+        assert_eq!(solution.multipliers.upper, [0.0, 2.0]);
+        assert!(verify_kkt(&problem, &solution, 0.0)?.is_optimal());
+        Ok(())
+    }
+
+    #[test]
+    fn infeasible() {
+        let options = SolveOptions::new().presolve(true);
+        let g = array![[1.0, 0.0], [0.0, 1.0]];
+        let problems = [
+            (
+                QpProblem::new(2)
+                    .hessian(g.view())
+                    .bounds(Bounds::new(array![0.0, 1.0], array![1.0, 0.0]).with_names(["x", "y"])),
+                "upper bound of 'y'",
+            ),
+            (
+                QpProblem::new(2).hessian(g.view()).eq(Constraints::new(
+                    array![[1.0, 0.0], [0.0, 0.0]],
+                    array![0.0, 1.0],
+                )),
+                "equality constraint 1",
+            ),
+            (
+                QpProblem::new(2)
+                    .hessian(g.view())
+                    .ineq(Constraints::ranged(
+                        array![[1.0, 1.0], [1.0, 1.0]],
+                        array![0.0, 2.0],
+                        array![1.0, 3.0],
+                    )),
+                "inequality constraint 1",
+            ),
+        ];
+        for (problem, constraint) in problems {
+            let err = problem
+                .build()
+                .unwrap()
+                .solve_with_options(&options)
+                .unwrap_err();
+            assert!(
+                matches!(&err, Error::Infeasible { constraint: c } if c == constraint),
+                "unexpected error: {:?}",
+                err
+            );
+        }
+    }
+}
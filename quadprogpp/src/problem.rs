@@ -0,0 +1,682 @@
+use ndarray::{s, Array1, Array2, ArrayBase, ArrayView1, ArrayView2, Data, Ix1, Ix2, OwnedRepr};
+
+use crate::presolve::Presolved;
+use crate::{utils, Bounds, Constraints, Error, Result, Solution, SolveOptions, Solver};
+
+pub(crate) type OwnedConstraints = Constraints<OwnedRepr<f64>, OwnedRepr<f64>>;
+
+/// The direction of optimization of a [`QpProblem`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub enum Objective {
+    /// Minimize \\(q(x)\\), which requires a positive definite Hessian
+    #[default]
+    Minimize,
+    /// Maximize \\(q(x)\\), which requires a negative definite Hessian
+    Maximize,
+}
+
+/// A validated quadratic programming problem.
+///
+/// Unlike [`solve`](crate::solve), which checks the problem every time it's called, a `QpProblem`
+/// is validated once by [`QpProblemBuilder::build`] and can then be solved repeatedly.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let problem = QpProblem::new(2)
+///     .hessian(array![[4.0, -2.0], [-2.0, 4.0]])
+///     .linear(array![6.0, 0.0])
+///     .eq(Constraints::new(array![[1.0], [1.0]], array![-3.0]))
+///     .ineq(Constraints::new(array![[1.0, 1.0, 0.0], [0.0, 1.0, 1.0]], array![0.0, -2.0, 0.0]))
+///     .build()?;
+/// let solution = problem.solve()?;
+/// assert_eq!(solution.objective, 12.0);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct QpProblem {
+    pub(crate) hessian: Array2<f64>,
+    pub(crate) linear: Array1<f64>,
+    pub(crate) eq: Option<OwnedConstraints>,
+    pub(crate) ineq: Option<OwnedConstraints>,
+    pub(crate) bounds: Bounds,
+    pub(crate) objective: Objective,
+    /// Number of variables, not counting the slack variables
+    pub(crate) n: usize,
+}
+
+/// A builder of [`QpProblem`], created by [`QpProblem::new`].
+///
+/// With the `serde` feature, the builder can be serialized and deserialized, e.g. to capture a
+/// problem and replay it later. [`QpProblem`] itself isn't deserialized, since it's validated.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub struct QpProblemBuilder {
+    n: usize,
+    hessian: Option<Array2<f64>>,
+    linear: Option<Array1<f64>>,
+    eq: Option<OwnedConstraints>,
+    ineq: Option<OwnedConstraints>,
+    bounds: Bounds,
+    objective: Objective,
+    soft: Vec<(usize, Penalty)>,
+}
+
+/// The penalty on the violation of a soft constraint, given to [`QpProblemBuilder::soft`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub enum Penalty {
+    /// Adds \\(w s\\) to the objective, where \\(s\\) is the violation and \\(w\\) is
+    /// the weight. An L1 penalty is exact: the constraint is satisfied whenever possible if the
+    /// weight is larger than the magnitude of its multiplier.
+    L1(f64),
+    /// Adds \\(\frac{w}{2} s^2\\) to the objective, where \\(s\\) is the violation and
+    /// \\(w\\) is the weight.
+    L2(f64),
+}
+
+/// The quadratic coefficient of the slack variables with an L1 penalty, relative to the weight.
+/// QuadProg++ needs a positive definite Hessian.
+const L1_REGULARIZATION: f64 = 1e-8;
+
+impl QpProblem {
+    /// Starts building a problem with `n` variables.
+    ///
+    /// The Hessian is required, and the linear term defaults to zero.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(n: usize) -> QpProblemBuilder {
+        QpProblemBuilder {
+            n,
+            hessian: None,
+            linear: None,
+            eq: None,
+            ineq: None,
+            bounds: Bounds::default(),
+            objective: Objective::Minimize,
+            soft: Vec::new(),
+        }
+    }
+
+    /// Returns the number of variables, equality constraints and inequality constraints, not
+    /// counting the bounds. The variables include the slack variables of
+    /// [soft](QpProblemBuilder::soft) constraints.
+    pub fn dim(&self) -> (usize, usize, usize) {
+        let p = self.eq.as_ref().map_or(0, |ce| ce.coeffs.ncols());
+        let m = self.ineq.as_ref().map_or(0, |ci| ci.coeffs.ncols());
+        (self.linear.len(), p, m)
+    }
+
+    /// Returns the direction of optimization.
+    pub fn objective(&self) -> Objective {
+        self.objective
+    }
+
+    /// Returns the Hessian \\(G\\) of the minimized objective, i.e. negated if the problem is
+    /// a maximization.
+    pub fn hessian(&self) -> ArrayView2<'_, f64> {
+        self.hessian.view()
+    }
+
+    /// Returns the linear term \\(g_0\\) of the minimized objective, i.e. negated if the problem
+    /// is a maximization.
+    pub fn linear(&self) -> ArrayView1<'_, f64> {
+        self.linear.view()
+    }
+
+    /// Returns the equality constraints.
+    pub fn eq(&self) -> Option<&OwnedConstraints> {
+        self.eq.as_ref()
+    }
+
+    /// Returns the inequality constraints.
+    pub fn ineq(&self) -> Option<&OwnedConstraints> {
+        self.ineq.as_ref()
+    }
+
+    /// Returns the bounds on the variables.
+    pub fn bounds(&self) -> &Bounds {
+        &self.bounds
+    }
+
+    /// Solves the problem like [`solve`](crate::solve).
+    pub fn solve(&self) -> Result<Solution> {
+        self.solve_with_options(&SolveOptions::default())
+    }
+
+    /// Solves the problem like [`solve_with_options`](crate::solve_with_options), after
+    /// simplifying it if [`SolveOptions::presolve`] is enabled.
+    pub fn solve_with_options(&self, options: &SolveOptions) -> Result<Solution> {
+        if options.presolve {
+            return Presolved::new(self, options)?.solve(options);
+        }
+        let (n, p, m) = self.dim();
+        Solver::try_new(n, p, m)?.solve_problem(self, options)
+    }
+
+    /// Solves the problem with `solver`, whose size should match [`dim`](Self::dim).
+    pub(crate) fn solve_with(
+        &self,
+        solver: &mut Solver,
+        options: &SolveOptions,
+    ) -> Result<Solution> {
+        let mut solution = solver.solve_with_bounds(
+            self.hessian.view(),
+            self.linear.view(),
+            self.eq.as_ref().map(Constraints::view),
+            self.ineq.as_ref().map(Constraints::view),
+            &self.bounds,
+            options,
+        )?;
+        self.finish(&mut solution);
+        Ok(solution)
+    }
+
+    /// Turns the solution of the minimization over all the variables into that of the problem,
+    /// i.e. negates the objective of a maximization and drops the slack variables.
+    pub(crate) fn finish(&self, solution: &mut Solution) {
+        if self.objective == Objective::Maximize {
+            solution.objective = -solution.objective;
+        }
+        if self.n < self.linear.len() {
+            solution.x = solution.x.slice(s![..self.n]).to_owned();
+            solution.multipliers.lower.truncate(self.n);
+            solution.multipliers.upper.truncate(self.n);
+            // The slack variables are only bounded below.
+            solution.active_lower.retain(|&i| i < self.n);
+        }
+    }
+}
+
+impl QpProblem {
+    /// Adds slack variables to the given soft inequality constraints.
+    fn with_slacks(self, soft: &[(usize, Penalty)]) -> Self {
+        let n = self.n;
+        let mut ci = self
+            .ineq
+            .expect("soft constraints without inequality constraints");
+        let ranged = ci.upper.is_some();
+        let k = if ranged { 2 * soft.len() } else { soft.len() };
+        let n2 = n + k;
+
+        let mut hessian = Array2::zeros((n2, n2));
+        hessian.slice_mut(s![..n, ..n]).assign(&self.hessian);
+        let mut linear = Array1::zeros(n2);
+        linear.slice_mut(s![..n]).assign(&self.linear);
+        let mut coeffs = Array2::zeros((n2, ci.coeffs.ncols()));
+        coeffs.slice_mut(s![..n, ..]).assign(&ci.coeffs);
+        let mut slack = n;
+        for &(j, penalty) in soft {
+            // A ranged constraint l <= a^T x <= u becomes l <= a^T x + s - t <= u.
+            let signs: &[f64] = if ranged { &[1.0, -1.0] } else { &[1.0] };
+            for &sign in signs {
+                coeffs[[slack, j]] = sign;
+                match penalty {
+                    Penalty::L1(w) => {
+                        hessian[[slack, slack]] = w * L1_REGULARIZATION;
+                        linear[slack] = w;
+                    }
+                    Penalty::L2(w) => hessian[[slack, slack]] = w,
+                }
+                slack += 1;
+            }
+        }
+        ci.coeffs = coeffs;
+
+        let eq = self.eq.map(|mut ce| {
+            let mut coeffs = Array2::zeros((n2, ce.coeffs.ncols()));
+            coeffs.slice_mut(s![..n, ..]).assign(&ce.coeffs);
+            ce.coeffs = coeffs;
+            ce
+        });
+
+        // The slack variables are nonnegative and unbounded above.
+        let mut lower = self.bounds.lower_bounds().to_vec();
+        lower.resize(n, f64::NEG_INFINITY);
+        lower.resize(n2, 0.0);
+        let mut bounds = if self.bounds.upper_bounds().is_empty() {
+            Bounds::lower(Array1::from(lower))
+        } else {
+            let mut upper = self.bounds.upper_bounds().to_vec();
+            upper.resize(n2, f64::INFINITY);
+            Bounds::new(Array1::from(lower), Array1::from(upper))
+        };
+        if !self.bounds.names().is_empty() {
+            let slacks = soft.iter().flat_map(|&(j, _)| {
+                let count = if ranged { 2 } else { 1 };
+                vec![format!("slack of constraint {}", j); count]
+            });
+            bounds = bounds.with_names(self.bounds.names().iter().cloned().chain(slacks));
+        }
+
+        Self {
+            hessian,
+            linear,
+            eq,
+            ineq: Some(ci),
+            bounds,
+            ..self
+        }
+    }
+}
+
+impl QpProblemBuilder {
+    /// Sets the Hessian \\(G\\), an \\(N \times N\\) symmetric matrix.
+    pub fn hessian<S: Data<Elem = f64>>(mut self, g: ArrayBase<S, Ix2>) -> Self {
+        self.hessian = Some(g.into_owned());
+        self
+    }
+
+    /// Sets the linear term \\(g_0\\), an \\(N\\)-vector.
+    pub fn linear<S: Data<Elem = f64>>(mut self, g0: ArrayBase<S, Ix1>) -> Self {
+        self.linear = Some(g0.into_owned());
+        self
+    }
+
+    /// Sets the equality constraints.
+    pub fn eq<S, S0>(mut self, ce: Constraints<S, S0>) -> Self
+    where
+        S: Data<Elem = f64>,
+        S0: Data<Elem = f64>,
+    {
+        self.eq = Some(ce.into_owned());
+        self
+    }
+
+    /// Sets the inequality constraints, which may be [ranged](Constraints::ranged).
+    pub fn ineq<S, S0>(mut self, ci: Constraints<S, S0>) -> Self
+    where
+        S: Data<Elem = f64>,
+        S0: Data<Elem = f64>,
+    {
+        self.ineq = Some(ci.into_owned());
+        self
+    }
+
+    /// Sets the direction of optimization. Defaults to [`Objective::Minimize`].
+    ///
+    /// A maximization is solved as the minimization of \\(-q(x)\\), so the multipliers of the
+    /// solution are those of the minimization, while its objective is the maximum of \\(q(x)\\).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // max -x^2 / 2 + 2x
+    /// let problem = QpProblem::new(1)
+    ///     .hessian(array![[-1.0]])
+    ///     .linear(array![2.0])
+    ///     .objective(Objective::Maximize)
+    ///     .build()?;
+    /// let solution = problem.solve()?;
+    /// assert_eq!(solution.x, array![2.0]);
+    /// assert_eq!(solution.objective, 2.0);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Makes the inequality constraints with the given indices soft, i.e. allows them to be
+    /// violated at the cost of `penalty`.
+    ///
+    /// A nonnegative slack variable is added to each soft constraint (two for a ranged
+    /// constraint), and the penalty on the slack variable is added to the objective. The slack
+    /// variables are removed from the solution, but its objective includes the penalties.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // min (x - 2)^2 / 2 s.t. x <= 1 and x >= 3 (soft)
+    /// let problem = QpProblem::new(1)
+    ///     .hessian(array![[1.0]])
+    ///     .linear(array![-2.0])
+    ///     .ineq(Constraints::new(array![[-1.0, 1.0]], array![1.0, -3.0]))
+    ///     .soft([1], Penalty::L2(1.0))
+    ///     .build()?;
+    /// let solution = problem.solve()?;
+    /// assert!((solution.x[0] - 1.0).abs() < 1e-12);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn soft<I: IntoIterator<Item = usize>>(mut self, indices: I, penalty: Penalty) -> Self {
+        for index in indices {
+            self.soft.retain(|&(i, _)| i != index);
+            self.soft.push((index, penalty));
+        }
+        self
+    }
+
+    /// Sets the bounds on the variables.
+    pub fn bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = bounds;
+        self
+    }
+
+    /// Validates the problem.
+    ///
+    /// # Errors
+    ///
+    /// * If the shapes of the matrices and vectors are inconsistent, or the Hessian is missing,
+    ///   this method returns [`Error::SizeMismatch`].
+    /// * If the Hessian isn't symmetric, it returns [`Error::NotSymmetric`].
+    /// * If a coefficient or constant is infinite or NaN, it returns [`Error::NonFinite`]. The
+    ///   limits of ranged constraints and bounds may be infinite, but not NaN.
+    /// * If ranged constraints are given as equality constraints, it returns
+    ///   [`Error::RangedEquality`].
+    /// * If a [soft](Self::soft) constraint doesn't exist, it returns [`Error::IndexOutOfRange`],
+    ///   and if its penalty weight isn't positive and finite, [`Error::InvalidPenalty`].
+    pub fn build(self) -> Result<QpProblem> {
+        let n = self.n;
+        let hessian = self.hessian.unwrap_or_else(|| Array2::zeros((0, 0)));
+        let (g_n, g_m) = hessian.dim();
+        assert_size!(hessian, n, g_n);
+        assert_size!(hessian, n, g_m);
+        let hessian = hessian.as_standard_layout().into_owned();
+        if let Some((row, col)) =
+            utils::asymmetry(hessian.as_slice().unwrap(), n, f64::EPSILON * 4.0)
+        {
+            return Err(Error::NotSymmetric { row, col });
+        }
+        assert_finite!(hessian);
+        let linear = self.linear.unwrap_or_else(|| Array1::zeros(n));
+        assert_size!(linear, n, linear.len());
+        assert_finite!(linear);
+        if let Some(ce) = &self.eq {
+            if ce.upper.is_some() {
+                return Err(Error::RangedEquality);
+            }
+            assert_size!(eq.coeffs(), n, ce.coeffs.nrows());
+            assert_size!(eq.consts(), ce.coeffs.ncols(), ce.consts.len());
+            if !ce.names.is_empty() {
+                assert_size!(eq.names(), ce.coeffs.ncols(), ce.names.len());
+            }
+            assert_finite!(ce.coeffs);
+            assert_finite!(ce.consts);
+        }
+        if let Some(ci) = &self.ineq {
+            assert_size!(ineq.coeffs(), n, ci.coeffs.nrows());
+            assert_size!(ineq.consts(), ci.coeffs.ncols(), ci.consts.len());
+            if !ci.names.is_empty() {
+                assert_size!(ineq.names(), ci.coeffs.ncols(), ci.names.len());
+            }
+            assert_finite!(ci.coeffs);
+            match &ci.upper {
+                Some(upper) => {
+                    assert_size!(ineq.upper(), ci.coeffs.ncols(), upper.len());
+                    assert_not_nan!(ci.consts);
+                    assert_not_nan!(upper);
+                }
+                None => assert_finite!(ci.consts),
+            }
+        }
+        let bounds = &self.bounds;
+        for (term, len) in [
+            ("bounds.lower_bounds()", bounds.lower_bounds().len()),
+            ("bounds.upper_bounds()", bounds.upper_bounds().len()),
+            ("bounds.names()", bounds.names().len()),
+        ] {
+            if len != 0 && len != n {
+                return Err(Error::SizeMismatch {
+                    term,
+                    expected: n,
+                    actual: len,
+                    shapes: None,
+                });
+            }
+        }
+        assert_not_nan!(bounds.lower_bounds());
+        assert_not_nan!(bounds.upper_bounds());
+        let m = self.ineq.as_ref().map_or(0, |ci| ci.coeffs.ncols());
+        for &(index, penalty) in &self.soft {
+            if index >= m {
+                return Err(Error::IndexOutOfRange { index, len: m });
+            }
+            let (Penalty::L1(weight) | Penalty::L2(weight)) = penalty;
+            if !(weight > 0.0 && weight.is_finite()) {
+                return Err(Error::InvalidPenalty { weight });
+            }
+        }
+        let (hessian, linear) = match self.objective {
+            Objective::Minimize => (hessian, linear),
+            Objective::Maximize => (-hessian, -linear),
+        };
+        let problem = QpProblem {
+            hessian: hessian.as_standard_layout().into_owned(),
+            linear: linear.as_standard_layout().into_owned(),
+            eq: self.eq,
+            ineq: self.ineq,
+            bounds: self.bounds,
+            objective: self.objective,
+            n,
+        };
+        if self.soft.is_empty() {
+            Ok(problem)
+        } else {
+            Ok(problem.with_slacks(&self.soft))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::{assert_abs_diff_eq, assert_ulps_eq};
+    use ndarray::array;
+
+    use super::*;
+    use crate::solve_with_bounds;
+
+    #[test]
+    fn matches_solve() -> Result<()> {
+        let g = array![[2.0, 0.5], [0.5, 1.0]];
+        let g0 = array![1.0, -1.0];
+        let ce = array![[1.0], [-1.0]];
+        let ce0 = array![0.5];
+        let ci = array![[1.0], [1.0]];
+        let bounds = Bounds::upper(array![0.0, f64::INFINITY]);
+        let problem = QpProblem::new(2)
+            .hessian(g.view())
+            .linear(g0.view())
+            .eq(Constraints::new(ce.view(), ce0.view()))
+            .ineq(Constraints::ranged(ci.view(), array![-1.0], array![1.0]))
+            .bounds(bounds.clone())
+            .build()?;
+        assert_eq!(problem.dim(), (2, 1, 1));
+        let expected = solve_with_bounds(
+            g,
+            g0,
+            Constraints::some(ce, ce0),
+            Some(Constraints::ranged(ci, array![-1.0], array![1.0])),
+            &bounds,
+            &SolveOptions::default(),
+        )?;
+        for _ in 0..2 {
+            let solution = problem.solve()?;
+            assert_ulps_eq!(solution.objective, expected.objective);
+            assert_eq!(solution.x, expected.x);
+            assert_eq!(solution.multipliers, expected.multipliers);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn maximize() -> Result<()> {
+        let g = array![[2.0, 0.5], [0.5, 1.0]];
+        let g0 = array![-1.0, -1.0];
+        let ci = Constraints::new(array![[-1.0], [-1.0]], array![1.0]);
+        let min = QpProblem::new(2)
+            .hessian(g.view())
+            .linear(g0.view())
+            .ineq(ci.clone())
+            .build()?
+            .solve()?;
+        let max = QpProblem::new(2)
+            .hessian(-&g)
+            .linear(-&g0)
+            .ineq(ci)
+            .objective(Objective::Maximize)
+            .build()?
+            .solve()?;
+        assert_eq!(max.x, min.x);
+        assert_eq!(max.objective, -min.objective);
+        assert_eq!(max.multipliers, min.multipliers);
+        Ok(())
+    }
+
+    #[test]
+    fn soft_constraints() -> Result<()> {
+        // min (x0^2 + x1^2) / 2 s.t. 1 <= x0 + x1 <= 2, x0 - x1 >= 4 (soft)
+        let g: Array2<f64> = Array2::eye(2);
+        let ci = Constraints::new(array![[1.0, 1.0], [1.0, -1.0]], array![-1.0, -4.0]);
+        let hard = QpProblem::new(2)
+            .hessian(g.view())
+            .ineq(ci.clone())
+            .build()?
+            .solve()?;
+
+        // A large L1 penalty doesn't change the solution of a feasible problem.
+        let l1 = QpProblem::new(2)
+            .hessian(g.view())
+            .ineq(ci.clone())
+            .soft([1], Penalty::L1(100.0))
+            .build()?;
+        assert_eq!(l1.dim(), (3, 0, 2));
+        let solution = l1.solve()?;
+        assert_eq!(solution.x.len(), 2);
+        assert_abs_diff_eq!(solution.x[0], hard.x[0], epsilon = 1e-6);
+        assert_abs_diff_eq!(solution.x[1], hard.x[1], epsilon = 1e-6);
+
+        // An L2 penalty trades off the violation against the objective:
+        // min (x0^2 + x1^2 + s^2) / 2 s.t. x0 - x1 + s >= 4 gives x0 = -x1 = s = 4 / 3.
+        let l2 = QpProblem::new(2)
+            .hessian(g.view())
+            .ineq(Constraints::new(array![[1.0], [-1.0]], array![-4.0]))
+            .soft([0], Penalty::L2(1.0))
+            .build()?
+            .solve()?;
+        assert_abs_diff_eq!(l2.x[0], 4.0 / 3.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(l2.x[1], -4.0 / 3.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(l2.objective, 8.0 / 3.0, epsilon = 1e-12);
+
+        // Conflicting ranged constraints: 0 <= x0 <= 1 (hard) and 2 <= x0 <= 3 (soft).
+        let ranged = QpProblem::new(2)
+            .hessian(g.view())
+            .ineq(Constraints::ranged(
+                array![[1.0, 1.0], [0.0, 0.0]],
+                array![0.0, 2.0],
+                array![1.0, 3.0],
+            ))
+            .bounds(Bounds::upper(array![5.0, 5.0]).with_names(["x0", "x1"]))
+            .soft([1], Penalty::L2(1.0))
+            .build()?;
+        assert_eq!(ranged.dim(), (4, 0, 2));
+        assert_eq!(ranged.bounds().names().len(), 4);
+        let solution = ranged.solve()?;
+        assert_abs_diff_eq!(solution.x[0], 1.0, epsilon = 1e-12);
+        assert_eq!(solution.multipliers.upper.len(), 2);
+
+        let err = QpProblem::new(2)
+            .hessian(g.view())
+            .ineq(ci.clone())
+            .soft([2], Penalty::L1(1.0))
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::IndexOutOfRange { index: 2, len: 2 }),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = QpProblem::new(2)
+            .hessian(g)
+            .ineq(ci)
+            .soft([0], Penalty::L2(0.0))
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidPenalty { .. }),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validation() {
+        let build =
+            |g: Array2<f64>, g0: Array1<f64>| QpProblem::new(2).hessian(g).linear(g0).build();
+        let err = QpProblem::new(2).build().unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "hessian",
+                    expected: 2,
+                    actual: 0,
+                    shapes: None
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = build(array![[1.0, 0.0], [1.0, 1.0]], array![0.0, 0.0]).unwrap_err();
+        assert!(
+            matches!(err, Error::NotSymmetric { row: 1, col: 0 }),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = build(array![[1.0, 0.0], [0.0, 1.0]], array![0.0, f64::NAN]).unwrap_err();
+        assert!(
+            matches!(err, Error::NonFinite { term: "linear" }),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = QpProblem::new(2)
+            .hessian(array![[1.0, 0.0], [0.0, 1.0]])
+            .ineq(Constraints::new(
+                array![[1.0], [1.0]],
+                array![f64::INFINITY],
+            ))
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::NonFinite { term: "ci.consts" }),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = QpProblem::new(2)
+            .hessian(array![[1.0, 0.0], [0.0, 1.0]])
+            .bounds(Bounds::lower(array![0.0]))
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "bounds.lower_bounds()",
+                    expected: 2,
+                    actual: 1,
+                    shapes: None
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
@@ -0,0 +1,453 @@
+#[cfg(feature = "pure-rust")]
+use std::borrow::Cow;
+#[cfg(feature = "pure-rust")]
+use std::convert::TryFrom;
+use std::fmt;
+
+#[cfg(feature = "pure-rust")]
+use ndarray::Array1;
+
+#[cfg(feature = "pure-rust")]
+use crate::backend::PORT_NAME;
+use crate::{backend_info, BackendInfo, Error, QpProblem, Result, Solution, SolveOptions};
+#[cfg(feature = "pure-rust")]
+use crate::{goldfarb_idnani, solver, utils, Status};
+
+/// A solver of [`QpProblem`]s.
+///
+/// Code which takes the backend as a parameter, e.g. a `&dyn QpBackend`, lets the solver be
+/// chosen by a feature or at runtime without changing that code. [`QuadProgpp`] is the solver of
+/// the rest of the crate, and [`GoldfarbIdnani`], with the `pure-rust` feature, is its pure-Rust
+/// port.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let problem = QpProblem::new(2)
+///     .hessian(array![[4.0, -2.0], [-2.0, 4.0]])
+///     .linear(array![6.0, 0.0])
+///     .eq(Constraints::new(array![[1.0], [1.0]], array![-3.0]))
+///     .ineq(Constraints::new(array![[1.0, 1.0, 0.0], [0.0, 1.0, 1.0]], array![0.0, -2.0, 0.0]))
+///     .build()?;
+/// let backend: Box<dyn QpBackend> = Box::new(QuadProgpp);
+/// let solution = backend.solve(&problem, &SolveOptions::default())?;
+/// assert_eq!(solution.objective, 12.0);
+/// println!("solved by {}", backend.info());
+/// # Ok::<(), Error>(())
+/// ```
+pub trait QpBackend {
+    /// Returns the build of the solver, like [`backend_info`] does for [`QuadProgpp`].
+    fn info(&self) -> BackendInfo;
+
+    /// Solves `problem` like [`QpProblem::solve_with_options`].
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`QpProblem::solve_with_options`], and [`Error::Unsupported`] if the
+    /// backend doesn't implement an option set in `options`.
+    ///
+    /// [`Error::Unsupported`]: crate::Error::Unsupported
+    fn solve(&self, problem: &QpProblem, options: &SolveOptions) -> Result<Solution>;
+}
+
+impl<B: QpBackend + ?Sized> QpBackend for &B {
+    fn info(&self) -> BackendInfo {
+        (**self).info()
+    }
+
+    fn solve(&self, problem: &QpProblem, options: &SolveOptions) -> Result<Solution> {
+        (**self).solve(problem, options)
+    }
+}
+
+impl<B: QpBackend + ?Sized> QpBackend for Box<B> {
+    fn info(&self) -> BackendInfo {
+        (**self).info()
+    }
+
+    fn solve(&self, problem: &QpProblem, options: &SolveOptions) -> Result<Solution> {
+        (**self).solve(problem, options)
+    }
+}
+
+/// QuadProg++ compiled from its C++ sources, which solves [`QpProblem::solve_with_options`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QuadProgpp;
+
+impl QpBackend for QuadProgpp {
+    fn info(&self) -> BackendInfo {
+        backend_info()
+    }
+
+    fn solve(&self, problem: &QpProblem, options: &SolveOptions) -> Result<Solution> {
+        problem.solve_with_options(options)
+    }
+}
+
+/// A [`QpBackend`] which tries backends in turn, falling back to the next one when a backend fails
+/// in a way which another backend, or other options, may avoid.
+///
+/// The chain moves on after [`Error::Infeasible`], which may be due to rounding,
+/// [`Error::SingularHessian`], [`Error::LinearlyDependent`], [`Error::Degenerate`] and
+/// [`Error::Unsupported`]. The other errors, e.g. about the data of the problem, are returned
+/// right away, like the error of the last backend. [`Solution::backend`] tells which backend
+/// solved the problem.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// // min x0^2 / 2 s.t. x0 + x1 = 2, whose Hessian is singular
+/// let problem = QpProblem::new(2)
+///     .hessian(array![[1.0, 0.0], [0.0, 0.0]])
+///     .linear(array![0.0, 0.0])
+///     .eq(Constraints::new(array![[1.0], [1.0]], array![-2.0]))
+///     .build()?;
+/// let chain = BackendChain::new(QuadProgpp)
+///     .then_with_options(QuadProgpp, SolveOptions::new().regularization(1e-9));
+/// let solution = chain.solve(&problem, &SolveOptions::default())?;
+/// assert!((solution.x[1] - 2.0).abs() < 1e-6);
+/// assert_eq!(solution.backend, "QuadProg++");
+/// # Ok::<(), Error>(())
+/// ```
+///
+/// [`Error::Infeasible`]: crate::Error::Infeasible
+/// [`Error::SingularHessian`]: crate::Error::SingularHessian
+/// [`Error::LinearlyDependent`]: crate::Error::LinearlyDependent
+/// [`Error::Degenerate`]: crate::Error::Degenerate
+/// [`Error::Unsupported`]: crate::Error::Unsupported
+pub struct BackendChain {
+    /// The backends, with the options replacing those passed to the chain, if any
+    backends: Vec<(Box<dyn QpBackend + Send + Sync>, Option<SolveOptions>)>,
+}
+
+impl BackendChain {
+    /// Creates a chain which starts with `backend`.
+    pub fn new<B: QpBackend + Send + Sync + 'static>(backend: B) -> Self {
+        Self {
+            backends: vec![(Box::new(backend), None)],
+        }
+    }
+
+    /// Appends `backend`, which is tried if the previous ones fail.
+    pub fn then<B: QpBackend + Send + Sync + 'static>(mut self, backend: B) -> Self {
+        self.backends.push((Box::new(backend), None));
+        self
+    }
+
+    /// Appends `backend` like [`then`](Self::then), to solve the problem with `options` instead
+    /// of those passed to the chain, e.g. to retry with
+    /// [`SolveOptions::regularization`].
+    pub fn then_with_options<B>(mut self, backend: B, options: SolveOptions) -> Self
+    where
+        B: QpBackend + Send + Sync + 'static,
+    {
+        self.backends.push((Box::new(backend), Some(options)));
+        self
+    }
+}
+
+impl fmt::Debug for BackendChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self.backends.iter().map(|(backend, _)| backend.info().name);
+        f.debug_list().entries(names).finish()
+    }
+}
+
+impl QpBackend for BackendChain {
+    /// Returns the build of the first backend.
+    fn info(&self) -> BackendInfo {
+        self.backends[0].0.info()
+    }
+
+    fn solve(&self, problem: &QpProblem, options: &SolveOptions) -> Result<Solution> {
+        let ((last, last_options), rest) = self
+            .backends
+            .split_last()
+            .expect("a chain has at least one backend");
+        for (backend, own_options) in rest {
+            match backend.solve(problem, own_options.as_ref().unwrap_or(options)) {
+                Err(
+                    Error::Infeasible { .. }
+                    | Error::SingularHessian { .. }
+                    | Error::LinearlyDependent
+                    | Error::Degenerate { .. }
+                    | Error::Unsupported { .. },
+                ) => continue,
+                result => return result,
+            }
+        }
+        last.solve(problem, last_options.as_ref().unwrap_or(options))
+    }
+}
+
+/// The pure-Rust port of QuadProg++, which also solves [`solve_slices`](crate::solve_slices)
+/// with the `pure-rust` feature. It returns the same solutions as [`QuadProgpp`] with the
+/// default options.
+///
+/// Of the options, it only implements those on the Hessian, i.e.
+/// [`upper_triangle`](SolveOptions::upper_triangle),
+/// [`symmetrize`](SolveOptions::symmetrize) and
+/// [`symmetry_tolerance`](SolveOptions::symmetry_tolerance), and returns
+/// [`Error::Unsupported`] if any other is set.
+#[cfg(feature = "pure-rust")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GoldfarbIdnani;
+
+#[cfg(feature = "pure-rust")]
+impl QpBackend for GoldfarbIdnani {
+    fn info(&self) -> BackendInfo {
+        crate::backend::port_info()
+    }
+
+    fn solve(&self, problem: &QpProblem, options: &SolveOptions) -> Result<Solution> {
+        if let Some(option) = options.unsupported() {
+            return Err(Error::Unsupported {
+                backend: PORT_NAME,
+                option,
+            });
+        }
+        let (n, p, m) = problem.dim();
+        let empty = || Cow::Borrowed(&[][..]);
+        // The arrays of a problem are owned, so their elements are initialized.
+        let g = unsafe { utils::row_major(&problem.hessian) };
+        let g = options.prepare_hessian(&g, n)?;
+        let g0 = unsafe { utils::row_major(&problem.linear) };
+        let (ce, ce0) = match &problem.eq {
+            Some(eq) => unsafe { (utils::row_major(&eq.coeffs), utils::row_major(&eq.consts)) },
+            None => (empty(), empty()),
+        };
+        let (ci, consts, upper, ci_names) = match &problem.ineq {
+            Some(ineq) => unsafe {
+                let ci = utils::row_major(&ineq.coeffs);
+                let consts = utils::row_major(&ineq.consts);
+                let upper = ineq.upper.as_ref().map(|upper| utils::row_major(upper));
+                (ci, consts, upper, &ineq.names[..])
+            },
+            None => (empty(), empty(), None, &[][..]),
+        };
+        let mut columns = Vec::new();
+        let mut ci0 = Vec::new();
+        let bounds = &problem.bounds;
+        solver::push_columns(&mut columns, &mut ci0, &consts, upper.as_deref(), bounds);
+        let mut ci_buf = Vec::with_capacity(n * columns.len());
+        solver::push_ci(&mut ci_buf, &columns, &ci, n, m);
+
+        let mut x = vec![0.0; n];
+        let describe = |k: usize| columns[k].describe(ci_names, bounds.names());
+        let mut storage = goldfarb_idnani::Storage::new(n, columns.len());
+        let mut workspace = storage.workspace();
+        let output = goldfarb_idnani::solve_full(
+            n,
+            &g,
+            &g0,
+            &ce,
+            &ce0,
+            &ci_buf,
+            &ci0,
+            &mut x,
+            &mut workspace,
+            describe,
+        )?;
+        let mut multipliers = vec![0.0; p + columns.len()];
+        for (k, u) in workspace.active_set(&output) {
+            match usize::try_from(k) {
+                Ok(k) => multipliers[p + k] = u,
+                Err(_) => multipliers[(-k - 1) as usize] = u,
+            }
+        }
+        let active = workspace.active_set(&output);
+        let active = active.filter_map(|(k, _)| usize::try_from(k).ok());
+        let (multipliers, active_set, active_lower, active_upper) =
+            solver::unmap_columns(&columns, n, m, &multipliers, active);
+        let mut solution = Solution {
+            x: Array1::from(x),
+            status: Status::Optimal,
+            objective: output.objective,
+            multipliers,
+            active_set,
+            active_lower,
+            active_upper,
+            iterations: output.iterations,
+            factorizations: 1,
+            additions: output.additions,
+            deletions: output.deletions,
+            rotations: output.rotations,
+            warm_started: false,
+            polished: false,
+            backend: PORT_NAME,
+        };
+        problem.finish(&mut solution);
+        Ok(solution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "pure-rust")]
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+    #[cfg(feature = "pure-rust")]
+    use ndarray::Array2;
+
+    use super::*;
+    use crate::Constraints;
+    #[cfg(feature = "pure-rust")]
+    use crate::{Bounds, Objective};
+
+    #[test]
+    fn dyn_backend() -> Result<()> {
+        let problem = QpProblem::new(1)
+            .hessian(array![[1.0]])
+            .linear(array![0.0])
+            .ineq(Constraints::new(array![[1.0]], array![-1.0]))
+            .build()?;
+        let backends: Vec<Box<dyn QpBackend>> = vec![
+            Box::new(QuadProgpp),
+            #[cfg(feature = "pure-rust")]
+            Box::new(GoldfarbIdnani),
+        ];
+        for backend in &backends {
+            let solution = backend.solve(&problem, &SolveOptions::default())?;
+            assert_eq!(solution.x, array![1.0], "solved by {}", backend.info());
+        }
+        assert_eq!(QuadProgpp.info(), backend_info());
+        Ok(())
+    }
+
+    /// A backend which fails with the error returned by its function.
+    struct Failing(fn() -> Error);
+
+    impl QpBackend for Failing {
+        fn info(&self) -> BackendInfo {
+            BackendInfo {
+                name: "failing",
+                ..backend_info()
+            }
+        }
+
+        fn solve(&self, _: &QpProblem, _: &SolveOptions) -> Result<Solution> {
+            Err((self.0)())
+        }
+    }
+
+    #[test]
+    fn chain() -> Result<()> {
+        let problem = QpProblem::new(1)
+            .hessian(array![[1.0]])
+            .linear(array![0.0])
+            .ineq(Constraints::new(array![[1.0]], array![-1.0]))
+            .build()?;
+        let options = SolveOptions::default();
+        let singular = || Error::SingularHessian { pivot: 0 };
+        let chain = BackendChain::new(Failing(singular)).then(QuadProgpp);
+        assert_eq!(format!("{:?}", chain), r#"["failing", "QuadProg++"]"#);
+        assert_eq!(chain.info().name, "failing");
+        let solution = chain.solve(&problem, &options)?;
+        assert_eq!(solution.x, array![1.0]);
+        assert_eq!(solution.backend, "QuadProg++");
+
+        // Errors about the problem aren't retried.
+        let asymmetric = || Error::NotSymmetric { row: 1, col: 0 };
+        let chain = BackendChain::new(Failing(asymmetric)).then(QuadProgpp);
+        let err = chain.solve(&problem, &options).unwrap_err();
+        assert!(
+            matches!(err, Error::NotSymmetric { row: 1, col: 0 }),
+            "unexpected error: {:?}",
+            err
+        );
+        // The error of the last backend is returned.
+        let chain = BackendChain::new(QuadProgpp).then(Failing(singular));
+        let best_effort = options.clone().max_iterations(0).best_effort(true);
+        let solution = chain.solve(&problem, &best_effort);
+        assert_eq!(solution?.backend, "QuadProg++");
+        let chain = BackendChain::new(Failing(singular)).then_with_options(
+            Failing(|| Error::Degenerate { iterations: 2 }),
+            options.clone(),
+        );
+        let err = chain.solve(&problem, &options).unwrap_err();
+        assert!(
+            matches!(err, Error::Degenerate { iterations: 2 }),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "pure-rust")]
+    #[test]
+    fn parity() -> Result<()> {
+        // max -|x - (1, 2, 3)|^2 / 2 s.t. 0 <= x0 + x1 + x2 <= 2.5, x0 >= 1.5, x2 <= 1, x1 = x2 - 0.5
+        let problem = QpProblem::new(3)
+            .hessian(-Array2::eye(3))
+            .linear(array![1.0, 2.0, 3.0])
+            .eq(Constraints::new(array![[0.0], [-1.0], [1.0]], array![-0.5]))
+            .ineq(Constraints::ranged(
+                array![[1.0], [1.0], [1.0]],
+                array![0.0],
+                array![2.5],
+            ))
+            .bounds(Bounds::new(
+                array![1.5, f64::NEG_INFINITY, f64::NEG_INFINITY],
+                array![f64::INFINITY, f64::INFINITY, 1.0],
+            ))
+            .objective(Objective::Maximize)
+            .build()?;
+        let options = SolveOptions::default();
+        let cpp = QuadProgpp.solve(&problem, &options)?;
+        let port = GoldfarbIdnani.solve(&problem, &options)?;
+        if quadprogpp_sys::EXTENDED_PRECISION || cfg!(feature = "simd") {
+            for (&actual, &expected) in port.x.iter().zip(&cpp.x) {
+                assert_abs_diff_eq!(actual, expected, epsilon = 1e-9);
+            }
+        } else {
+            let port = Solution {
+                backend: cpp.backend,
+                ..port.clone()
+            };
+            assert_eq!(port, cpp);
+        }
+        assert_eq!(port.backend, "goldfarb-idnani");
+        for (&actual, &expected) in port.x.iter().zip(&[1.5, 0.25, 0.75]) {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+        }
+        assert_eq!(port.active_set, [0]);
+        assert_eq!(port.active_lower, [0]);
+        assert!(port.active_upper.is_empty());
+        Ok(())
+    }
+
+    #[cfg(feature = "pure-rust")]
+    #[test]
+    fn port_errors() -> Result<()> {
+        // x0 <= 0 and x0 >= 1
+        let problem = QpProblem::new(1)
+            .hessian(array![[1.0]])
+            .linear(array![0.0])
+            .ineq(Constraints::new(array![[-1.0]], array![0.0]).with_names(["cap"]))
+            .bounds(Bounds::lower(array![1.0]))
+            .build()?;
+        let options = SolveOptions::default();
+        let cpp = QuadProgpp.solve(&problem, &options).unwrap_err();
+        let err = GoldfarbIdnani.solve(&problem, &options).unwrap_err();
+        assert_eq!(err.to_string(), cpp.to_string());
+        let err = GoldfarbIdnani
+            .solve(&problem, &options.max_iterations(10))
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::Unsupported {
+                    option: "max_iterations",
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use crate::polish::Problem;
+
+/// Maximum number of passes of Ruiz equilibration. Each pass takes the square root of the norms
+/// of the rows and columns, so a few passes bring them close to one.
+const MAX_PASSES: usize = 20;
+
+/// Ruiz equilibration stops once all the norms are within this distance from one.
+const TOLERANCE: f64 = 1e-3;
+
+/// The scaling of [`SolveOptions::equilibrate`](crate::SolveOptions::equilibrate) and the scaled
+/// problem.
+///
+/// The variables are scaled as \\(x = D \tilde{x}\\) and the constraints by \\(E\\), so the
+/// scaled problem has \\(\tilde{G} = D G D\\), \\(\tilde{g}_0 = D g_0\\),
+/// \\(\tilde{C} = D C E\\) and \\(\tilde{c} = E c\\). Its objective is that of the original
+/// problem, and its multipliers are \\(\tilde{\lambda} = E^{-1} \lambda\\).
+#[derive(Debug, Default)]
+pub(crate) struct Scaling {
+    /// Whether the Hessian factorized by the solver is the scaled one, which the following
+    /// solves reusing the factorization have to keep scaling with the same \\(D\\)
+    pub(crate) hessian: bool,
+    /// The diagonal of \\(D\\)
+    d: Vec<f64>,
+    /// The diagonal of \\(E\\), the equality constraints followed by the inequality constraints
+    e: Vec<f64>,
+    g: Vec<f64>,
+    g0: Vec<f64>,
+    ce: Vec<f64>,
+    ce0: Vec<f64>,
+    ci: Vec<f64>,
+    ci0: Vec<f64>,
+}
+
+impl Scaling {
+    /// Scales `problem` by Ruiz equilibration of its KKT matrix, i.e. by alternately dividing
+    /// each row and column of \\(\begin{bmatrix} G & C \\\\ C^\intercal & 0 \end{bmatrix}\\) by
+    /// the square root of its infinity norm.
+    ///
+    /// If `hessian` is `false`, \\(G\\) and \\(D\\) are kept from the last call, and only the
+    /// constraints are equilibrated.
+    pub(crate) fn equilibrate(&mut self, problem: &Problem<'_>, hessian: bool) {
+        let n = problem.g0.len();
+        let (p, m) = (problem.ce0.len(), problem.ci0.len());
+        if hessian {
+            self.g.clear();
+            self.g.extend_from_slice(problem.g);
+            self.d.clear();
+            self.d.resize(n, 1.0);
+        }
+        self.e.clear();
+        self.e.resize(p + m, 1.0);
+        // Start from the constraints scaled by the current D.
+        self.ce.clear();
+        self.ci.clear();
+        for i in 0..n {
+            let d = self.d[i];
+            self.ce
+                .extend(problem.ce[i * p..(i + 1) * p].iter().map(|a| a * d));
+            self.ci
+                .extend(problem.ci[i * m..(i + 1) * m].iter().map(|a| a * d));
+        }
+
+        if hessian {
+            for _ in 0..MAX_PASSES {
+                let (rows, cols) = self.norms(n, p, m);
+                let converged = (rows.iter().chain(&cols))
+                    .all(|&norm| norm == 0.0 || (1.0 - norm).abs() <= TOLERANCE);
+                if converged {
+                    break;
+                }
+                let factor = |norm: f64| if norm > 0.0 { 1.0 / norm.sqrt() } else { 1.0 };
+                let rows: Vec<f64> = rows.into_iter().map(factor).collect();
+                let cols: Vec<f64> = cols.into_iter().map(factor).collect();
+                self.apply(p, &rows, &cols);
+            }
+        } else {
+            // With D fixed, the columns are normalized in one go.
+            let (_, cols) = self.norms(n, p, m);
+            let cols: Vec<f64> = (cols.into_iter())
+                .map(|norm| if norm > 0.0 { 1.0 / norm } else { 1.0 })
+                .collect();
+            self.apply(p, &vec![1.0; n], &cols);
+        }
+
+        self.g0.clear();
+        self.g0
+            .extend(problem.g0.iter().zip(&self.d).map(|(g, d)| g * d));
+        self.ce0.clear();
+        self.ce0
+            .extend(problem.ce0.iter().zip(&self.e).map(|(c, e)| c * e));
+        self.ci0.clear();
+        self.ci0
+            .extend(problem.ci0.iter().zip(&self.e[p..]).map(|(c, e)| c * e));
+    }
+
+    /// Returns the infinity norms of the rows and the constraint columns of the scaled KKT
+    /// matrix.
+    fn norms(&self, n: usize, p: usize, m: usize) -> (Vec<f64>, Vec<f64>) {
+        let mut rows = vec![0.0f64; n];
+        let mut cols = vec![0.0f64; p + m];
+        for (i, row) in rows.iter_mut().enumerate() {
+            let g = self.g[i * n..(i + 1) * n].iter();
+            *row = g.fold(0.0, |max, a| max.max(a.abs()));
+            let ce = self.ce[i * p..(i + 1) * p].iter();
+            let ci = self.ci[i * m..(i + 1) * m].iter();
+            for (col, a) in cols.iter_mut().zip(ce.chain(ci)) {
+                *row = row.max(a.abs());
+                *col = col.max(a.abs());
+            }
+        }
+        (rows, cols)
+    }
+
+    /// Multiplies the rows and the columns of the scaled KKT matrix by `rows` and `cols`.
+    /// The first `p` columns are the equality constraints.
+    fn apply(&mut self, p: usize, rows: &[f64], cols: &[f64]) {
+        let (n, m) = (rows.len(), cols.len() - p);
+        for (i, &row) in rows.iter().enumerate() {
+            let g = self.g[i * n..(i + 1) * n].iter_mut().zip(rows);
+            g.for_each(|(a, col)| *a *= row * col);
+            let ce = self.ce[i * p..(i + 1) * p].iter_mut().zip(&cols[..p]);
+            ce.for_each(|(a, col)| *a *= row * col);
+            let ci = self.ci[i * m..(i + 1) * m].iter_mut().zip(&cols[p..]);
+            ci.for_each(|(a, col)| *a *= row * col);
+            self.d[i] *= row;
+        }
+        for (e, c) in self.e.iter_mut().zip(cols) {
+            *e *= c;
+        }
+    }
+
+    /// Returns the scaled problem.
+    pub(crate) fn problem(&self) -> Problem<'_> {
+        Problem {
+            g: &self.g,
+            g0: &self.g0,
+            ce: &self.ce,
+            ce0: &self.ce0,
+            ci: &self.ci,
+            ci0: &self.ci0,
+        }
+    }
+
+    /// Scales a point of the original problem, \\(\tilde{x} = D^{-1} x\\).
+    pub(crate) fn scale(&self, x: &mut [f64]) {
+        x.iter_mut().zip(&self.d).for_each(|(x, d)| *x /= d);
+    }
+
+    /// Maps a solution of the scaled problem back to the original one.
+    pub(crate) fn unscale(&self, x: &mut [f64], multipliers: &mut [f64]) {
+        x.iter_mut().zip(&self.d).for_each(|(x, d)| *x *= d);
+        multipliers
+            .iter_mut()
+            .zip(&self.e)
+            .for_each(|(u, e)| *u *= e);
+    }
+}
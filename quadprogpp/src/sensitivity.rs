@@ -0,0 +1,412 @@
+use ndarray::{s, Array1, Array2, ArrayBase, ArrayView1, Data, Ix1, Ix2};
+
+use crate::linalg::Lu;
+use crate::{Constraints, Error, Result, Solution};
+
+/// Sensitivities of the solution of a quadratic programming problem with respect to the problem
+/// data.
+///
+/// The sensitivities are computed by differentiating the KKT conditions at the solution,
+/// \\(G x + g_0 = C y\\) and \\(C^\intercal x + c = 0\\), where \\(C\\) and \\(c\\) are the
+/// equality constraints followed by the inequality constraints in [`Solution::active_set`], and
+/// \\(y\\) are their multipliers. The active set is assumed not to change under small
+/// perturbations, i.e. the multipliers of the active inequality constraints are positive.
+///
+/// Only available with the `sensitivity` feature.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// // min (x - a)^2 + (y - b)^2 s.t. x + y <= 1
+/// let g = array![[2.0, 0.0], [0.0, 2.0]];
+/// let ci = Constraints::new(array![[-1.0], [-1.0]], array![1.0]);
+/// let solution = solve(g.view(), array![-2.0, -2.0], Constraints::NONE, Some(ci.clone()))?;
+/// assert_eq!(solution.x, array![0.5, 0.5]);
+///
+/// let sensitivity = Sensitivity::new(g, Constraints::NONE, Some(ci), &solution)?;
+/// // moving along the constraint, x only depends on g0[0] - g0[1]
+/// assert_eq!(sensitivity.dx_dg0(), array![[-0.25, 0.25], [0.25, -0.25]]);
+/// // relaxing the constraint moves both variables equally
+/// assert_eq!(sensitivity.dx_dci0(), array![[0.5], [0.5]]);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sensitivity {
+    n: usize,
+    x: Array1<f64>,
+    /// The active constraints in the order of the columns of \\(C\\)
+    active: Vec<Active>,
+    /// Multipliers of the active constraints
+    y: Array1<f64>,
+    /// The LU factorization of the KKT matrix
+    lu: Lu,
+    p: usize,
+    m: usize,
+}
+
+/// Gradients of a scalar loss with respect to the problem data, computed by
+/// [`Sensitivity::backward`].
+///
+/// Each gradient has the shape of the corresponding argument of [`solve`](crate::solve). The
+/// gradients of the inactive inequality constraints are zero.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradients {
+    /// Gradient with respect to \\(G\\), symmetrized
+    pub g: Array2<f64>,
+    /// Gradient with respect to \\(g_0\\)
+    pub g0: Array1<f64>,
+    /// Gradient with respect to \\(CE\\)
+    pub ce: Array2<f64>,
+    /// Gradient with respect to \\(ce_0\\)
+    pub ce0: Array1<f64>,
+    /// Gradient with respect to \\(CI\\)
+    pub ci: Array2<f64>,
+    /// Gradient with respect to \\(ci_0\\), or the active limits of ranged constraints
+    pub ci0: Array1<f64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Eq,
+    Ineq,
+}
+
+/// An active constraint, i.e. a column of \\(C\\).
+#[derive(Clone, Copy, Debug)]
+struct Active {
+    kind: Kind,
+    index: usize,
+    /// -1 if the column is the negated coefficients, i.e. the upper limit of a ranged constraint
+    sign: f64,
+    /// The derivative of the constant \\(c\\) with respect to the user-facing constant, which is
+    /// -1 for the lower limit of a ranged constraint
+    scale: f64,
+}
+
+impl Sensitivity {
+    /// Computes the sensitivities at `solution`, which should be the solution of the problem given
+    /// by `g`, `ce` and `ci`, solved without [`Bounds`](crate::Bounds).
+    ///
+    /// For a [ranged](Constraints::ranged) inequality constraint, the derivative with respect to
+    /// \\(ci_0\\) is the one with respect to the active limit.
+    ///
+    /// # Errors
+    ///
+    /// * If the shapes of the matrices/vectors don't match the solution, this function returns
+    ///   [`Error::SizeMismatch`].
+    /// * If [`Solution::active_set`] refers to a nonexistent inequality constraint, it returns
+    ///   [`Error::IndexOutOfRange`].
+    /// * If the active constraints are linearly dependent, the KKT system is singular and this
+    ///   function returns [`Error::Singular`].
+    pub fn new<G, CE, CE0, CI, CI0>(
+        g: ArrayBase<G, Ix2>,
+        ce: Option<Constraints<CE, CE0>>,
+        ci: Option<Constraints<CI, CI0>>,
+        solution: &Solution,
+    ) -> Result<Self>
+    where
+        G: Data<Elem = f64>,
+        CE: Data<Elem = f64>,
+        CE0: Data<Elem = f64>,
+        CI: Data<Elem = f64>,
+        CI0: Data<Elem = f64>,
+    {
+        let n = solution.x.len();
+        let p = solution.multipliers.eq.len();
+        let m = solution.multipliers.ineq.len();
+        let (g_n, g_m) = g.dim();
+        assert_size!(g, n, g_n);
+        assert_size!(g, n, g_m);
+        let mut active = Vec::with_capacity(p + solution.active_set.len());
+        let mut y = Vec::with_capacity(p + solution.active_set.len());
+        let mut columns = Vec::with_capacity(p + solution.active_set.len());
+        match &ce {
+            Some(ce) => {
+                assert_size!(ce.dim(), n, ce.coeffs.nrows());
+                assert_size!(ce.dim(), p, ce.coeffs.ncols());
+                for j in 0..p {
+                    active.push(Active {
+                        kind: Kind::Eq,
+                        index: j,
+                        sign: 1.0,
+                        scale: 1.0,
+                    });
+                    y.push(solution.multipliers.eq[j]);
+                    columns.push(ce.coeffs.column(j).to_owned());
+                }
+            }
+            None => assert_size!(ce.dim(), p, 0),
+        }
+        match &ci {
+            Some(ci) => {
+                assert_size!(ci.dim(), n, ci.coeffs.nrows());
+                assert_size!(ci.dim(), m, ci.coeffs.ncols());
+                for &j in &solution.active_set {
+                    if j >= m {
+                        return Err(Error::IndexOutOfRange { index: j, len: m });
+                    }
+                    let a = ci.coeffs.column(j);
+                    // a^T x - l >= 0 or -a^T x + u >= 0, whichever limit is closer to a^T x
+                    let (sign, scale) = match &ci.upper {
+                        Some(upper) => {
+                            let ax = a.dot(&solution.x);
+                            if (ax - ci.consts[j]).abs() <= (upper[j] - ax).abs() {
+                                (1.0, -1.0)
+                            } else {
+                                (-1.0, 1.0)
+                            }
+                        }
+                        None => (1.0, 1.0),
+                    };
+                    active.push(Active {
+                        kind: Kind::Ineq,
+                        index: j,
+                        sign,
+                        scale,
+                    });
+                    y.push(sign * solution.multipliers.ineq[j]);
+                    columns.push(&a * sign);
+                }
+            }
+            None => assert_size!(ci.dim(), m, 0),
+        }
+        // K = [[G, -C], [-C^T, 0]]
+        let k = columns.len();
+        let mut kkt = Array2::zeros((n + k, n + k));
+        kkt.slice_mut(s![..n, ..n]).assign(&g);
+        for (l, c) in columns.iter().enumerate() {
+            kkt.slice_mut(s![..n, n + l]).assign(&-c);
+            kkt.slice_mut(s![n + l, ..n]).assign(&-c);
+        }
+        let lu = Lu::new(kkt).ok_or(Error::Singular)?;
+        Ok(Self {
+            n,
+            x: solution.x.clone(),
+            active,
+            y: Array1::from(y),
+            lu,
+            p,
+            m,
+        })
+    }
+
+    /// Returns the \\(N \times N\\) Jacobian \\(\partial x / \partial g_0\\).
+    pub fn dx_dg0(&self) -> Array2<f64> {
+        let mut jacobian = Array2::zeros((self.n, self.n));
+        let mut rhs = Array1::zeros(self.lu.len());
+        for i in 0..self.n {
+            rhs.fill(0.0);
+            rhs[i] = -1.0;
+            let sol = self.lu.solve(rhs.view());
+            jacobian.column_mut(i).assign(&sol.slice(s![..self.n]));
+        }
+        jacobian
+    }
+
+    /// Returns the \\(N \times P\\) Jacobian \\(\partial x / \partial ce_0\\).
+    pub fn dx_dce0(&self) -> Array2<f64> {
+        self.dx_dconsts(Kind::Eq, self.p)
+    }
+
+    /// Returns the \\(N \times M\\) Jacobian \\(\partial x / \partial ci_0\\). The columns of the
+    /// inactive inequality constraints are zero.
+    pub fn dx_dci0(&self) -> Array2<f64> {
+        self.dx_dconsts(Kind::Ineq, self.m)
+    }
+
+    fn dx_dconsts(&self, kind: Kind, len: usize) -> Array2<f64> {
+        let mut jacobian = Array2::zeros((self.n, len));
+        let mut rhs = Array1::zeros(self.lu.len());
+        for (l, active) in self.active.iter().enumerate() {
+            if active.kind != kind {
+                continue;
+            }
+            rhs.fill(0.0);
+            rhs[self.n + l] = active.scale;
+            let sol = self.lu.solve(rhs.view());
+            jacobian
+                .column_mut(active.index)
+                .assign(&sol.slice(s![..self.n]));
+        }
+        jacobian
+    }
+
+    /// Backpropagates the gradient of a scalar loss with respect to \\(x\\) to the problem data,
+    /// as in a differentiable optimization layer.
+    ///
+    /// # Errors
+    ///
+    /// If `grad_x` isn't an \\(N\\)-vector, this method returns [`Error::SizeMismatch`].
+    pub fn backward<S: Data<Elem = f64>>(&self, grad_x: &ArrayBase<S, Ix1>) -> Result<Gradients> {
+        let n = self.n;
+        assert_size!(grad_x, n, grad_x.len());
+        let mut rhs = Array1::zeros(self.lu.len());
+        rhs.slice_mut(s![..n]).assign(grad_x);
+        // K is symmetric, so K^-T [grad_x; 0] = K^-1 [grad_x; 0].
+        let sol = self.lu.solve(rhs.view());
+        let (u, v) = (sol.slice(s![..n]), sol.slice(s![n..]));
+        let x = self.x.view();
+        let outer = |a: ArrayView1<'_, f64>, b: ArrayView1<'_, f64>| {
+            let a = a.insert_axis(ndarray::Axis(1));
+            let b = b.insert_axis(ndarray::Axis(0));
+            a.dot(&b)
+        };
+        let ux = outer(u, x);
+        let mut gradients = Gradients {
+            g: -(&ux + &ux.t()) / 2.0,
+            g0: -&u,
+            ce: Array2::zeros((n, self.p)),
+            ce0: Array1::zeros(self.p),
+            ci: Array2::zeros((n, self.m)),
+            ci0: Array1::zeros(self.m),
+        };
+        for (l, active) in self.active.iter().enumerate() {
+            // d/dC = u y^T + x v^T for the signed column
+            let grad_c = (&u * self.y[l] + &x * v[l]) * active.sign;
+            let (coeffs, consts) = match active.kind {
+                Kind::Eq => (&mut gradients.ce, &mut gradients.ce0),
+                Kind::Ineq => (&mut gradients.ci, &mut gradients.ci0),
+            };
+            coeffs.column_mut(active.index).assign(&grad_c);
+            consts[active.index] = v[l] * active.scale;
+        }
+        Ok(gradients)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Array};
+
+    use super::*;
+    use crate::solve;
+
+    const H: f64 = 1e-6;
+
+    fn assert_close<D: ndarray::Dimension>(actual: &Array<f64, D>, expected: &Array<f64, D>) {
+        assert_eq!(actual.shape(), expected.shape());
+        for (&a, &e) in actual.iter().zip(expected) {
+            assert_abs_diff_eq!(a, e, epsilon = 1e-12);
+        }
+    }
+
+    fn solve_demo(
+        g0: &Array1<f64>,
+        ce0: &Array1<f64>,
+        ci: &Array2<f64>,
+        ci0: &Array1<f64>,
+    ) -> Result<Solution> {
+        #[rustfmt::skip]
+        let g = array![
+            [4.0, -2.0],
+            [-2.0, 4.0],
+        ];
+        solve(
+            g,
+            g0.view(),
+            Constraints::some(array![[1.0], [1.0]], ce0.view()),
+            Constraints::some(ci.view(), ci0.view()),
+        )
+    }
+
+    #[test]
+    fn finite_differences() -> Result<()> {
+        #[rustfmt::skip]
+        let g = array![
+            [4.0, -2.0],
+            [-2.0, 4.0],
+        ];
+        let g0 = array![6.0, 0.0];
+        let ce = array![[1.0], [1.0]];
+        let ce0 = array![-3.0];
+        // x >= 1.5, x + y >= 2, y >= 0.5
+        let ci = array![[1.0, 1.0, 0.0], [0.0, 1.0, 1.0]];
+        let ci0 = array![-1.5, -2.0, -0.5];
+        let solution = solve_demo(&g0, &ce0, &ci, &ci0)?;
+        assert_eq!(solution.active_set, [0]);
+        let sensitivity = Sensitivity::new(
+            g.view(),
+            Constraints::some(ce.view(), ce0.view()),
+            Constraints::some(ci.view(), ci0.view()),
+            &solution,
+        )?;
+
+        let dx_dg0 = sensitivity.dx_dg0();
+        for i in 0..2 {
+            let mut g0 = g0.clone();
+            g0[i] += H;
+            let x = solve_demo(&g0, &ce0, &ci, &ci0)?.x;
+            for k in 0..2 {
+                let fd = (x[k] - solution.x[k]) / H;
+                assert_abs_diff_eq!(dx_dg0[[k, i]], fd, epsilon = 1e-6);
+            }
+        }
+        let x = solve_demo(&g0, &(&ce0 + H), &ci, &ci0)?.x;
+        let dx_dce0 = sensitivity.dx_dce0();
+        for k in 0..2 {
+            assert_abs_diff_eq!(dx_dce0[[k, 0]], (x[k] - solution.x[k]) / H, epsilon = 1e-6);
+        }
+        let dx_dci0 = sensitivity.dx_dci0();
+        for j in 0..3 {
+            let mut ci0 = ci0.clone();
+            ci0[j] += H;
+            let x = solve_demo(&g0, &ce0, &ci, &ci0)?.x;
+            for k in 0..2 {
+                let fd = (x[k] - solution.x[k]) / H;
+                assert_abs_diff_eq!(dx_dci0[[k, j]], fd, epsilon = 1e-6);
+            }
+        }
+
+        // loss = w^T x
+        let w = array![1.0, -3.0];
+        let gradients = sensitivity.backward(&w)?;
+        assert_close(&gradients.g0, &dx_dg0.t().dot(&w));
+        assert_close(&gradients.ce0, &dx_dce0.t().dot(&w));
+        assert_close(&gradients.ci0, &dx_dci0.t().dot(&w));
+        for i in 0..2 {
+            for j in 0..3 {
+                let mut perturbed = ci.clone();
+                perturbed[[i, j]] += H;
+                let x = solve_demo(&g0, &ce0, &perturbed, &ci0)?.x;
+                let fd = w.dot(&(&x - &solution.x)) / H;
+                assert_abs_diff_eq!(gradients.ci[[i, j]], fd, epsilon = 1e-5);
+            }
+        }
+
+        let err = sensitivity.backward(&array![1.0]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    expected: 2,
+                    actual: 1,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ranged() -> Result<()> {
+        // min (x - 2)^2 + y^2 s.t. 0 <= x + y <= 1
+        let g = array![[2.0, 0.0], [0.0, 2.0]];
+        let g0 = array![-4.0, 0.0];
+        let ci = Constraints::ranged(array![[1.0], [1.0]], array![0.0], array![1.0]);
+        let solution = solve(g.view(), g0.view(), Constraints::NONE, Some(ci.clone()))?;
+        let sensitivity = Sensitivity::new(g.view(), Constraints::NONE, Some(ci), &solution)?;
+        // x + y = u, so raising the upper limit moves both variables by half
+        assert_close(&sensitivity.dx_dci0(), &array![[0.5], [0.5]]);
+
+        let ci = Constraints::ranged(array![[1.0], [1.0]], array![1.0], array![2.0]);
+        let solution = solve(g.view(), -&g0, Constraints::NONE, Some(ci.clone()))?;
+        let sensitivity = Sensitivity::new(g.view(), Constraints::NONE, Some(ci), &solution)?;
+        assert_close(&sensitivity.dx_dci0(), &array![[0.5], [0.5]]);
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::batch::Solvers;
+use crate::{QpProblem, Result, Solution, SolveOptions};
+
+/// A pool of long-running solver threads which take problems from a channel.
+///
+/// Each thread keeps a [`Solver`](crate::Solver) for each size of the problems it has solved, and
+/// can be given the sizes to expect in [`new`](Self::new) so that the first problems don't pay
+/// for the allocations either. [`submit`](Self::submit) queues a problem for the next idle thread
+/// and returns a receiver of its result, so a stream of problems can be fed from any thread
+/// without waiting for the solutions.
+///
+/// Dropping the service lets the threads finish the queued problems, and waits for them.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let service = SolverService::new(2, &[(1, 0, 0)]);
+/// let receivers: Vec<_> = (0..10)
+///     .map(|i| {
+///         let problem = QpProblem::new(1).hessian(array![[1.0]]).linear(array![-(i as f64)]);
+///         service.submit(problem.build().unwrap(), SolveOptions::default())
+///     })
+///     .collect();
+/// for (i, receiver) in receivers.into_iter().enumerate() {
+///     let solution = receiver.recv().unwrap()?;
+///     assert_eq!(solution.x, array![i as f64]);
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SolverService {
+    sender: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+/// A problem submitted to a [`SolverService`], with the sender of its result
+#[derive(Debug)]
+struct Job {
+    problem: QpProblem,
+    options: SolveOptions,
+    reply: Sender<Result<Solution>>,
+}
+
+impl SolverService {
+    /// Starts `threads` solver threads, at least one, each with solvers for problems of the given
+    /// sizes, i.e. numbers of variables, equality constraints and inequality constraints.
+    pub fn new(threads: usize, sizes: &[(usize, usize, usize)]) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..threads.max(1))
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                let mut solvers = Solvers::with_sizes(sizes);
+                thread::spawn(move || loop {
+                    let job = receiver.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => {
+                            let result = solvers.solve(&job.problem, &job.options);
+                            // The caller may have dropped the receiver.
+                            let _ = job.reply.send(result);
+                        }
+                        Err(_) => return,
+                    }
+                })
+            })
+            .collect();
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// Queues `problem` to be solved like [`QpProblem::solve_with_options`], and returns the
+    /// receiver of the result.
+    pub fn submit(&self, problem: QpProblem, options: SolveOptions) -> Receiver<Result<Solution>> {
+        let (reply, receiver) = mpsc::channel();
+        let job = Job {
+            problem,
+            options,
+            reply,
+        };
+        self.sender
+            .as_ref()
+            .expect("the sender is only taken on drop")
+            .send(job)
+            .expect("the solver threads don't stop before the service is dropped");
+        receiver
+    }
+}
+
+impl Drop for SolverService {
+    fn drop(&mut self) {
+        // Closing the channel stops the threads once it's empty.
+        self.sender.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+    use crate::{Bounds, Constraints};
+
+    #[test]
+    fn solves_submitted_problems() -> Result<()> {
+        let problem = |c: f64| {
+            QpProblem::new(2)
+                .hessian(array![[2.0, 1.0], [1.0, 2.0]])
+                .linear(array![-c, c])
+                .ineq(Constraints::new(array![[-1.0], [-1.0]], array![1.0]))
+                .bounds(Bounds::lower(array![0.0, -1.0]))
+                .build()
+        };
+        let problems = (0..50)
+            .map(|k| problem(k as f64 / 5.0 - 5.0))
+            .collect::<Result<Vec<_>>>()?;
+        let service = SolverService::new(3, &[(2, 0, 1)]);
+        thread::scope(|scope| {
+            // Submit from several threads.
+            for chunk in problems.chunks(10) {
+                let service = &service;
+                scope.spawn(move || {
+                    let receivers: Vec<_> = chunk
+                        .iter()
+                        .map(|problem| service.submit(problem.clone(), SolveOptions::default()))
+                        .collect();
+                    for (problem, receiver) in chunk.iter().zip(receivers) {
+                        let solution = receiver.recv().unwrap();
+                        assert_eq!(solution.ok(), problem.solve().ok());
+                    }
+                });
+            }
+        });
+        // Queued problems are solved before the threads stop.
+        let receiver = service.submit(problems[0].clone(), SolveOptions::default());
+        drop(service);
+        assert_eq!(receiver.recv().unwrap()?, problems[0].solve()?);
+        Ok(())
+    }
+}
@@ -0,0 +1,376 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+#[cfg(any(not(feature = "cpp"), feature = "pure-rust"))]
+use crate::goldfarb_idnani;
+use crate::{Error, Result};
+#[cfg(feature = "cpp")]
+use quadprogpp_sys as sys;
+
+/// Solves a quadratic programming problem given as row-major slices, and writes the minimizer
+/// into `x`. Returns the value of the objective function.
+///
+/// The problem is the same as that of [`solve`](crate::solve), with `n` variables:
+///
+/// * `g` is the \\(N \times N\\) matrix \\(G\\)
+/// * `g0` is the \\(N\\)-vector \\(g_0\\)
+/// * `ce` is the \\(N \times P\\) matrix \\(CE\\), where \\(P\\) is the length of `ce0`
+/// * `ci` is the \\(N \times M\\) matrix \\(CI\\), where \\(M\\) is the length of `ci0`
+/// * `x` is the \\(N\\)-vector \\(x\\)
+///
+/// The matrices are in row-major order, i.e. element \\((i, j)\\) of \\(CE\\) is
+/// `ce[i * p + j]`. Empty slices stand for no constraints.
+///
+/// This function doesn't depend on `ndarray`, so it's available without the default `ndarray`
+/// feature, which the rest of the API requires. Without the default `cpp` feature either, it's
+/// solved by a pure-Rust port of QuadProg++ instead of the C++ library, which allows building for
+/// targets without a C++ toolchain such as `wasm32-unknown-unknown`. The `pure-rust` feature
+/// selects the port even with the `cpp` feature, which still serves the rest of the API.
+///
+/// # Errors
+///
+/// * If the lengths of the slices don't match `n` and the numbers of constraints, this function
+///   returns [`Error::SizeMismatch`].
+/// * If the problem doesn't have a feasible solution, it returns [`Error::Infeasible`].
+/// * If \\(G\\) isn't positive definite, it returns [`Error::SingularHessian`].
+/// * If the constraints are linearly dependent, it returns [`Error::LinearlyDependent`].
+/// * If the matrices are too large for QuadProg++, it returns [`Error::TooLarge`].
+///
+/// # Examples
+///
+/// ```
+/// # use quadprogpp::*;
+/// // The demo of QuadProg++
+/// let mut x = [0.0; 2];
+/// let objective = solve_slices(
+///     2,
+///     &[4.0, -2.0, -2.0, 4.0],
+///     &[6.0, 0.0],
+///     &[1.0, 1.0],
+///     &[-3.0],
+///     &[1.0, 1.0, 0.0, 0.0, 1.0, 1.0],
+///     &[0.0, -2.0, 0.0],
+///     &mut x,
+/// )?;
+/// assert!((objective - 12.0).abs() < 1e-12);
+/// assert!((x[0] - 1.0).abs() < 1e-12 && (x[1] - 2.0).abs() < 1e-12);
+/// # Ok::<(), Error>(())
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn solve_slices(
+    n: usize,
+    g: &[f64],
+    g0: &[f64],
+    ce: &[f64],
+    ce0: &[f64],
+    ci: &[f64],
+    ci0: &[f64],
+    x: &mut [f64],
+) -> Result<f64> {
+    let (p, m) = (ce0.len(), ci0.len());
+    assert_size!(g, n * n, g.len());
+    assert_size!(g0, n, g0.len());
+    assert_size!(ce, n * p, ce.len());
+    assert_size!(ci, n * m, ci.len());
+    assert_size!(x, n, x.len());
+    solve_f64(n, g, g0, ce, ce0, ci, ci0, x)
+}
+
+#[cfg(any(not(feature = "cpp"), feature = "pure-rust"))]
+use goldfarb_idnani::solve as solve_f64;
+#[cfg(all(feature = "cpp", not(feature = "pure-rust")))]
+use solve_cpp as solve_f64;
+
+/// Solves the problem of [`solve_slices`] with QuadProg++, also used by the parity tests of the
+/// port.
+#[cfg(feature = "cpp")]
+#[cfg_attr(feature = "pure-rust", allow(dead_code))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn solve_cpp(
+    n: usize,
+    g: &[f64],
+    g0: &[f64],
+    ce: &[f64],
+    ce0: &[f64],
+    ci: &[f64],
+    ci0: &[f64],
+    x: &mut [f64],
+) -> Result<f64> {
+    let (n32, p32, m32) = ffi_dims(n, ce0.len(), ci0.len())?;
+    if n == 0 {
+        return solve_empty(ce0.len(), ci0, 0.0, inequality);
+    }
+    let mut info = sys::SolveInfo::default();
+    // The lengths of the slices have been checked by the caller.
+    let objective = unsafe {
+        sys::solve_quadprog_raw(
+            n32,
+            p32,
+            m32,
+            g.as_ptr(),
+            g0.as_ptr(),
+            ce.as_ptr(),
+            ce0.as_ptr(),
+            ci.as_ptr(),
+            ci0.as_ptr(),
+            x.as_mut_ptr(),
+            &sys::SolveParams::default(),
+            &mut info,
+        )?
+    };
+    check(&info)?;
+    Ok(objective)
+}
+
+/// Solves a quadratic programming problem given as row-major slices of `f32`, and writes the
+/// minimizer into `x`. Returns the value of the objective function.
+///
+/// The arguments are the same as those of [`solve_slices`]. The elements are widened to `f64` as
+/// they're copied into the solver, which always copies its input, so this costs no more than
+/// [`solve_slices`]. The solver itself works in `f64`: its tolerances are relative to the machine
+/// epsilon, which is too coarse in `f32` for the updates of the active set to stay accurate.
+///
+/// # Errors
+///
+/// The errors are the same as those of [`solve_slices`].
+///
+/// # Examples
+///
+/// ```
+/// # use quadprogpp::*;
+/// let mut x = [0.0f32; 2];
+/// let objective = solve_slices_f32(
+///     2,
+///     &[4.0, -2.0, -2.0, 4.0],
+///     &[6.0, 0.0],
+///     &[1.0, 1.0],
+///     &[-3.0],
+///     &[1.0, 1.0, 0.0, 0.0, 1.0, 1.0],
+///     &[0.0, -2.0, 0.0],
+///     &mut x,
+/// )?;
+/// assert_eq!((objective, x), (12.0, [1.0, 2.0]));
+/// # Ok::<(), Error>(())
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn solve_slices_f32(
+    n: usize,
+    g: &[f32],
+    g0: &[f32],
+    ce: &[f32],
+    ce0: &[f32],
+    ci: &[f32],
+    ci0: &[f32],
+    x: &mut [f32],
+) -> Result<f32> {
+    let (p, m) = (ce0.len(), ci0.len());
+    assert_size!(g, n * n, g.len());
+    assert_size!(g0, n, g0.len());
+    assert_size!(ce, n * p, ce.len());
+    assert_size!(ci, n * m, ci.len());
+    assert_size!(x, n, x.len());
+    solve_f32(n, g, g0, ce, ce0, ci, ci0, x)
+}
+
+#[cfg(any(not(feature = "cpp"), feature = "pure-rust"))]
+#[allow(clippy::too_many_arguments)]
+fn solve_f32(
+    n: usize,
+    g: &[f32],
+    g0: &[f32],
+    ce: &[f32],
+    ce0: &[f32],
+    ci: &[f32],
+    ci0: &[f32],
+    x: &mut [f32],
+) -> Result<f32> {
+    let widen = |a: &[f32]| a.iter().copied().map(f64::from).collect::<Vec<_>>();
+    let mut solution = vec![0.0; n];
+    let objective = goldfarb_idnani::solve(
+        n,
+        &widen(g),
+        &widen(g0),
+        &widen(ce),
+        &widen(ce0),
+        &widen(ci),
+        &widen(ci0),
+        &mut solution,
+    )?;
+    for (x, &solution) in x.iter_mut().zip(&solution) {
+        *x = solution as f32;
+    }
+    Ok(objective as f32)
+}
+
+#[cfg(all(feature = "cpp", not(feature = "pure-rust")))]
+#[allow(clippy::too_many_arguments)]
+fn solve_f32(
+    n: usize,
+    g: &[f32],
+    g0: &[f32],
+    ce: &[f32],
+    ce0: &[f32],
+    ci: &[f32],
+    ci0: &[f32],
+    x: &mut [f32],
+) -> Result<f32> {
+    let (n32, p32, m32) = ffi_dims(n, ce0.len(), ci0.len())?;
+    if n == 0 {
+        let ci0 = ci0.iter().copied().map(f64::from).collect::<Vec<_>>();
+        return Ok(solve_empty(ce0.len(), &ci0, 0.0, inequality)? as f32);
+    }
+    let (g, ce, ci) = unsafe {
+        (
+            sys::new_matrix_f32_from_ptr(g.as_ptr(), n32, n32),
+            sys::new_matrix_f32_from_ptr(ce.as_ptr(), n32, p32),
+            sys::new_matrix_f32_from_ptr(ci.as_ptr(), n32, m32),
+        )
+    };
+    let (g0, ce0, ci0) = unsafe {
+        (
+            sys::new_vector_f32_from_ptr(g0.as_ptr(), n32),
+            sys::new_vector_f32_from_ptr(ce0.as_ptr(), p32),
+            sys::new_vector_f32_from_ptr(ci0.as_ptr(), m32),
+        )
+    };
+    let mut solution = sys::new_vector_f32(n32);
+    let mut info = sys::SolveInfo::default();
+    let objective = sys::solve_quadprog_f32(
+        &g,
+        &g0,
+        &ce,
+        &ce0,
+        &ci,
+        &ci0,
+        solution.pin_mut(),
+        &sys::SolveParams::default(),
+        &mut info,
+    )?;
+    check(&info)?;
+    sys::vector_f32_copy_to(&solution, x);
+    Ok(objective as f32)
+}
+
+/// Solves a problem without variables, whose constraints are constants, which QuadProg++ can't
+/// be given. Like the dual method, which adds the most violated inequality constraint first, the
+/// problem is infeasible at that constraint, described by `describe`, if any is violated beyond
+/// `tolerance`.
+#[cfg(feature = "cpp")]
+pub(crate) fn solve_empty(
+    p: usize,
+    ci0: &[f64],
+    tolerance: f64,
+    describe: impl Fn(usize) -> String,
+) -> Result<f64> {
+    if p > 0 {
+        return Err(Error::LinearlyDependent);
+    }
+    let mut violated = None;
+    let mut ss = -tolerance;
+    for (i, &s) in ci0.iter().enumerate() {
+        if s < ss {
+            ss = s;
+            violated = Some(i);
+        }
+    }
+    match violated {
+        Some(i) => Err(Error::Infeasible {
+            constraint: describe(i),
+        }),
+        None => Ok(0.0),
+    }
+}
+
+/// Converts the numbers of variables and constraints to `u32`, checking that the matrices fit in
+/// QuadProg++.
+#[cfg(feature = "cpp")]
+fn ffi_dims(n: usize, p: usize, m: usize) -> Result<(u32, u32, u32)> {
+    let (n32, _) = crate::ffi_dim("g", n, n)?;
+    let (_, p32) = crate::ffi_dim("ce", n, p)?;
+    let (_, m32) = crate::ffi_dim("ci", n, m)?;
+    Ok((n32, p32, m32))
+}
+
+/// Describes inequality constraint `i` in [`Error::Infeasible`].
+#[cfg(feature = "cpp")]
+fn inequality(i: usize) -> String {
+    format!("inequality constraint {}", i)
+}
+
+/// Returns the error corresponding to the status of a solve with the default parameters, if any.
+#[cfg(feature = "cpp")]
+fn check(info: &sys::SolveInfo) -> Result<()> {
+    match info.status {
+        sys::SolveStatus::Infeasible => Err(Error::Infeasible {
+            constraint: inequality(info.infeasible_constraint as usize),
+        }),
+        sys::SolveStatus::NotPositiveDefinite => Err(Error::SingularHessian {
+            pivot: info.singular_pivot as usize,
+        }),
+        // The default parameters neither limit the iterations nor interrupt the solver.
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors() {
+        let mut x = [0.0; 2];
+        let identity = [1.0, 0.0, 0.0, 1.0];
+        let err = solve_slices(2, &identity, &[0.0], &[], &[], &[], &[], &mut x).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "g0",
+                    expected: 2,
+                    actual: 1,
+                    shapes: None
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+
+        // x0 <= 0 and x0 >= 1
+        let ci = [-1.0, 1.0, 0.0, 0.0];
+        let err =
+            solve_slices(2, &identity, &[0.0; 2], &[], &[], &ci, &[0.0, -1.0], &mut x).unwrap_err();
+        assert!(
+            matches!(&err, Error::Infeasible { constraint } if constraint == "inequality constraint 0"),
+            "unexpected error: {:?}",
+            err
+        );
+
+        let singular = [1.0, 1.0, 1.0, 1.0];
+        let err = solve_slices(2, &singular, &[0.0; 2], &[], &[], &[], &[], &mut x).unwrap_err();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn no_variables() {
+        // 0 x + 1 >= 0
+        let objective = solve_slices(0, &[], &[], &[], &[], &[], &[1.0], &mut []).unwrap();
+        assert_eq!(objective, 0.0);
+        let objective = solve_slices_f32(0, &[], &[], &[], &[], &[], &[1.0], &mut []).unwrap();
+        assert_eq!(objective, 0.0);
+        // 0 x + 1 >= 0 and 0 x - 1 >= 0
+        for err in [
+            solve_slices(0, &[], &[], &[], &[], &[], &[1.0, -1.0], &mut []).unwrap_err(),
+            solve_slices_f32(0, &[], &[], &[], &[], &[], &[1.0, -1.0], &mut []).unwrap_err(),
+        ] {
+            assert!(
+                matches!(&err, Error::Infeasible { constraint } if constraint == "inequality constraint 1"),
+                "unexpected error: {:?}",
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use ndarray::Array1;
+
+/// Lagrange multipliers of the constraints at the solution.
+///
+/// The multipliers satisfy the stationarity condition
+/// \\(G x + g_0 = CE \, \lambda_{eq} + CI \, \lambda_{ineq} + \lambda_{lower} - \lambda_{upper}\\)
+/// with \\(\lambda_{ineq}, \lambda_{lower}, \lambda_{upper} \ge 0\\).
+/// The multipliers of inequality constraints and bounds which aren't active at the solution are
+/// zero.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub struct Multipliers {
+    /// Multipliers of the equality constraints, one for each column of \\(CE\\)
+    pub eq: Vec<f64>,
+    /// Multipliers of the inequality constraints, one for each column of \\(CI\\)
+    pub ineq: Vec<f64>,
+    /// Multipliers of the lower bounds, one for each variable
+    pub lower: Vec<f64>,
+    /// Multipliers of the upper bounds, one for each variable
+    pub upper: Vec<f64>,
+}
+
+/// How the solver terminated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+#[non_exhaustive]
+pub enum Status {
+    /// The solution is optimal.
+    Optimal,
+    /// The iteration limit was reached, and the solution is the last iterate, returned because of
+    /// [`SolveOptions::best_effort`](crate::SolveOptions::best_effort).
+    ///
+    /// The dual method only reaches a feasible point at the optimum, so the iterate satisfies the
+    /// equality constraints and the inequality constraints in [`Solution::active_set`], but may
+    /// violate the others. Its objective is a lower bound of the optimal one.
+    MaxIterations,
+    /// The solver was stopped by [`SolveOptions::deadline`](crate::SolveOptions::deadline) or
+    /// [`SolveOptions::cancellation`](crate::SolveOptions::cancellation), and the solution is the
+    /// last iterate like with [`Status::MaxIterations`].
+    Interrupted,
+    /// The solver exceeded
+    /// [`SolveOptions::max_degenerate_steps`](crate::SolveOptions::max_degenerate_steps), and the
+    /// solution is the last iterate like with [`Status::MaxIterations`].
+    Degenerate,
+}
+
+/// The solution of a quadratic programming problem returned by [`solve`](crate::solve).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+#[non_exhaustive]
+pub struct Solution {
+    /// The minimizer \\(x\\)
+    pub x: Array1<f64>,
+    /// How the solver terminated
+    pub status: Status,
+    /// The value of the objective function \\(q(x)\\)
+    pub objective: f64,
+    /// Lagrange multipliers of the constraints
+    pub multipliers: Multipliers,
+    /// Indices of the inequality constraints in the active set at termination. The equality
+    /// constraints are always active, so they are omitted.
+    pub active_set: Vec<usize>,
+    /// Indices of the variables whose lower bounds are in the active set at termination
+    pub active_lower: Vec<usize>,
+    /// Indices of the variables whose upper bounds are in the active set at termination
+    pub active_upper: Vec<usize>,
+    /// Number of iterations of the active-set loop
+    pub iterations: usize,
+    /// Number of Cholesky factorizations of \\(G\\)
+    pub factorizations: usize,
+    /// Number of constraints added to the active set, including the equality constraints and
+    /// those of a warm start
+    pub additions: usize,
+    /// Number of inequality constraints and bounds dropped from the active set
+    pub deletions: usize,
+    /// Number of Givens rotations updating the factorization of the active constraints when
+    /// adding and dropping them, which dominate the cost of an iteration
+    pub rotations: usize,
+    /// Whether the solver started from the active set given by
+    /// [`SolveOptions::active_set`](crate::SolveOptions::active_set) or
+    /// [`SolveOptions::initial_point`](crate::SolveOptions::initial_point)
+    pub warm_started: bool,
+    /// Whether the solution was refined by [`SolveOptions::polish`](crate::SolveOptions::polish)
+    pub polished: bool,
+    /// The [name](crate::BackendInfo::name) of the backend which solved the problem, e.g. the
+    /// one chosen by a [`BackendChain`](crate::BackendChain), or `"direct"` if
+    /// [`solve`](crate::solve) bypassed QuadProg++ for a problem without inequality constraints.
+    /// It's empty in deserialized solutions.
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    pub backend: &'static str,
+}
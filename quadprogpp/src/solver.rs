@@ -0,0 +1,1164 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::ptr;
+
+use ndarray::{Array1, ArrayBase, ArrayView2, Data, Ix1, Ix2, RawData};
+use quadprogpp_sys::{self as sys, UniquePtr};
+
+use crate::backend::CPP_NAME;
+use crate::polish;
+use crate::scaling::Scaling;
+use crate::slices;
+use crate::{
+    utils, Bounds, Constraints, Error, Multipliers, Progress, QpProblem, Result, Shapes, Solution,
+    SolveOptions, Status,
+};
+
+/// A reusable solver for problems of a fixed size.
+///
+/// [`solve`](crate::solve) allocates the matrices and vectors passed to QuadProg++ as well as the
+/// internal buffers of the solver on every call. A `Solver` allocates them once in [`new`] and
+/// overwrites them on each [`solve`](Self::solve), which avoids heap traffic when solving many
+/// problems of the same size.
+///
+/// [`new`]: Self::new
+///
+/// A `Solver` is [`Send`] and [`Sync`], so it can be moved to another thread, e.g. to keep one
+/// per worker thread. Solvers don't share any state, so they can solve problems in parallel.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let mut solver = Solver::new(1, 0, 1);
+/// for b in 1..10 {
+///     let ci = Constraints::some(array![[1.0]], array![-(b as f64)]);
+///     let solution = solver.solve(array![[1.0]], array![0.0], Constraints::NONE, ci)?;
+///     assert_eq!(solution.x, array![b as f64]);
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub struct Solver {
+    n: usize,
+    p: usize,
+    m: usize,
+    g: UniquePtr<sys::MatrixF64>,
+    g0: UniquePtr<sys::VectorF64>,
+    x: UniquePtr<sys::VectorF64>,
+    workspace: UniquePtr<sys::Workspace>,
+    columns: Vec<Column>,
+    ci_buf: Vec<f64>,
+    ci0_buf: Vec<f64>,
+    /// A copy of the Hessian, which QuadProg++ overwrites, for polishing
+    g_buf: Vec<f64>,
+    /// Whether the last solution was polished
+    polished: bool,
+    scaling: Scaling,
+    /// How the last solve terminated
+    status: Status,
+    params: sys::SolveParams,
+    info: sys::SolveInfo,
+}
+
+/// The origin of a column of the inequality constraint matrix passed to QuadProg++.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Column {
+    /// The lower (or only) limit of an inequality constraint
+    Lower(usize),
+    /// The upper limit of a ranged inequality constraint
+    Upper(usize),
+    /// A lower bound on a variable
+    LowerBound(usize),
+    /// An upper bound on a variable
+    UpperBound(usize),
+}
+
+impl Column {
+    /// Describes the constraint for error messages.
+    pub(crate) fn describe(self, ci_names: &[String], variable_names: &[String]) -> String {
+        let constraint = |j| describe_constraint("inequality", ci_names, j);
+        let variable = |i| describe_variable(variable_names, i);
+        match self {
+            Column::Lower(j) => constraint(j),
+            Column::Upper(j) => format!("upper limit of {}", constraint(j)),
+            Column::LowerBound(i) => format!("lower bound of {}", variable(i)),
+            Column::UpperBound(i) => format!("upper bound of {}", variable(i)),
+        }
+    }
+}
+
+/// Appends the columns of the inequality constraints and the finite bounds to `columns`, and
+/// their constant terms to `ci0_buf`. `upper` holds the upper limits of ranged constraints.
+pub(crate) fn push_columns(
+    columns: &mut Vec<Column>,
+    ci0_buf: &mut Vec<f64>,
+    ci0: &[f64],
+    upper: Option<&[f64]>,
+    bounds: &Bounds,
+) {
+    if let Some(upper) = upper {
+        // A ranged constraint l <= a^T x <= u is split into a^T x - l >= 0 and
+        // -a^T x + u >= 0, omitting the infinite sides.
+        for (j, (&l, &u)) in ci0.iter().zip(upper.iter()).enumerate() {
+            if l.is_finite() {
+                columns.push(Column::Lower(j));
+                ci0_buf.push(-l);
+            }
+            if u.is_finite() {
+                columns.push(Column::Upper(j));
+                ci0_buf.push(u);
+            }
+        }
+    } else {
+        columns.extend((0..ci0.len()).map(Column::Lower));
+        ci0_buf.extend_from_slice(ci0);
+    }
+    for (i, is_upper, b) in bounds.finite() {
+        if is_upper {
+            columns.push(Column::UpperBound(i));
+            ci0_buf.push(b);
+        } else {
+            columns.push(Column::LowerBound(i));
+            ci0_buf.push(-b);
+        }
+    }
+}
+
+/// Appends the row-major \\(N \times K\\) matrix of `columns` to `ci_buf`, where `ci` is the
+/// row-major \\(N \times M\\) matrix of the inequality constraints.
+pub(crate) fn push_ci(ci_buf: &mut Vec<f64>, columns: &[Column], ci: &[f64], n: usize, m: usize) {
+    for i in 0..n {
+        ci_buf.extend(columns.iter().map(|&c| match c {
+            Column::Lower(j) => ci[i * m + j],
+            Column::Upper(j) => -ci[i * m + j],
+            Column::LowerBound(k) if k == i => 1.0,
+            Column::UpperBound(k) if k == i => -1.0,
+            Column::LowerBound(_) | Column::UpperBound(_) => 0.0,
+        }));
+    }
+}
+
+/// Maps the multipliers of the equality constraints and of `columns`, and the indices of the
+/// active columns, back to the constraints and bounds of a problem with `n` variables and `m`
+/// inequality constraints. Returns the multipliers, and the active inequality constraints,
+/// lower bounds and upper bounds.
+pub(crate) fn unmap_columns(
+    columns: &[Column],
+    n: usize,
+    m: usize,
+    multipliers: &[f64],
+    active: impl Iterator<Item = usize>,
+) -> (Multipliers, Vec<usize>, Vec<usize>, Vec<usize>) {
+    let p = multipliers.len() - columns.len();
+    let mut ineq = vec![0.0; m];
+    let mut lower = vec![0.0; n];
+    let mut upper = vec![0.0; n];
+    for (&c, &u) in columns.iter().zip(&multipliers[p..]) {
+        match c {
+            Column::Lower(j) => ineq[j] += u,
+            Column::Upper(j) => ineq[j] -= u,
+            Column::LowerBound(i) => lower[i] = u,
+            Column::UpperBound(i) => upper[i] = u,
+        }
+    }
+    let mut active_set = Vec::new();
+    let mut active_lower = Vec::new();
+    let mut active_upper = Vec::new();
+    for k in active {
+        match columns[k] {
+            Column::Lower(j) | Column::Upper(j) => {
+                if !active_set.contains(&j) {
+                    active_set.push(j);
+                }
+            }
+            Column::LowerBound(i) => active_lower.push(i),
+            Column::UpperBound(i) => active_upper.push(i),
+        }
+    }
+    let multipliers = Multipliers {
+        eq: multipliers[..p].to_vec(),
+        ineq,
+        lower,
+        upper,
+    };
+    (multipliers, active_set, active_lower, active_upper)
+}
+
+/// Describes the equality or inequality constraint `j` for error messages, by its name if it has
+/// one.
+pub(crate) fn describe_constraint(kind: &str, names: &[String], j: usize) -> String {
+    match names.get(j) {
+        Some(name) if !name.is_empty() => format!("constraint '{}'", name),
+        _ => format!("{} constraint {}", kind, j),
+    }
+}
+
+/// Describes the variable `i` for error messages, by its name if it has one.
+pub(crate) fn describe_variable(names: &[String], i: usize) -> String {
+    match names.get(i) {
+        Some(name) if !name.is_empty() => format!("'{}'", name),
+        _ => format!("variable {}", i),
+    }
+}
+
+impl Solver {
+    /// Creates a solver for problems with `n` variables, `p` equality constraints and `m`
+    /// inequality constraints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the problems are too large for QuadProg++. See [`try_new`](Self::try_new).
+    pub fn new(n: usize, p: usize, m: usize) -> Self {
+        Self::try_new(n, p, m).unwrap()
+    }
+
+    /// Creates a solver like [`new`](Self::new), or returns [`Error::TooLarge`] if \\(G\\),
+    /// \\(CE\\) or \\(CI\\) would have more rows, columns or elements than QuadProg++ can index.
+    pub fn try_new(n: usize, p: usize, m: usize) -> Result<Self> {
+        let (n32, _) = crate::ffi_dim("g", n, n)?;
+        let (_, p32) = crate::ffi_dim("ce", n, p)?;
+        let (_, m32) = crate::ffi_dim("ci", n, m)?;
+        // The dimensions fit in u32 from here on, except for the columns added for bounds and
+        // ranged constraints, which `run` checks.
+        Ok(unsafe {
+            Self {
+                n,
+                p,
+                m,
+                g: sys::new_matrix_from_ptr(ptr::null(), 0, 0),
+                g0: sys::new_vector(n32),
+                x: sys::new_vector(n32),
+                workspace: sys::new_workspace(n32, p32, m32),
+                columns: Vec::new(),
+                ci_buf: Vec::new(),
+                ci0_buf: Vec::new(),
+                g_buf: Vec::new(),
+                polished: false,
+                scaling: Scaling::default(),
+                status: Status::Optimal,
+                params: sys::SolveParams::default(),
+                info: sys::SolveInfo::default(),
+            }
+        })
+    }
+
+    /// Returns the number of variables, equality constraints and inequality constraints of the
+    /// problems this solver accepts.
+    pub fn dim(&self) -> (usize, usize, usize) {
+        (self.n, self.p, self.m)
+    }
+
+    /// Solves a quadratic programming problem like [`solve`](crate::solve).
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`solve`](crate::solve), this method returns
+    /// [`Error::SizeMismatch`] if the size of the problem differs from [`dim`](Self::dim).
+    pub fn solve<G, G0, CE, CE0, CI, CI0>(
+        &mut self,
+        g: ArrayBase<G, Ix2>,
+        g0: ArrayBase<G0, Ix1>,
+        ce: Option<Constraints<CE, CE0>>,
+        ci: Option<Constraints<CI, CI0>>,
+    ) -> Result<Solution>
+    where
+        G: RawData<Elem = f64>,
+        G0: RawData<Elem = f64>,
+        CE: RawData<Elem = f64>,
+        CE0: RawData<Elem = f64>,
+        CI: RawData<Elem = f64>,
+        CI0: RawData<Elem = f64>,
+    {
+        self.solve_with_options(g, g0, ce, ci, &SolveOptions::default())
+    }
+
+    /// Solves a quadratic programming problem like [`solve_with_options`](crate::solve_with_options).
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`solve_with_options`](crate::solve_with_options), this
+    /// method returns [`Error::SizeMismatch`] if the size of the problem differs from
+    /// [`dim`](Self::dim).
+    pub fn solve_with_options<G, G0, CE, CE0, CI, CI0>(
+        &mut self,
+        g: ArrayBase<G, Ix2>,
+        g0: ArrayBase<G0, Ix1>,
+        ce: Option<Constraints<CE, CE0>>,
+        ci: Option<Constraints<CI, CI0>>,
+        options: &SolveOptions,
+    ) -> Result<Solution>
+    where
+        G: RawData<Elem = f64>,
+        G0: RawData<Elem = f64>,
+        CE: RawData<Elem = f64>,
+        CE0: RawData<Elem = f64>,
+        CI: RawData<Elem = f64>,
+        CI0: RawData<Elem = f64>,
+    {
+        self.solve_with_bounds(g, g0, ce, ci, &Bounds::default(), options)
+    }
+
+    /// Solves a validated [`QpProblem`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`solve_with_options`](Self::solve_with_options).
+    pub fn solve_problem(
+        &mut self,
+        problem: &QpProblem,
+        options: &SolveOptions,
+    ) -> Result<Solution> {
+        problem.solve_with(self, options)
+    }
+
+    /// Solves a quadratic programming problem like [`solve_with_bounds`](crate::solve_with_bounds).
+    ///
+    /// The bounds don't count towards the number of inequality constraints given to
+    /// [`new`](Self::new).
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`solve_with_bounds`](crate::solve_with_bounds), this
+    /// method returns [`Error::SizeMismatch`] if the size of the problem differs from
+    /// [`dim`](Self::dim).
+    pub fn solve_with_bounds<G, G0, CE, CE0, CI, CI0>(
+        &mut self,
+        g: ArrayBase<G, Ix2>,
+        g0: ArrayBase<G0, Ix1>,
+        ce: Option<Constraints<CE, CE0>>,
+        ci: Option<Constraints<CI, CI0>>,
+        bounds: &Bounds,
+        options: &SolveOptions,
+    ) -> Result<Solution>
+    where
+        G: RawData<Elem = f64>,
+        G0: RawData<Elem = f64>,
+        CE: RawData<Elem = f64>,
+        CE0: RawData<Elem = f64>,
+        CI: RawData<Elem = f64>,
+        CI0: RawData<Elem = f64>,
+    {
+        let objective = self.run(Some(g), g0, ce, ci, bounds, options)?;
+        Ok(self.solution(objective))
+    }
+
+    /// Sets the Hessian \\(G\\) shared by the problems solved by [`solve_for`](Self::solve_for)
+    /// and factorizes it.
+    ///
+    /// The solver Cholesky-factorizes \\(G\\) on every solve. When a sequence of problems only
+    /// differs in \\(g_0\\) and the constraints, the factorization can be computed once here and
+    /// reused by [`solve_for`](Self::solve_for).
+    ///
+    /// # Errors
+    ///
+    /// If `g` isn't an \\(N \times N\\) matrix, this method returns [`Error::SizeMismatch`]. If
+    /// `g` isn't positive definite, it returns [`Error::SingularHessian`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let mut solver = Solver::new(2, 0, 1).with_hessian(array![[2.0, 0.0], [0.0, 2.0]])?;
+    /// // x + y <= 1
+    /// let ci = array![[-1.0], [-1.0]];
+    /// for a in 1..5 {
+    ///     let g0 = array![-(a as f64), 0.0];
+    ///     let ci = Constraints::some(ci.view(), array![1.0]);
+    ///     let solution = solver.solve_for(g0, Constraints::NONE, ci)?;
+    ///     assert_eq!(solution.factorizations, 0);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn with_hessian<G: Data<Elem = f64>>(mut self, g: ArrayBase<G, Ix2>) -> Result<Self> {
+        let n = self.n;
+        let (g_n, g_m) = g.dim();
+        assert_size!(g, n, g_n);
+        assert_size!(g, g_n, g_m);
+        let (rows, cols) = (g.strides()[0], g.strides()[1]);
+        let n32 = n as u32;
+        unsafe {
+            sys::matrix_set_from_ptr_strided(self.g.pin_mut(), g.as_ptr(), n32, n32, rows, cols);
+        }
+        self.g_buf.clear();
+        self.g_buf.extend(g.iter());
+        self.scaling.hessian = false;
+        let pivot = sys::factorize_hessian(self.workspace.pin_mut(), self.g.pin_mut())?;
+        if let Ok(pivot) = usize::try_from(pivot) {
+            return Err(Error::SingularHessian { pivot });
+        }
+        Ok(self)
+    }
+
+    /// Solves a quadratic programming problem like [`solve`](Self::solve), reusing the
+    /// factorization of the Hessian set by [`with_hessian`](Self::with_hessian).
+    ///
+    /// Solving a problem with [`solve`](Self::solve) or its variants replaces the Hessian, so
+    /// subsequent calls reuse the factorization of the Hessian of that problem.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`solve`](Self::solve). If no Hessian has been factorized, this method returns
+    /// [`Error::NotFactorized`].
+    pub fn solve_for<G0, CE, CE0, CI, CI0>(
+        &mut self,
+        g0: ArrayBase<G0, Ix1>,
+        ce: Option<Constraints<CE, CE0>>,
+        ci: Option<Constraints<CI, CI0>>,
+    ) -> Result<Solution>
+    where
+        G0: RawData<Elem = f64>,
+        CE: RawData<Elem = f64>,
+        CE0: RawData<Elem = f64>,
+        CI: RawData<Elem = f64>,
+        CI0: RawData<Elem = f64>,
+    {
+        self.solve_for_with_options(g0, ce, ci, &SolveOptions::default())
+    }
+
+    /// Solves a quadratic programming problem like [`solve_for`](Self::solve_for) with the given
+    /// [`SolveOptions`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`solve_with_options`](Self::solve_with_options) and
+    /// [`solve_for`](Self::solve_for).
+    pub fn solve_for_with_options<G0, CE, CE0, CI, CI0>(
+        &mut self,
+        g0: ArrayBase<G0, Ix1>,
+        ce: Option<Constraints<CE, CE0>>,
+        ci: Option<Constraints<CI, CI0>>,
+        options: &SolveOptions,
+    ) -> Result<Solution>
+    where
+        G0: RawData<Elem = f64>,
+        CE: RawData<Elem = f64>,
+        CE0: RawData<Elem = f64>,
+        CI: RawData<Elem = f64>,
+        CI0: RawData<Elem = f64>,
+    {
+        let g = None::<ArrayView2<'_, f64>>;
+        let objective = self.run(g, g0, ce, ci, &Bounds::default(), options)?;
+        Ok(self.solution(objective))
+    }
+
+    /// Collects the solution left in `self.x` and `self.info` by [`run`](Self::run).
+    fn solution(&self, objective: f64) -> Solution {
+        let (n, m) = (self.n, self.m);
+        let mut x = Array1::zeros(n);
+        let slice = x.as_slice_mut().expect("a new array is contiguous");
+        sys::vector_copy_to(&self.x, slice);
+        let info = &self.info;
+        let active = info
+            .active_set
+            .iter()
+            .filter_map(|&k| usize::try_from(k).ok());
+        let (multipliers, active_set, active_lower, active_upper) =
+            unmap_columns(&self.columns, n, m, &info.multipliers, active);
+        Solution {
+            x,
+            status: self.status,
+            objective,
+            multipliers,
+            active_set,
+            active_lower,
+            active_upper,
+            iterations: info.iterations as usize,
+            factorizations: info.factorizations as usize,
+            additions: info.additions as usize,
+            deletions: info.deletions as usize,
+            rotations: info.rotations as usize,
+            warm_started: info.warm_started,
+            polished: self.polished,
+            backend: CPP_NAME,
+        }
+    }
+
+    /// Solves a quadratic programming problem like [`solve_with_options`](Self::solve_with_options),
+    /// but writes the minimizer into `x` and only returns the value of the objective function.
+    ///
+    /// The buffers of the solver are reused, so this method doesn't allocate once the solver has
+    /// solved a problem with the same number of constraints, except for a small buffer when
+    /// `options` warm-starts the solver.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`solve_with_options`](Self::solve_with_options), this
+    /// method returns [`Error::SizeMismatch`] if the length of `x` isn't the number of variables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// let mut solver = Solver::new(1, 0, 1);
+    /// let mut x = [0.0];
+    /// let ci = Constraints::some(array![[1.0]], array![-2.0]);
+    /// let options = SolveOptions::default();
+    /// let objective =
+    ///     solver.solve_into(array![[1.0]], array![0.0], Constraints::NONE, ci, &options, &mut x)?;
+    /// assert_eq!(x, [2.0]);
+    /// assert_eq!(objective, 2.0);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn solve_into<G, G0, CE, CE0, CI, CI0>(
+        &mut self,
+        g: ArrayBase<G, Ix2>,
+        g0: ArrayBase<G0, Ix1>,
+        ce: Option<Constraints<CE, CE0>>,
+        ci: Option<Constraints<CI, CI0>>,
+        options: &SolveOptions,
+        x: &mut [f64],
+    ) -> Result<f64>
+    where
+        G: RawData<Elem = f64>,
+        G0: RawData<Elem = f64>,
+        CE: RawData<Elem = f64>,
+        CE0: RawData<Elem = f64>,
+        CI: RawData<Elem = f64>,
+        CI0: RawData<Elem = f64>,
+    {
+        assert_size!(x, self.n, x.len());
+        let objective = self.run(Some(g), g0, ce, ci, &Bounds::default(), options)?;
+        sys::vector_copy_to(&self.x, x);
+        Ok(objective)
+    }
+
+    /// Solves the problem, leaving the solution in `self.x` and `self.info`, and returns the
+    /// value of the objective function. If `g` is `None`, the factorization of the last Hessian
+    /// is reused.
+    fn run<G, G0, CE, CE0, CI, CI0>(
+        &mut self,
+        g: Option<ArrayBase<G, Ix2>>,
+        g0: ArrayBase<G0, Ix1>,
+        ce: Option<Constraints<CE, CE0>>,
+        ci: Option<Constraints<CI, CI0>>,
+        bounds: &Bounds,
+        options: &SolveOptions,
+    ) -> Result<f64>
+    where
+        G: RawData<Elem = f64>,
+        G0: RawData<Elem = f64>,
+        CE: RawData<Elem = f64>,
+        CE0: RawData<Elem = f64>,
+        CI: RawData<Elem = f64>,
+        CI0: RawData<Elem = f64>,
+    {
+        let (n, p, m) = (self.n, self.p, self.m);
+        let shapes = Shapes {
+            g: g.as_ref().map(ArrayBase::dim),
+            g0: g0.dim(),
+            ce: ce.as_ref().map(Constraints::shape),
+            ci: ci.as_ref().map(Constraints::shape),
+        };
+        if let Some(g) = &g {
+            let (g_n, g_m) = g.dim();
+            assert_size!(g, n, g_n, shapes);
+            assert_size!(g, g_n, g_m, shapes);
+            let g_data = unsafe { utils::row_major(g) };
+            // Only the upper triangle may be read, so G is checked as it's solved.
+            let g = options.prepare_hessian(&g_data, n)?;
+            assert_finite!(g);
+            unsafe { sys::matrix_set_from_ptr(self.g.pin_mut(), g.as_ptr(), n as u32, n as u32) };
+            self.g_buf.clear();
+            self.g_buf.extend_from_slice(&g);
+        } else if self.g_buf.len() != n * n {
+            // QuadProg++ would reject the empty G before noticing the missing factorization.
+            return Err(Error::NotFactorized);
+        }
+        let g0_n = g0.dim();
+        assert_size!(g0.dim(), n, g0_n, shapes);
+        let g0_data = unsafe { utils::row_major(&g0) };
+        assert_finite!(g0, g0_data);
+        unsafe { sys::vector_set_from_ptr(self.g0.pin_mut(), g0_data.as_ptr(), n as u32) };
+        if let Some(x0) = &options.initial_point {
+            assert_size!(initial_point, n, x0.len());
+        }
+        let (ce_data, ce0_data) = match &ce {
+            Some(Constraints {
+                coeffs,
+                consts,
+                upper,
+                names,
+            }) => {
+                if upper.is_some() {
+                    return Err(Error::RangedEquality);
+                }
+                let (ce_n, ce_m) = coeffs.dim();
+                assert_size!(ce.dim(), n, ce_n, shapes);
+                assert_size!(ce.dim(), p, ce_m, shapes);
+                let ce0_n = consts.dim();
+                assert_size!(ce0.dim(), ce0_n, ce_m, shapes);
+                if !names.is_empty() {
+                    assert_size!(ce.names(), ce_m, names.len());
+                }
+                let (ce, ce0) = unsafe { (utils::row_major(coeffs), utils::row_major(consts)) };
+                assert_finite!(ce);
+                assert_finite!(ce0);
+                (ce, ce0)
+            }
+            None => {
+                assert_size!(ce.dim(), p, 0, shapes);
+                (Cow::Borrowed(&[][..]), Cow::Borrowed(&[][..]))
+            }
+        };
+        if !bounds.lower_bounds().is_empty() {
+            assert_size!(bounds.lower_bounds(), n, bounds.lower_bounds().len());
+        }
+        if !bounds.upper_bounds().is_empty() {
+            assert_size!(bounds.upper_bounds(), n, bounds.upper_bounds().len());
+        }
+        if !bounds.names().is_empty() {
+            assert_size!(bounds.names(), n, bounds.names().len());
+        }
+        let (ci, ci0, upper, ci_names) = match &ci {
+            Some(Constraints {
+                coeffs,
+                consts,
+                upper,
+                names,
+            }) => {
+                let (ci_n, ci_m) = coeffs.dim();
+                assert_size!(ci.dim(), n, ci_n, shapes);
+                assert_size!(ci.dim(), m, ci_m, shapes);
+                let ci0_n = consts.dim();
+                assert_size!(ci0.dim(), ci0_n, ci_m, shapes);
+                if !names.is_empty() {
+                    assert_size!(ci.names(), ci_m, names.len());
+                }
+                let (ci, ci0) = unsafe { (utils::row_major(coeffs), utils::row_major(consts)) };
+                assert_finite!(ci);
+                // The limits of ranged constraints may be infinite.
+                let upper = match upper {
+                    Some(upper) => {
+                        let upper_n = upper.dim();
+                        assert_size!(upper.dim(), upper_n, ci_m);
+                        let upper = unsafe { utils::row_major(upper) };
+                        assert_not_nan!(ci0);
+                        assert_not_nan!(upper);
+                        Some(upper)
+                    }
+                    None => {
+                        assert_finite!(ci0);
+                        None
+                    }
+                };
+                (ci, ci0, upper, &names[..])
+            }
+            None => {
+                assert_size!(ci.dim(), m, 0, shapes);
+                let empty = || Cow::Borrowed(&[][..]);
+                (empty(), empty(), None, &[][..])
+            }
+        };
+        let (ci, ci0) = (&ci[..], &ci0[..]);
+        self.columns.clear();
+        self.ci0_buf.clear();
+        let upper = upper.as_deref();
+        push_columns(&mut self.columns, &mut self.ci0_buf, ci0, upper, bounds);
+        let cols = self.columns.len();
+        crate::ffi_dim("ci", n, cols)?;
+        if n == 0 {
+            // QuadProg++ crashes on problems without variables.
+            let describe = |k: usize| self.columns[k].describe(ci_names, bounds.names());
+            let tolerance = options.feasibility_tolerance;
+            let objective = slices::solve_empty(p, &self.ci0_buf, tolerance, describe)?;
+            self.info = sys::SolveInfo {
+                multipliers: vec![0.0; cols],
+                ..Default::default()
+            };
+            self.status = Status::Optimal;
+            self.polished = false;
+            return Ok(objective);
+        }
+        let trivial = self
+            .columns
+            .iter()
+            .enumerate()
+            .all(|(k, &c)| c == Column::Lower(k));
+        if !trivial {
+            self.ci_buf.clear();
+            push_ci(&mut self.ci_buf, &self.columns, ci, n, m);
+        }
+        // A reused factorization is that of the scaled Hessian if the last one was scaled.
+        let scaled = match &g {
+            Some(_) => options.equilibrate,
+            None => self.scaling.hessian,
+        };
+        if scaled {
+            let problem = polish::Problem {
+                g: &self.g_buf,
+                g0: &g0_data,
+                ce: &ce_data,
+                ce0: &ce0_data,
+                ci: if trivial { ci } else { &self.ci_buf },
+                ci0: &self.ci0_buf,
+            };
+            self.scaling.equilibrate(&problem, g.is_some());
+            let scaled = self.scaling.problem();
+            let n32 = n as u32;
+            unsafe {
+                if g.is_some() {
+                    sys::matrix_set_from_ptr(self.g.pin_mut(), scaled.g.as_ptr(), n32, n32);
+                }
+                sys::vector_set_from_ptr(self.g0.pin_mut(), scaled.g0.as_ptr(), n32);
+            }
+        }
+        if g.is_some() {
+            self.scaling.hessian = scaled;
+        }
+        // Warm-start with the first column of each constraint, i.e. the lower limit of ranged
+        // constraints unless it's infinite.
+        options.fill_params(&mut self.params);
+        if scaled {
+            self.scaling.scale(&mut self.params.initial_point);
+        }
+        self.params.reuse_factorization = g.is_none();
+        self.params.active_set.clear();
+        for &index in &options.active_set {
+            if index >= m {
+                return Err(Error::IndexOutOfRange { index, len: m });
+            }
+            let k = self
+                .columns
+                .iter()
+                .position(|&c| c == Column::Lower(index) || c == Column::Upper(index));
+            if let Some(k) = k {
+                self.params.active_set.push(k as i32);
+            }
+        }
+        // QuadProg++ only reads the constraints, so it's given views of them instead of copies.
+        let views = if scaled {
+            let scaled = self.scaling.problem();
+            (scaled.ce, scaled.ce0, scaled.ci, scaled.ci0)
+        } else {
+            let ci = if trivial { ci } else { &self.ci_buf[..] };
+            (&ce_data[..], &ce0_data[..], ci, &self.ci0_buf[..])
+        };
+        let (n32, p32, cols32) = (n as u32, p as u32, cols as u32);
+        let (ce_view, ci_view) = (
+            sys::MatrixRef::new(views.0, n32, p32),
+            sys::MatrixRef::new(views.2, n32, cols32),
+        );
+        let (ce0_view, ci0_view) = (sys::VectorRef::new(views.1), sys::VectorRef::new(views.3));
+        let best = if options.is_monitored() {
+            let mut interrupted = |_| options.interrupted();
+            // The iterations of the subproblems of the LP fallback and the refinement are
+            // numbered from one each, so count them here.
+            let mut iteration = 0;
+            let mut report = |progress: &sys::Progress| {
+                iteration += 1;
+                options.report(&Progress {
+                    iteration,
+                    objective: progress.objective,
+                    infeasibility: progress.infeasibility,
+                    active_set_size: progress.active_constraints as usize,
+                });
+            };
+            sys::solve_quadprog_monitored_view(
+                self.workspace.pin_mut(),
+                self.g.pin_mut(),
+                self.g0.pin_mut(),
+                &ce_view,
+                &ce0_view,
+                &ci_view,
+                &ci0_view,
+                self.x.pin_mut(),
+                &self.params,
+                &mut self.info,
+                &mut sys::Monitor::new(&mut interrupted, &mut report),
+            )?
+        } else {
+            sys::solve_quadprog_view(
+                self.workspace.pin_mut(),
+                self.g.pin_mut(),
+                self.g0.pin_mut(),
+                &ce_view,
+                &ce0_view,
+                &ci_view,
+                &ci0_view,
+                self.x.pin_mut(),
+                &self.params,
+                &mut self.info,
+            )?
+        };
+        match self.info.status {
+            sys::SolveStatus::Infeasible => {
+                let column = self.columns[self.info.infeasible_constraint as usize];
+                return Err(Error::Infeasible {
+                    constraint: column.describe(ci_names, bounds.names()),
+                });
+            }
+            sys::SolveStatus::NotPositiveDefinite => {
+                return Err(Error::SingularHessian {
+                    pivot: self.info.singular_pivot as usize,
+                })
+            }
+            sys::SolveStatus::MaxIterations if !options.best_effort => {
+                return Err(Error::MaxIterations {
+                    iterations: self.info.iterations as usize,
+                })
+            }
+            sys::SolveStatus::Interrupted if !options.best_effort => {
+                return Err(Error::Interrupted {
+                    iterations: self.info.iterations as usize,
+                })
+            }
+            sys::SolveStatus::Degenerate if !options.best_effort => {
+                return Err(Error::Degenerate {
+                    iterations: self.info.iterations as usize,
+                })
+            }
+            _ => {}
+        }
+        self.status = match self.info.status {
+            sys::SolveStatus::MaxIterations => Status::MaxIterations,
+            sys::SolveStatus::Interrupted => Status::Interrupted,
+            sys::SolveStatus::Degenerate => Status::Degenerate,
+            _ => Status::Optimal,
+        };
+        self.polished = false;
+        if !options.polish && !scaled && self.status == Status::Optimal {
+            return Ok(best);
+        }
+        let problem = if scaled {
+            self.scaling.problem()
+        } else {
+            polish::Problem {
+                g: &self.g_buf,
+                g0: &g0_data,
+                ce: &ce_data,
+                ce0: &ce0_data,
+                ci: if trivial { ci } else { &self.ci_buf },
+                ci0: &self.ci0_buf,
+            }
+        };
+        let mut x = vec![0.0; n];
+        sys::vector_copy_to(&self.x, &mut x);
+        let mut objective = best;
+        if self.status != Status::Optimal {
+            // The objective returned by the proximal point method of the LP fallback isn't the
+            // one of the problem.
+            objective = problem.objective(&x);
+        } else if options.polish {
+            let active: Vec<usize> = (self.info.active_set.iter())
+                .filter_map(|&k| usize::try_from(k).ok())
+                .collect();
+            if polish::polish(&problem, &active, &mut x, &mut self.info.multipliers) {
+                self.polished = true;
+                objective = problem.objective(&x);
+            }
+        }
+        if scaled {
+            self.scaling.unscale(&mut x, &mut self.info.multipliers);
+        }
+        if scaled || self.polished {
+            unsafe { sys::vector_set_from_ptr(self.x.pin_mut(), x.as_ptr(), n as u32) };
+        }
+        Ok(objective)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_ulps_eq;
+    use ndarray::{array, Array2};
+
+    use super::*;
+
+    #[test]
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Solver>();
+        assert_send_sync::<crate::IncrementalSolver>();
+        assert_send_sync::<QpProblem>();
+        assert_send_sync::<SolveOptions>();
+        assert_send_sync::<Solution>();
+        assert_send_sync::<Error>();
+    }
+
+    #[test]
+    fn concurrent_solves() -> Result<()> {
+        let problem = |k: usize| {
+            let c = k as f64 / 10.0;
+            QpProblem::new(3)
+                .hessian(array![[2.0, 1.0, 0.0], [1.0, 2.0, 0.5], [0.0, 0.5, 1.0]])
+                .linear(array![-c, 1.0 - c, c - 2.0])
+                .eq(Constraints::new(array![[1.0], [1.0], [1.0]], array![-1.0]))
+                .ineq(Constraints::new(
+                    array![[1.0, 0.0], [0.0, -1.0], [0.0, 0.0]],
+                    array![0.0, 0.5],
+                ))
+                .bounds(Bounds::lower(array![-1.0, -1.0, 0.0]))
+                .build()
+        };
+        let problems = (0..200).map(problem).collect::<Result<Vec<_>>>()?;
+        let expected = problems
+            .iter()
+            .map(QpProblem::solve)
+            .collect::<Result<Vec<_>>>()?;
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                // Each thread moves in a solver, and solves the problems in its own order.
+                let mut solver = Solver::new(3, 1, 2);
+                let (problems, expected) = (&problems, &expected);
+                scope.spawn(move || {
+                    for round in 0..5 {
+                        for k in (0..problems.len()).map(|k| (k * 7 + round) % problems.len()) {
+                            let solution = solver
+                                .solve_problem(&problems[k], &SolveOptions::default())
+                                .unwrap();
+                            assert_eq!(solution, expected[k]);
+                        }
+                    }
+                });
+            }
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn reuse() -> Result<()> {
+        #[rustfmt::skip]
+        let g = array![
+            [4.0, -2.0],
+            [-2.0, 4.0],
+        ];
+        let ce = array![[1.0], [1.0]];
+        let ce0 = array![-3.0];
+        #[rustfmt::skip]
+        let ci = array![
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 1.0],
+        ];
+        let ci0 = array![0.0, -2.0, 0.0];
+        let mut solver = Solver::new(2, 1, 3);
+        for _ in 0..3 {
+            let solution = solver.solve(
+                g.view(),
+                array![6.0, 0.0],
+                Constraints::some(ce.view(), ce0.view()),
+                Constraints::some(ci.view(), ci0.view()),
+            )?;
+            assert_ulps_eq!(solution.objective, 12.0);
+            assert_ulps_eq!(solution.x[0], 1.0);
+            assert_ulps_eq!(solution.x[1], 2.0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn solve_for() -> Result<()> {
+        #[rustfmt::skip]
+        let g = array![
+            [4.0, -2.0],
+            [-2.0, 4.0],
+        ];
+        let ce = array![[1.0], [1.0]];
+        let ce0 = array![-3.0];
+        let ci = array![[1.0, 1.0, 0.0], [0.0, 1.0, 1.0]];
+        let ci0 = array![0.0, -2.0, 0.0];
+        let mut solver = Solver::new(2, 1, 3);
+        let err = solver
+            .solve_for(
+                array![6.0, 0.0],
+                Constraints::some(ce.view(), ce0.view()),
+                Constraints::some(ci.view(), ci0.view()),
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::NotFactorized),
+            "unexpected error: {:?}",
+            err
+        );
+
+        let mut solver = solver.with_hessian(g.view())?;
+        for g0 in &[array![6.0, 0.0], array![0.0, 6.0], array![-1.0, 1.0]] {
+            let solution = solver.solve_for(
+                g0.view(),
+                Constraints::some(ce.view(), ce0.view()),
+                Constraints::some(ci.view(), ci0.view()),
+            )?;
+            let expected = crate::solve(
+                g.view(),
+                g0.view(),
+                Constraints::some(ce.view(), ce0.view()),
+                Constraints::some(ci.view(), ci0.view()),
+            )?;
+            assert_eq!(solution.factorizations, 0);
+            assert_eq!(solution.x, expected.x);
+            assert_eq!(solution.objective, expected.objective);
+            assert_eq!(solution.multipliers, expected.multipliers);
+        }
+
+        // a regular solve replaces the Hessian
+        let g = &g * 2.0;
+        solver.solve(
+            g.view(),
+            array![6.0, 0.0],
+            Constraints::some(ce.view(), ce0.view()),
+            Constraints::some(ci.view(), ci0.view()),
+        )?;
+        let solution = solver.solve_for(
+            array![0.0, 6.0],
+            Constraints::some(ce.view(), ce0.view()),
+            Constraints::some(ci.view(), ci0.view()),
+        )?;
+        let expected = crate::solve(
+            g.view(),
+            array![0.0, 6.0],
+            Constraints::some(ce.view(), ce0.view()),
+            Constraints::some(ci.view(), ci0.view()),
+        )?;
+        assert_eq!(solution.x, expected.x);
+
+        let err = Solver::new(2, 0, 0)
+            .with_hessian(array![[1.0, 0.0], [0.0, -1.0]])
+            .err()
+            .unwrap();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn size_mismatch() {
+        let mut solver = Solver::new(2, 0, 1);
+        let err = solver
+            .solve(
+                array![[1.0, 0.0], [0.0, 1.0]],
+                array![0.0, 0.0],
+                Constraints::NONE,
+                Constraints::NONE,
+            )
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    expected: 1,
+                    actual: 0,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+
+        // G would have 2^32 elements.
+        let err = Solver::try_new(1 << 16, 0, 0).err().unwrap();
+        assert!(
+            matches!(
+                err,
+                Error::TooLarge {
+                    term: "g",
+                    len: 0x1_0000_0000
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn no_variables() -> Result<()> {
+        let (g, g0) = (Array2::zeros((0, 0)), Array1::zeros(0));
+        // 0 x + 1 >= 0
+        let ci = || Constraints::new(Array2::zeros((0, 1)), array![1.0]);
+        let solution = crate::solve(g.view(), g0.view(), Constraints::NONE, Some(ci()))?;
+        assert_eq!(solution.x.len(), 0);
+        assert_eq!(solution.objective, 0.0);
+        assert_eq!(solution.multipliers.ineq, [0.0]);
+        let problem = QpProblem::new(0).ineq(ci()).build()?;
+        assert_eq!(problem.solve()?.objective, 0.0);
+        let mut solver = Solver::new(0, 0, 1);
+        let solution = solver.solve(g.view(), g0.view(), Constraints::NONE, Some(ci()))?;
+        assert_eq!(solution.active_set, []);
+
+        // 0 x + 1 >= 0, 0 x - 1 >= 0 and 0 x - 2 >= 0, of which the last is the most violated
+        let ci = || {
+            Constraints::new(Array2::zeros((0, 3)), array![1.0, -1.0, -2.0])
+                .with_names(["a", "b", "c"])
+        };
+        let err = crate::solve(g.view(), g0.view(), Constraints::NONE, Some(ci())).unwrap_err();
+        assert!(
+            matches!(&err, Error::Infeasible { constraint } if constraint == "constraint 'c'"),
+            "unexpected error: {:?}",
+            err
+        );
+        let problem = QpProblem::new(0).ineq(ci()).build()?;
+        assert!(matches!(problem.solve(), Err(Error::Infeasible { .. })));
+        let mut solver = Solver::new(0, 0, 3);
+        let err = solver
+            .solve(g.view(), g0.view(), Constraints::NONE, Some(ci()))
+            .unwrap_err();
+        assert!(matches!(err, Error::Infeasible { .. }));
+
+        // 0 x = 1
+        let ce = Constraints::some(Array2::zeros((0, 1)), array![-1.0]);
+        let err = Solver::new(0, 1, 0)
+            .solve(g.view(), g0.view(), ce, Constraints::NONE)
+            .unwrap_err();
+        assert!(matches!(err, Error::LinearlyDependent));
+        Ok(())
+    }
+
+    #[test]
+    fn solve_into() -> Result<()> {
+        #[rustfmt::skip]
+        let g = array![
+            [4.0, -2.0],
+            [-2.0, 4.0],
+        ];
+        let ci = array![[1.0, 1.0, 0.0], [0.0, 1.0, 1.0]];
+        let ci0 = array![0.0, -2.0, 0.0];
+        let mut solver = Solver::new(2, 0, 3);
+        let expected = solver.solve(
+            g.view(),
+            array![6.0, 0.0],
+            Constraints::NONE,
+            Constraints::some(ci.view(), ci0.view()),
+        )?;
+        let mut x = [0.0; 2];
+        for _ in 0..2 {
+            let objective = solver.solve_into(
+                g.view(),
+                array![6.0, 0.0],
+                Constraints::NONE,
+                Constraints::some(ci.view(), ci0.view()),
+                &SolveOptions::default(),
+                &mut x,
+            )?;
+            assert_eq!(objective, expected.objective);
+            assert_eq!(x[..], expected.x.as_slice().unwrap()[..]);
+        }
+
+        let err = solver
+            .solve_into(
+                g.view(),
+                array![6.0, 0.0],
+                Constraints::NONE,
+                Constraints::some(ci.view(), ci0.view()),
+                &SolveOptions::default(),
+                &mut [0.0; 3],
+            )
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    expected: 2,
+                    actual: 3,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,718 @@
+use ndarray::{Array1, Array2, ArrayBase, Ix1, OwnedRepr, RawData};
+
+use crate::backend::SPARSE_NAME;
+use crate::linalg::dot;
+use crate::solver::{self, Column};
+use crate::{
+    block, utils, Bounds, Constraints, Error, IntoQpMatrix, Result, Solution, SolveOptions, Status,
+};
+
+/// Tolerance of the test of linear dependence, relative to \\(n_p^\intercal G^{-1} n_p\\)
+const DEPENDENCE: f64 = 1e-12;
+/// Tolerance of the violation of a constraint, relative to the magnitudes of its terms
+const FEASIBILITY: f64 = 1e-10;
+
+/// A sparse symmetric matrix \\(G\\) in compressed sparse column (CSC) format.
+///
+/// [`solve`](crate::solve) solves a problem with a sparse \\(G\\) without forming the dense
+/// matrix, by a range-space variant of the dual method of QuadProg++. It factorizes \\(G\\) by a
+/// sparse Cholesky factorization, and the active constraints by a dense one of
+/// \\(N^\intercal G^{-1} N\\), where \\(N\\) holds their normals. With \\(q\\) active constraints,
+/// an iteration costs \\(O(\textrm{nnz}(L) + N q + q^2)\\) rather than \\(O(N^2)\\), which pays
+/// off for thousands of variables with sparse constraints, e.g. in model predictive control. The
+/// variables aren't reordered, so the fill-in of \\(L\\) depends on their order, and banded
+/// matrices keep their band.
+///
+/// The constraints are read from their dense matrices, keeping the nonzero coefficients. The
+/// problem is solved densely like any other if [`SolveOptions`] other than the defaults are
+/// given, or if the shapes are wrong, so that the errors are those of the dense solver. The
+/// solution agrees with the one of QuadProg++ up to rounding, and its
+/// [`backend`](Solution::backend) is `"sparse"`.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// // min x^T G x / 2 - x0 for a tridiagonal G, s.t. x0 <= 0.25
+/// let g = SparseHessian::from_csc(
+///     3,
+///     &[0, 2, 5, 7],
+///     &[0, 1, 0, 1, 2, 1, 2],
+///     &[2.0, -1.0, -1.0, 2.0, -1.0, -1.0, 2.0],
+/// )?;
+/// let ci = Constraints::some(array![[-1.0], [0.0], [0.0]], array![0.25]);
+/// let solution = solve(&g, array![-1.0, 0.0, 0.0], Constraints::NONE, ci)?;
+/// assert!((solution.x[0] - 0.25).abs() < 1e-12);
+/// assert_eq!(solution.backend, "sparse");
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseHessian {
+    n: usize,
+    col_offsets: Vec<usize>,
+    row_indices: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl SparseHessian {
+    /// Creates the \\(N \times N\\) matrix from its CSC storage, where the row indices of column
+    /// `j` are `row_indices[col_offsets[j]..col_offsets[j + 1]]`, and duplicate entries are
+    /// summed.
+    ///
+    /// Like [`utils::hessian_from_csc`], both triangles of \\(G\\) have to be stored. The solver
+    /// only reads the upper one, like QuadProg++.
+    ///
+    /// # Errors
+    ///
+    /// * If the length of `col_offsets` isn't \\(N + 1\\), or the offsets or the row indices are
+    ///   out of range, this function returns [`Error::SizeMismatch`].
+    /// * If the sparsity pattern isn't structurally symmetric, it returns
+    ///   [`Error::NotSymmetric`] with the location of the element in the lower triangle.
+    pub fn from_csc(
+        n: usize,
+        col_offsets: &[usize],
+        row_indices: &[usize],
+        values: &[f64],
+    ) -> Result<Self> {
+        let nnz = values.len();
+        assert_size!(col_offsets, n + 1, col_offsets.len());
+        assert_size!(row_indices, nnz, row_indices.len());
+        let mut pattern = Vec::with_capacity(nnz);
+        for (j, offsets) in col_offsets.windows(2).enumerate() {
+            let (start, end) = (offsets[0], offsets[1]);
+            if start > end || end > nnz {
+                // The offsets must be nondecreasing and within the entries.
+                assert_size!(col_offsets, nnz, end.max(nnz + 1));
+            }
+            for &i in &row_indices[start..end] {
+                if i >= n {
+                    assert_size!(row_indices, n, i + 1);
+                }
+                pattern.push((i, j));
+            }
+        }
+        pattern.sort_unstable();
+        let asymmetric = (pattern.iter())
+            .filter(|&&(i, j)| pattern.binary_search(&(j, i)).is_err())
+            .map(|&(i, j)| (i.max(j), i.min(j)))
+            .min();
+        if let Some((row, col)) = asymmetric {
+            return Err(Error::NotSymmetric { row, col });
+        }
+        Ok(Self {
+            n,
+            col_offsets: col_offsets.to_vec(),
+            row_indices: row_indices.to_vec(),
+            values: values.to_vec(),
+        })
+    }
+
+    /// Returns the number of rows and columns.
+    pub fn dim(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the number of stored elements.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the stored elements of column `j` in the upper triangle, with their rows.
+    fn upper(&self, j: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let range = self.col_offsets[j]..self.col_offsets[j + 1];
+        (self.row_indices[range.clone()].iter().copied())
+            .zip(self.values[range].iter().copied())
+            .filter(move |&(i, _)| i <= j)
+    }
+}
+
+impl IntoQpMatrix for SparseHessian {
+    type Data = OwnedRepr<f64>;
+
+    fn into_qp_matrix(self) -> Result<Array2<f64>> {
+        (&self).into_qp_matrix()
+    }
+
+    fn sparse_hessian(&self) -> Option<&SparseHessian> {
+        Some(self)
+    }
+}
+
+impl IntoQpMatrix for &SparseHessian {
+    type Data = OwnedRepr<f64>;
+
+    /// Assembles the dense matrix.
+    fn into_qp_matrix(self) -> Result<Array2<f64>> {
+        let shape = (self.n, self.n);
+        utils::csc_to_dense(shape, &self.col_offsets, &self.row_indices, &self.values)
+    }
+
+    fn sparse_hessian(&self) -> Option<&SparseHessian> {
+        Some(self)
+    }
+}
+
+/// A sparse vector, as its nonzero elements and their indices
+type SparseVector = Vec<(usize, f64)>;
+
+fn sparse_dot(a: &[(usize, f64)], x: &[f64]) -> f64 {
+    a.iter().map(|&(i, a)| a * x[i]).sum()
+}
+
+/// The Cholesky factor \\(L\\) of \\(G = L L^\intercal\\), by columns, each of which starts with
+/// its diagonal element and continues in increasing order of the rows.
+struct Cholesky {
+    columns: Vec<SparseVector>,
+}
+
+impl Cholesky {
+    /// Factorizes `g` row by row, reading its upper triangle. Fails with
+    /// [`Error::SingularHessian`] if `g` isn't positive definite.
+    fn new(g: &SparseHessian) -> Result<Self> {
+        let n = g.n;
+        let mut columns = vec![Vec::new(); n];
+        // The elimination tree, built along with the rows
+        let mut parent = vec![None; n];
+        let mut visited = vec![usize::MAX; n];
+        let mut x = vec![0.0; n];
+        let mut pattern = Vec::new();
+        for k in 0..n {
+            // The pattern of row k of L is made of the paths from the rows of column k of G up
+            // the elimination tree.
+            pattern.clear();
+            visited[k] = k;
+            for (i, a) in g.upper(k) {
+                x[i] += a;
+                let mut j = i;
+                while visited[j] != k {
+                    visited[j] = k;
+                    pattern.push(j);
+                    match parent[j] {
+                        Some(p) => j = p,
+                        None => {
+                            parent[j] = Some(k);
+                            break;
+                        }
+                    }
+                }
+            }
+            pattern.sort_unstable();
+            let mut d = x[k];
+            x[k] = 0.0;
+            for &j in &pattern {
+                let column: &SparseVector = &columns[j];
+                let l_kj = x[j] / column[0].1;
+                x[j] = 0.0;
+                for &(i, l_ij) in &column[1..] {
+                    x[i] -= l_ij * l_kj;
+                }
+                d -= l_kj * l_kj;
+                columns[j].push((k, l_kj));
+            }
+            if d <= 0.0 {
+                return Err(Error::SingularHessian { pivot: k });
+            }
+            columns[k].push((k, d.sqrt()));
+        }
+        Ok(Self { columns })
+    }
+
+    /// Overwrites `b` with \\(G^{-1} b\\).
+    fn solve(&self, b: &mut [f64]) {
+        for (j, column) in self.columns.iter().enumerate() {
+            b[j] /= column[0].1;
+            let b_j = b[j];
+            for &(i, l_ij) in &column[1..] {
+                b[i] -= l_ij * b_j;
+            }
+        }
+        for (j, column) in self.columns.iter().enumerate().rev() {
+            let mut sum = b[j];
+            for &(i, l_ij) in &column[1..] {
+                sum -= l_ij * b[i];
+            }
+            b[j] = sum / column[0].1;
+        }
+    }
+}
+
+/// The directions of a step for the constraint with normal \\(n_p\\)
+struct Step {
+    /// \\(w = G^{-1} n_p\\)
+    w: Vec<f64>,
+    /// \\(v = W^\intercal n_p = N^\intercal w\\)
+    v: Vec<f64>,
+    /// \\(y = L_S^{-1} v\\)
+    y: Vec<f64>,
+    /// The negative of the step direction in the dual space, \\(r = S^{-1} v\\)
+    r: Vec<f64>,
+    /// The step direction in the primal space, \\(z = w - W r\\)
+    z: Vec<f64>,
+}
+
+impl Step {
+    /// Returns \\(n_p^\intercal G^{-1} n_p\\) and the part of it which isn't explained by the
+    /// active constraints, which vanishes if \\(n_p\\) depends on their normals.
+    fn pivot(&self, np: &[(usize, f64)]) -> (f64, f64) {
+        let total = sparse_dot(np, &self.w);
+        (total, total - dot(&self.y, &self.y))
+    }
+
+    /// Returns whether \\(n_p\\) is linearly dependent on the normals of the active constraints.
+    fn is_dependent(&self, np: &[(usize, f64)]) -> bool {
+        let (total, pivot) = self.pivot(np);
+        pivot <= DEPENDENCE * total
+    }
+}
+
+/// The active set of the range-space dual method, with \\(W = G^{-1} N\\) and the Cholesky factor
+/// \\(L_S\\) of \\(S = N^\intercal G^{-1} N\\), where \\(N\\) holds the normals of the active
+/// constraints.
+#[derive(Default)]
+struct ActiveSet {
+    /// The active constraints, equality constraints first
+    a: Vec<usize>,
+    /// The multipliers of the active constraints
+    u: Vec<f64>,
+    /// The columns of \\(W\\)
+    w: Vec<Vec<f64>>,
+    /// The rows of \\(S\\)
+    s: Vec<Vec<f64>>,
+    /// The rows of \\(L_S\\)
+    l: Vec<Vec<f64>>,
+}
+
+impl ActiveSet {
+    /// Computes the directions of a step for the constraint with normal `np`.
+    fn step(&self, g: &Cholesky, np: &[(usize, f64)]) -> Step {
+        let mut w = vec![0.0; g.columns.len()];
+        for &(i, np) in np {
+            w[i] = np;
+        }
+        g.solve(&mut w);
+        let v: Vec<_> = self.w.iter().map(|w| sparse_dot(np, w)).collect();
+        let q = v.len();
+        let mut y = v.clone();
+        for i in 0..q {
+            y[i] = (y[i] - dot(&self.l[i][..i], &y[..i])) / self.l[i][i];
+        }
+        let mut r = y.clone();
+        for i in (0..q).rev() {
+            let sum: f64 = (i + 1..q).map(|j| self.l[j][i] * r[j]).sum();
+            r[i] = (r[i] - sum) / self.l[i][i];
+        }
+        let mut z = w.clone();
+        for (w, &r) in self.w.iter().zip(&r) {
+            z.iter_mut().zip(w).for_each(|(z, w)| *z -= r * w);
+        }
+        Step { w, v, y, r, z }
+    }
+
+    /// Adds constraint `k` with multiplier `u`, whose normal \\(n_p\\) is independent of the
+    /// active ones, extending \\(L_S\\) by a row.
+    fn add(&mut self, k: usize, u: f64, np: &[(usize, f64)], step: Step) {
+        let (total, pivot) = step.pivot(np);
+        for (s, &v) in self.s.iter_mut().zip(&step.v) {
+            s.push(v);
+        }
+        let mut s = step.v;
+        s.push(total);
+        self.s.push(s);
+        let mut l = step.y;
+        l.push(pivot.sqrt());
+        self.l.push(l);
+        self.a.push(k);
+        self.u.push(u);
+        self.w.push(step.w);
+    }
+
+    /// Removes the active constraint at `position`, and factorizes \\(S\\) again.
+    fn delete(&mut self, position: usize) {
+        self.a.remove(position);
+        self.u.remove(position);
+        self.w.remove(position);
+        self.s.remove(position);
+        for s in &mut self.s {
+            s.remove(position);
+        }
+        let q = self.s.len();
+        self.l = vec![Vec::new(); q];
+        for i in 0..q {
+            for j in 0..=i {
+                let sum = self.s[i][j] - dot(&self.l[i][..j], &self.l[j][..j]);
+                let l = if i == j {
+                    sum.sqrt()
+                } else {
+                    sum / self.l[j][j]
+                };
+                self.l[i].push(l);
+            }
+        }
+    }
+}
+
+/// Solves the problem with the sparse `hessian`, or returns `None` if it has to be solved densely
+/// because of the options or the shapes.
+pub(crate) fn solve_sparse<G0, CE, CE0, CI, CI0>(
+    hessian: &SparseHessian,
+    g0: &ArrayBase<G0, Ix1>,
+    ce: &Option<Constraints<CE, CE0>>,
+    ci: &Option<Constraints<CI, CI0>>,
+    bounds: &Bounds,
+    options: &SolveOptions,
+) -> Option<Result<Solution>>
+where
+    G0: RawData<Elem = f64>,
+    CE: RawData<Elem = f64>,
+    CE0: RawData<Elem = f64>,
+    CI: RawData<Elem = f64>,
+    CI0: RawData<Elem = f64>,
+{
+    let n = hessian.n;
+    if *options != SolveOptions::default() {
+        return None;
+    }
+    let ce = ce.as_ref().map(block::owned);
+    let ci = ci.as_ref().map(block::owned);
+    let valid = g0.len() == n
+        && bounds.has_size(n)
+        && ce.iter().all(|ce| ce.has_size(n) && ce.upper.is_none())
+        && ci.iter().all(|ci| ci.has_size(n));
+    if !valid {
+        return None;
+    }
+    let g0 = unsafe { utils::row_major(g0) };
+    let sparse_column = |a: &Array2<f64>, j: usize, sign: f64| -> SparseVector {
+        a.column(j)
+            .iter()
+            .enumerate()
+            .filter(|&(_, &a)| a != 0.0)
+            .map(|(i, &a)| (i, sign * a))
+            .collect()
+    };
+    let mut normals = Vec::new();
+    let mut consts = Vec::new();
+    if let Some(ce) = &ce {
+        normals.extend((0..ce.consts.len()).map(|j| sparse_column(&ce.coeffs, j, 1.0)));
+        consts.extend(ce.consts.iter());
+    }
+    let p = normals.len();
+    let no_ci = Array2::zeros((n, 0));
+    let (ci_coeffs, ci0, upper, ci_names) = match &ci {
+        Some(ci) => {
+            let upper = ci.upper.as_ref().map(Array1::to_vec);
+            (&ci.coeffs, ci.consts.to_vec(), upper, &ci.names[..])
+        }
+        None => (&no_ci, Vec::new(), None, &[][..]),
+    };
+    let m = ci0.len();
+    let mut columns = Vec::new();
+    solver::push_columns(&mut columns, &mut consts, &ci0, upper.as_deref(), bounds);
+    normals.extend(columns.iter().map(|&c| match c {
+        Column::Lower(j) => sparse_column(ci_coeffs, j, 1.0),
+        Column::Upper(j) => sparse_column(ci_coeffs, j, -1.0),
+        Column::LowerBound(i) => vec![(i, 1.0)],
+        Column::UpperBound(i) => vec![(i, -1.0)],
+    }));
+    let describe = |k: usize| columns[k].describe(ci_names, bounds.names());
+    let output = solve_range_space(hessian, &g0, &normals, &consts, p, describe);
+    Some(output.map(|output| {
+        let mut multipliers = vec![0.0; normals.len()];
+        for (&k, &u) in output.active.a.iter().zip(&output.active.u) {
+            multipliers[k] = u;
+        }
+        let active = output.active.a.iter().filter_map(|&k| k.checked_sub(p));
+        let (multipliers, active_set, active_lower, active_upper) =
+            solver::unmap_columns(&columns, n, m, &multipliers, active);
+        Solution {
+            x: Array1::from(output.x),
+            status: Status::Optimal,
+            objective: output.objective,
+            multipliers,
+            active_set,
+            active_lower,
+            active_upper,
+            iterations: output.iterations,
+            factorizations: 1,
+            additions: output.additions,
+            deletions: output.deletions,
+            rotations: 0,
+            warm_started: false,
+            polished: false,
+            backend: SPARSE_NAME,
+        }
+    }))
+}
+
+/// The solution of the range-space dual method
+struct Output {
+    x: Vec<f64>,
+    objective: f64,
+    active: ActiveSet,
+    iterations: usize,
+    additions: usize,
+    deletions: usize,
+}
+
+/// Minimizes \\(\frac{1}{2} x^\intercal G x + g_0^\intercal x\\) subject to
+/// \\(n_i^\intercal x + b_i = 0\\) for the first `p` constraints and \\(\ge 0\\) for the
+/// others, by the dual method of Goldfarb and Idnani with the factorizations of [`ActiveSet`].
+/// `describe` describes inequality constraint `k - p` if the problem is infeasible.
+fn solve_range_space(
+    hessian: &SparseHessian,
+    g0: &[f64],
+    normals: &[SparseVector],
+    b: &[f64],
+    p: usize,
+    describe: impl Fn(usize) -> String,
+) -> Result<Output> {
+    let g = Cholesky::new(hessian)?;
+    // The unconstrained minimizer, x = -G^-1 g0
+    let mut x = g0.to_vec();
+    g.solve(&mut x);
+    x.iter_mut().for_each(|x| *x = -*x);
+    let mut set = ActiveSet::default();
+    let step_dual = |set: &mut ActiveSet, step: &Step, t: f64| {
+        set.u.iter_mut().zip(&step.r).for_each(|(u, r)| *u -= t * r);
+    };
+    for (i, np) in normals[..p].iter().enumerate() {
+        let step = set.step(&g, np);
+        if step.is_dependent(np) {
+            return Err(Error::LinearlyDependent);
+        }
+        // The step which makes the constraint feasible
+        let t = -(sparse_dot(np, &x) + b[i]) / sparse_dot(np, &step.z);
+        x.iter_mut().zip(&step.z).for_each(|(x, z)| *x += t * z);
+        step_dual(&mut set, &step, t);
+        set.add(i, t, np, step);
+    }
+
+    let (mut iterations, mut additions, mut deletions) = (0, p, 0);
+    loop {
+        iterations += 1;
+        // Step 1: choose the most violated constraint
+        let mut worst = 0.0;
+        let mut ip = None;
+        for (k, np) in normals.iter().enumerate().skip(p) {
+            let slack = sparse_dot(np, &x) + b[k];
+            let scale = np.iter().map(|&(i, a)| (a * x[i]).abs()).sum::<f64>() + b[k].abs();
+            if slack < worst && slack < -FEASIBILITY * scale && !set.a.contains(&k) {
+                worst = slack;
+                ip = Some(k);
+            }
+        }
+        let ip = match ip {
+            Some(ip) => ip,
+            None => break,
+        };
+        let np = &normals[ip];
+        let mut u_ip = 0.0;
+        loop {
+            // Step 2: determine the step direction and length
+            let step = set.step(&g, np);
+            // partial step length: maximum step in dual space without violating dual feasibility
+            let mut t1 = f64::INFINITY;
+            let mut l = 0;
+            for (position, (&k, &r)) in set.a.iter().zip(&step.r).enumerate() {
+                if k >= p && r > 0.0 && set.u[position] / r < t1 {
+                    t1 = set.u[position] / r;
+                    l = position;
+                }
+            }
+            // full step length: minimum step in primal space such that ip becomes feasible
+            let mut t2 = f64::INFINITY;
+            if !step.is_dependent(np) {
+                t2 = -(sparse_dot(np, &x) + b[ip]) / sparse_dot(np, &step.z);
+            }
+            let t = t1.min(t2);
+            if t >= f64::INFINITY {
+                return Err(Error::Infeasible {
+                    constraint: describe(ip - p),
+                });
+            }
+            if t2 < f64::INFINITY {
+                x.iter_mut().zip(&step.z).for_each(|(x, z)| *x += t * z);
+            }
+            step_dual(&mut set, &step, t);
+            u_ip += t;
+            if t2 <= t1 {
+                // full step: add constraint ip to the active set
+                set.add(ip, u_ip, np, step);
+                additions += 1;
+                break;
+            }
+            // partial step: drop constraint l
+            set.delete(l);
+            deletions += 1;
+        }
+    }
+
+    // The objective from the upper triangle of G
+    let mut objective = dot(g0, &x);
+    for j in 0..hessian.n {
+        for (i, a) in hessian.upper(j) {
+            let weight = if i == j { 0.5 } else { 1.0 };
+            objective += weight * a * x[i] * x[j];
+        }
+    }
+    Ok(Output {
+        x,
+        objective,
+        active: set,
+        iterations,
+        additions,
+        deletions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Array};
+
+    use super::*;
+    use crate::{solve, solve_with_bounds, solve_with_options, Multipliers};
+
+    /// Returns the \\(N \times N\\) tridiagonal matrix with 4 on the diagonal and -1 next to it,
+    /// densely and sparsely.
+    fn tridiagonal(n: usize) -> (Array2<f64>, SparseHessian) {
+        let dense = Array2::from_shape_fn((n, n), |(i, j)| match i.abs_diff(j) {
+            0 => 4.0,
+            1 => -1.0,
+            _ => 0.0,
+        });
+        let (mut col_offsets, mut row_indices, mut values) = (vec![0], Vec::new(), Vec::new());
+        for j in 0..n {
+            for i in j.saturating_sub(1)..(j + 2).min(n) {
+                row_indices.push(i);
+                values.push(dense[[i, j]]);
+            }
+            col_offsets.push(row_indices.len());
+        }
+        let sparse = SparseHessian::from_csc(n, &col_offsets, &row_indices, &values).unwrap();
+        (dense, sparse)
+    }
+
+    #[test]
+    fn matches_dense() -> Result<()> {
+        let n = 40;
+        let (dense, sparse) = tridiagonal(n);
+        assert_eq!((&sparse).into_qp_matrix()?, dense);
+        let g0 = Array::from_shape_fn(n, |i| 5.0 * (i as f64 * 0.7).sin());
+        // The sum of x is 1, x0 - x1 >= 2, and -0.2 <= x2 + x39 <= 0.1
+        let ce = Constraints::new(Array2::ones((n, 1)), array![-1.0]);
+        let mut ci = Array2::zeros((n, 2));
+        ci[[0, 0]] = 1.0;
+        ci[[1, 0]] = -1.0;
+        let mut ranged = Array2::zeros((n, 1));
+        ranged[[2, 0]] = 1.0;
+        ranged[[n - 1, 0]] = 1.0;
+        let bounds = Bounds::new(Array::from_elem(n, -0.3), Array::from_elem(n, 0.6));
+        let ci = Constraints::new(ci, array![-2.0, 1.0]);
+        let ranged = Constraints::ranged(ranged, array![-0.2], array![0.1]);
+        for (ci, bounds) in [(ci, Bounds::default()), (ranged, bounds)] {
+            let options = SolveOptions::default();
+            let expected = solve_with_bounds(
+                &dense,
+                &g0,
+                Some(ce.view()),
+                Some(ci.view()),
+                &bounds,
+                &options,
+            )?;
+            let solution =
+                solve_with_bounds(&sparse, &g0, Some(ce.view()), Some(ci), &bounds, &options)?;
+            assert_eq!(solution.backend, SPARSE_NAME);
+            assert_abs_diff_eq!(solution.objective, expected.objective, epsilon = 1e-10);
+            for (x, expected) in solution.x.iter().zip(&expected.x) {
+                assert_abs_diff_eq!(x, expected, epsilon = 1e-10);
+            }
+            let Multipliers {
+                eq,
+                ineq,
+                lower,
+                upper,
+            } = &solution.multipliers;
+            let actual = eq.iter().chain(ineq).chain(lower).chain(upper);
+            let m = &expected.multipliers;
+            let expected_multipliers = m.eq.iter().chain(&m.ineq).chain(&m.lower).chain(&m.upper);
+            for (u, expected) in actual.zip(expected_multipliers) {
+                assert_abs_diff_eq!(u, expected, epsilon = 1e-9);
+            }
+            assert!(!solution.active_set.is_empty());
+            assert_eq!(solution.active_set, expected.active_set);
+            assert_eq!(solution.active_lower, expected.active_lower);
+            assert_eq!(solution.active_upper, expected.active_upper);
+        }
+
+        // min |x|^2 s.t. 2 x0 + 2 x1 >= 4, x0 >= 3: the first constraint is dropped by a
+        // partial step
+        let g = SparseHessian::from_csc(2, &[0, 1, 2], &[0, 1], &[2.0, 2.0])?;
+        let ci = Constraints::some(array![[2.0, 1.0], [2.0, 0.0]], array![-4.0, -3.0]);
+        let solution = solve(&g, array![0.0, 0.0], Constraints::NONE, ci)?;
+        assert_abs_diff_eq!(solution.x[0], 3.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(solution.x[1], 0.0, epsilon = 1e-12);
+        assert_eq!((solution.active_set, solution.deletions), (vec![1], 1));
+
+        // The dense solver for other options
+        let options = SolveOptions::new().max_iterations(100);
+        let solution = solve_with_options(&sparse, &g0, Some(ce), Constraints::NONE, &options)?;
+        assert_ne!(solution.backend, SPARSE_NAME);
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        // x0 <= 0 and x0 >= 1
+        let (_, g) = tridiagonal(2);
+        let ci = Constraints::new(array![[-1.0, 1.0], [0.0, 0.0]], array![0.0, -1.0]);
+        let err = solve(&g, array![0.0, 0.0], Constraints::NONE, Some(ci)).unwrap_err();
+        assert!(
+            matches!(&err, Error::Infeasible { constraint } if constraint == "inequality constraint 0"),
+            "unexpected error: {:?}",
+            err
+        );
+        // x0 + x1 = 1 twice
+        let ce = Constraints::some(Array2::ones((2, 2)), array![-1.0, -1.0]);
+        let err = solve(&g, array![0.0, 0.0], ce, Constraints::NONE).unwrap_err();
+        assert!(
+            matches!(err, Error::LinearlyDependent),
+            "unexpected error: {:?}",
+            err
+        );
+        let singular = SparseHessian::from_csc(2, &[0, 2, 4], &[0, 1, 0, 1], &[1.0; 4]).unwrap();
+        let err = solve(
+            &singular,
+            array![0.0, 0.0],
+            Constraints::NONE,
+            Constraints::NONE,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+
+        // The upper triangle only
+        let err = SparseHessian::from_csc(2, &[0, 1, 3], &[0, 0, 1], &[2.0, 1.0, 3.0]).unwrap_err();
+        assert!(
+            matches!(err, Error::NotSymmetric { row: 1, col: 0 }),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = SparseHessian::from_csc(2, &[0, 1, 2], &[0, 2], &[1.0, 1.0]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "row_indices",
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
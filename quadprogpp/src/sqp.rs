@@ -0,0 +1,486 @@
+//! Sequential quadratic programming for smooth nonlinear problems.
+//!
+//! [`solve`] minimizes a smooth function \\(f(x)\\) subject to nonlinear constraints
+//! \\(c_E(x) = 0\\) and \\(c_I(x) \ge 0\\), and bounds on the variables. Each iteration
+//! linearizes the constraints at the current point \\(x\\) and solves the quadratic subproblem
+//!
+//! $$
+//! \min_{d} \quad \frac{1}{2} d^\intercal B d + \nabla f(x)^\intercal d
+//! \quad \textrm{subject to} \quad
+//! \nabla c_E(x)^\intercal d + c_E(x) = 0, \quad \nabla c_I(x)^\intercal d + c_I(x) \ge 0,
+//! \quad lb \le x + d \le ub
+//! $$
+//!
+//! by [`solve_with_bounds`](crate::solve_with_bounds), where \\(B\\) is a damped BFGS
+//! approximation of the Hessian of the Lagrangian, which stays positive definite as the dual
+//! method requires. The step along \\(d\\) is chosen by a backtracking line search on the
+//! \\(\ell_1\\) merit function
+//! \\(f(x) + \rho (\lVert c_E(x) \rVert_1 + \lVert \min(c_I(x), 0) \rVert_1)\\), where \\(\rho\\)
+//! exceeds the magnitude of every multiplier.
+//!
+//! The method converges to a local minimum from a starting point close enough to it, but isn't
+//! guaranteed to converge from anywhere; in particular, the subproblem may be infeasible far from
+//! the solution even if the problem isn't.
+
+use ndarray::{Array1, Array2, ArrayBase, ArrayView1, Data, Ix1};
+
+use crate::{Bounds, Constraints, Error, Multipliers, Result, SolveOptions};
+
+/// A smooth nonlinear problem solved by [`solve`].
+///
+/// The Jacobians are laid out like the constraint matrices of [`solve`](crate::solve): they have
+/// one row per variable and one column per constraint, i.e. each column is the gradient of a
+/// constraint.
+pub trait NonlinearProblem {
+    /// Returns the number of variables.
+    fn dim(&self) -> usize;
+
+    /// Evaluates the objective \\(f(x)\\).
+    fn objective(&self, x: ArrayView1<'_, f64>) -> f64;
+
+    /// Evaluates the gradient \\(\nabla f(x)\\).
+    fn gradient(&self, x: ArrayView1<'_, f64>) -> Array1<f64>;
+
+    /// Evaluates the equality constraints \\(c_E(x) = 0\\). There are none by default.
+    fn eq(&self, _x: ArrayView1<'_, f64>) -> Array1<f64> {
+        Array1::zeros(0)
+    }
+
+    /// Evaluates the Jacobian of the equality constraints, \\(\nabla c_E(x)\\).
+    fn eq_jacobian(&self, _x: ArrayView1<'_, f64>) -> Array2<f64> {
+        Array2::zeros((self.dim(), 0))
+    }
+
+    /// Evaluates the inequality constraints \\(c_I(x) \ge 0\\). There are none by default.
+    fn ineq(&self, _x: ArrayView1<'_, f64>) -> Array1<f64> {
+        Array1::zeros(0)
+    }
+
+    /// Evaluates the Jacobian of the inequality constraints, \\(\nabla c_I(x)\\).
+    fn ineq_jacobian(&self, _x: ArrayView1<'_, f64>) -> Array2<f64> {
+        Array2::zeros((self.dim(), 0))
+    }
+
+    /// Returns the bounds on the variables, which every iterate satisfies. There are none by
+    /// default.
+    fn bounds(&self) -> Bounds {
+        Bounds::default()
+    }
+}
+
+/// Options of [`solve`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SqpOptions {
+    max_iterations: usize,
+    tolerance: f64,
+    qp_options: SolveOptions,
+}
+
+impl Default for SqpOptions {
+    fn default() -> Self {
+        Self {
+            max_iterations: 100,
+            tolerance: 1e-8,
+            qp_options: SolveOptions::default(),
+        }
+    }
+}
+
+impl SqpOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the number of iterations, i.e. of quadratic subproblems. Defaults to 100.
+    ///
+    /// [`solve`] fails with [`Error::MaxIterations`] when the limit is reached.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sets the tolerance of the convergence test, which requires the step and the violation of
+    /// the constraints to be within it. Defaults to `1e-8`.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the options of the quadratic subproblems.
+    pub fn qp_options(mut self, options: SolveOptions) -> Self {
+        self.qp_options = options;
+        self
+    }
+}
+
+/// A local solution of a [`NonlinearProblem`], returned by [`solve`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SqpSolution {
+    /// The local minimizer \\(x\\)
+    pub x: Array1<f64>,
+    /// The objective \\(f(x)\\)
+    pub objective: f64,
+    /// Lagrange multipliers of the constraints and the bounds, from the last subproblem, which
+    /// satisfy \\(\nabla f(x) = \nabla c_E(x) \lambda_{eq} + \nabla c_I(x) \lambda_{ineq} +
+    /// \lambda_{lower} - \lambda_{upper}\\) at convergence
+    pub multipliers: Multipliers,
+    /// Number of quadratic subproblems solved
+    pub iterations: usize,
+}
+
+/// The values of the problem at a point
+struct Point {
+    x: Array1<f64>,
+    objective: f64,
+    gradient: Array1<f64>,
+    eq: Array1<f64>,
+    eq_jacobian: Array2<f64>,
+    ineq: Array1<f64>,
+    ineq_jacobian: Array2<f64>,
+}
+
+impl Point {
+    fn new<P: NonlinearProblem + ?Sized>(problem: &P, x: Array1<f64>) -> Result<Self> {
+        let n = x.len();
+        let point = Point {
+            objective: problem.objective(x.view()),
+            gradient: problem.gradient(x.view()),
+            eq: problem.eq(x.view()),
+            eq_jacobian: problem.eq_jacobian(x.view()),
+            ineq: problem.ineq(x.view()),
+            ineq_jacobian: problem.ineq_jacobian(x.view()),
+            x,
+        };
+        assert_size!(gradient, n, point.gradient.len());
+        assert_size!(eq_jacobian, n, point.eq_jacobian.nrows());
+        assert_size!(eq_jacobian, point.eq.len(), point.eq_jacobian.ncols());
+        assert_size!(ineq_jacobian, n, point.ineq_jacobian.nrows());
+        assert_size!(ineq_jacobian, point.ineq.len(), point.ineq_jacobian.ncols());
+        Ok(point)
+    }
+
+    /// Returns the \\(\ell_1\\) norm of the violation of the constraints.
+    fn violation(&self) -> f64 {
+        self.eq.iter().map(|c| c.abs()).sum::<f64>()
+            + self.ineq.iter().map(|c| (-c).max(0.0)).sum::<f64>()
+    }
+
+    fn merit(&self, rho: f64) -> f64 {
+        self.objective + rho * self.violation()
+    }
+
+    /// Returns the gradient of the Lagrangian, without the bounds whose gradients are constant.
+    fn lagrangian_gradient(&self, multipliers: &Multipliers) -> Array1<f64> {
+        let eq = ArrayView1::from(&multipliers.eq);
+        let ineq = ArrayView1::from(&multipliers.ineq);
+        &self.gradient - &self.eq_jacobian.dot(&eq) - &self.ineq_jacobian.dot(&ineq)
+    }
+}
+
+/// Minimizes `problem` locally by sequential quadratic programming, starting from `x0`.
+///
+/// The starting point is projected onto the bounds first, so that every iterate satisfies them.
+///
+/// # Errors
+///
+/// * If `x0` doesn't have one element per variable, or the gradient or a Jacobian doesn't have
+///   the size implied by the number of variables and constraints, this function returns
+///   [`Error::SizeMismatch`].
+/// * If it doesn't converge within [`SqpOptions::max_iterations`], it returns
+///   [`Error::MaxIterations`].
+/// * It forwards the errors of the quadratic subproblems, e.g. [`Error::Infeasible`] if the
+///   linearized constraints are inconsistent.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::{array, Array1, Array2, ArrayView1};
+/// # use quadprogpp::Error;
+/// use quadprogpp::sqp::{self, NonlinearProblem, SqpOptions};
+///
+/// // min x + y s.t. x^2 + y^2 = 2
+/// struct Circle;
+///
+/// impl NonlinearProblem for Circle {
+///     fn dim(&self) -> usize {
+///         2
+///     }
+///     fn objective(&self, x: ArrayView1<f64>) -> f64 {
+///         x[0] + x[1]
+///     }
+///     fn gradient(&self, _x: ArrayView1<f64>) -> Array1<f64> {
+///         array![1.0, 1.0]
+///     }
+///     fn eq(&self, x: ArrayView1<f64>) -> Array1<f64> {
+///         array![x[0] * x[0] + x[1] * x[1] - 2.0]
+///     }
+///     fn eq_jacobian(&self, x: ArrayView1<f64>) -> Array2<f64> {
+///         array![[2.0 * x[0]], [2.0 * x[1]]]
+///     }
+/// }
+///
+/// let solution = sqp::solve(&Circle, array![-0.5, -1.5], &SqpOptions::default())?;
+/// assert!((solution.x[0] + 1.0).abs() < 1e-6);
+/// assert!((solution.x[1] + 1.0).abs() < 1e-6);
+/// assert!((solution.multipliers.eq[0] + 0.5).abs() < 1e-6);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn solve<P, S>(problem: &P, x0: ArrayBase<S, Ix1>, options: &SqpOptions) -> Result<SqpSolution>
+where
+    P: NonlinearProblem + ?Sized,
+    S: Data<Elem = f64>,
+{
+    let n = problem.dim();
+    assert_size!(x0, n, x0.len());
+    let bounds = problem.bounds();
+    let lower = |i: usize| {
+        bounds
+            .lower_bounds()
+            .get(i)
+            .copied()
+            .unwrap_or(f64::NEG_INFINITY)
+    };
+    let upper = |i: usize| {
+        bounds
+            .upper_bounds()
+            .get(i)
+            .copied()
+            .unwrap_or(f64::INFINITY)
+    };
+    let x = Array1::from_shape_fn(n, |i| x0[i].max(lower(i)).min(upper(i)));
+    let mut point = Point::new(problem, x)?;
+    let mut hessian = Array2::eye(n);
+    let mut rho = 0.0_f64;
+
+    for iteration in 1..=options.max_iterations {
+        let ce = Constraints::new(point.eq_jacobian.view(), point.eq.view());
+        let ci = Constraints::new(point.ineq_jacobian.view(), point.ineq.view());
+        let step_bounds = Bounds::new(
+            Array1::from_shape_fn(n, |i| lower(i) - point.x[i]),
+            Array1::from_shape_fn(n, |i| upper(i) - point.x[i]),
+        );
+        let qp = crate::solve_with_bounds(
+            hessian.view(),
+            point.gradient.view(),
+            (!point.eq.is_empty()).then_some(ce),
+            (!point.ineq.is_empty()).then_some(ci),
+            &step_bounds,
+            &options.qp_options,
+        )?;
+        let d = qp.x;
+        let multipliers = qp.multipliers;
+
+        let violation = point
+            .eq
+            .iter()
+            .map(|c| c.abs())
+            .chain(point.ineq.iter().map(|c| -c))
+            .fold(0.0, f64::max);
+        let step = d.iter().fold(0.0, |max: f64, d| max.max(d.abs()));
+        if step <= options.tolerance && violation <= options.tolerance {
+            return Ok(SqpSolution {
+                objective: point.objective,
+                x: point.x,
+                multipliers,
+                iterations: iteration,
+            });
+        }
+
+        // The penalty exceeds the multipliers, so that d is a descent direction of the merit
+        // function.
+        let max_multiplier = multipliers
+            .eq
+            .iter()
+            .chain(&multipliers.ineq)
+            .fold(0.0, |max: f64, u| max.max(u.abs()));
+        rho = rho.max(1.1 * max_multiplier + options.tolerance);
+        let merit = point.merit(rho);
+        let derivative = point.gradient.dot(&d) - rho * point.violation();
+        let mut alpha = 1.0;
+        let mut next = Point::new(problem, &point.x + &d)?;
+        let mut accepted = true;
+        while next.merit(rho) > merit + 1e-4 * alpha * derivative {
+            if alpha < 1e-10 {
+                accepted = false;
+                break;
+            }
+            alpha *= 0.5;
+            next = Point::new(problem, &point.x + &(alpha * &d))?;
+        }
+
+        if accepted {
+            // The damped BFGS update of Powell, which keeps the approximation positive definite
+            let s = &next.x - &point.x;
+            let y =
+                next.lagrangian_gradient(&multipliers) - point.lagrangian_gradient(&multipliers);
+            let bs = hessian.dot(&s);
+            let sbs = s.dot(&bs);
+            let sy = s.dot(&y);
+            if sbs > 0.0 {
+                let theta = if sy >= 0.2 * sbs {
+                    1.0
+                } else {
+                    0.8 * sbs / (sbs - sy)
+                };
+                let r = theta * &y + (1.0 - theta) * &bs;
+                let sr = s.dot(&r);
+                hessian = hessian - outer(&bs, &bs) / sbs + outer(&r, &r) / sr;
+            }
+        } else {
+            // The line search failed, so the model is restarted from the identity.
+            hessian = Array2::eye(n);
+        }
+        point = next;
+    }
+    Err(Error::MaxIterations {
+        iterations: options.max_iterations,
+    })
+}
+
+/// Returns \\(a b^\intercal\\).
+fn outer(a: &Array1<f64>, b: &Array1<f64>) -> Array2<f64> {
+    Array2::from_shape_fn((a.len(), b.len()), |(i, j)| a[i] * b[j])
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (&actual, &expected) in actual.iter().zip(expected) {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-6);
+        }
+    }
+
+    /// The Rosenbrock function with the disk \\(x^2 + y^2 \le 1.5\\) and the bound
+    /// \\(x \le 0.9\\), or without them
+    struct Rosenbrock {
+        constrained: bool,
+    }
+
+    impl NonlinearProblem for Rosenbrock {
+        fn dim(&self) -> usize {
+            2
+        }
+
+        fn objective(&self, x: ArrayView1<'_, f64>) -> f64 {
+            (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2)
+        }
+
+        fn gradient(&self, x: ArrayView1<'_, f64>) -> Array1<f64> {
+            array![
+                -2.0 * (1.0 - x[0]) - 400.0 * x[0] * (x[1] - x[0] * x[0]),
+                200.0 * (x[1] - x[0] * x[0]),
+            ]
+        }
+
+        fn ineq(&self, x: ArrayView1<'_, f64>) -> Array1<f64> {
+            if self.constrained {
+                array![1.5 - x[0] * x[0] - x[1] * x[1]]
+            } else {
+                Array1::zeros(0)
+            }
+        }
+
+        fn ineq_jacobian(&self, x: ArrayView1<'_, f64>) -> Array2<f64> {
+            if self.constrained {
+                array![[-2.0 * x[0]], [-2.0 * x[1]]]
+            } else {
+                Array2::zeros((2, 0))
+            }
+        }
+
+        fn bounds(&self) -> Bounds {
+            if self.constrained {
+                Bounds::upper(array![0.9, f64::INFINITY])
+            } else {
+                Bounds::default()
+            }
+        }
+    }
+
+    #[test]
+    fn rosenbrock() -> Result<()> {
+        let options = SqpOptions::new().max_iterations(200);
+        let unconstrained = Rosenbrock { constrained: false };
+        let solution = solve(&unconstrained, array![-1.2, 1.0], &options)?;
+        assert_close(solution.x.as_slice().unwrap(), &[1.0, 1.0]);
+        assert_abs_diff_eq!(solution.objective, 0.0, epsilon = 1e-10);
+
+        // The bound is active at (0.9, 0.81), inside the disk. The start is projected onto it.
+        let constrained = Rosenbrock { constrained: true };
+        let solution = solve(&constrained, array![1.2, -1.0], &options)?;
+        assert_close(solution.x.as_slice().unwrap(), &[0.9, 0.81]);
+        assert_eq!(solution.multipliers.ineq, [0.0]);
+        // The gradient of f is (-0.2, 0) there.
+        assert_abs_diff_eq!(solution.multipliers.upper[0], 0.2, epsilon = 1e-6);
+        Ok(())
+    }
+
+    /// \\(\min -x y\\) s.t. \\(x + 2 y \le 4\\) and \\(x^2 + y^2 \le 5\\)
+    struct Product;
+
+    impl NonlinearProblem for Product {
+        fn dim(&self) -> usize {
+            2
+        }
+
+        fn objective(&self, x: ArrayView1<'_, f64>) -> f64 {
+            -x[0] * x[1]
+        }
+
+        fn gradient(&self, x: ArrayView1<'_, f64>) -> Array1<f64> {
+            array![-x[1], -x[0]]
+        }
+
+        fn ineq(&self, x: ArrayView1<'_, f64>) -> Array1<f64> {
+            array![4.0 - x[0] - 2.0 * x[1], 5.0 - x[0] * x[0] - x[1] * x[1]]
+        }
+
+        fn ineq_jacobian(&self, x: ArrayView1<'_, f64>) -> Array2<f64> {
+            array![[-1.0, -2.0 * x[0]], [-2.0, -2.0 * x[1]]]
+        }
+    }
+
+    #[test]
+    fn nonlinear_inequality() -> Result<()> {
+        // Both constraints are active at (2, 1), but only the linear one is binding:
+        // (-1, -2) = -u (1, 2) - v (4, 2) with u = 1 and v = 0.
+        let solution = solve(&Product, array![0.5, 0.5], &SqpOptions::default())?;
+        assert_close(solution.x.as_slice().unwrap(), &[2.0, 1.0]);
+        assert_abs_diff_eq!(solution.objective, -2.0, epsilon = 1e-6);
+        assert_close(&solution.multipliers.ineq, &[1.0, 0.0]);
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let err = solve(&Product, array![1.0], &SqpOptions::default()).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "x0",
+                    expected: 2,
+                    actual: 1,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+
+        let options = SqpOptions::new().max_iterations(2);
+        let err = solve(&Product, array![0.5, 0.5], &options).unwrap_err();
+        assert!(
+            matches!(err, Error::MaxIterations { iterations: 2 }),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use ndarray::{ArrayBase, Data, Ix1, Ix2};
+
+use crate::{solve_with_options, Constraints, Error, Result, Solution, SolveOptions};
+
+/// Solves a quadratic programming problem in the standard form used by OSQP, CVXPY and Python's
+/// `quadprog`/`qpsolvers`:
+///
+/// $$
+/// \begin{align*}
+/// &\min_{x} \quad \frac{1}{2} x^\intercal P x + q^\intercal x\\\\
+/// &\textrm{subject to} \\\\
+/// &\qquad\qquad
+/// \begin{aligned}
+/// A x &= b \\\\
+/// G x &\le h
+/// \end{aligned}
+/// \end{align*}
+/// $$
+/// where each row of \\(A\\) and \\(G\\) is a constraint. The shapes of the matrices and the
+/// vectors are:
+///
+/// * \\(P\\) is an \\(N \times N\\) matrix
+/// * \\(q\\) is an \\(N\\)-vector
+/// * \\(A\\) is a \\(P \times N\\) matrix
+/// * \\(b\\) is a \\(P\\)-vector
+/// * \\(G\\) is an \\(M \times N\\) matrix
+/// * \\(h\\) is an \\(M\\)-vector
+///
+/// Pass matrices with zero rows to omit the equality or inequality constraints.
+///
+/// The multipliers of the solution follow the same convention: they satisfy
+/// \\(P x + q + A^\intercal \lambda_{eq} + G^\intercal \lambda_{ineq} = 0\\) with
+/// \\(\lambda_{ineq} \ge 0\\). Note that the sign of [`Multipliers::eq`](crate::Multipliers::eq)
+/// is the opposite of the one returned by [`solve`](crate::solve).
+///
+/// # Errors
+///
+/// Same as [`solve`](crate::solve).
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::{array, Array1, Array2};
+/// # use quadprogpp::*;
+/// // min x^2 + y^2 s.t. x + y = 1, x <= 0.25
+/// let p = array![[2.0, 0.0], [0.0, 2.0]];
+/// let q = array![0.0, 0.0];
+/// let a = array![[1.0, 1.0]];
+/// let b = array![1.0];
+/// let g = array![[1.0, 0.0]];
+/// let h = array![0.25];
+/// let solution = solve_standard(&p, &q, &a, &b, &g, &h)?;
+/// assert_eq!(solution.x, array![0.25, 0.75]);
+///
+/// let solution = solve_standard(&p, &q, &a, &b, &Array2::zeros((0, 2)), &Array1::zeros(0))?;
+/// assert_eq!(solution.x, array![0.5, 0.5]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn solve_standard<P, Q, A, B, G, H>(
+    p: &ArrayBase<P, Ix2>,
+    q: &ArrayBase<Q, Ix1>,
+    a: &ArrayBase<A, Ix2>,
+    b: &ArrayBase<B, Ix1>,
+    g: &ArrayBase<G, Ix2>,
+    h: &ArrayBase<H, Ix1>,
+) -> Result<Solution>
+where
+    P: Data<Elem = f64>,
+    Q: Data<Elem = f64>,
+    A: Data<Elem = f64>,
+    B: Data<Elem = f64>,
+    G: Data<Elem = f64>,
+    H: Data<Elem = f64>,
+{
+    let n = q.len();
+    assert_size!(a, n, a.ncols());
+    assert_size!(b, a.nrows(), b.len());
+    assert_size!(g, n, g.ncols());
+    assert_size!(h, g.nrows(), h.len());
+    let ce = a.t().as_standard_layout().into_owned();
+    let ce0 = -b;
+    let ci = -&g.t().as_standard_layout();
+    let ci0 = h.as_standard_layout();
+    let mut solution = solve_with_options(
+        p.as_standard_layout(),
+        q.as_standard_layout(),
+        Constraints::some(ce, ce0),
+        Constraints::some(ci, ci0),
+        &SolveOptions::default(),
+    )?;
+    for y in &mut solution.multipliers.eq {
+        *y = -*y;
+    }
+    Ok(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+    use crate::solve;
+
+    #[test]
+    fn matches_solve() -> Result<()> {
+        #[rustfmt::skip]
+        let p = array![
+            [4.0, -2.0],
+            [-2.0, 4.0],
+        ];
+        let q = array![6.0, 0.0];
+        let a = array![[1.0, 1.0]];
+        let b = array![3.0];
+        // x >= 0, x + y >= 2, y >= 0
+        #[rustfmt::skip]
+        let g = array![
+            [-1.0,  0.0],
+            [-1.0, -1.0],
+            [ 0.0, -1.0],
+        ];
+        let h = array![0.0, -2.0, 0.0];
+        let standard = solve_standard(&p, &q, &a, &b, &g, &h)?;
+        let expected = solve(
+            p.view(),
+            q.view(),
+            Constraints::some(array![[1.0], [1.0]], array![-3.0]),
+            Constraints::some(
+                array![[1.0, 1.0, 0.0], [0.0, 1.0, 1.0]],
+                array![0.0, -2.0, 0.0],
+            ),
+        )?;
+        assert_abs_diff_eq!(standard.objective, expected.objective, epsilon = 1e-12);
+        assert_abs_diff_eq!(standard.x[0], 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(standard.x[1], 2.0, epsilon = 1e-12);
+        assert_eq!(standard.multipliers.eq, [-expected.multipliers.eq[0]]);
+        assert_eq!(standard.multipliers.ineq, expected.multipliers.ineq);
+
+        // P x + q + A^T y + G^T z = 0
+        let y = array![standard.multipliers.eq[0]];
+        let z = array![
+            standard.multipliers.ineq[0],
+            standard.multipliers.ineq[1],
+            standard.multipliers.ineq[2]
+        ];
+        let residual = p.dot(&standard.x) + &q + a.t().dot(&y) + g.t().dot(&z);
+        assert_abs_diff_eq!(residual[0], 0.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(residual[1], 0.0, epsilon = 1e-12);
+
+        let err = solve_standard(&p, &q, &a, &array![3.0, 4.0], &g, &h).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    expected: 1,
+                    actual: 2,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+}
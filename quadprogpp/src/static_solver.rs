@@ -0,0 +1,217 @@
+use core::slice;
+
+use crate::goldfarb_idnani::{self, Workspace};
+use crate::Result;
+
+/// A solver for problems with `N` variables, `P` equality constraints and `M` inequality
+/// constraints, which keeps all its storage in arrays, e.g. for model predictive control at a
+/// fixed horizon length on a microcontroller.
+///
+/// It runs the pure-Rust port of QuadProg++ which serves [`solve_slices`](crate::solve_slices)
+/// without the `cpp` feature, so that it returns the same bits, and is available when the port is
+/// built, i.e. without the `cpp` feature or with the `pure-rust` one. [`StaticSolver::solve`]
+/// doesn't allocate, except for the description of the constraint in
+/// [`Error::Infeasible`](crate::Error::Infeasible), and its running time only depends on the
+/// problem, not on previous solves.
+///
+/// The storage takes about \\(8 (3 N^2 + 11 N + 2 M)\\) bytes on 64-bit targets, so that large
+/// solvers are better kept in a `static` than on the stack, which [`StaticSolver::new`] allows as
+/// a `const fn`.
+///
+/// # Examples
+///
+/// ```
+/// # use quadprogpp::*;
+/// // The demo of QuadProg++
+/// let mut solver = StaticSolver::<2, 1, 3>::new();
+/// let mut x = [0.0; 2];
+/// let objective = solver.solve(
+///     &[[4.0, -2.0], [-2.0, 4.0]],
+///     &[6.0, 0.0],
+///     &[[1.0], [1.0]],
+///     &[-3.0],
+///     &[[1.0, 0.0, 1.0], [0.0, 1.0, 1.0]],
+///     &[0.0, 0.0, -2.0],
+///     &mut x,
+/// )?;
+/// assert!((objective - 12.0).abs() < 1e-12);
+/// assert!((x[0] - 1.0).abs() < 1e-12 && (x[1] - 2.0).abs() < 1e-12);
+/// # Ok::<(), Error>(())
+/// ```
+pub struct StaticSolver<const N: usize, const P: usize, const M: usize> {
+    matrices: [[[f64; N]; N]; 3],
+    vectors: [[f64; N]; 9],
+    indices: [[isize; N]; 2],
+    iai: [isize; M],
+    iaexcl: [bool; M],
+    s: [f64; M],
+}
+
+impl<const N: usize, const P: usize, const M: usize> StaticSolver<N, P, M> {
+    /// Creates a solver.
+    pub const fn new() -> Self {
+        Self {
+            matrices: [[[0.0; N]; N]; 3],
+            vectors: [[0.0; N]; 9],
+            indices: [[0; N]; 2],
+            iai: [0; M],
+            iaexcl: [true; M],
+            s: [0.0; M],
+        }
+    }
+
+    /// Solves the problem of [`solve_slices`](crate::solve_slices) with `N` variables, given as
+    /// arrays of rows, and writes the minimizer into `x`. Returns the value of the objective
+    /// function.
+    ///
+    /// # Errors
+    ///
+    /// * If the problem doesn't have a feasible solution, this function returns
+    ///   [`Error::Infeasible`](crate::Error::Infeasible).
+    /// * If \\(G\\) isn't positive definite, it returns
+    ///   [`Error::SingularHessian`](crate::Error::SingularHessian).
+    /// * If the constraints are linearly dependent, it returns
+    ///   [`Error::LinearlyDependent`](crate::Error::LinearlyDependent).
+    #[allow(clippy::too_many_arguments)]
+    pub fn solve(
+        &mut self,
+        g: &[[f64; N]; N],
+        g0: &[f64; N],
+        ce: &[[f64; P]; N],
+        ce0: &[f64; P],
+        ci: &[[f64; M]; N],
+        ci0: &[f64; M],
+        x: &mut [f64; N],
+    ) -> Result<f64> {
+        let [l, r, j] = &mut self.matrices;
+        let [e, z, y, d, np, r_vector, x_old, u, u_old] = &mut self.vectors;
+        let [a, a_old] = &mut self.indices;
+        let mut workspace = Workspace {
+            matrices: [flatten_mut(l), flatten_mut(r), flatten_mut(j)],
+            vectors: [e, z, y, d, np, r_vector, x_old, u, u_old],
+            indices: [a, a_old],
+            iai: &mut self.iai,
+            iaexcl: &mut self.iaexcl,
+            s: &mut self.s,
+        };
+        let output = goldfarb_idnani::solve_full(
+            N,
+            flatten(g),
+            g0,
+            flatten(ce),
+            ce0,
+            flatten(ci),
+            ci0,
+            x,
+            &mut workspace,
+            goldfarb_idnani::inequality,
+        )?;
+        Ok(output.objective)
+    }
+}
+
+/// Returns the rows of a matrix as one row-major slice.
+fn flatten<const N: usize>(rows: &[[f64; N]]) -> &[f64] {
+    // `[[f64; N]; K]` has the layout of `[f64; K * N]`.
+    unsafe { slice::from_raw_parts(rows.as_ptr().cast(), rows.len() * N) }
+}
+
+/// Returns the rows of a matrix as one mutable row-major slice.
+fn flatten_mut<const N: usize>(rows: &mut [[f64; N]]) -> &mut [f64] {
+    unsafe { slice::from_raw_parts_mut(rows.as_mut_ptr().cast(), rows.len() * N) }
+}
+
+impl<const N: usize, const P: usize, const M: usize> Default for StaticSolver<N, P, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn matches_port() {
+        // min |x - (1, 2, 3)|^2 / 2 s.t. x0 + x1 + x2 = 2, x0 >= 1.5, x2 - x1 <= 0.5
+        let g = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let g0 = [-1.0, -2.0, -3.0];
+        let ce = [[1.0], [1.0], [1.0]];
+        let ci = [[1.0, 0.0], [0.0, 1.0], [0.0, -1.0]];
+        let (ce0, ci0) = ([-2.0], [-1.5, 0.5]);
+        let mut expected = [0.0; 3];
+        let expected_objective = goldfarb_idnani::solve(
+            3,
+            g.as_flattened(),
+            &g0,
+            ce.as_flattened(),
+            &ce0,
+            ci.as_flattened(),
+            &ci0,
+            &mut expected,
+        )
+        .unwrap();
+        let mut solver = StaticSolver::<3, 1, 2>::new();
+        // The second solve starts from the state left by the first.
+        for _ in 0..2 {
+            let mut x = [0.0; 3];
+            let objective = solver.solve(&g, &g0, &ce, &ce0, &ci, &ci0, &mut x).unwrap();
+            assert_eq!((objective, x), (expected_objective, expected));
+        }
+
+        // Without constraints
+        let mut x = [0.0; 2];
+        let objective = StaticSolver::<2, 0, 0>::default()
+            .solve(
+                &[[1.0, 0.0], [0.0, 1.0]],
+                &[-1.0, 2.0],
+                &[[]; 2],
+                &[],
+                &[[]; 2],
+                &[],
+                &mut x,
+            )
+            .unwrap();
+        assert_eq!((objective, x), (-2.5, [1.0, -2.0]));
+    }
+
+    #[test]
+    fn errors() {
+        let mut solver = StaticSolver::<2, 0, 2>::new();
+        let mut x = [0.0; 2];
+        let identity = [[1.0, 0.0], [0.0, 1.0]];
+        // x0 <= 0 and x0 >= 1
+        let ci = [[-1.0, 1.0], [0.0, 0.0]];
+        let err = (solver.solve(
+            &identity,
+            &[0.0; 2],
+            &[[]; 2],
+            &[],
+            &ci,
+            &[0.0, -1.0],
+            &mut x,
+        ))
+        .unwrap_err();
+        assert!(
+            matches!(&err, Error::Infeasible { constraint } if constraint == "inequality constraint 0"),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = (solver.solve(
+            &[[1.0; 2]; 2],
+            &[0.0; 2],
+            &[[]; 2],
+            &[],
+            &ci,
+            &[0.0; 2],
+            &mut x,
+        ))
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+}
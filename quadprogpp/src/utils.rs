@@ -0,0 +1,460 @@
+//! Helpers for preparing problem data.
+
+use std::borrow::Cow;
+use std::slice;
+
+use ndarray::{Array1, Array2, ArrayBase, Data, Dimension, Ix1, Ix2, RawData};
+use quadprogpp_sys as sys;
+
+use crate::{Error, Result};
+
+/// Maximum number of sweeps of the Jacobi eigenvalue algorithm. Convergence is quadratic, so
+/// symmetric matrices of any practical size converge in far fewer.
+const MAX_SWEEPS: usize = 100;
+
+/// Returns the positive semidefinite matrix nearest to `a` in the Frobenius norm.
+///
+/// The nearest symmetric matrix is \\((A + A^\intercal) / 2\\), and its nearest positive
+/// semidefinite matrix is obtained by clipping its negative eigenvalues to zero (Higham, 1988).
+/// This repairs the indefiniteness of estimated covariance matrices, e.g. pairwise-complete
+/// correlations. `a` must be square.
+///
+/// The result may still be singular, which [`solve`](crate::solve) rejects with
+/// [`Error::SingularHessian`](crate::Error::SingularHessian), so it's typically combined with
+/// [`SolveOptions::regularization`](crate::SolveOptions::regularization).
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let g = array![[1.0, 2.0], [2.0, 1.0]];
+/// let g0 = array![-1.0, 0.0];
+/// let ce = Constraints::some(array![[1.0], [1.0]], array![-1.0]);
+/// let ci = Constraints::some(array![[1.0, 0.0], [0.0, 1.0]], array![0.0, 0.0]);
+/// assert!(solve(g.view(), g0.view(), ce.clone(), ci.clone()).is_err());
+///
+/// let g = utils::nearest_psd(&g);
+/// assert!((g[[0, 1]] - 1.5).abs() < 1e-12);
+/// let options = SolveOptions::new().regularization(1e-9);
+/// let solution = solve_with_options(g, g0, ce, ci, &options)?;
+/// assert!((solution.x[0] - 1.0).abs() < 1e-6);
+/// # Ok::<(), Error>(())
+/// ```
+///
+/// # Panics
+///
+/// Panics if `a` isn't square.
+pub fn nearest_psd(a: &Array2<f64>) -> Array2<f64> {
+    assert!(a.is_square(), "nearest_psd requires a square matrix");
+    let symmetric = (a + &a.t()) * 0.5;
+    let (values, vectors) = symmetric_eigen(symmetric);
+    let clipped = values.mapv(|value| value.max(0.0));
+    let psd = (&vectors * &clipped).dot(&vectors.t());
+    // Rounding makes the product slightly asymmetric.
+    (&psd + &psd.t()) * 0.5
+}
+
+/// Unpacks the upper triangle of a symmetric matrix, stored row by row, into the full matrix.
+///
+/// The packed vector of an \\(N \times N\\) matrix has \\(N (N + 1) / 2\\) elements,
+/// \\(G_{00}, G_{01}, \ldots, G_{0,N-1}, G_{11}, G_{12}, \ldots, G_{N-1,N-1}\\).
+///
+/// # Errors
+///
+/// If the length of `packed` isn't a triangular number, this function returns
+/// [`Error::SizeMismatch`] with the next larger one as the expected length.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let g = utils::unpack_upper(array![2.0, 1.0, 0.5, 3.0, 0.0, 4.0])?;
+/// assert_eq!(g, array![[2.0, 1.0, 0.5], [1.0, 3.0, 0.0], [0.5, 0.0, 4.0]]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn unpack_upper<S: Data<Elem = f64>>(packed: ArrayBase<S, Ix1>) -> Result<Array2<f64>> {
+    let len = packed.len();
+    let mut n = 0;
+    while n * (n + 1) / 2 < len {
+        n += 1;
+    }
+    assert_size!(packed, n * (n + 1) / 2, len);
+    let mut g = Array2::zeros((n, n));
+    let mut elements = packed.iter();
+    for i in 0..n {
+        for j in i..n {
+            let x = *elements.next().unwrap();
+            g[[i, j]] = x;
+            g[[j, i]] = x;
+        }
+    }
+    Ok(g)
+}
+
+/// Converts the \\(N \times N\\) Hessian \\(G\\) in compressed sparse column (CSC) format to a
+/// dense matrix. The row indices of column `j` are `row_indices[col_offsets[j]..col_offsets[j + 1]]`,
+/// and duplicate entries are summed.
+///
+/// This is the storage of `nalgebra_sparse::CscMatrix`, i.e. its `col_offsets()`,
+/// `row_indices()` and `values()`. Both triangles of \\(G\\) have to be stored.
+///
+/// # Errors
+///
+/// * If the length of `col_offsets` isn't \\(N + 1\\), or the offsets or the row indices are
+///   out of range, this function returns [`Error::SizeMismatch`].
+/// * If the sparsity pattern isn't structurally symmetric, i.e. an element is stored but its
+///   transpose isn't, it returns [`Error::NotSymmetric`] with the location of the element in the
+///   lower triangle. The values are checked by the solvers.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let g = utils::hessian_from_csc(2, &[0, 2, 4], &[0, 1, 0, 1], &[2.0, 1.0, 1.0, 3.0])?;
+/// assert_eq!(g, array![[2.0, 1.0], [1.0, 3.0]]);
+///
+/// // The upper triangle only
+/// let err = utils::hessian_from_csc(2, &[0, 1, 3], &[0, 0, 1], &[2.0, 1.0, 3.0]).unwrap_err();
+/// assert!(matches!(err, Error::NotSymmetric { row: 1, col: 0 }));
+/// # Ok::<(), Error>(())
+/// ```
+pub fn hessian_from_csc(
+    n: usize,
+    col_offsets: &[usize],
+    row_indices: &[usize],
+    values: &[f64],
+) -> Result<Array2<f64>> {
+    let g = csc_to_dense((n, n), col_offsets, row_indices, values)?;
+    // Explicitly stored zeros are part of the pattern, so it's built separately from the values.
+    let mut pattern = Array2::from_elem((n, n), false);
+    for (j, offsets) in col_offsets.windows(2).enumerate() {
+        for &i in &row_indices[offsets[0]..offsets[1]] {
+            pattern[[i, j]] = true;
+        }
+    }
+    for i in 0..n {
+        for j in 0..i {
+            if pattern[[i, j]] != pattern[[j, i]] {
+                return Err(Error::NotSymmetric { row: i, col: j });
+            }
+        }
+    }
+    Ok(g)
+}
+
+/// Computes the lower triangular \\(L\\) of the Cholesky factorization \\(A = L L^\intercal\\)
+/// of the symmetric positive definite matrix `a`, with the factorization used by QuadProg++. Only
+/// the upper triangle of `a` is read.
+///
+/// # Errors
+///
+/// * If `a` isn't square, this function returns [`Error::SizeMismatch`].
+/// * If `a` isn't positive definite, it returns [`Error::SingularHessian`] with the index of the
+///   nonpositive pivot.
+///
+/// # Examples
+///
+/// ```
+/// # use ndarray::array;
+/// # use quadprogpp::*;
+/// let l = utils::cholesky(&array![[4.0, 2.0], [2.0, 5.0]])?;
+/// assert_eq!(l, array![[2.0, 0.0], [1.0, 2.0]]);
+/// let x = utils::cholesky_solve(&l, &array![2.0, 5.0])?;
+/// assert!((x[0] - 0.0).abs() < 1e-12 && (x[1] - 1.0).abs() < 1e-12);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn cholesky<S: Data<Elem = f64>>(a: &ArrayBase<S, Ix2>) -> Result<Array2<f64>> {
+    let (n, m) = a.dim();
+    assert_size!(a, n, m);
+    let data = unsafe { row_major(a) };
+    let mut l = sys::Matrix::from_row_major(&data, n, n);
+    l.cholesky()
+        .map_err(|pivot| Error::SingularHessian { pivot })?;
+    Ok(Array2::from_shape_vec((n, n), l.to_vec()).expect("the shape is that of `a`"))
+}
+
+/// Solves \\(L L^\intercal x = b\\) for \\(x\\), where \\(L\\) is the lower triangle of
+/// `l`, e.g. as computed by [`cholesky`].
+///
+/// # Errors
+///
+/// If `l` isn't square or its size doesn't match `b`, this function returns
+/// [`Error::SizeMismatch`].
+pub fn cholesky_solve<S, T>(l: &ArrayBase<S, Ix2>, b: &ArrayBase<T, Ix1>) -> Result<Array1<f64>>
+where
+    S: Data<Elem = f64>,
+    T: Data<Elem = f64>,
+{
+    let (n, m) = l.dim();
+    assert_size!(l, n, m);
+    assert_size!(b, n, b.len());
+    let data = unsafe { row_major(l) };
+    let l = sys::Matrix::from_row_major(&data, n, n);
+    let b = sys::Vector::from(&unsafe { row_major(b) }[..]);
+    Ok(Array1::from(l.cholesky_substitute(&b).to_vec()))
+}
+
+/// Converts a matrix in compressed sparse column (CSC) format to a dense one. The row indices of
+/// column `j` are `row_indices[col_offsets[j]..col_offsets[j + 1]]`, and duplicate entries are
+/// summed.
+pub(crate) fn csc_to_dense(
+    shape: (usize, usize),
+    col_offsets: &[usize],
+    row_indices: &[usize],
+    values: &[f64],
+) -> Result<Array2<f64>> {
+    let (nrows, ncols) = shape;
+    let nnz = values.len();
+    assert_size!(col_offsets, ncols + 1, col_offsets.len());
+    assert_size!(row_indices, nnz, row_indices.len());
+    let mut a = Array2::zeros(shape);
+    for (j, offsets) in col_offsets.windows(2).enumerate() {
+        let (start, end) = (offsets[0], offsets[1]);
+        if start > end || end > nnz {
+            // The offsets must be nondecreasing and within the entries.
+            assert_size!(col_offsets, nnz, end.max(nnz + 1));
+        }
+        for (&i, &value) in row_indices[start..end].iter().zip(&values[start..end]) {
+            if i >= nrows {
+                assert_size!(row_indices, nrows, i + 1);
+            }
+            a[[i, j]] += value;
+        }
+    }
+    Ok(a)
+}
+
+/// Returns the location in the lower triangle of the element of the row-major \\(N \times N\\)
+/// matrix `g` with the largest relative difference from its transpose, if that difference exceeds
+/// `tolerance`.
+pub(crate) fn asymmetry(g: &[f64], n: usize, tolerance: f64) -> Option<(usize, usize)> {
+    let mut worst = None;
+    let mut worst_difference = 0.0;
+    for i in 0..n {
+        for j in 0..i {
+            let (a, b) = (g[i * n + j], g[j * n + i]);
+            let difference = (a - b).abs() / a.abs().max(b.abs());
+            if difference > tolerance && difference > worst_difference {
+                worst = Some((i, j));
+                worst_difference = difference;
+            }
+        }
+    }
+    worst
+}
+
+/// Returns the elements of `a` in row-major order, borrowing them if `a` is in standard layout and
+/// copying them otherwise, e.g. if it's a transposed or sliced view or a column-major array.
+///
+/// # Safety
+///
+/// The solvers accept arrays with any [`RawData`] storage, so the caller must ensure that the
+/// elements of `a` are initialized and readable, like when reading them through `a.as_ptr()`.
+pub(crate) unsafe fn row_major<S, D>(a: &ArrayBase<S, D>) -> Cow<'_, [f64]>
+where
+    S: RawData<Elem = f64>,
+    D: Dimension,
+{
+    if a.is_standard_layout() {
+        Cow::Borrowed(slice::from_raw_parts(a.as_ptr(), a.len()))
+    } else {
+        Cow::Owned(a.raw_view().deref_into_view().iter().copied().collect())
+    }
+}
+
+/// Computes the eigenvalues and eigenvectors of the symmetric matrix `a` by the cyclic Jacobi
+/// method. The eigenvectors are the columns of the second matrix.
+pub(crate) fn symmetric_eigen(mut a: Array2<f64>) -> (Array1<f64>, Array2<f64>) {
+    let n = a.nrows();
+    let mut v = Array2::eye(n);
+    for _ in 0..MAX_SWEEPS {
+        let off: f64 = (0..n)
+            .flat_map(|p| (p + 1..n).map(move |q| (p, q)))
+            .map(|(p, q)| a[[p, q]] * a[[p, q]])
+            .sum();
+        let diagonal: f64 = a.diag().iter().map(|x| x * x).sum();
+        if off <= f64::EPSILON * f64::EPSILON * diagonal || off == 0.0 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[[p, q]];
+                if apq == 0.0 {
+                    continue;
+                }
+                let theta = (a[[q, q]] - a[[p, p]]) / (2.0 * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                rotate_columns(&mut a, p, q, c, s);
+                for k in 0..n {
+                    let (apk, aqk) = (a[[p, k]], a[[q, k]]);
+                    a[[p, k]] = c * apk - s * aqk;
+                    a[[q, k]] = s * apk + c * aqk;
+                }
+                rotate_columns(&mut v, p, q, c, s);
+            }
+        }
+    }
+    (a.diag().to_owned(), v)
+}
+
+fn rotate_columns(a: &mut Array2<f64>, p: usize, q: usize, c: f64, s: f64) {
+    for k in 0..a.nrows() {
+        let (akp, akq) = (a[[k, p]], a[[k, q]]);
+        a[[k, p]] = c * akp - s * akq;
+        a[[k, q]] = s * akp + c * akq;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn unpack_upper_checks_length() {
+        assert_eq!(
+            unpack_upper(Array1::zeros(0)).unwrap(),
+            Array2::zeros((0, 0))
+        );
+        assert_eq!(unpack_upper(array![1.0]).unwrap(), array![[1.0]]);
+        let err = unpack_upper(array![1.0, 2.0, 3.0, 4.0]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "packed",
+                    expected: 6,
+                    actual: 4,
+                    shapes: None
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn cholesky_factorizes() -> Result<()> {
+        let a = array![[4.0, 2.0, -2.0], [2.0, 10.0, 2.0], [-2.0, 2.0, 6.0]];
+        let l = cholesky(&a)?;
+        for (&actual, &expected) in l.dot(&l.t()).iter().zip(&a) {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+        }
+        assert_eq!(l[[0, 1]], 0.0);
+        let x = cholesky_solve(&l, &array![1.0, 2.0, 3.0])?;
+        for (&actual, &expected) in a.dot(&x).iter().zip(&[1.0, 2.0, 3.0]) {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+        }
+        // Column-major arrays are copied.
+        assert_eq!(cholesky(&a.t())?, l);
+        // The lower triangle isn't read.
+        let garbage = array![
+            [4.0, 2.0, -2.0],
+            [100.0, 10.0, 2.0],
+            [f64::NAN, -100.0, 6.0]
+        ];
+        assert_eq!(cholesky(&garbage)?, l);
+
+        let err = cholesky(&array![[1.0, 2.0], [2.0, 1.0]]).unwrap_err();
+        assert!(
+            matches!(err, Error::SingularHessian { pivot: 1 }),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = cholesky_solve(&l, &array![1.0, 2.0]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "b",
+                    expected: 3,
+                    actual: 2,
+                    shapes: None
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn csc_to_dense_checks_indices() -> Result<()> {
+        let a = csc_to_dense((2, 3), &[0, 1, 1, 4], &[1, 0, 1, 0], &[1.0, 2.0, 3.0, 4.0])?;
+        assert_eq!(a, array![[0.0, 0.0, 6.0], [1.0, 0.0, 3.0]]);
+
+        let err = csc_to_dense((2, 2), &[0, 1, 3], &[1, 0], &[1.0, 2.0]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "col_offsets",
+                    expected: 2,
+                    actual: 3,
+                    shapes: None
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        let err = csc_to_dense((2, 2), &[0, 1, 2], &[1, 2], &[1.0, 2.0]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "row_indices",
+                    expected: 2,
+                    actual: 3,
+                    shapes: None
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn nearest_psd_clips_eigenvalues() {
+        let a = array![[1.0, 2.0], [2.0, 1.0]];
+        let psd = nearest_psd(&a);
+        for (&actual, &expected) in psd.iter().zip(&array![[1.5, 1.5], [1.5, 1.5]]) {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+        }
+
+        // Already positive definite, but asymmetric
+        let a = array![[4.0, 1.0, 0.5], [2.0, 3.0, 0.0], [0.5, 0.0, 2.0]];
+        let psd = nearest_psd(&a);
+        let expected = array![[4.0, 1.5, 0.5], [1.5, 3.0, 0.0], [0.5, 0.0, 2.0]];
+        for (&actual, &expected) in psd.iter().zip(&expected) {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+        }
+
+        let a = array![
+            [1.0, 0.9, -0.9, 0.2],
+            [0.9, 1.0, 0.9, -0.3],
+            [-0.9, 0.9, 1.0, 0.4],
+            [0.2, -0.3, 0.4, 1.0],
+        ];
+        let psd = nearest_psd(&a);
+        assert_eq!(psd, psd.t());
+        let (values, vectors) = symmetric_eigen(psd.clone());
+        assert!(values.iter().all(|&value| value >= -1e-12));
+        // The eigenvectors are orthonormal
+        let identity = vectors.t().dot(&vectors);
+        for (&actual, &expected) in identity.iter().zip(&Array2::<f64>::eye(4)) {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+        }
+        // Clipping again doesn't change anything
+        for (&actual, &expected) in nearest_psd(&psd).iter().zip(&psd) {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use ndarray::{Array1, ArrayBase, Data, Ix1};
+
+use crate::{Constraints, Error, Result};
+
+/// The residuals of constraints at a point, returned by [`Constraints::violations`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violations {
+    /// \\(a_j^\intercal x + c_j\\) for each constraint, or \\(a_j^\intercal x\\) for ranged
+    /// constraints
+    pub residuals: Array1<f64>,
+    /// The amount by which each constraint is violated as an inequality constraint, i.e. the
+    /// distance of its residual from the feasible range, which is zero if it's satisfied
+    pub violations: Array1<f64>,
+    /// The maximum of [`violations`](Self::violations), zero if there are no constraints
+    pub max: f64,
+    /// The index of the most violated constraint, or `None` if all the constraints are satisfied
+    pub worst: Option<usize>,
+}
+
+impl Violations {
+    /// Returns the maximum absolute residual, which is the maximum violation if the constraints
+    /// are equality constraints.
+    pub fn max_residual(&self) -> f64 {
+        self.residuals.iter().fold(0.0, |max, r| max.max(r.abs()))
+    }
+}
+
+impl<S, S0> Constraints<S, S0>
+where
+    S: Data<Elem = f64>,
+    S0: Data<Elem = f64>,
+{
+    /// Evaluates the constraints at `x`, as inequality constraints.
+    ///
+    /// This can be used to check the feasibility of a point which didn't come from the solver, or
+    /// the accuracy of a solution. For equality constraints, see
+    /// [`Violations::max_residual`].
+    ///
+    /// # Errors
+    ///
+    /// If `x` doesn't have one element per row of the coefficient matrix, this method returns
+    /// [`Error::SizeMismatch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ndarray::array;
+    /// # use quadprogpp::*;
+    /// // x >= 0, y >= 0, 1 <= x + y <= 2
+    /// let ci = Constraints::ranged(
+    ///     array![[1.0, 0.0, 1.0], [0.0, 1.0, 1.0]],
+    ///     array![0.0, 0.0, 1.0],
+    ///     array![f64::INFINITY, f64::INFINITY, 2.0],
+    /// );
+    /// let violations = ci.violations(&array![-0.5, 3.0])?;
+    /// assert_eq!(violations.residuals, array![-0.5, 3.0, 2.5]);
+    /// assert_eq!(violations.violations, array![0.5, 0.0, 0.5]);
+    /// assert_eq!(violations.max, 0.5);
+    /// assert_eq!(violations.worst, Some(0));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn violations<X: Data<Elem = f64>>(&self, x: &ArrayBase<X, Ix1>) -> Result<Violations> {
+        assert_size!(x, self.coeffs.nrows(), x.len());
+        let mut residuals = self.coeffs.t().dot(x);
+        let violations = match &self.upper {
+            Some(upper) => residuals
+                .iter()
+                .zip(self.consts.iter().zip(upper))
+                .map(|(&r, (&l, &u))| (l - r).max(r - u).max(0.0))
+                .collect(),
+            None => {
+                residuals += &self.consts;
+                residuals.mapv(|r| (-r).max(0.0))
+            }
+        };
+        let mut max = 0.0;
+        let mut worst = None;
+        for (j, &v) in violations.iter().enumerate() {
+            if v > max {
+                max = v;
+                worst = Some(j);
+            }
+        }
+        Ok(Violations {
+            residuals,
+            violations,
+            max,
+            worst,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+    use crate::solve;
+
+    #[test]
+    fn solution_is_feasible() -> Result<()> {
+        let g = array![[2.0, 0.5], [0.5, 1.0]];
+        let g0 = array![-4.0, -3.0];
+        let ce = Constraints::new(array![[1.0], [-1.0]], array![0.5]);
+        let ci = Constraints::new(array![[-1.0, 0.0], [0.0, -1.0]], array![1.0, 2.0]);
+        let solution = solve(g, g0, Some(ce.view()), Some(ci.view()))?;
+        let violations = ci.violations(&solution.x)?;
+        assert!(violations.max <= 1e-12);
+        assert_eq!(violations.worst, None);
+        assert!(ce.violations(&solution.x)?.max_residual() <= 1e-12);
+
+        let violations = ci.violations(&array![2.0, 0.0])?;
+        assert_eq!(violations.violations, array![1.0, 0.0]);
+        assert_eq!(violations.worst, Some(0));
+        let violations = ce.violations(&array![2.0, 0.0])?;
+        assert_eq!(violations.max_residual(), 2.5);
+
+        let err = ci.violations(&array![0.0]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::SizeMismatch {
+                    term: "x",
+                    expected: 2,
+                    actual: 1,
+                    ..
+                }
+            ),
+            "unexpected error: {:?}",
+            err
+        );
+        Ok(())
+    }
+}